@@ -1,6 +1,10 @@
 //! CLI argument definitions using clap.
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use rtls_link_core::device::mavlink::RetryPolicy;
+use rtls_link_core::device::ota::{OtaAuth, OtaEndpoint};
+
+use crate::types::GpsOrigin;
 
 /// RTLS-Link CLI - Command-line interface for RTLS-Link device management
 #[derive(Parser, Debug)]
@@ -20,7 +24,8 @@ pub struct Cli {
     #[arg(short, long, global = true)]
     pub verbose: bool,
 
-    /// Exit non-zero on any partial failure (for bulk operations)
+    /// Exit non-zero on any partial failure (for bulk operations) or, with
+    /// --verify, on any parameter that reads back differently
     #[arg(long, global = true)]
     pub strict: bool,
 
@@ -59,6 +64,9 @@ pub enum Commands {
 
     /// Calibrate anchor antenna delays using inter-anchor ToF
     Calibrate(CalibrateArgs),
+
+    /// Back up or restore all local configs and presets
+    BackupData(BackupDataArgs),
 }
 
 // ==================== Discover ====================
@@ -80,10 +88,45 @@ pub struct DiscoverArgs {
 
 #[derive(ValueEnum, Clone, Debug)]
 pub enum RoleFilter {
+    #[value(alias = "anchor")]
     AnchorTdoa,
+    #[value(alias = "tag")]
     TagTdoa,
 }
 
+// ==================== Backup Data ====================
+
+#[derive(Args, Debug)]
+pub struct BackupDataArgs {
+    #[command(subcommand)]
+    pub command: BackupDataCommands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum BackupDataCommands {
+    /// Write all local configs and presets to an archive (.tar.gz)
+    Export(BackupDataExportArgs),
+
+    /// Restore local configs and presets from an archive
+    Import(BackupDataImportArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct BackupDataExportArgs {
+    /// Output archive file
+    pub file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct BackupDataImportArgs {
+    /// Archive file to restore
+    pub file: String,
+
+    /// Replace existing configs and presets with the same name
+    #[arg(long)]
+    pub overwrite: bool,
+}
+
 // ==================== Calibrate ====================
 
 #[derive(Args, Debug)]
@@ -238,6 +281,33 @@ pub enum ConfigCommands {
 
     /// Delete a named configuration from device storage
     Delete(ConfigDeleteArgs),
+
+    /// Rename a locally saved configuration
+    Rename(ConfigRenameArgs),
+
+    /// Export a locally saved configuration to a portable file
+    ExportLocal(ConfigExportLocalArgs),
+
+    /// Import a configuration file into local storage
+    ImportLocal(ConfigImportLocalArgs),
+
+    /// List locally saved configurations
+    ListLocal(ConfigListLocalArgs),
+
+    /// Compare two configuration files (or local configurations with --local)
+    Diff(ConfigDiffArgs),
+
+    /// Find configurations whose parameter names or values match a query
+    Search(ConfigSearchArgs),
+
+    /// Check a configuration file for invalid values before applying it
+    Validate(ConfigValidateArgs),
+
+    /// List previous versions of a locally saved configuration
+    History(ConfigHistoryArgs),
+
+    /// Restore a previous version of a locally saved configuration
+    Restore(ConfigRestoreArgs),
 }
 
 #[derive(Args, Debug)]
@@ -248,6 +318,18 @@ pub struct ConfigBackupArgs {
     /// Output file (default: stdout or <ip>_config.json)
     #[arg(short, long)]
     pub output: Option<String>,
+
+    /// Save the backup to local config storage under this name
+    #[arg(long, value_name = "NAME")]
+    pub save_local: Option<String>,
+
+    /// Replace an existing local config with the same name
+    #[arg(long, requires = "save_local")]
+    pub force: bool,
+
+    /// Fail on backups in older firmware shapes instead of upgrading them
+    #[arg(long)]
+    pub strict_parse: bool,
 }
 
 #[derive(Args, Debug)]
@@ -258,8 +340,15 @@ pub struct ConfigApplyArgs {
     /// Configuration file to apply
     pub file: String,
 
-    /// Skip devShortAddr (preserve device identity)
-    #[arg(long, default_value = "true")]
+    /// Skip devShortAddr (preserve device identity); pass
+    /// `--skip-short-addr=false` to clone a device including its identity
+    #[arg(
+        long,
+        default_value_t = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        action = ArgAction::Set
+    )]
     pub skip_short_addr: bool,
 
     /// Filter by role when target is "all"
@@ -269,6 +358,20 @@ pub struct ConfigApplyArgs {
     /// Concurrency limit for bulk operations
     #[arg(long, default_value = "3")]
     pub concurrency: usize,
+
+    /// Apply despite validation warnings (errors always block)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Read each parameter back after writing and report any that differ
+    /// from what was sent; with --strict, differences fail the command
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Wait MS between commands to each device, for devices that drop
+    /// frames when writes arrive back to back
+    #[arg(long, value_name = "MS")]
+    pub pacing_ms: Option<u64>,
 }
 
 #[derive(Args, Debug)]
@@ -283,6 +386,9 @@ pub struct ConfigReadArgs {
     /// Parameter name
     #[arg(short, long)]
     pub name: String,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
 }
 
 #[derive(Args, Debug)]
@@ -302,9 +408,22 @@ pub struct ConfigWriteArgs {
     #[arg(short = 'd', long = "data")]
     pub value: String,
 
+    #[command(flatten)]
+    pub retry: RetryArgs,
+
     /// Save to flash after writing
     #[arg(long)]
     pub save: bool,
+
+    /// Write a parameter the manager does not know about (values of known
+    /// parameters are always checked)
+    #[arg(long)]
+    pub force: bool,
+
+    /// Read each parameter back after writing and report any that differ
+    /// from what was sent; with --strict, differences fail the command
+    #[arg(long)]
+    pub verify: bool,
 }
 
 #[derive(Args, Debug)]
@@ -340,6 +459,105 @@ pub struct ConfigDeleteArgs {
     pub name: String,
 }
 
+#[derive(Args, Debug)]
+pub struct ConfigRenameArgs {
+    /// Current configuration name
+    pub old_name: String,
+
+    /// New configuration name
+    pub new_name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigExportLocalArgs {
+    /// Local configuration name
+    pub name: String,
+
+    /// Output file
+    pub file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigImportLocalArgs {
+    /// Exported configuration file (or a plain config JSON)
+    pub file: String,
+
+    /// Save under this name instead of the one in the file
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Replace an existing configuration with the same name
+    #[arg(long, visible_alias = "force")]
+    pub overwrite: bool,
+
+    /// Set the description (replaces the one in the file)
+    #[arg(long)]
+    pub description: Option<String>,
+
+    /// Set tags, e.g. site:hallA (repeatable or comma-separated; replaces
+    /// the ones in the file)
+    #[arg(long = "tag", value_delimiter = ',')]
+    pub tags: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigListLocalArgs {
+    /// Only list configurations with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigDiffArgs {
+    /// Left-hand configuration file (or local name with --local)
+    pub a: String,
+
+    /// Right-hand configuration file (or local name with --local)
+    pub b: String,
+
+    /// Compare locally saved configurations by name
+    #[arg(long)]
+    pub local: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigSearchArgs {
+    /// Text to look for in parameter names and values (case-insensitive)
+    pub query: String,
+
+    /// Configuration files to search (ignored with --local)
+    pub files: Vec<String>,
+
+    /// Search locally saved configurations
+    #[arg(long)]
+    pub local: bool,
+
+    /// Treat the query as a regular expression
+    #[arg(long)]
+    pub regex: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigValidateArgs {
+    /// Configuration file to check
+    pub file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigHistoryArgs {
+    /// Local configuration name
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ConfigRestoreArgs {
+    /// Local configuration name
+    pub name: String,
+
+    /// Revision timestamp (see `config history`)
+    pub timestamp: String,
+}
+
 // ==================== Preset ====================
 
 #[derive(Args, Debug)]
@@ -350,8 +568,8 @@ pub struct PresetArgs {
 
 #[derive(Subcommand, Debug)]
 pub enum PresetCommands {
-    /// List all local presets
-    List,
+    /// List local presets
+    List(PresetListArgs),
 
     /// Show details of a preset
     Show(PresetShowArgs),
@@ -362,8 +580,48 @@ pub enum PresetCommands {
     /// Delete a local preset
     Delete(PresetDeleteArgs),
 
+    /// Rename a local preset
+    Rename(PresetRenameArgs),
+
+    /// Copy a local preset under a new name
+    Copy(PresetCopyArgs),
+
+    /// Convert a local preset to another type
+    Convert(PresetConvertArgs),
+
+    /// Combine a full preset with the anchor layout of a locations preset
+    Merge(PresetMergeArgs),
+
+    /// Export a local preset to a shareable file
+    Export(PresetExportArgs),
+
+    /// Import a preset file into local storage
+    Import(PresetImportArgs),
+
+    /// Generate a locations preset from a room template
+    Template(PresetTemplateArgs),
+
     /// Upload a preset to device(s)
     Upload(PresetUploadArgs),
+
+    /// Compare a preset against a device's current configuration
+    ///
+    /// Exits with code 8 when they differ.
+    Diff(PresetDiffArgs),
+
+    /// Move unreadable preset files into the corrupt/ subfolder
+    Repair,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetListArgs {
+    /// Only list presets of this type
+    #[arg(long = "type", value_enum)]
+    pub preset_type: Option<PresetTypeArg>,
+
+    /// Only list presets with this tag
+    #[arg(long)]
+    pub tag: Option<String>,
 }
 
 #[derive(Args, Debug)]
@@ -385,6 +643,18 @@ pub struct PresetSaveArgs {
     #[arg(long)]
     pub from_file: Option<String>,
 
+    /// Survey CSV with an id,x,y,z header; saves a locations preset
+    #[arg(long, requires = "origin", conflicts_with_all = ["from_device", "from_file"])]
+    pub from_anchors_csv: Option<String>,
+
+    /// GPS origin for --from-anchors-csv as LAT,LON,ALT
+    #[arg(long, value_parser = parse_origin, allow_hyphen_values = true)]
+    pub origin: Option<GpsOrigin>,
+
+    /// Coordinate rotation in degrees for --from-anchors-csv
+    #[arg(long, allow_hyphen_values = true)]
+    pub rotation: Option<f64>,
+
     /// Preset type
     #[arg(long, value_enum, default_value = "full")]
     pub preset_type: PresetTypeArg,
@@ -392,9 +662,17 @@ pub struct PresetSaveArgs {
     /// Optional description
     #[arg(short, long)]
     pub description: Option<String>,
+
+    /// Tags, e.g. siteA (repeatable or comma-separated)
+    #[arg(long = "tag", value_delimiter = ',')]
+    pub tags: Vec<String>,
+
+    /// Replace an existing preset whose name differs only by case
+    #[arg(long)]
+    pub force: bool,
 }
 
-#[derive(ValueEnum, Clone, Debug)]
+#[derive(ValueEnum, Clone, Copy, Debug)]
 pub enum PresetTypeArg {
     Full,
     Locations,
@@ -410,6 +688,159 @@ pub struct PresetDeleteArgs {
     pub force: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct PresetRenameArgs {
+    /// Current preset name
+    pub old_name: String,
+
+    /// New preset name
+    pub new_name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetCopyArgs {
+    /// Preset to copy
+    pub source: String,
+
+    /// Name of the copy
+    pub target: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetConvertArgs {
+    /// Preset name
+    pub name: String,
+
+    /// Target preset type
+    #[arg(long, value_enum)]
+    pub to: PresetTypeArg,
+
+    /// Save the result under this name instead of replacing the preset
+    #[arg(long = "as")]
+    pub as_name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetMergeArgs {
+    /// Full preset providing the device settings
+    pub full: String,
+
+    /// Locations preset providing anchors, origin and rotation
+    pub locations: String,
+
+    /// Name of the merged preset to save
+    pub output: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetExportArgs {
+    /// Preset name
+    pub name: String,
+
+    /// Output file
+    pub file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetImportArgs {
+    /// Exported preset file
+    pub file: String,
+
+    /// Save under this name instead of the one in the file
+    #[arg(long)]
+    pub name: Option<String>,
+
+    /// Replace an existing preset with the same name
+    #[arg(long, visible_alias = "force")]
+    pub overwrite: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetTemplateArgs {
+    #[command(subcommand)]
+    pub layout: PresetTemplateLayout,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PresetTemplateLayout {
+    /// Four anchors, one in each corner of the room
+    Square(PresetTemplateRoomArgs),
+
+    /// Six anchors: the corners plus the middle of both long walls
+    Rectangle(PresetTemplateRoomArgs),
+
+    /// Six anchors on the outer corners of an L-shaped room
+    LShape(PresetTemplateLShapeArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct PresetTemplateRoomArgs {
+    /// Room size along X in meters
+    #[arg(long)]
+    pub width: f64,
+
+    /// Room size along Y in meters
+    #[arg(long)]
+    pub length: f64,
+
+    /// Anchor mounting height in meters
+    #[arg(long)]
+    pub height: f64,
+
+    #[command(flatten)]
+    pub save: PresetTemplateSaveArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetTemplateLShapeArgs {
+    /// Overall room size along X in meters
+    #[arg(long)]
+    pub width: f64,
+
+    /// Overall room size along Y in meters
+    #[arg(long)]
+    pub length: f64,
+
+    /// Size along X of the corner cut out of the room
+    #[arg(long)]
+    pub notch_width: f64,
+
+    /// Size along Y of the corner cut out of the room
+    #[arg(long)]
+    pub notch_length: f64,
+
+    /// Anchor mounting height in meters
+    #[arg(long)]
+    pub height: f64,
+
+    #[command(flatten)]
+    pub save: PresetTemplateSaveArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetTemplateSaveArgs {
+    /// Name of the preset to save
+    #[arg(long)]
+    pub name: String,
+
+    /// Anchor ids in placement order (default: 0, 1, 2, ...)
+    #[arg(long, value_delimiter = ',')]
+    pub ids: Vec<String>,
+
+    /// Tags, e.g. siteA (repeatable or comma-separated)
+    #[arg(long = "tag", value_delimiter = ',')]
+    pub tags: Vec<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct PresetDiffArgs {
+    /// Preset name
+    pub name: String,
+
+    /// Device IP address
+    pub ip: String,
+}
+
 #[derive(Args, Debug)]
 pub struct PresetUploadArgs {
     /// Preset name
@@ -425,6 +856,50 @@ pub struct PresetUploadArgs {
     /// Concurrency limit for bulk operations
     #[arg(long, default_value = "3")]
     pub concurrency: usize,
+
+    /// Upload even if the anchor layout has validation warnings
+    #[arg(long)]
+    pub force: bool,
+
+    /// Replace the anchors, origin and rotation of a full preset with those
+    /// of this locations preset (not saved)
+    #[arg(long, value_name = "NAME")]
+    pub with_locations: Option<String>,
+
+    /// Print the commands that would be sent to each device without sending
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Per-device change: GROUP:DEVICE:NAME=VALUE to replace a value or
+    /// GROUP:DEVICE:NAME to skip it, where DEVICE is a MAC or UWB short
+    /// address (repeatable)
+    #[arg(long = "override", value_name = "SPEC")]
+    pub overrides: Vec<String>,
+
+    /// Reboot each device after saving, so settings such as the UWB channel
+    /// take effect
+    #[arg(long)]
+    pub reboot: bool,
+
+    /// After rebooting, wait up to SECS for the devices to reappear in
+    /// discovery
+    #[arg(long, value_name = "SECS", requires = "reboot")]
+    pub wait_online: Option<u64>,
+
+    /// Read each parameter back after writing and report any that differ
+    /// from what was sent; with --strict, differences fail the command
+    #[arg(long)]
+    pub verify: bool,
+
+    /// Also upload a locations preset to TDoA anchors, giving each anchor
+    /// only its own position (matched by UWB short address) and the origin
+    #[arg(long)]
+    pub include_anchors: bool,
+
+    /// Wait MS between commands to each device, for devices that drop
+    /// frames when writes arrive back to back
+    #[arg(long, value_name = "MS")]
+    pub pacing_ms: Option<u64>,
 }
 
 // ==================== OTA ====================
@@ -456,6 +931,55 @@ pub struct OtaUpdateArgs {
     /// Concurrency limit for bulk operations
     #[arg(long, default_value = "3")]
     pub concurrency: usize,
+
+    /// Upload over HTTPS, for devices behind a TLS reverse proxy
+    #[arg(long)]
+    pub tls: bool,
+
+    /// Port of the devices' OTA endpoint (default: 80, or 443 with --tls)
+    #[arg(long, value_name = "PORT")]
+    pub device_port: Option<u16>,
+
+    /// Accept self-signed or otherwise invalid certificates
+    #[arg(long, requires = "tls")]
+    pub accept_invalid_certs: bool,
+
+    /// HTTP basic auth credentials for the device web server
+    #[arg(long, value_name = "USER:PASS", value_parser = parse_basic_auth, env = "RTLS_CLI_AUTH", hide_env_values = true)]
+    pub auth: Option<OtaAuth>,
+
+    /// Bearer token for the device web server
+    #[arg(
+        long,
+        value_name = "TOKEN",
+        conflicts_with = "auth",
+        env = "RTLS_CLI_AUTH_TOKEN",
+        hide_env_values = true
+    )]
+    pub auth_token: Option<String>,
+
+    /// Probe each device's OTA endpoint first and skip those that do not
+    /// answer, instead of waiting for their uploads to time out
+    #[arg(long)]
+    pub skip_unreachable: bool,
+}
+
+impl OtaUpdateArgs {
+    pub fn endpoint(&self) -> OtaEndpoint {
+        let auth = match (&self.auth, &self.auth_token) {
+            (Some(auth), _) => auth.clone(),
+            (None, Some(token)) => OtaAuth::Bearer {
+                token: token.clone(),
+            },
+            (None, None) => OtaAuth::None,
+        };
+        OtaEndpoint {
+            tls: self.tls,
+            port: self.device_port,
+            accept_invalid_certs: self.accept_invalid_certs,
+            auth,
+        }
+    }
 }
 
 // ==================== Logs ====================
@@ -465,21 +989,152 @@ pub struct LogsArgs {
     /// Device IP address (optional, default: all devices)
     pub ip: Option<String>,
 
+    #[command(subcommand)]
+    pub command: Option<LogsCommands>,
+
+    /// Only show logs from these devices (repeatable or comma-separated)
+    #[arg(long = "ip", value_delimiter = ',', global = true)]
+    pub ips: Vec<String>,
+
+    /// Hide logs from these devices (repeatable or comma-separated)
+    #[arg(long, value_delimiter = ',', global = true)]
+    pub exclude_ip: Vec<String>,
+
+    /// Only show logs from devices with this role (resolved by discovery,
+    /// refreshed every 30s)
+    #[arg(long, visible_alias = "role", value_enum, global = true)]
+    pub filter_role: Option<RoleFilter>,
+
     /// Minimum log level to display
-    #[arg(short, long, default_value = "info")]
+    #[arg(short, long, default_value = "info", global = true)]
     pub level: String,
 
     /// Filter by tag pattern (glob-style, e.g., "uwb*")
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub tag: Option<String>,
 
+    /// Minimum level for tags matching a glob, e.g. 'uwb*=debug'
+    /// (repeatable; the longest matching glob wins over --level)
+    #[arg(long, value_name = "GLOB=LEVEL", global = true)]
+    pub tag_level: Vec<String>,
+
+    /// Only show messages matching this regex (repeatable, any may match)
+    #[arg(long, global = true)]
+    pub grep: Vec<String>,
+
+    /// Show messages that match none of the --grep patterns instead
+    #[arg(long, requires = "grep", global = true)]
+    pub grep_invert: bool,
+
     /// UDP port to listen on
-    #[arg(long, default_value = "3334")]
+    #[arg(long, default_value = "3334", global = true)]
     pub port: u16,
 
+    /// If the port is in use, listen on the next free port instead
+    #[arg(long, global = true)]
+    pub port_fallback: bool,
+
+    /// After falling back, point discovered devices' logUdpPort at the new
+    /// port (not saved to flash)
+    #[arg(long, requires = "port_fallback", global = true)]
+    pub auto_retarget: bool,
+
     /// Output as newline-delimited JSON (NDJSON)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub ndjson: bool,
+
+    /// Show device timestamps aligned to local wall-clock time
+    #[arg(long, global = true)]
+    pub wallclock: bool,
+
+    /// Resolve device IPs to device ids with a discovery pass at startup
+    #[arg(long, conflicts_with = "stats", global = true)]
+    pub resolve_ids: bool,
+
+    /// Hex-dump every received packet before parsing, ignoring level, tag
+    /// and --grep filters (device filters still apply)
+    #[arg(long, conflicts_with = "stats", global = true)]
+    pub raw: bool,
+
+    /// Print per-device and per-tag statistics instead of each message
+    #[arg(long, global = true)]
+    pub stats: bool,
+
+    /// Stop after this many seconds (default with --stats: 30)
+    #[arg(long, global = true)]
+    pub duration: Option<u64>,
+
+    /// Stop after this many messages have been received
+    #[arg(long, global = true)]
+    pub count: Option<u64>,
+
+    /// Also write every displayed log to this file as NDJSON
+    #[arg(short, long, conflicts_with = "stats", global = true)]
+    pub output: Option<String>,
+
+    /// Rotate the output file when it reaches this size (e.g. 512K, 10M, 1G)
+    #[arg(long, value_parser = parse_size, requires = "output", global = true)]
+    pub max_size: Option<u64>,
+
+    /// Number of rotated output files to keep besides the current one
+    #[arg(long, default_value = "5", requires = "max_size", global = true)]
+    pub max_files: usize,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum LogsCommands {
+    /// Enable UDP logging on a device and stream its logs
+    Follow(LogsFollowArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct LogsFollowArgs {
+    /// Device IP address
+    pub ip: String,
+
+    /// Restore the device's previous log settings on exit
+    #[arg(long)]
+    pub restore_on_exit: bool,
+}
+
+/// Parse a `LAT,LON,ALT` GPS origin
+fn parse_origin(s: &str) -> Result<GpsOrigin, String> {
+    let values: Vec<f64> = s
+        .split(',')
+        .map(|v| v.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("Invalid origin: {}", s))?;
+    match values[..] {
+        [lat, lon, alt] => GpsOrigin::new(lat, lon, alt).map_err(|e| e.to_string()),
+        _ => Err(format!("Expected LAT,LON,ALT, got '{}'", s)),
+    }
+}
+
+/// Parse `USER:PASS` basic auth credentials (the password may contain ':')
+fn parse_basic_auth(s: &str) -> Result<OtaAuth, String> {
+    match s.split_once(':') {
+        Some((username, password)) if !username.is_empty() => Ok(OtaAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        }),
+        _ => Err("Expected USER:PASS".to_string()),
+    }
+}
+
+/// Parse a byte size with an optional K/M/G suffix (powers of 1024)
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let (digits, multiplier) = match s.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&s[..i], 1024),
+        Some((i, 'm' | 'M')) => (&s[..i], 1024 * 1024),
+        Some((i, 'g' | 'G')) => (&s[..i], 1024 * 1024 * 1024),
+        _ => (s, 1),
+    };
+    let value: u64 = digits.parse().map_err(|_| format!("Invalid size: {}", s))?;
+    match value.checked_mul(multiplier) {
+        Some(0) | None => Err(format!("Invalid size: {}", s)),
+        Some(bytes) => Ok(bytes),
+    }
 }
 
 // ==================== Anchor Telemetry ====================
@@ -560,6 +1215,26 @@ pub struct CmdArgs {
     /// Expect JSON response
     #[arg(long)]
     pub expect_json: bool,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
+}
+
+#[derive(Args, Debug, Clone, Copy)]
+pub struct RetryArgs {
+    /// Retry timeouts and transport errors up to N times, waiting 500 ms
+    /// before the first retry and doubling the wait each time
+    #[arg(long = "retry", value_name = "N", default_value_t = 0)]
+    pub retries: usize,
+}
+
+impl RetryArgs {
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            retries: self.retries,
+            ..RetryPolicy::default()
+        }
+    }
 }
 
 // ==================== Bulk ====================
@@ -602,6 +1277,14 @@ pub struct BulkTargetArgs {
     /// Discovery duration (seconds)
     #[arg(long, default_value = "3")]
     pub discovery_duration: u64,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
+
+    /// Probe devices first and skip those that do not answer, instead of
+    /// waiting for their commands to time out
+    #[arg(long)]
+    pub skip_unreachable: bool,
 }
 
 #[derive(Args, Debug)]
@@ -624,4 +1307,12 @@ pub struct BulkCmdArgs {
     /// Discovery duration (seconds)
     #[arg(long, default_value = "3")]
     pub discovery_duration: u64,
+
+    #[command(flatten)]
+    pub retry: RetryArgs,
+
+    /// Probe devices first and skip those that do not answer, instead of
+    /// waiting for their commands to time out
+    #[arg(long)]
+    pub skip_unreachable: bool,
 }
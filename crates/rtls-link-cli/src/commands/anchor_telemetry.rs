@@ -12,7 +12,7 @@ use crate::cli::{
     AnchorTelemetryListenArgs, RoleFilter,
 };
 use crate::device::discovery::{discover_devices, DiscoveryOptions, DISCOVERY_PORT};
-use crate::error::CliError;
+use crate::error::{CliError, CommandError};
 use crate::output::get_formatter;
 use crate::types::{Device, DeviceRole};
 
@@ -55,7 +55,7 @@ async fn run_configure(
         return Err(CliError::NoDevicesFound);
     }
 
-    let commands = telemetry_config_commands(&args);
+    let commands = telemetry_config_commands(&args)?;
     let mut results = Vec::new();
 
     for ip in &ips {
@@ -109,7 +109,9 @@ fn validate_configure_args(args: &AnchorTelemetryConfigureArgs) -> Result<(), Cl
     Ok(())
 }
 
-fn telemetry_config_commands(args: &AnchorTelemetryConfigureArgs) -> Vec<String> {
+fn telemetry_config_commands(
+    args: &AnchorTelemetryConfigureArgs,
+) -> Result<Vec<String>, CommandError> {
     let mut commands = Vec::new();
 
     if args.enable || args.disable {
@@ -117,24 +119,24 @@ fn telemetry_config_commands(args: &AnchorTelemetryConfigureArgs) -> Vec<String>
             "uwb",
             "tdoaAnchorTelemetryEnable",
             if args.enable { "1" } else { "0" },
-        ));
+        )?);
     }
     if let Some(interval_ms) = args.interval_ms {
         commands.push(Commands::write_param(
             "uwb",
             "tdoaAnchorTelemetryIntervalMs",
             &interval_ms.to_string(),
-        ));
+        )?);
     }
     if let Some(port) = args.port {
         commands.push(Commands::write_param(
             "uwb",
             "tdoaAnchorTelemetryPort",
             &port.to_string(),
-        ));
+        )?);
     }
 
-    commands
+    Ok(commands)
 }
 
 async fn apply_telemetry_config(
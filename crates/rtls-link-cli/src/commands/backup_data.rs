@@ -0,0 +1,84 @@
+//! Whole-data backup commands.
+
+use std::path::{Path, PathBuf};
+
+use crate::cli::{BackupDataArgs, BackupDataCommands};
+use crate::error::CliError;
+
+use rtls_link_core::storage::{default_data_dir, export_all, import_all};
+
+/// Run the backup-data command
+pub async fn run_backup_data(args: BackupDataArgs, json: bool) -> Result<(), CliError> {
+    let data_dir = data_dir()?;
+
+    match args.command {
+        BackupDataCommands::Export(args) => run_export(&data_dir, &args.file, json).await,
+        BackupDataCommands::Import(args) => {
+            run_import(&data_dir, &args.file, args.overwrite, json).await
+        }
+    }
+}
+
+fn data_dir() -> Result<PathBuf, CliError> {
+    default_data_dir()
+        .ok_or_else(|| CliError::Other("Could not determine app data directory".to_string()))
+}
+
+async fn run_export(data_dir: &Path, file: &str, json_output: bool) -> Result<(), CliError> {
+    let app_version = concat!("rtls-link-cli ", env!("CARGO_PKG_VERSION"));
+    let manifest = export_all(data_dir, Path::new(file), app_version).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "file": file,
+            "manifest": manifest
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!(
+            "Exported {} config(s) and {} preset(s) to {}",
+            manifest.configs.len(),
+            manifest.presets.len(),
+            file
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_import(
+    data_dir: &Path,
+    file: &str,
+    overwrite: bool,
+    json_output: bool,
+) -> Result<(), CliError> {
+    let report = import_all(data_dir, Path::new(file), overwrite).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "file": file,
+            "report": report
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!(
+            "Imported {} config(s) and {} preset(s) from {}",
+            report.configs.len(),
+            report.presets.len(),
+            file
+        );
+        if !report.skipped.is_empty() {
+            println!(
+                "Skipped {} existing item(s) (use --overwrite to replace):",
+                report.skipped.len()
+            );
+            for name in &report.skipped {
+                println!("  {}", name);
+            }
+        }
+    }
+
+    Ok(())
+}
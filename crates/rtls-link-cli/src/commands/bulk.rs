@@ -9,6 +9,7 @@ use crate::output::get_formatter;
 use crate::types::{Device, DeviceRole};
 
 use rtls_link_core::device::mavlink::BatchSender;
+use rtls_link_core::device::probe::DEFAULT_PROBE_TIMEOUT;
 use rtls_link_core::protocol::commands::Commands;
 
 /// Run bulk command
@@ -48,30 +49,22 @@ async fn run_bulk_command(
     }
 
     let formatter = get_formatter(json);
-    let sender = BatchSender::new(timeout, target.concurrency);
+    let mut sender =
+        BatchSender::new(timeout, target.concurrency).with_retry(target.retry.policy());
+    if target.skip_unreachable {
+        sender = sender.skip_unreachable(DEFAULT_PROBE_TIMEOUT);
+    }
 
     println!("Running '{}' on {} device(s)...", command, ips.len());
 
     let results = sender.send_to_all(&ips, command).await;
 
-    let formatted_results: Vec<(String, bool, String)> = results
-        .into_iter()
-        .map(|(ip, result)| {
-            let success = result.is_ok();
-            let message = match result {
-                Ok(response) => format_bulk_message(&response, json),
-                Err(e) => e.to_string(),
-            };
-            (ip, success, message)
-        })
-        .collect();
-
-    println!("{}", formatter.format_bulk_results(&formatted_results));
-
-    let failed_count = formatted_results.iter().filter(|(_, s, _)| !s).count();
+    println!("{}", formatter.format_batch_results(&results));
+
+    let failed_count = results.iter().filter(|result| !result.is_success()).count();
     if strict && failed_count > 0 {
         return Err(CliError::PartialFailure {
-            succeeded: formatted_results.len() - failed_count,
+            succeeded: results.len() - failed_count,
             failed: failed_count,
         });
     }
@@ -91,52 +84,11 @@ async fn run_bulk_raw_command(
         ips: args.ips.clone(),
         concurrency: args.concurrency,
         discovery_duration: args.discovery_duration,
+        retry: args.retry,
+        skip_unreachable: args.skip_unreachable,
     };
 
-    let ips = get_target_ips(&target).await?;
-
-    if ips.is_empty() {
-        return Err(CliError::NoDevicesFound);
-    }
-
-    let formatter = get_formatter(json);
-    let sender = BatchSender::new(timeout, args.concurrency);
-
-    println!("Running '{}' on {} device(s)...", command, ips.len());
-
-    let results = sender.send_to_all(&ips, command).await;
-
-    let formatted_results: Vec<(String, bool, String)> = results
-        .into_iter()
-        .map(|(ip, result)| {
-            let success = result.is_ok();
-            let message = match result {
-                Ok(response) => format_bulk_message(&response, json),
-                Err(e) => e.to_string(),
-            };
-            (ip, success, message)
-        })
-        .collect();
-
-    println!("{}", formatter.format_bulk_results(&formatted_results));
-
-    let failed_count = formatted_results.iter().filter(|(_, s, _)| !s).count();
-    if strict && failed_count > 0 {
-        return Err(CliError::PartialFailure {
-            succeeded: formatted_results.len() - failed_count,
-            failed: failed_count,
-        });
-    }
-
-    Ok(())
-}
-
-fn format_bulk_message(response: &str, json: bool) -> String {
-    if json || response.len() <= 100 {
-        return response.trim().to_string();
-    }
-    let preview: String = response.chars().take(100).collect();
-    format!("{}...", preview)
+    run_bulk_command(command, &target, timeout, json, strict).await
 }
 
 async fn get_target_ips(target: &BulkTargetArgs) -> Result<Vec<String>, CliError> {
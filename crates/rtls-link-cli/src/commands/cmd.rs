@@ -6,7 +6,8 @@ use crate::cli::CmdArgs;
 use crate::error::CliError;
 use crate::output::get_formatter;
 
-use rtls_link_core::device::mavlink::send_command;
+use colored::Colorize;
+use rtls_link_core::device::mavlink::send_command_retrying;
 use rtls_link_core::protocol::commands::is_structured_response_command;
 
 /// Run the cmd command
@@ -16,7 +17,21 @@ pub async fn run_cmd(args: CmdArgs, timeout: u64, json: bool) -> Result<(), CliE
 
     let expect_structured = args.expect_json || is_structured_response_command(&args.command);
 
-    let response = send_command(&args.ip, &args.command, timeout_duration).await?;
+    let (response, attempts) = send_command_retrying(
+        &args.ip,
+        &args.command,
+        timeout_duration,
+        args.retry.policy(),
+    )
+    .await?;
+    if attempts > 1 && !json {
+        eprintln!(
+            "{}: {} answered after {} attempts",
+            "warning".yellow(),
+            args.ip,
+            attempts
+        );
+    }
 
     if json {
         if expect_structured {
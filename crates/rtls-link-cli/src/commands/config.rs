@@ -1,17 +1,32 @@
 //! Configuration commands implementation.
 
+use std::path::Path;
 use std::time::Duration;
 
-use crate::cli::{ConfigArgs, ConfigCommands, RoleFilter};
+use crate::cli::{
+    ConfigApplyArgs, ConfigArgs, ConfigBackupArgs, ConfigCommands, ConfigDiffArgs,
+    ConfigImportLocalArgs, ConfigReadArgs, ConfigSearchArgs, ConfigWriteArgs, RoleFilter,
+};
 use crate::device::discovery::{discover_devices, DiscoveryOptions, DISCOVERY_PORT};
-use crate::error::{CliError, ConfigError};
+use crate::error::{CliError, ConfigError, ParamError, StorageError};
 use crate::output::get_formatter;
-use crate::types::{Device, DeviceConfig, DeviceRole};
-
-use rtls_link_core::device::mavlink::{send_command, DeviceConnection};
+use crate::types::{ConfigSearchResult, Device, DeviceConfig, DeviceRole};
+
+use colored::*;
+use comfy_table::{ContentArrangement, Table};
+use rtls_link_core::config_diff::{diff_configs, ConfigDiffKind};
+use rtls_link_core::config_validation::{
+    check_role_matches_config, validate_config, ValidationIssue,
+};
+use rtls_link_core::device::mavlink::{send_command, send_command_retrying, DeviceConnection};
 use rtls_link_core::protocol::commands::Commands;
-use rtls_link_core::protocol::config_params::{config_to_params, device_config_from_backup_value};
-use rtls_link_core::protocol::response::parse_json_response;
+use rtls_link_core::protocol::config_params::{
+    config_to_params, device_config_from_backup_value, device_config_from_backup_value_strict,
+    ParamOptions, ParamPlan, PresetUploadPlan,
+};
+use rtls_link_core::protocol::param_registry::{lookup, validate_param};
+use rtls_link_core::protocol::response::{parse_json_response, ParamMismatch};
+use rtls_link_core::storage::{default_data_dir, ConfigLabels, ConfigQuery, ConfigStorage};
 
 /// Run the config command
 pub async fn run_config(
@@ -24,37 +39,10 @@ pub async fn run_config(
     let timeout_duration = Duration::from_millis(timeout);
 
     match args.command {
-        ConfigCommands::Backup(args) => {
-            run_backup(&args.ip, args.output.as_deref(), timeout_duration, json).await
-        }
-        ConfigCommands::Apply(args) => {
-            run_apply(
-                &args.target,
-                &args.file,
-                args.skip_short_addr,
-                args.filter_role,
-                args.concurrency,
-                timeout_duration,
-                json,
-                strict,
-            )
-            .await
-        }
-        ConfigCommands::Read(args) => {
-            run_read(&args.ip, &args.group, &args.name, timeout_duration, json).await
-        }
-        ConfigCommands::Write(args) => {
-            run_write(
-                &args.ip,
-                &args.group,
-                &args.name,
-                &args.value,
-                args.save,
-                timeout_duration,
-                json,
-            )
-            .await
-        }
+        ConfigCommands::Backup(args) => run_backup(args, timeout_duration, json).await,
+        ConfigCommands::Apply(args) => run_apply(args, timeout_duration, json, strict).await,
+        ConfigCommands::Read(args) => run_read(&args, timeout_duration, json).await,
+        ConfigCommands::Write(args) => run_write(&args, timeout_duration, json, strict).await,
         ConfigCommands::List(args) => run_list(&args.ip, timeout_duration, json).await,
         ConfigCommands::SaveAs(args) => {
             run_save_as(&args.ip, &args.name, timeout_duration, json).await
@@ -63,28 +51,71 @@ pub async fn run_config(
         ConfigCommands::Delete(args) => {
             run_delete(&args.ip, &args.name, timeout_duration, json).await
         }
+        ConfigCommands::Rename(args) => run_rename(&args.old_name, &args.new_name, json).await,
+        ConfigCommands::ExportLocal(args) => run_export_local(&args.name, &args.file, json).await,
+        ConfigCommands::ImportLocal(args) => run_import_local(args, json).await,
+        ConfigCommands::ListLocal(args) => run_list_local(args.tag.as_deref(), json).await,
+        ConfigCommands::Diff(args) => run_diff(args, json).await,
+        ConfigCommands::Search(args) => run_search(args, json).await,
+        ConfigCommands::Validate(args) => run_validate(&args.file, json),
+        ConfigCommands::History(args) => run_history(&args.name, json).await,
+        ConfigCommands::Restore(args) => run_restore(&args.name, &args.timestamp, json).await,
     }
 }
 
+fn create_config_storage() -> Result<ConfigStorage, CliError> {
+    let data_dir = default_data_dir()
+        .ok_or_else(|| CliError::Other("Could not determine app data directory".to_string()))?;
+    ConfigStorage::new(data_dir.join("configs")).map_err(CliError::from)
+}
+
 async fn run_backup(
-    ip: &str,
-    output: Option<&str>,
+    args: ConfigBackupArgs,
     timeout: Duration,
-    _json_output: bool,
+    json_output: bool,
 ) -> Result<(), CliError> {
+    let ip = args.ip.as_str();
     let response = send_command(ip, Commands::backup_config(), timeout).await?;
 
     let json: serde_json::Value = parse_json_response(&response, ip)?;
-    let config: DeviceConfig =
-        device_config_from_backup_value(json).map_err(ConfigError::ParseError)?;
+    let config: DeviceConfig = if args.strict_parse {
+        device_config_from_backup_value_strict(json)
+    } else {
+        device_config_from_backup_value(json)
+    }
+    .map_err(ConfigError::ParseError)?;
 
     let config_json = serde_json::to_string_pretty(&config).map_err(ConfigError::ParseError)?;
 
-    if let Some(output_path) = output {
+    if let Some(output_path) = &args.output {
         std::fs::write(output_path, &config_json)
             .map_err(|e| CliError::Other(format!("Failed to write file: {}", e)))?;
         println!("Configuration saved to {}", output_path);
-    } else {
+    }
+
+    if let Some(name) = &args.save_local {
+        let storage = create_config_storage()?;
+        storage.save(name, &config, args.force).await?;
+        let info = storage
+            .info(name)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(name.clone()))?;
+        let warnings = validate_config(&config);
+
+        if json_output {
+            let output = serde_json::json!({
+                "ip": ip,
+                "saved": info,
+                "warnings": warnings
+            });
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        } else {
+            for warning in &warnings {
+                println!("{}: {}", "warning".yellow(), warning);
+            }
+            println!("Configuration from {} saved locally as '{}'", ip, info.name);
+        }
+    } else if args.output.is_none() {
         println!("{}", config_json);
     }
 
@@ -92,22 +123,56 @@ async fn run_backup(
 }
 
 async fn run_apply(
-    target: &str,
-    file: &str,
-    _skip_short_addr: bool,
-    filter_role: Option<RoleFilter>,
-    _concurrency: usize,
+    args: ConfigApplyArgs,
     timeout: Duration,
     json_output: bool,
     strict: bool,
 ) -> Result<(), CliError> {
-    let config_content = std::fs::read_to_string(file)
-        .map_err(|e| CliError::Other(format!("Failed to read config file: {}", e)))?;
+    let (target, file) = (args.target.as_str(), args.file.as_str());
+    let config = read_config_file(file)?;
+
+    // Pre-flight: nothing is sent to a device unless the file passes
+    let issues = validate_config(&config);
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    let warnings = issues.len() - errors;
+    let validation = serde_json::json!({
+        "valid": errors == 0,
+        "issues": issues
+    });
+    let blocked = if errors > 0 {
+        Some(format!("{} has {} validation error(s)", file, errors))
+    } else if warnings > 0 && !args.force {
+        Some(format!(
+            "{} has {} validation warning(s); use --force to apply anyway",
+            file, warnings
+        ))
+    } else {
+        None
+    };
 
-    let config: DeviceConfig =
-        serde_json::from_str(&config_content).map_err(ConfigError::ParseError)?;
+    if let Some(reason) = blocked {
+        if json_output {
+            let output = serde_json::json!({
+                "file": file,
+                "validation": validation,
+                "applied": false
+            });
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        } else {
+            print_validation_report(&issues);
+        }
+        return Err(CliError::Validation(reason));
+    }
+    if !json_output && !issues.is_empty() {
+        print_validation_report(&issues);
+        println!();
+    }
 
-    let params = config_to_params(&config).map_err(CliError::Other)?;
+    let options = ParamOptions {
+        include_short_addr: !args.skip_short_addr,
+        ..ParamOptions::default()
+    };
+    let params = config_to_params(&config, options).map_err(CliError::Other)?;
 
     let ips = if target.to_lowercase() == "all" {
         let options = DiscoveryOptions {
@@ -115,7 +180,15 @@ async fn run_apply(
             duration: Duration::from_secs(3),
         };
         let devices = discover_devices(options).await?;
-        let devices = filter_devices_by_role(devices, filter_role);
+        let devices = filter_devices_by_role(devices, args.filter_role);
+        if !json_output {
+            for issue in devices
+                .iter()
+                .filter_map(|d| check_role_matches_config(d, &config))
+            {
+                eprintln!("{}: {}", "warning".yellow(), issue.message);
+            }
+        }
         devices.into_iter().map(|d| d.ip).collect()
     } else if target.contains(',') {
         target.split(',').map(|s| s.trim().to_string()).collect()
@@ -129,18 +202,49 @@ async fn run_apply(
 
     let formatter = get_formatter(json_output);
     let mut results = Vec::new();
+    let mut mismatches = serde_json::Map::new();
+    let mut mismatch_count = 0;
 
+    let pacing = args.pacing_ms.map(Duration::from_millis);
     for ip in &ips {
-        let result = apply_config_to_device(ip, &params, timeout).await;
+        let result = apply_config_to_device(ip, &params, args.verify, timeout, pacing).await;
         let success = result.is_ok();
         let message = match &result {
-            Ok(_) => "Configuration applied".to_string(),
+            Ok((found, reconnects)) if !found.is_empty() => format!(
+                "Configuration applied, {} parameter(s) read back differently{}",
+                found.len(),
+                reconnect_note(*reconnects)
+            ),
+            Ok((_, reconnects)) => {
+                format!("Configuration applied{}", reconnect_note(*reconnects))
+            }
             Err(e) => e.to_string(),
         };
+        if let Ok((found, _)) = result {
+            warn_mismatches(ip, &found, json_output);
+            mismatch_count += found.len();
+            if args.verify {
+                mismatches.insert(ip.clone(), serde_json::json!(found));
+            }
+        }
         results.push((ip.clone(), success, message));
     }
 
-    println!("{}", formatter.format_bulk_results(&results));
+    let report = formatter.format_bulk_results(&results);
+    if json_output {
+        let mut output: serde_json::Value = serde_json::from_str(&report).unwrap_or_default();
+        if let Some(fields) = output.as_object_mut() {
+            fields.insert("file".to_string(), serde_json::json!(file));
+            fields.insert("validation".to_string(), validation);
+            fields.insert("applied".to_string(), serde_json::json!(true));
+            if args.verify {
+                fields.insert("mismatches".to_string(), mismatches.into());
+            }
+        }
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("{}", report);
+    }
 
     let failed_count = results.iter().filter(|(_, s, _)| !s).count();
     if failed_count == results.len() || (strict && failed_count > 0) {
@@ -149,42 +253,74 @@ async fn run_apply(
             failed: failed_count,
         });
     }
+    if strict && mismatch_count > 0 {
+        return Err(CliError::VerifyFailed(mismatch_count));
+    }
 
     Ok(())
 }
 
+/// Apply `params` to one device, returning those that read back
+/// differently when `verify` is set and how often the link was reopened
 async fn apply_config_to_device(
     ip: &str,
-    params: &[(String, String, String)],
+    params: &ParamPlan,
+    verify: bool,
     timeout: Duration,
-) -> Result<(), CliError> {
-    let mut conn = DeviceConnection::connect(ip, timeout).await?;
+    pacing: Option<Duration>,
+) -> Result<(Vec<ParamMismatch>, usize), CliError> {
+    let plan = PresetUploadPlan {
+        params: params.clone(),
+        save_command: Commands::save_config().to_string(),
+    };
+    let mut conn = DeviceConnection::connect_resilient(ip, timeout).await?;
+    conn.set_pacing(pacing);
+    let mismatches = if verify {
+        conn.upload_plan_verified(&plan).await?
+    } else {
+        conn.upload_plan(&plan).await?;
+        Vec::new()
+    };
 
-    for (group, name, value) in params {
-        let cmd = Commands::write_param(group, name, value);
-        conn.send_raw(&cmd).await?;
-    }
+    Ok((mismatches, conn.reconnects()))
+}
 
-    conn.send_raw(Commands::save_config()).await?;
+/// Note for a result message that the link to a device was reopened
+pub(crate) fn reconnect_note(reconnects: usize) -> String {
+    match reconnects {
+        0 => String::new(),
+        1 => " (reconnected once)".to_string(),
+        n => format!(" (reconnected {} times)", n),
+    }
+}
 
-    Ok(())
+/// Warn about each parameter of `ip` that read back differently
+pub(crate) fn warn_mismatches(ip: &str, mismatches: &[ParamMismatch], json_output: bool) {
+    if json_output {
+        return;
+    }
+    for mismatch in mismatches {
+        eprintln!("{}: {} {}", "warning".yellow(), ip, mismatch);
+    }
 }
 
 async fn run_read(
-    ip: &str,
-    group: &str,
-    name: &str,
+    args: &ConfigReadArgs,
     timeout: Duration,
     json_output: bool,
 ) -> Result<(), CliError> {
-    let cmd = Commands::read_param(group, name);
-    let response = send_command(ip, &cmd, timeout).await?;
+    let (ip, group, name) = (args.ip.as_str(), args.group.as_str(), args.name.as_str());
+    let cmd = Commands::read_param(group, name)?;
+    let (response, attempts) =
+        send_command_retrying(ip, &cmd, timeout, args.retry.policy()).await?;
+    warn_if_retried(ip, attempts, json_output);
 
     if json_output {
         let output = serde_json::json!({
             "group": group,
             "name": name,
-            "value": response.trim()
+            "value": response.trim(),
+            "attempts": attempts
         });
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
@@ -194,20 +330,66 @@ async fn run_read(
     Ok(())
 }
 
+fn warn_if_retried(ip: &str, attempts: usize, json_output: bool) {
+    if attempts > 1 && !json_output {
+        eprintln!(
+            "{}: {} answered after {} attempts",
+            "warning".yellow(),
+            ip,
+            attempts
+        );
+    }
+}
+
 async fn run_write(
-    ip: &str,
-    group: &str,
-    name: &str,
-    value: &str,
-    save: bool,
+    args: &ConfigWriteArgs,
     timeout: Duration,
     json_output: bool,
+    strict: bool,
 ) -> Result<(), CliError> {
-    let cmd = Commands::write_param(group, name, value);
-    let _response = send_command(ip, &cmd, timeout).await?;
+    let (group, name, value) = (args.group.as_str(), args.name.as_str(), args.value.as_str());
+
+    match validate_param(group, name, value) {
+        Ok(()) => {}
+        Err(ParamError::Unknown { .. }) if args.force => {
+            if !json_output {
+                eprintln!(
+                    "{}: {}.{} is not a known parameter, writing it anyway",
+                    "warning".yellow(),
+                    group,
+                    name
+                );
+            }
+        }
+        Err(e @ ParamError::Unknown { .. }) => {
+            return Err(CliError::InvalidArgument(format!(
+                "{} (use --force to write it anyway)",
+                e
+            )));
+        }
+        Err(e) => return Err(CliError::Validation(e.to_string())),
+    }
+    let requires_reboot = lookup(group, name).is_some_and(|spec| spec.requires_reboot);
+
+    let policy = args.retry.policy();
+    let cmd = Commands::write_param(group, name, value)?;
+    let (_response, attempts) = send_command_retrying(&args.ip, &cmd, timeout, policy).await?;
+    warn_if_retried(&args.ip, attempts, json_output);
+
+    let mismatches = if args.verify {
+        let written = [(group.to_string(), name.to_string(), value.to_string())];
+        let mut conn = DeviceConnection::connect(&args.ip, timeout).await?;
+        let mismatches = conn.verify_params(&written).await?;
+        warn_mismatches(&args.ip, &mismatches, json_output);
+        Some(mismatches)
+    } else {
+        None
+    };
 
-    if save {
-        send_command(ip, Commands::save_config(), timeout).await?;
+    if args.save {
+        let (_response, attempts) =
+            send_command_retrying(&args.ip, Commands::save_config(), timeout, policy).await?;
+        warn_if_retried(&args.ip, attempts, json_output);
     }
 
     if json_output {
@@ -216,17 +398,30 @@ async fn run_write(
             "group": group,
             "name": name,
             "value": value,
-            "saved": save
+            "saved": args.save,
+            "requiresReboot": requires_reboot,
+            "attempts": attempts,
+            "mismatches": mismatches
         });
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
         println!("Parameter written: {}:{} = {}", group, name, value);
-        if save {
+        if mismatches.as_ref().is_some_and(|m| m.is_empty()) {
+            println!("Verified: the device reads back the written value.");
+        }
+        if args.save {
             println!("Configuration saved to flash.");
         }
+        if requires_reboot {
+            println!("Reboot the device for this change to take effect.");
+        }
+    }
+    match mismatches {
+        Some(mismatches) if strict && !mismatches.is_empty() => {
+            Err(CliError::VerifyFailed(mismatches.len()))
+        }
+        _ => Ok(()),
     }
-
-    Ok(())
 }
 
 async fn run_list(ip: &str, timeout: Duration, json_output: bool) -> Result<(), CliError> {
@@ -294,7 +489,7 @@ async fn run_save_as(
     timeout: Duration,
     json_output: bool,
 ) -> Result<(), CliError> {
-    let cmd = Commands::save_config_as(name);
+    let cmd = Commands::save_config_as(name)?;
     let response = send_command(ip, &cmd, timeout).await?;
 
     if json_output {
@@ -322,7 +517,7 @@ async fn run_load(
     timeout: Duration,
     json_output: bool,
 ) -> Result<(), CliError> {
-    let cmd = Commands::load_config_named(name);
+    let cmd = Commands::load_config_named(name)?;
     let response = send_command(ip, &cmd, timeout).await?;
 
     if json_output {
@@ -350,7 +545,7 @@ async fn run_delete(
     timeout: Duration,
     json_output: bool,
 ) -> Result<(), CliError> {
-    let cmd = Commands::delete_config(name);
+    let cmd = Commands::delete_config(name)?;
     let response = send_command(ip, &cmd, timeout).await?;
 
     if json_output {
@@ -385,3 +580,336 @@ fn filter_devices_by_role(devices: Vec<Device>, filter: Option<RoleFilter>) -> V
         None => devices,
     }
 }
+
+async fn run_rename(old_name: &str, new_name: &str, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    storage.rename(old_name, new_name).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "oldName": old_name,
+            "newName": new_name
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Configuration '{}' renamed to '{}'", old_name, new_name);
+    }
+
+    Ok(())
+}
+
+async fn run_export_local(name: &str, file: &str, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    let app_version = concat!("rtls-link-cli ", env!("CARGO_PKG_VERSION"));
+    storage.export(name, Path::new(file), app_version).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "file": file
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Configuration '{}' exported to {}", name, file);
+    }
+
+    Ok(())
+}
+
+async fn run_import_local(args: ConfigImportLocalArgs, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    let name = storage
+        .import(Path::new(&args.file), args.name.as_deref(), args.overwrite)
+        .await?;
+
+    if args.description.is_some() || !args.tags.is_empty() {
+        let imported = storage
+            .read(&name)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(name.clone()))?;
+        let description = args
+            .description
+            .as_deref()
+            .or(imported.description.as_deref());
+        let tags = if args.tags.is_empty() {
+            imported.tags
+        } else {
+            args.tags
+        };
+        storage
+            .set_labels(&name, &ConfigLabels::new(description, tags))
+            .await?;
+    }
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "file": args.file
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Configuration imported from {} as '{}'", args.file, name);
+    }
+
+    Ok(())
+}
+
+async fn run_list_local(tag: Option<&str>, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    let configs = match tag {
+        Some(tag) => storage.list_with_tag(tag).await?,
+        None => storage.list().await?,
+    };
+
+    if json_output {
+        let output = serde_json::json!({
+            "configs": configs,
+            "count": configs.len()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if configs.is_empty() {
+        println!("No local configurations saved.");
+    } else {
+        println!("Local configurations:");
+        println!(
+            "  {:<24} {:<7} {:>7} {:>4} ORIGIN",
+            "NAME", "MODE", "ANCHORS", "CH"
+        );
+        for config in &configs {
+            let tags = if config.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", config.tags.join(", "))
+            };
+            let desc = config
+                .description
+                .as_ref()
+                .map(|d| format!(" - {}", d))
+                .unwrap_or_default();
+            let columns = match &config.summary {
+                Some(summary) => format!(
+                    "{:<7} {:>7} {:>4} {:<3}",
+                    match summary.uwb_mode {
+                        3 => "anchor".to_string(),
+                        4 => "tag".to_string(),
+                        mode => mode.to_string(),
+                    },
+                    summary.anchor_count,
+                    summary
+                        .channel
+                        .map_or_else(|| "-".to_string(), |c| c.to_string()),
+                    if summary.has_origin { "yes" } else { "no" }
+                ),
+                None => format!("{:<7} {:>7} {:>4} {:<3}", "-", "-", "-", "-"),
+            };
+            println!("  {:<24} {}{}{}", config.name, columns, tags, desc);
+        }
+        println!("\n{} configuration(s) total", configs.len());
+    }
+
+    Ok(())
+}
+
+async fn run_diff(args: ConfigDiffArgs, json_output: bool) -> Result<(), CliError> {
+    let (a, b) = if args.local {
+        let storage = create_config_storage()?;
+        let mut configs = Vec::new();
+        for name in [&args.a, &args.b] {
+            let config = storage
+                .read(name)
+                .await?
+                .ok_or_else(|| StorageError::NotFound(name.clone()))?;
+            configs.push(config.config);
+        }
+        let b = configs.pop().unwrap();
+        (configs.pop().unwrap(), b)
+    } else {
+        (read_config_file(&args.a)?, read_config_file(&args.b)?)
+    };
+
+    let diff = diff_configs(&a, &b);
+
+    if json_output {
+        let output = serde_json::json!({
+            "left": args.a,
+            "right": args.b,
+            "differences": diff,
+            "count": diff.len()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if diff.is_empty() {
+        println!("No differences between '{}' and '{}'.", args.a, args.b);
+    } else {
+        println!("{} {}", "---".red(), args.a);
+        println!("{} {}", "+++".green(), args.b);
+        for entry in &diff {
+            let param = format!("{}.{}", entry.group, entry.name);
+            let left = entry.left.as_deref().unwrap_or_default();
+            let right = entry.right.as_deref().unwrap_or_default();
+            match entry.kind {
+                ConfigDiffKind::Added => println!("{}", format!("+ {} = {}", param, right).green()),
+                ConfigDiffKind::Removed => println!("{}", format!("- {} = {}", param, left).red()),
+                ConfigDiffKind::Changed => println!(
+                    "{} {}: {} -> {}",
+                    "~".yellow(),
+                    param,
+                    left.red(),
+                    right.green()
+                ),
+            }
+        }
+        println!("\n{} difference(s)", diff.len());
+    }
+
+    Ok(())
+}
+
+async fn run_search(args: ConfigSearchArgs, json_output: bool) -> Result<(), CliError> {
+    let results = if args.local {
+        create_config_storage()?
+            .search(&args.query, args.regex)
+            .await?
+    } else if args.files.is_empty() {
+        return Err(CliError::InvalidArgument(
+            "Give configuration files to search, or --local".to_string(),
+        ));
+    } else {
+        let query = ConfigQuery::new(&args.query, args.regex)?;
+        let mut results = Vec::new();
+        for file in &args.files {
+            let matches = query.matches(&read_config_file(file)?);
+            if !matches.is_empty() {
+                results.push(ConfigSearchResult {
+                    name: file.clone(),
+                    matches,
+                });
+            }
+        }
+        results
+    };
+
+    if json_output {
+        let output = serde_json::json!({
+            "query": args.query,
+            "results": results,
+            "count": results.len()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if results.is_empty() {
+        println!("No configurations match '{}'.", args.query);
+    } else {
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Config", "Parameter", "Value"]);
+        for result in &results {
+            for m in &result.matches {
+                table.add_row(vec![
+                    result.name.clone(),
+                    format!("{}.{}", m.group, m.name),
+                    m.value.clone(),
+                ]);
+            }
+        }
+        println!("{}\n\n{} configuration(s) match", table, results.len());
+    }
+
+    Ok(())
+}
+
+fn run_validate(file: &str, json_output: bool) -> Result<(), CliError> {
+    let config = read_config_file(file)?;
+    let issues = validate_config(&config);
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+
+    if json_output {
+        let output = serde_json::json!({
+            "file": file,
+            "valid": errors == 0,
+            "issues": issues
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if issues.is_empty() {
+        println!("{} is valid", file);
+    } else {
+        print_validation_report(&issues);
+    }
+
+    if errors > 0 {
+        return Err(CliError::Validation(format!(
+            "{} has {} validation error(s)",
+            file, errors
+        )));
+    }
+
+    Ok(())
+}
+
+pub(crate) fn print_validation_report(issues: &[ValidationIssue]) {
+    for issue in issues {
+        let label = if issue.is_error() {
+            "error".red().bold()
+        } else {
+            "warning".yellow()
+        };
+        println!("{}: {}", label, issue);
+    }
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    println!(
+        "\n{} error(s), {} warning(s)",
+        errors,
+        issues.len() - errors
+    );
+}
+
+fn read_config_file(file: &str) -> Result<DeviceConfig, CliError> {
+    let content = std::fs::read_to_string(file)
+        .map_err(|e| CliError::Other(format!("Failed to read config file {}: {}", file, e)))?;
+    Ok(serde_json::from_str(&content).map_err(ConfigError::ParseError)?)
+}
+
+async fn run_history(name: &str, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    let revisions = storage.list_revisions(name).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "name": name,
+            "revisions": revisions,
+            "count": revisions.len()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if revisions.is_empty() {
+        println!("No previous versions of '{}'.", name);
+    } else {
+        println!("Previous versions of '{}' (newest first):", name);
+        for revision in &revisions {
+            println!("  {}  saved {}", revision.timestamp, revision.updated_at);
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_restore(name: &str, timestamp: &str, json_output: bool) -> Result<(), CliError> {
+    let storage = create_config_storage()?;
+    storage.restore_revision(name, timestamp).await?;
+
+    if json_output {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "timestamp": timestamp
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!(
+            "Configuration '{}' restored to revision {}",
+            name, timestamp
+        );
+    }
+
+    Ok(())
+}
@@ -1,22 +1,443 @@
 //! Log streaming command.
 
-use std::io::{self, Write};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
 
+use clap::ValueEnum;
 use colored::*;
 use regex::Regex;
-use socket2::{Domain, Protocol, Socket, Type};
 use tokio::net::UdpSocket;
 
-use crate::cli::LogsArgs;
+use crate::cli::{LogsArgs, LogsCommands, RoleFilter};
+use crate::device::discovery::{discover_devices, DiscoveryOptions};
 use crate::error::CliError;
-use crate::types::{LogLevel, LogMessage};
-use rtls_link_core::protocol::binary::decode_log_message;
+use crate::output::get_formatter;
+use crate::types::{DeviceRole, LogLevel, LogMessage};
+use rtls_link_core::device::mavlink::{send_command, DeviceConnection};
+use rtls_link_core::discovery::service::create_exclusive_socket;
+use rtls_link_core::log_clock::WallClockOffset;
+use rtls_link_core::log_filter::TagLevelFilter;
+use rtls_link_core::log_packet::parse_log_packet;
+use rtls_link_core::log_stats::{LogStatsSnapshot, LogStatsTracker};
+use rtls_link_core::protocol::commands::Commands;
+
+/// Maximum repeats collapsed into one "(xN)" line before a new run starts
+const MAX_REPEAT_COUNT: u32 = 1000;
+
+/// Maximum lines waiting to be written to the --output file
+const OUTPUT_QUEUE_CAPACITY: usize = 4096;
+
+/// How often buffered --output lines are flushed to disk
+const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Listening time in --stats mode when --duration is not given
+const DEFAULT_STATS_DURATION_SECS: u64 = 30;
+
+/// Number of tags listed in the statistics summary
+const MAX_TOP_TAGS: usize = 20;
+
+/// How often `--filter-role` is re-resolved by discovery
+const ROLE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Number of alternate ports tried with --port-fallback
+const PORT_FALLBACK_ATTEMPTS: u16 = 10;
+
+/// Silence after which `logs follow` re-checks the device's log settings
+const FOLLOW_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Run of identical consecutive messages from one device
+///
+/// The first message is printed immediately; repeats are suppressed and
+/// summarized with an "(xN)" suffix once a different message arrives.
+#[derive(Default)]
+struct RepeatRun {
+    last: Option<LogMessage>,
+    wall_ts: Option<u64>,
+    count: u32,
+}
+
+impl RepeatRun {
+    /// Absorb `log` if it repeats the previous message, returning true if so
+    fn absorb(&mut self, log: &LogMessage, wall_ts: Option<u64>) -> bool {
+        let repeats = self.last.as_ref().is_some_and(|last| {
+            last.level == log.level && last.tag == log.tag && last.message == log.message
+        });
+        if repeats && self.count < MAX_REPEAT_COUNT {
+            self.count += 1;
+            self.wall_ts = wall_ts;
+            return true;
+        }
+        false
+    }
+
+    /// Print the summary of the current run (if it repeated) and start a new one
+    fn restart(
+        &mut self,
+        log: &LogMessage,
+        wall_ts: Option<u64>,
+        grep: &MessageGrep,
+        ids: &HashMap<String, String>,
+    ) {
+        self.finish(grep, ids);
+        self.last = Some(log.clone());
+        self.wall_ts = wall_ts;
+        self.count = 1;
+    }
+
+    /// Print the summary of the current run (if it repeated) and end it
+    fn finish(&mut self, grep: &MessageGrep, ids: &HashMap<String, String>) {
+        if let Some(last) = self.last.take() {
+            if self.count > 1 {
+                print_colored_log(&last, self.wall_ts, self.count, grep, ids);
+            }
+        }
+        self.count = 0;
+    }
+}
+
+/// Size-based rotation of the --output file
+struct Rotation {
+    max_size: u64,
+    max_files: usize,
+}
+
+/// NDJSON log file written by a background thread
+///
+/// Lines are queued without blocking the UDP receive loop; if the writer
+/// falls behind, lines are dropped and counted.
+struct LogFileWriter {
+    sender: SyncSender<String>,
+    dropped: u64,
+    thread: thread::JoinHandle<io::Result<u64>>,
+}
+
+impl LogFileWriter {
+    /// Open (or append to) `path` and start the writer thread
+    fn create(path: &Path, rotation: Option<Rotation>) -> io::Result<Self> {
+        let file = open_append(path)?;
+        let size = file.metadata()?.len();
+        let (sender, receiver) = mpsc::sync_channel(OUTPUT_QUEUE_CAPACITY);
+        let path = path.to_path_buf();
+        let thread = thread::spawn(move || write_lines(&path, file, size, rotation, receiver));
+
+        Ok(Self {
+            sender,
+            dropped: 0,
+            thread,
+        })
+    }
+
+    /// Queue a line without waiting, dropping it if the queue is full
+    fn write(&mut self, line: String) {
+        if self.sender.try_send(line).is_err() {
+            self.dropped += 1;
+        }
+    }
+
+    /// Flush queued lines and stop the writer, returning (written, dropped)
+    fn finish(self) -> io::Result<(u64, u64)> {
+        drop(self.sender);
+        let written = self
+            .thread
+            .join()
+            .map_err(|_| io::Error::other("log file writer panicked"))??;
+        Ok((written, self.dropped))
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+fn write_lines(
+    path: &Path,
+    file: File,
+    mut size: u64,
+    rotation: Option<Rotation>,
+    receiver: mpsc::Receiver<String>,
+) -> io::Result<u64> {
+    let mut writer = BufWriter::new(file);
+    let mut written = 0;
+    let mut last_flush = Instant::now();
+
+    loop {
+        match receiver.recv_timeout(OUTPUT_FLUSH_INTERVAL) {
+            Ok(line) => {
+                let line_len = line.len() as u64 + 1;
+                if let Some(rotation) = &rotation {
+                    if size > 0 && size + line_len > rotation.max_size {
+                        writer.flush()?;
+                        rotate_files(path, rotation.max_files)?;
+                        writer = BufWriter::new(open_append(path)?);
+                        size = 0;
+                    }
+                }
+                writeln!(writer, "{}", line)?;
+                size += line_len;
+                written += 1;
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        if last_flush.elapsed() >= OUTPUT_FLUSH_INTERVAL {
+            writer.flush()?;
+            last_flush = Instant::now();
+        }
+    }
+
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Move `path` to `path.1`, shifting older files up and deleting the oldest
+fn rotate_files(path: &Path, max_files: usize) -> io::Result<()> {
+    if max_files == 0 {
+        return fs::remove_file(path);
+    }
+
+    let rotated = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+
+    let oldest = rotated(max_files);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+    for n in (1..max_files).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            fs::rename(&from, rotated(n + 1))?;
+        }
+    }
+    fs::rename(path, rotated(1))
+}
+
+/// Message body regexes, applied after level and tag filters
+struct MessageGrep {
+    patterns: Vec<Regex>,
+    invert: bool,
+}
+
+impl MessageGrep {
+    fn from_args(args: &LogsArgs) -> Result<Self, CliError> {
+        let patterns = args
+            .grep
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    CliError::InvalidArgument(format!("Invalid --grep regex '{}': {}", pattern, e))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            patterns,
+            invert: args.grep_invert,
+        })
+    }
+
+    fn is_active(&self) -> bool {
+        !self.patterns.is_empty()
+    }
+
+    /// Whether any pattern matches the message
+    fn matches(&self, message: &str) -> bool {
+        self.patterns.iter().any(|re| re.is_match(message))
+    }
+
+    /// Whether the message passes the filter
+    fn accepts(&self, message: &str) -> bool {
+        !self.is_active() || self.matches(message) != self.invert
+    }
+
+    /// Render the message with every matching span in reverse video
+    fn highlight(&self, message: &str) -> String {
+        if self.invert {
+            return message.to_string();
+        }
+
+        let mut spans: Vec<(usize, usize)> = self
+            .patterns
+            .iter()
+            .flat_map(|re| re.find_iter(message))
+            .filter(|m| !m.is_empty())
+            .map(|m| (m.start(), m.end()))
+            .collect();
+        spans.sort_unstable();
+
+        let mut out = String::with_capacity(message.len());
+        let mut pos = 0;
+        for (start, end) in spans {
+            if end <= pos {
+                continue;
+            }
+            let start = start.max(pos);
+            out.push_str(&message[pos..start]);
+            out.push_str(&message[start..end].reversed().to_string());
+            pos = end;
+        }
+        out.push_str(&message[pos..]);
+        out
+    }
+}
+
+/// Source device selection, applied before level and tag filters
+struct DeviceFilter {
+    /// Only these IPs pass (None means all)
+    allow: Option<HashSet<String>>,
+    /// IPs of devices with the `--filter-role` role, refreshed periodically
+    role_ips: Option<Arc<RwLock<HashSet<String>>>>,
+    /// These IPs never pass
+    exclude: HashSet<String>,
+}
+
+impl DeviceFilter {
+    /// Build the filter from the arguments, resolving `--filter-role` by
+    /// discovery and refreshing it in the background
+    async fn from_args(args: &LogsArgs) -> Result<Self, CliError> {
+        let ips: HashSet<String> = args
+            .ip
+            .iter()
+            .chain(&args.ips)
+            .map(|ip| ip.trim().to_string())
+            .filter(|ip| !ip.is_empty())
+            .collect();
+
+        let role_ips = match &args.filter_role {
+            Some(role) => {
+                let name = role_name(role);
+                eprintln!("Discovering {} devices to filter logs...", name);
+                let resolved = discover_role_ips(role).await?;
+                if resolved.is_empty() {
+                    eprintln!(
+                        "No {} devices found yet; retrying every {}s",
+                        name,
+                        ROLE_REFRESH_INTERVAL.as_secs()
+                    );
+                } else {
+                    eprintln!("Resolved {} devices: {}", name, sorted_ips(&resolved));
+                }
+                let shared = Arc::new(RwLock::new(resolved));
+                tokio::spawn(refresh_role_ips(role.clone(), shared.clone()));
+                Some(shared)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            allow: (!ips.is_empty()).then_some(ips),
+            role_ips,
+            exclude: args
+                .exclude_ip
+                .iter()
+                .map(|ip| ip.trim().to_string())
+                .collect(),
+        })
+    }
+
+    fn accepts(&self, ip: &str) -> bool {
+        !self.exclude.contains(ip)
+            && self.allow.as_ref().is_none_or(|allow| allow.contains(ip))
+            && self
+                .role_ips
+                .as_ref()
+                .is_none_or(|role_ips| role_ips.read().unwrap().contains(ip))
+    }
+
+    /// Human-readable description for the startup banner
+    fn describe(&self) -> String {
+        let mut description = String::new();
+        if let Some(allow) = &self.allow {
+            description.push_str(&format!(" from {}", sorted_ips(allow)));
+        }
+        if self.role_ips.is_some() {
+            description.push_str(" (role filtered)");
+        }
+        if !self.exclude.is_empty() {
+            description.push_str(&format!(" excluding {}", sorted_ips(&self.exclude)));
+        }
+        description
+    }
+}
+
+fn sorted_ips(ips: &HashSet<String>) -> String {
+    let mut ips: Vec<&str> = ips.iter().map(String::as_str).collect();
+    ips.sort();
+    ips.join(", ")
+}
+
+fn role_name(role: &RoleFilter) -> String {
+    role.to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+async fn discover_role_ips(role: &RoleFilter) -> Result<HashSet<String>, CliError> {
+    Ok(discover_devices(DiscoveryOptions::default())
+        .await?
+        .into_iter()
+        .filter(|device| role_matches(role, &device.role))
+        .map(|device| device.ip)
+        .collect())
+}
+
+/// Re-run discovery periodically so devices that change IP keep flowing
+async fn refresh_role_ips(role: RoleFilter, role_ips: Arc<RwLock<HashSet<String>>>) {
+    loop {
+        tokio::time::sleep(ROLE_REFRESH_INTERVAL).await;
+
+        // Keep the previous mapping if a pass fails or sees no devices
+        let resolved = match discover_role_ips(&role).await {
+            Ok(resolved) if !resolved.is_empty() => resolved,
+            _ => continue,
+        };
+        let mut current = role_ips.write().unwrap();
+        if *current != resolved {
+            eprintln!(
+                "Updated {} devices: {}",
+                role_name(&role),
+                sorted_ips(&resolved)
+            );
+            *current = resolved;
+        }
+    }
+}
+
+fn role_matches(filter: &RoleFilter, role: &DeviceRole) -> bool {
+    match filter {
+        RoleFilter::AnchorTdoa => *role == DeviceRole::AnchorTdoa,
+        RoleFilter::TagTdoa => *role == DeviceRole::TagTdoa,
+    }
+}
 
 /// Run the logs command
-pub async fn run_logs(args: LogsArgs, json: bool) -> Result<(), CliError> {
+pub async fn run_logs(mut args: LogsArgs, timeout: u64, json: bool) -> Result<(), CliError> {
+    if let Some(LogsCommands::Follow(follow)) = &args.command {
+        if args.ip.is_some() {
+            return Err(CliError::InvalidArgument(
+                "Pass the device IP to `logs follow` only".to_string(),
+            ));
+        }
+        args.ip = Some(follow.ip.clone());
+    }
+
     let min_level = LogLevel::from_str(&args.level)
         .ok_or_else(|| CliError::InvalidArgument(format!("Invalid log level: {}", args.level)))?;
+    let mut level_filter = TagLevelFilter::new(min_level);
+    for rule in &args.tag_level {
+        let (pattern, level) = TagLevelFilter::parse_rule(rule)
+            .map_err(|e| CliError::InvalidArgument(format!("Invalid --tag-level: {}", e)))?;
+        level_filter = level_filter.with_override(pattern, level);
+    }
 
     let tag_pattern = args
         .tag
@@ -27,36 +448,132 @@ pub async fn run_logs(args: LogsArgs, json: bool) -> Result<(), CliError> {
         })
         .flatten();
 
-    let socket = create_log_socket(args.port)?;
-    let socket = UdpSocket::from_std(socket.into())?;
+    let grep = MessageGrep::from_args(&args)?;
+    let devices = DeviceFilter::from_args(&args).await?;
+    let ids = if args.resolve_ids {
+        resolve_device_ids().await?
+    } else {
+        HashMap::new()
+    };
+
+    let (socket, port) = bind_log_socket(&args)?;
+    let socket = UdpSocket::from_std(socket)?;
+    let timeout = Duration::from_millis(timeout);
+    let session = match &args.command {
+        Some(LogsCommands::Follow(follow)) => {
+            Some(FollowSession::start(&follow.ip, port, follow.restore_on_exit, timeout).await?)
+        }
+        None => None,
+    };
+    if port != args.port && args.auto_retarget && session.is_none() {
+        retarget_devices(args.port, port, &devices, timeout).await?;
+    }
 
-    println!(
-        "Listening for logs on port {} (level >= {}){}",
-        args.port,
-        min_level,
-        if args.ip.is_some() {
-            format!(" from {}", args.ip.as_ref().unwrap())
-        } else {
-            String::new()
+    if args.stats {
+        let result =
+            collect_stats(&socket, &args, &devices, tag_pattern.as_ref(), &grep, json).await;
+        if let Some(session) = session {
+            session.finish().await;
         }
-    );
+        return result;
+    }
+
+    if args.raw {
+        println!("Dumping raw packets on port {}{}", port, devices.describe());
+    } else {
+        println!(
+            "Listening for logs on port {} (level >= {}){}",
+            port,
+            min_level,
+            devices.describe()
+        );
+    }
+    if !args.raw && !level_filter.overrides().is_empty() {
+        let rules: Vec<String> = level_filter
+            .overrides()
+            .iter()
+            .map(|(pattern, level)| format!("{}={}", pattern, level))
+            .collect();
+        println!("Tag level overrides: {}", rules.join(", "));
+    }
+    let mut output = match &args.output {
+        Some(path) => {
+            let rotation = args.max_size.map(|max_size| Rotation {
+                max_size,
+                max_files: args.max_files,
+            });
+            let writer = LogFileWriter::create(Path::new(path), rotation).map_err(|e| {
+                CliError::Other(format!("Failed to open output file {}: {}", path, e))
+            })?;
+            println!("Writing logs to {}", path);
+            Some(writer)
+        }
+        None => None,
+    };
     println!("Press Ctrl+C to stop.\n");
 
-    let mut buf = vec![0u8; 4096];
+    let mut buf = vec![0u8; 65_507];
+    let mut clocks: HashMap<String, WallClockOffset> = HashMap::new();
+    let mut runs: HashMap<String, RepeatRun> = HashMap::new();
+    let mut trackers: BTreeMap<String, LogStatsTracker> = BTreeMap::new();
+    let mut tag_counts: HashMap<String, u64> = HashMap::new();
+    let mut received: u64 = 0;
+    let deadline = args
+        .duration
+        .map(|secs| tokio::time::Instant::now() + Duration::from_secs(secs));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut follow_check = session.as_ref().map(|_| {
+        tokio::time::interval_at(
+            tokio::time::Instant::now() + FOLLOW_CHECK_INTERVAL,
+            FOLLOW_CHECK_INTERVAL,
+        )
+    });
+    let mut last_packet = Instant::now();
 
-    loop {
-        let (len, addr) = socket.recv_from(&mut buf).await?;
+    'receive: loop {
+        let (len, addr) = tokio::select! {
+            result = socket.recv_from(&mut buf) => result?,
+            _ = &mut ctrl_c => break,
+            _ = sleep_until(deadline) => break,
+            _ = tick(follow_check.as_mut()) => {
+                if let Some(session) = &session {
+                    if last_packet.elapsed() >= FOLLOW_CHECK_INTERVAL {
+                        session.reapply().await;
+                    }
+                }
+                continue;
+            }
+        };
+        let received_at = now_ms();
 
         let ip = addr.ip().to_string();
 
-        if let Some(ref filter_ip) = args.ip {
-            if &ip != filter_ip {
-                continue;
+        if !devices.accepts(&ip) {
+            continue;
+        }
+        last_packet = Instant::now();
+
+        if args.raw {
+            print_raw_packet(&buf[..len], addr, received_at, args.ndjson || json);
+            received += 1;
+            if args.count.is_some_and(|count| received >= count) {
+                break;
             }
+            continue;
         }
 
-        if let Ok(log_msg) = parse_log_message(&buf[..len], &ip) {
-            if (log_msg.level as u8) > (min_level as u8) {
+        for log_msg in parse_packet(&buf[..len], &ip) {
+            // Align before filtering so every message refines the offset
+            let wall_ts = if args.wallclock {
+                log_msg
+                    .timestamp
+                    .map(|ts| clocks.entry(ip.clone()).or_default().observe(ts, now_ms()))
+            } else {
+                None
+            };
+
+            if !level_filter.allows(level_of(&log_msg), &log_msg.tag) {
                 continue;
             }
 
@@ -66,69 +583,542 @@ pub async fn run_logs(args: LogsArgs, json: bool) -> Result<(), CliError> {
                 }
             }
 
+            if !grep.accepts(&log_msg.message) {
+                continue;
+            }
+            let matched = grep.is_active().then(|| grep.matches(&log_msg.message));
+
+            received += 1;
+            trackers
+                .entry(ip.clone())
+                .or_default()
+                .record(level_of(&log_msg), now_ms());
+            *tag_counts.entry(log_msg.tag.clone()).or_default() += 1;
+
+            let id = ids.get(&ip).map(String::as_str);
+            if let Some(writer) = output.as_mut() {
+                let record = log_to_json(&log_msg, received_at, wall_ts, matched, id);
+                writer.write(record.to_string());
+            }
+
             if args.ndjson || json {
-                let output = serde_json::json!({
-                    "ip": log_msg.ip,
-                    "level": log_msg.level.as_str().to_lowercase(),
-                    "tag": log_msg.tag,
-                    "message": log_msg.message,
-                    "timestamp": log_msg.timestamp
-                });
-                println!("{}", serde_json::to_string(&output).unwrap());
+                println!(
+                    "{}",
+                    log_to_json(&log_msg, received_at, wall_ts, matched, id)
+                );
             } else {
-                print_colored_log(&log_msg);
+                let run = runs.entry(ip.clone()).or_default();
+                if !run.absorb(&log_msg, wall_ts) {
+                    run.restart(&log_msg, wall_ts, &grep, &ids);
+                    print_colored_log(&log_msg, wall_ts, 1, &grep, &ids);
+                }
             }
 
             io::stdout().flush().ok();
+
+            if args.count.is_some_and(|count| received >= count) {
+                break 'receive;
+            }
         }
     }
+
+    // Runs still repeating when the capture stopped were never summarized
+    let mut pending: Vec<_> = runs.into_iter().collect();
+    pending.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (_, mut run) in pending {
+        run.finish(&grep, &ids);
+    }
+    io::stdout().flush().ok();
+
+    if let Some(session) = session {
+        session.finish().await;
+    }
+
+    if let (Some(writer), Some(path)) = (output, &args.output) {
+        let (written, dropped) = writer.finish()?;
+        eprintln!(
+            "\nWrote {} log lines to {}{}",
+            written,
+            path,
+            if dropped > 0 {
+                format!(" ({} dropped, writer fell behind)", dropped)
+            } else {
+                " (none dropped)".to_string()
+            }
+        );
+    }
+
+    // Scripted captures get a summary and can detect a dead log pipeline
+    if args.duration.is_some() || args.count.is_some() {
+        if received == 0 {
+            return Err(CliError::NoLogMessages);
+        }
+        if !args.raw {
+            eprintln!(
+                "\n{}",
+                get_formatter(false)
+                    .format_log_stats(&device_stats(trackers), &top_tags(tag_counts))
+            );
+        }
+    }
+
+    Ok(())
 }
 
-fn create_log_socket(port: u16) -> Result<std::net::UdpSocket, std::io::Error> {
-    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+/// Wait until the deadline, or forever if there is none
+async fn sleep_until(deadline: Option<tokio::time::Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
 
-    socket.set_reuse_address(true)?;
+/// Wait for the next tick, or forever if there is no interval
+async fn tick(interval: Option<&mut tokio::time::Interval>) {
+    match interval {
+        Some(interval) => {
+            interval.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
 
-    #[cfg(unix)]
-    socket.set_reuse_port(true)?;
+/// Log settings enabled on a device by `logs follow`
+///
+/// Changes are not saved to flash, so a reboot reverts them; the receive
+/// loop re-applies them when the device goes quiet.
+struct FollowSession {
+    ip: String,
+    timeout: Duration,
+    /// Wanted value for each wifi parameter
+    wanted: Vec<(&'static str, String)>,
+    /// Values the device had before they were changed
+    previous: Vec<(&'static str, String)>,
+    restore_on_exit: bool,
+}
+
+impl FollowSession {
+    /// Enable UDP logging to `port`, recording the settings it replaces
+    async fn start(
+        ip: &str,
+        port: u16,
+        restore_on_exit: bool,
+        timeout: Duration,
+    ) -> Result<Self, CliError> {
+        let mut session = Self {
+            ip: ip.to_string(),
+            timeout,
+            wanted: vec![
+                ("logUdpEnabled", "1".to_string()),
+                ("logUdpPort", port.to_string()),
+            ],
+            previous: Vec::new(),
+            restore_on_exit,
+        };
+
+        session.previous = session.apply().await?;
+        if session.previous.is_empty() {
+            eprintln!("{}: UDP logging already enabled on port {}", ip, port);
+        }
+        for (name, value) in &session.previous {
+            eprintln!(
+                "{}: wifi.{} {} -> {}",
+                ip,
+                name,
+                value,
+                session.wanted_value(name)
+            );
+        }
+        Ok(session)
+    }
+
+    fn wanted_value(&self, name: &str) -> &str {
+        self.wanted
+            .iter()
+            .find(|(wanted, _)| *wanted == name)
+            .map_or("", |(_, value)| value)
+    }
+
+    /// Write every wanted parameter that differs, returning the old values
+    async fn apply(&self) -> Result<Vec<(&'static str, String)>, CliError> {
+        let mut connection = DeviceConnection::connect(&self.ip, self.timeout).await?;
+        let mut changed = Vec::new();
+        for (name, value) in &self.wanted {
+            let current = connection
+                .send_raw(&Commands::read_param("wifi", name)?)
+                .await?;
+            let current = current.trim();
+            if param_equals(current, value) {
+                continue;
+            }
+            connection
+                .send_raw(&Commands::write_param("wifi", name, value)?)
+                .await?;
+            changed.push((*name, current.to_string()));
+        }
+        Ok(changed)
+    }
+
+    /// Re-apply the settings if the device lost them, e.g. after a reboot
+    ///
+    /// Errors are ignored since a rebooting device is briefly unreachable;
+    /// the next check retries.
+    async fn reapply(&self) {
+        if let Ok(changed) = self.apply().await {
+            if !changed.is_empty() {
+                eprintln!(
+                    "{}: log settings were reset (device rebooted?), re-enabled UDP logging",
+                    self.ip
+                );
+            }
+        }
+    }
+
+    /// Restore the previous settings if --restore-on-exit was given
+    async fn finish(self) {
+        if self.previous.is_empty() {
+            return;
+        }
+        if !self.restore_on_exit {
+            eprintln!(
+                "{}: leaving UDP logging enabled until the next reboot (use --restore-on-exit to undo)",
+                self.ip
+            );
+            return;
+        }
 
-    let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().unwrap();
-    socket.bind(&addr.into())?;
+        let result = async {
+            let mut connection = DeviceConnection::connect(&self.ip, self.timeout).await?;
+            for (name, value) in &self.previous {
+                connection
+                    .send_raw(&Commands::write_param("wifi", name, value)?)
+                    .await?;
+            }
+            Ok::<_, CliError>(())
+        }
+        .await;
+        match result {
+            Ok(()) => eprintln!("{}: restored previous log settings", self.ip),
+            Err(e) => eprintln!("{}: failed to restore log settings: {}", self.ip, e),
+        }
+    }
+}
 
-    socket.set_nonblocking(true)?;
+/// Compare parameter values, treating `true`/`1` and `1.0`/`1` as equal
+fn param_equals(current: &str, wanted: &str) -> bool {
+    fn normalize(value: &str) -> String {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "true" => "1".to_string(),
+            "false" => "0".to_string(),
+            other => other
+                .parse::<f64>()
+                .map_or_else(|_| other.to_string(), |n| n.to_string()),
+        }
+    }
+    normalize(current) == normalize(wanted)
+}
 
-    Ok(socket.into())
+/// Map device IPs to device ids with a one-shot discovery pass
+async fn resolve_device_ids() -> Result<HashMap<String, String>, CliError> {
+    eprintln!("Discovering devices to resolve ids...");
+    let ids: HashMap<String, String> = discover_devices(DiscoveryOptions::default())
+        .await?
+        .into_iter()
+        .filter(|device| !device.id.is_empty())
+        .map(|device| (device.ip, device.id))
+        .collect();
+    eprintln!("Resolved {} device id(s)", ids.len());
+    Ok(ids)
 }
 
-fn parse_log_message(data: &[u8], ip: &str) -> Result<LogMessage, String> {
-    if let Ok(log) = decode_log_message(data, ip) {
-        return Ok(log);
+/// NDJSON record for a log message
+///
+/// `received_at` is the local receive time (unix ms), `matched` is set
+/// when a --grep filter is active and `id` when the device id is known.
+fn log_to_json(
+    log_msg: &LogMessage,
+    received_at: u64,
+    wall_ts: Option<u64>,
+    matched: Option<bool>,
+    id: Option<&str>,
+) -> serde_json::Value {
+    let mut record = serde_json::json!({
+        "ip": log_msg.ip,
+        "level": log_msg.level.as_str(),
+        "tag": log_msg.tag,
+        "message": log_msg.message,
+        "timestamp": log_msg.timestamp,
+        "received_at": received_at
+    });
+    if let Some(id) = id {
+        record["id"] = id.into();
     }
+    if let Some(wall_ts) = wall_ts {
+        record["wall_ts"] = wall_ts.into();
+    }
+    if let Some(matched) = matched {
+        record["matched"] = matched.into();
+    }
+    record
+}
 
-    let json: serde_json::Value = serde_json::from_slice(data).map_err(|e| e.to_string())?;
+/// Listen for `args.duration` seconds (or until `args.count` messages)
+/// and print per-device level statistics
+async fn collect_stats(
+    socket: &UdpSocket,
+    args: &LogsArgs,
+    devices: &DeviceFilter,
+    tag_pattern: Option<&Regex>,
+    grep: &MessageGrep,
+    json: bool,
+) -> Result<(), CliError> {
+    let duration = args.duration.unwrap_or(DEFAULT_STATS_DURATION_SECS);
+    eprintln!(
+        "Collecting log statistics on port {} for {}s{}...",
+        socket.local_addr()?.port(),
+        duration,
+        devices.describe()
+    );
 
-    let level = json["level"]
-        .as_u64()
-        .map(|value| LogLevel::from_u8(value as u8))
-        .or_else(|| json["lvl"].as_str().and_then(LogLevel::from_str))
-        .unwrap_or(LogLevel::Info);
+    let mut trackers: BTreeMap<String, LogStatsTracker> = BTreeMap::new();
+    let mut tag_counts: HashMap<String, u64> = HashMap::new();
+    let mut received: u64 = 0;
+    let mut buf = vec![0u8; 65_507];
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(duration);
 
-    Ok(LogMessage {
-        ip: ip.to_string(),
-        level,
-        tag: json["tag"].as_str().unwrap_or("").to_string(),
-        message: json["msg"]
-            .as_str()
-            .or_else(|| json["message"].as_str())
-            .unwrap_or("")
-            .to_string(),
-        timestamp: json["ts"].as_u64().or_else(|| json["timestamp"].as_u64()),
-    })
+    'receive: loop {
+        let (len, addr) = match tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await
+        {
+            Ok(result) => result?,
+            Err(_) => break,
+        };
+
+        let ip = addr.ip().to_string();
+        if !devices.accepts(&ip) {
+            continue;
+        }
+
+        for log_msg in parse_packet(&buf[..len], &ip) {
+            if tag_pattern.is_some_and(|pattern| !pattern.is_match(&log_msg.tag))
+                || !grep.accepts(&log_msg.message)
+            {
+                continue;
+            }
+            trackers
+                .entry(ip.clone())
+                .or_default()
+                .record(level_of(&log_msg), now_ms());
+            *tag_counts.entry(log_msg.tag).or_default() += 1;
+
+            received += 1;
+            if args.count.is_some_and(|count| received >= count) {
+                break 'receive;
+            }
+        }
+    }
+
+    if received == 0 {
+        return Err(CliError::NoLogMessages);
+    }
+
+    println!(
+        "{}",
+        get_formatter(json || args.ndjson)
+            .format_log_stats(&device_stats(trackers), &top_tags(tag_counts))
+    );
+
+    Ok(())
+}
+
+/// Snapshot every device, most errors (then warnings) first
+fn device_stats(trackers: BTreeMap<String, LogStatsTracker>) -> Vec<(String, LogStatsSnapshot)> {
+    let now = now_ms();
+    let mut stats: Vec<_> = trackers
+        .into_iter()
+        .map(|(ip, tracker)| (ip, tracker.snapshot(now)))
+        .collect();
+    stats.sort_by(|(a_ip, a), (b_ip, b)| {
+        (b.totals.error, b.totals.warn)
+            .cmp(&(a.totals.error, a.totals.warn))
+            .then_with(|| a_ip.cmp(b_ip))
+    });
+    stats
+}
+
+/// The noisiest tags by message count
+fn top_tags(counts: HashMap<String, u64>) -> Vec<(String, u64)> {
+    let mut tags: Vec<(String, u64)> = counts.into_iter().collect();
+    tags.sort_by(|(a_tag, a), (b_tag, b)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+    tags.truncate(MAX_TOP_TAGS);
+    tags
+}
+
+fn now_ms() -> u64 {
+    chrono::Utc::now().timestamp_millis().max(0) as u64
+}
+
+/// Bind the log socket, explaining a port conflict or, with
+/// --port-fallback, moving to the next free port
+fn bind_log_socket(args: &LogsArgs) -> Result<(std::net::UdpSocket, u16), CliError> {
+    match create_exclusive_socket(args.port) {
+        Ok(socket) => return Ok((socket, args.port)),
+        Err(e) if !is_port_in_use(&e) => return Err(e.into()),
+        Err(_) if !args.port_fallback => {
+            return Err(CliError::Other(format!(
+                "UDP port {} is already in use, most likely by the RTLS Link Manager \
+                 desktop app, which receives device logs on this port. Close the app \
+                 or rerun with --port-fallback to listen on another port.",
+                args.port
+            )));
+        }
+        Err(_) => {}
+    }
+
+    for port in (args.port.saturating_add(1)..=u16::MAX).take(PORT_FALLBACK_ATTEMPTS as usize) {
+        match create_exclusive_socket(port) {
+            Ok(socket) => {
+                eprintln!(
+                    "UDP port {} is in use; listening on port {} instead.",
+                    args.port, port
+                );
+                if !args.auto_retarget && args.command.is_none() {
+                    eprintln!(
+                        "Devices still send logs to port {}. Point them at port {} with \
+                         `rtls-link-cli config write <ip> -g wifi -n logUdpPort -d {}` \
+                         or rerun with --auto-retarget.",
+                        args.port, port, port
+                    );
+                }
+                return Ok((socket, port));
+            }
+            Err(e) if is_port_in_use(&e) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    Err(CliError::Other(format!(
+        "UDP port {} and the next {} ports are all in use",
+        args.port, PORT_FALLBACK_ATTEMPTS
+    )))
 }
 
-fn print_colored_log(log: &LogMessage) {
-    let level_str = format!("{:>7}", log.level.as_str());
-    let level_colored = match log.level {
+/// Windows reports a port bound with SO_EXCLUSIVEADDRUSE as WSAEACCES
+/// rather than in use
+fn is_port_in_use(error: &io::Error) -> bool {
+    const WSAEACCES: i32 = 10013;
+    error.kind() == io::ErrorKind::AddrInUse
+        || (cfg!(windows) && error.raw_os_error() == Some(WSAEACCES))
+}
+
+/// Point the logUdpPort of discovered devices sending to `from_port` at
+/// `to_port`, without saving to flash
+async fn retarget_devices(
+    from_port: u16,
+    to_port: u16,
+    devices: &DeviceFilter,
+    timeout: Duration,
+) -> Result<(), CliError> {
+    eprintln!("Discovering devices to retarget their logs...");
+    let targets: Vec<_> = discover_devices(DiscoveryOptions::default())
+        .await?
+        .into_iter()
+        .filter(|device| devices.accepts(&device.ip))
+        .filter(|device| device.log_udp_port.is_none_or(|port| port == from_port))
+        .collect();
+
+    let cmd = Commands::write_param("wifi", "logUdpPort", &to_port.to_string())?;
+    for device in &targets {
+        match send_command(&device.ip, &cmd, timeout).await {
+            Ok(_) => eprintln!("  {} ({}): logUdpPort = {}", device.ip, device.id, to_port),
+            Err(e) => eprintln!("  {} ({}): failed to retarget: {}", device.ip, device.id, e),
+        }
+    }
+    eprintln!(
+        "Retargeted {} device(s); the change is not saved and reverts on reboot.",
+        targets.len()
+    );
+    Ok(())
+}
+
+/// Every message in a packet, skipping unparseable packets and entries
+fn parse_packet(data: &[u8], ip: &str) -> Vec<LogMessage> {
+    parse_log_packet(data, ip)
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+/// Level used for filtering and statistics; unrecognized levels count as INFO
+fn level_of(log: &LogMessage) -> LogLevel {
+    log.level.level().unwrap_or(LogLevel::Info)
+}
+
+/// Print a received datagram as a hex+ASCII dump (or NDJSON record)
+fn print_raw_packet(data: &[u8], addr: SocketAddr, received_at: u64, json: bool) {
+    if json {
+        let hex: String = data.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let record = serde_json::json!({
+            "ip": addr.ip().to_string(),
+            "port": addr.port(),
+            "len": data.len(),
+            "hex": hex,
+            "received_at": received_at
+        });
+        println!("{}", record);
+    } else {
+        let time = chrono::DateTime::from_timestamp_millis(received_at as i64)
+            .map(|time| {
+                time.with_timezone(&chrono::Local)
+                    .format("%H:%M:%S%.3f")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        println!(
+            "{} {} ({} bytes)",
+            time.dimmed(),
+            addr.to_string().cyan(),
+            data.len()
+        );
+        print!("{}", hex_dump(data));
+    }
+    io::stdout().flush().ok();
+}
+
+/// Format bytes as 16-byte rows of offset, hex and printable ASCII
+fn hex_dump(data: &[u8]) -> String {
+    let mut out = String::new();
+    for (row, chunk) in data.chunks(16).enumerate() {
+        let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "  {:04x}  {:<47}  |{}|\n",
+            row * 16,
+            hex.join(" "),
+            ascii
+        ));
+    }
+    out
+}
+
+fn print_colored_log(
+    log: &LogMessage,
+    wall_ts: Option<u64>,
+    repeat_count: u32,
+    grep: &MessageGrep,
+    ids: &HashMap<String, String>,
+) {
+    let level_str = format!("{:>7}", log.level.as_str().to_uppercase());
+    let level_colored = match level_of(log) {
         LogLevel::Error => level_str.red().bold(),
         LogLevel::Warn => level_str.yellow(),
         LogLevel::Info => level_str.green(),
@@ -137,14 +1127,58 @@ fn print_colored_log(log: &LogMessage) {
         LogLevel::None => level_str.normal(),
     };
 
-    let ip_str = format!("{:>15}", log.ip);
+    let source = ids.get(&log.ip).unwrap_or(&log.ip);
+    let ip_str = format!("{:>15}", source);
     let tag_str = format!("[{}]", log.tag).cyan();
 
+    if let Some(time) = wall_ts
+        .and_then(|ms| chrono::DateTime::from_timestamp_millis(ms as i64))
+        .map(|time| time.with_timezone(&chrono::Local))
+    {
+        print!("{} ", time.format("%H:%M:%S%.3f").to_string().dimmed());
+    }
+
+    let repeat_str = if repeat_count > 1 {
+        format!(" (x{})", repeat_count).bold().to_string()
+    } else {
+        String::new()
+    };
+
     println!(
-        "{} {} {} {}",
+        "{} {} {} {}{}",
         ip_str.dimmed(),
         level_colored,
         tag_str,
-        log.message
+        grep.highlight(&log.message),
+        repeat_str
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands as CliCommands};
+    use clap::Parser;
+
+    fn logs_args(port: u16, extra: &[&str]) -> LogsArgs {
+        let port = port.to_string();
+        let argv = ["rtls-link-cli", "logs", "--port", port.as_str()];
+        let cli = Cli::try_parse_from(argv.iter().chain(extra)).unwrap();
+        match cli.command {
+            CliCommands::Logs(args) => args,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_bind_log_socket_reports_a_taken_port() {
+        let (first, _) = bind_log_socket(&logs_args(0, &[])).unwrap();
+        let port = first.local_addr().unwrap().port();
+
+        let err = bind_log_socket(&logs_args(port, &[])).unwrap_err();
+        assert!(err.to_string().contains("already in use"), "{err}");
+
+        let (_, fallback) = bind_log_socket(&logs_args(port, &["--port-fallback"])).unwrap();
+        assert_ne!(fallback, port);
+    }
+}
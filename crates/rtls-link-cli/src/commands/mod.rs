@@ -1,6 +1,7 @@
 //! Command implementations.
 
 pub mod anchor_telemetry;
+pub mod backup_data;
 pub mod bulk;
 pub mod calibrate;
 pub mod cmd;
@@ -12,6 +13,7 @@ pub mod preset;
 pub mod status;
 
 pub use anchor_telemetry::run_anchor_telemetry;
+pub use backup_data::run_backup_data;
 pub use bulk::run_bulk;
 pub use calibrate::run_calibrate;
 pub use cmd::run_cmd;
@@ -4,6 +4,7 @@ use std::path::Path;
 use std::time::Duration;
 
 use indicatif::{ProgressBar, ProgressStyle};
+use tokio::time::Instant;
 
 use crate::cli::{OtaArgs, OtaCommands, RoleFilter};
 use crate::device::discovery::{discover_devices, DiscoveryOptions, DISCOVERY_PORT};
@@ -11,9 +12,18 @@ use crate::error::CliError;
 use crate::output::get_formatter;
 use crate::types::{Device, DeviceRole};
 
+use rtls_link_core::device::mavlink::DeviceConnection;
 use rtls_link_core::device::ota::{
-    upload_firmware_bulk, upload_firmware_with_progress, OtaProgressHandler,
+    self, upload_firmware_bulk, upload_firmware_with_progress, OtaEndpoint, OtaProgressHandler,
 };
+use rtls_link_core::device::probe::DEFAULT_PROBE_TIMEOUT;
+
+/// Timeout of one firmware-info request
+const INFO_TIMEOUT: Duration = Duration::from_secs(2);
+/// Time a device is given to reboot into the new firmware
+const REBOOT_TIMEOUT: Duration = Duration::from_secs(45);
+/// Wait after an upload so the device is not asked before it restarts
+const REBOOT_GRACE: Duration = Duration::from_secs(3);
 
 /// CLI progress handler using indicatif
 struct CliProgress;
@@ -53,11 +63,14 @@ impl OtaProgressHandler for CliProgressBar {
 pub async fn run_ota(args: OtaArgs, json: bool, strict: bool) -> Result<(), CliError> {
     match args.command {
         OtaCommands::Update(args) => {
+            let endpoint = args.endpoint();
             run_update(
                 &args.target,
                 &args.firmware,
                 args.filter_role,
                 args.concurrency,
+                &endpoint,
+                args.skip_unreachable,
                 json,
                 strict,
             )
@@ -66,11 +79,14 @@ pub async fn run_ota(args: OtaArgs, json: bool, strict: bool) -> Result<(), CliE
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn run_update(
     target: &str,
     firmware: &str,
     filter_role: Option<RoleFilter>,
     concurrency: usize,
+    endpoint: &OtaEndpoint,
+    skip_unreachable: bool,
     json: bool,
     strict: bool,
 ) -> Result<(), CliError> {
@@ -120,7 +136,30 @@ async fn run_update(
         .unwrap_or("firmware.bin")
         .to_string();
 
-    if ips.len() == 1 && !json {
+    // Decide before probing, so skipped devices are still reported
+    let bulk = ips.len() > 1 || json;
+    let (ips, skipped) = if skip_unreachable {
+        let endpoints = ips
+            .iter()
+            .map(|ip| (ip.clone(), endpoint.clone()))
+            .collect();
+        ota::skip_unreachable(&ips, &endpoints, DEFAULT_PROBE_TIMEOUT).await
+    } else {
+        (ips, Vec::new())
+    };
+    if !bulk && !skipped.is_empty() {
+        return Err(CliError::Other(format!(
+            "Skipped: {} is unreachable",
+            skipped[0].0
+        )));
+    }
+
+    let mut versions_before = Vec::with_capacity(ips.len());
+    for ip in &ips {
+        versions_before.push(version_before_update(ip).await);
+    }
+
+    if !bulk {
         // Single device with progress bar
         let ip = &ips[0];
         let file_size = firmware_data.len() as u64;
@@ -137,7 +176,8 @@ async fn run_update(
         let progress = CliProgressBar {
             progress_bar: pb.clone(),
         };
-        let result = upload_firmware_with_progress(ip, firmware_data, &file_name, &progress).await;
+        let result =
+            upload_firmware_with_progress(ip, endpoint, firmware_data, &file_name, &progress).await;
 
         if result.is_ok() {
             pb.finish_with_message(format!("Upload to {} complete", ip));
@@ -146,26 +186,56 @@ async fn run_update(
         }
 
         result?;
-        println!("Firmware upload complete. Device will reboot.");
+        println!("Firmware upload complete. Waiting for the device to reboot...");
+
+        tokio::time::sleep(REBOOT_GRACE).await;
+        let after = version_after_reboot(ip, Instant::now() + REBOOT_TIMEOUT).await;
+        println!(
+            "Firmware version: {}",
+            version_change(versions_before[0].as_deref(), after.as_deref())
+        );
     } else {
         // Bulk upload
         println!("Uploading firmware to {} device(s)...", ips.len());
 
         let progress = CliProgress;
-        let results =
-            upload_firmware_bulk(&ips, firmware_data, &file_name, concurrency, &progress).await;
+        let results = upload_firmware_bulk(
+            &ips,
+            endpoint,
+            firmware_data,
+            &file_name,
+            concurrency,
+            &progress,
+        )
+        .await;
 
-        let formatted_results: Vec<(String, bool, String)> = results
-            .into_iter()
-            .map(|(ip, result)| {
-                let success = result.is_ok();
-                let message = match result {
-                    Ok(_) => "Firmware uploaded".to_string(),
-                    Err(e) => e.to_string(),
-                };
-                (ip, success, message)
-            })
-            .collect();
+        if results.iter().any(|(_, result)| result.is_ok()) {
+            tokio::time::sleep(REBOOT_GRACE).await;
+        }
+        let deadline = Instant::now() + REBOOT_TIMEOUT;
+        let mut formatted_results: Vec<(String, bool, String)> = Vec::with_capacity(results.len());
+        for (ip, result) in results {
+            let success = result.is_ok();
+            let message = match result {
+                Ok(_) => {
+                    let before = ips
+                        .iter()
+                        .position(|target| *target == ip)
+                        .and_then(|index| versions_before[index].clone());
+                    let after = version_after_reboot(&ip, deadline).await;
+                    format!(
+                        "Firmware uploaded ({})",
+                        version_change(before.as_deref(), after.as_deref())
+                    )
+                }
+                Err(e) => e.to_string(),
+            };
+            formatted_results.push((ip, success, message));
+        }
+        for (ip, _) in skipped {
+            let message = format!("Skipped: {} is unreachable", ip);
+            formatted_results.push((ip, false, message));
+        }
 
         println!("{}", formatter.format_bulk_results(&formatted_results));
 
@@ -181,6 +251,46 @@ async fn run_update(
     Ok(())
 }
 
+/// The firmware version `ip` reports, or `None` if it does not answer
+async fn firmware_version(ip: &str) -> Option<String> {
+    let mut conn = DeviceConnection::connect(ip, INFO_TIMEOUT).await.ok()?;
+    conn.firmware_info().await.ok()?.version
+}
+
+/// Like [`firmware_version`], but rides out a dropped reply, since a missing
+/// "before" version makes the summary less useful
+async fn version_before_update(ip: &str) -> Option<String> {
+    let mut conn = DeviceConnection::connect_resilient(ip, INFO_TIMEOUT)
+        .await
+        .ok()?;
+    conn.firmware_info().await.ok()?.version
+}
+
+/// Poll `ip` until it answers after an update reboot or `deadline` passes
+async fn version_after_reboot(ip: &str, deadline: Instant) -> Option<String> {
+    loop {
+        if let Some(version) = firmware_version(ip).await {
+            return Some(version);
+        }
+        if Instant::now() >= deadline {
+            return None;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Describe the version change of an update, e.g. "2.3.0 -> 2.3.1"
+fn version_change(before: Option<&str>, after: Option<&str>) -> String {
+    match (before, after) {
+        (_, None) => format!(
+            "device did not report a version within {}s",
+            REBOOT_TIMEOUT.as_secs()
+        ),
+        (Some(before), Some(after)) if before == after => format!("still {}", after),
+        (before, Some(after)) => format!("{} -> {}", before.unwrap_or("unknown"), after),
+    }
+}
+
 fn filter_devices_by_role(devices: Vec<Device>, filter: Option<RoleFilter>) -> Vec<Device> {
     match filter {
         Some(RoleFilter::AnchorTdoa) => devices
@@ -1,23 +1,38 @@
 //! Preset commands implementation.
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::time::Duration;
 
 use chrono::Utc;
+use colored::*;
+use comfy_table::{ContentArrangement, Table};
 
-use crate::cli::{PresetArgs, PresetCommands, PresetTypeArg, RoleFilter};
+use crate::cli::{
+    PresetArgs, PresetCommands, PresetListArgs, PresetSaveArgs, PresetTemplateArgs,
+    PresetTemplateLayout, PresetTypeArg, PresetUploadArgs, RoleFilter,
+};
+use crate::commands::config::{print_validation_report, reconnect_note, warn_mismatches};
 use crate::device::discovery::{discover_devices, DiscoveryOptions, DISCOVERY_PORT};
 use crate::error::CliError;
 use crate::output::get_formatter;
-use crate::types::{Device, DeviceRole, GpsOrigin, LocationData, Preset, PresetInfo, PresetType};
+use crate::types::{
+    AppliedRecord, Device, DeviceRole, LocationData, ParamOverride, Preset, PresetList, PresetType,
+};
 
+use rtls_link_core::anchor_csv::parse_anchors_csv;
+use rtls_link_core::config_diff::{diff_preset, ConfigDiffKind};
+use rtls_link_core::config_validation::{check_role_matches_config, validate_locations};
 use rtls_link_core::device::mavlink::{send_command, DeviceConnection};
 use rtls_link_core::error::StorageError;
+use rtls_link_core::location_template::{preset_from_template, Layout};
 use rtls_link_core::protocol::commands::Commands;
 use rtls_link_core::protocol::config_params::{
-    config_to_params, device_config_from_backup_value, location_to_params,
+    anchor_position_params, device_config_from_backup_value, overrides_for, parse_override,
+    preview_preset, PresetUploadPlan,
 };
-use rtls_link_core::protocol::response::parse_json_response;
-use rtls_link_core::storage::{default_data_dir, PresetStorage};
+use rtls_link_core::protocol::response::{parse_json_response, ParamMismatch};
+use rtls_link_core::storage::{default_data_dir, merge_presets, PresetStorage};
 
 fn create_preset_storage() -> Result<PresetStorage, CliError> {
     let data_dir = default_data_dir()
@@ -37,61 +52,113 @@ pub async fn run_preset(
     let timeout_duration = Duration::from_millis(timeout);
 
     match args.command {
-        PresetCommands::List => run_list(json).await,
+        PresetCommands::List(args) => run_list(args, json).await,
         PresetCommands::Show(args) => run_show(&args.name, json).await,
-        PresetCommands::Save(args) => {
-            run_save(
-                &args.name,
-                args.from_device.as_deref(),
-                args.from_file.as_deref(),
-                args.preset_type,
-                args.description.as_deref(),
-                timeout_duration,
-                json,
-            )
-            .await
-        }
+        PresetCommands::Save(args) => run_save(args, timeout_duration, json).await,
         PresetCommands::Delete(args) => run_delete(&args.name, args.force, json).await,
-        PresetCommands::Upload(args) => {
-            run_upload(
-                &args.name,
-                &args.target,
-                args.filter_role,
-                args.concurrency,
-                timeout_duration,
-                json,
-                strict,
-            )
-            .await
+        PresetCommands::Rename(args) => run_rename(&args.old_name, &args.new_name, json).await,
+        PresetCommands::Copy(args) => run_copy(&args.source, &args.target, json).await,
+        PresetCommands::Convert(args) => {
+            run_convert(&args.name, args.to, args.as_name.as_deref(), json).await
+        }
+        PresetCommands::Merge(args) => {
+            run_merge(&args.full, &args.locations, &args.output, json).await
+        }
+        PresetCommands::Export(args) => run_export(&args.name, &args.file, json).await,
+        PresetCommands::Import(args) => {
+            run_import(&args.file, args.name.as_deref(), args.overwrite, json).await
         }
+        PresetCommands::Template(args) => run_template(args, json).await,
+        PresetCommands::Upload(args) => run_upload(args, timeout_duration, json, strict).await,
+        PresetCommands::Diff(args) => run_diff(&args.name, &args.ip, timeout_duration, json).await,
+        PresetCommands::Repair => run_repair(json).await,
     }
 }
 
-async fn run_list(json: bool) -> Result<(), CliError> {
+async fn run_list(args: PresetListArgs, json: bool) -> Result<(), CliError> {
     let storage = create_preset_storage()?;
-    let presets: Vec<PresetInfo> = storage.list().await.map_err(CliError::from)?;
+    let PresetList { presets, problems } = storage
+        .list(
+            args.preset_type.map(preset_type_from_arg),
+            args.tag.as_deref(),
+        )
+        .await
+        .map_err(CliError::from)?;
 
     if json {
         let output = serde_json::json!({
             "presets": presets,
-            "count": presets.len()
+            "count": presets.len(),
+            "problems": problems
         });
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
         if presets.is_empty() {
-            println!("No presets saved.");
+            if args.preset_type.is_some() || args.tag.is_some() {
+                println!("No presets match the filter.");
+            } else {
+                println!("No presets saved.");
+            }
         } else {
             println!("Saved presets:");
             for preset in &presets {
+                let tags = if preset.tags.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", preset.tags.join(", "))
+                };
                 let desc = preset
                     .description
                     .as_ref()
                     .map(|d| format!(" - {}", d))
                     .unwrap_or_default();
-                println!("  {} [{}]{}", preset.name, preset.preset_type, desc);
+                let applied = preset
+                    .last_applied_at
+                    .as_deref()
+                    .map(|at| format!(" (applied {})", format_age(at)))
+                    .unwrap_or_default();
+                println!(
+                    "  {} [{}]{}{}{}",
+                    preset.name, preset.preset_type, tags, desc, applied
+                );
             }
             println!("\n{} preset(s) total", presets.len());
         }
+
+        if !problems.is_empty() {
+            println!();
+            for problem in &problems {
+                println!(
+                    "{}: skipped {}: {}",
+                    "warning".yellow(),
+                    problem.file,
+                    problem.error
+                );
+            }
+            println!("Run 'preset repair' to move these files into the corrupt/ folder.");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run_repair(json: bool) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    let moved = storage.move_corrupt().await.map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "moved": moved
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if moved.is_empty() {
+        println!("No corrupt preset files found.");
+    } else {
+        for problem in &moved {
+            println!("Moved {} ({})", problem.file, problem.error);
+        }
+        println!("\n{} file(s) moved to corrupt/", moved.len());
     }
 
     Ok(())
@@ -117,8 +184,21 @@ async fn run_show(name: &str, json: bool) -> Result<(), CliError> {
         if let Some(ref desc) = preset.description {
             println!("Description: {}", desc);
         }
+        if !preset.tags.is_empty() {
+            println!("Tags: {}", preset.tags.join(", "));
+        }
         println!("Created: {}", preset.created_at);
         println!("Updated: {}", preset.updated_at);
+        if let Some(ref at) = preset.last_applied_at {
+            println!("Last applied: {} ({})", at, format_age(at));
+            for record in preset.applied_to.iter().rev() {
+                let device = match record.mac {
+                    Some(ref mac) => format!("{} ({})", record.ip, mac),
+                    None => record.ip.clone(),
+                };
+                println!("  {}  {}", record.at, device);
+            }
+        }
 
         match preset.preset_type {
             PresetType::Full => {
@@ -153,38 +233,33 @@ async fn run_show(name: &str, json: bool) -> Result<(), CliError> {
     Ok(())
 }
 
-async fn run_save(
-    name: &str,
-    from_device: Option<&str>,
-    from_file: Option<&str>,
-    preset_type: PresetTypeArg,
-    description: Option<&str>,
-    timeout: Duration,
-    json: bool,
-) -> Result<(), CliError> {
+async fn run_save(args: PresetSaveArgs, timeout: Duration, json: bool) -> Result<(), CliError> {
+    if args.from_anchors_csv.is_some() {
+        return run_save_from_anchors_csv(args, json).await;
+    }
+
     let storage = create_preset_storage()?;
+    let name = args.name.as_str();
+    let description = args.description.as_deref();
 
-    let config = if let Some(ip) = from_device {
+    let config = if let Some(ref ip) = args.from_device {
         let response = send_command(ip, Commands::backup_config(), timeout).await?;
         let json: serde_json::Value = parse_json_response(&response, ip)?;
         device_config_from_backup_value(json)
             .map_err(|e| CliError::Other(format!("Failed to parse config: {}", e)))?
-    } else if let Some(file) = from_file {
+    } else if let Some(ref file) = args.from_file {
         let content = std::fs::read_to_string(file)
             .map_err(|e| CliError::Other(format!("Failed to read file: {}", e)))?;
         serde_json::from_str(&content)
             .map_err(|e| CliError::Other(format!("Failed to parse config: {}", e)))?
     } else {
         return Err(CliError::InvalidArgument(
-            "Must specify --from-device or --from-file".to_string(),
+            "Must specify --from-device, --from-file or --from-anchors-csv".to_string(),
         ));
     };
 
     let now = Utc::now().to_rfc3339();
-    let preset_type = match preset_type {
-        PresetTypeArg::Full => PresetType::Full,
-        PresetTypeArg::Locations => PresetType::Locations,
-    };
+    let preset_type = preset_type_from_arg(args.preset_type);
 
     let preset = match preset_type {
         PresetType::Full => Preset {
@@ -193,44 +268,198 @@ async fn run_save(
             preset_type: PresetType::Full,
             config: Some(config),
             locations: None,
+            tags: args.tags,
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: now.clone(),
             updated_at: now,
         },
-        PresetType::Locations => {
-            let locations = LocationData {
-                origin: GpsOrigin {
-                    lat: config.uwb.origin_lat.unwrap_or(0.0),
-                    lon: config.uwb.origin_lon.unwrap_or(0.0),
-                    alt: config.uwb.origin_alt.unwrap_or(0.0),
+        PresetType::Locations => Preset {
+            name: name.to_string(),
+            description: description.map(String::from),
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(LocationData::from_config(&config)),
+            tags: args.tags,
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        },
+    };
+
+    if args.force {
+        storage.replace(&preset).await
+    } else {
+        storage.save(&preset).await
+    }
+    .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "type": preset.preset_type.to_string()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Preset '{}' saved ({} type)", name, preset.preset_type);
+    }
+
+    Ok(())
+}
+
+async fn run_save_from_anchors_csv(args: PresetSaveArgs, json: bool) -> Result<(), CliError> {
+    let file = args.from_anchors_csv.unwrap_or_default();
+    let origin = args.origin.ok_or_else(|| {
+        CliError::InvalidArgument("--from-anchors-csv requires --origin LAT,LON,ALT".to_string())
+    })?;
+    let content = std::fs::read_to_string(&file)
+        .map_err(|e| CliError::Other(format!("Failed to read file: {}", e)))?;
+    let anchors = parse_anchors_csv(&content)
+        .map_err(|e| CliError::InvalidArgument(format!("{}: {}", file, e)))?;
+
+    let locations = LocationData {
+        origin,
+        rotation: args.rotation.unwrap_or(0.0),
+        anchors,
+        use_2d_estimator: None,
+    };
+    let issues = validate_locations(&locations);
+    let errors = issues.iter().filter(|issue| issue.is_error()).count();
+    if errors > 0 {
+        if json {
+            let output = serde_json::json!({
+                "preset": args.name,
+                "validation": {
+                    "valid": false,
+                    "issues": issues
                 },
-                rotation: config.uwb.rotation_degrees.unwrap_or(0.0),
-                anchors: config.uwb.anchors.unwrap_or_default(),
-                use_2d_estimator: config.uwb.use_2d_estimator,
-            };
+                "success": false
+            });
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        } else {
+            print_validation_report(&issues);
+        }
+        return Err(CliError::Validation(format!(
+            "Anchors in {} have {} validation error(s)",
+            file, errors
+        )));
+    }
 
-            Preset {
-                name: name.to_string(),
-                description: description.map(String::from),
-                preset_type: PresetType::Locations,
-                config: None,
-                locations: Some(locations),
-                created_at: now.clone(),
-                updated_at: now,
-            }
+    let now = Utc::now().to_rfc3339();
+    let anchor_count = locations.anchors.len();
+    let preset = Preset {
+        name: args.name,
+        description: args.description,
+        preset_type: PresetType::Locations,
+        config: None,
+        locations: Some(locations),
+        tags: args.tags,
+        overrides: Default::default(),
+        last_applied_at: None,
+        applied_to: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    };
+    let storage = create_preset_storage()?;
+    if args.force {
+        storage.replace(&preset).await
+    } else {
+        storage.save(&preset).await
+    }
+    .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "name": preset.name,
+            "type": preset.preset_type.to_string(),
+            "anchors": anchor_count,
+            "issues": issues
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        if !issues.is_empty() {
+            print_validation_report(&issues);
+            println!();
         }
+        println!(
+            "Preset '{}' saved ({} type, {} anchors from {})",
+            preset.name, preset.preset_type, anchor_count, file
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_template(args: PresetTemplateArgs, json: bool) -> Result<(), CliError> {
+    let (layout, save) = match args.layout {
+        PresetTemplateLayout::Square(room) => (
+            Layout::Square {
+                width: room.width,
+                length: room.length,
+                height: room.height,
+            },
+            room.save,
+        ),
+        PresetTemplateLayout::Rectangle(room) => (
+            Layout::Rectangle {
+                width: room.width,
+                length: room.length,
+                height: room.height,
+            },
+            room.save,
+        ),
+        PresetTemplateLayout::LShape(room) => (
+            Layout::LShape {
+                width: room.width,
+                length: room.length,
+                notch_width: room.notch_width,
+                notch_length: room.notch_length,
+                height: room.height,
+            },
+            room.save,
+        ),
     };
 
+    let mut preset =
+        preset_from_template(&save.name, layout, &save.ids).map_err(CliError::InvalidArgument)?;
+    preset.tags = save.tags;
+
+    let storage = create_preset_storage()?;
     storage.save(&preset).await.map_err(CliError::from)?;
 
+    let anchors = preset
+        .locations
+        .as_ref()
+        .map(|locations| locations.anchors.as_slice())
+        .unwrap_or_default();
     if json {
         let output = serde_json::json!({
             "success": true,
-            "name": name,
-            "type": preset.preset_type.to_string()
+            "name": preset.name,
+            "type": preset.preset_type.to_string(),
+            "description": preset.description,
+            "anchors": anchors,
         });
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
-        println!("Preset '{}' saved ({} type)", name, preset.preset_type);
+        println!(
+            "Preset '{}' saved ({} type)",
+            preset.name, preset.preset_type
+        );
+        if let Some(ref description) = preset.description {
+            println!("{}", description);
+        }
+        for anchor in anchors {
+            println!(
+                "  {} @ ({:.2}, {:.2}, {:.2})",
+                anchor.id, anchor.x, anchor.y, anchor.z
+            );
+        }
     }
 
     Ok(())
@@ -240,7 +469,7 @@ async fn run_delete(name: &str, force: bool, json: bool) -> Result<(), CliError>
     let storage = create_preset_storage()?;
 
     if !force {
-        if !storage.exists(name) {
+        if !storage.exists(name).await {
             return Err(CliError::Core(rtls_link_core::error::CoreError::Storage(
                 StorageError::PresetNotFound(name.to_string()),
             )));
@@ -262,15 +491,167 @@ async fn run_delete(name: &str, force: bool, json: bool) -> Result<(), CliError>
     Ok(())
 }
 
-async fn run_upload(
+async fn run_rename(old_name: &str, new_name: &str, json: bool) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    storage
+        .rename(old_name, new_name)
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "oldName": old_name,
+            "name": new_name
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Preset '{}' renamed to '{}'", old_name, new_name);
+    }
+
+    Ok(())
+}
+
+async fn run_copy(source: &str, target: &str, json: bool) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    storage
+        .duplicate(source, target)
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "source": source,
+            "name": target
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Preset '{}' copied to '{}'", source, target);
+    }
+
+    Ok(())
+}
+
+async fn run_convert(
     name: &str,
-    target: &str,
-    filter_role: Option<RoleFilter>,
-    _concurrency: usize,
+    to: PresetTypeArg,
+    as_name: Option<&str>,
+    json: bool,
+) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    let converted = storage
+        .convert(name, preset_type_from_arg(to), as_name)
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "source": name,
+            "name": converted.name,
+            "type": converted.preset_type.to_string()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        let anchors = converted
+            .locations
+            .as_ref()
+            .map_or(0, |locations| locations.anchors.len());
+        println!(
+            "Preset '{}' converted to {} preset '{}' ({} anchors)",
+            name, converted.preset_type, converted.name, anchors
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_merge(
+    full_name: &str,
+    locations_name: &str,
+    output_name: &str,
+    json: bool,
+) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    let merged = storage
+        .merge(full_name, locations_name, output_name)
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "name": merged.name,
+            "full": full_name,
+            "locations": locations_name
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!(
+            "Preset '{}' saved with settings from '{}' and locations from '{}'",
+            merged.name, full_name, locations_name
+        );
+    }
+
+    Ok(())
+}
+
+async fn run_export(name: &str, file: &str, json: bool) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    storage
+        .export(name, Path::new(file))
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "file": file
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Preset '{}' exported to {}", name, file);
+    }
+
+    Ok(())
+}
+
+async fn run_import(
+    file: &str,
+    name: Option<&str>,
+    overwrite: bool,
+    json: bool,
+) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    let name = storage
+        .import(Path::new(file), name, overwrite)
+        .await
+        .map_err(CliError::from)?;
+
+    if json {
+        let output = serde_json::json!({
+            "success": true,
+            "name": name,
+            "file": file
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("Preset imported from {} as '{}'", file, name);
+    }
+
+    Ok(())
+}
+
+async fn run_upload(
+    args: PresetUploadArgs,
     timeout: Duration,
     json: bool,
     strict: bool,
 ) -> Result<(), CliError> {
+    let name = args.name.as_str();
+    let target = args.target.as_str();
     let storage = create_preset_storage()?;
     let preset: Preset = storage
         .get(name)
@@ -282,47 +663,316 @@ async fn run_upload(
             ))
         })?;
 
-    let params = preset_to_params(&preset)?;
+    let layout_preset = match args.with_locations {
+        Some(ref locations_name) => Some(
+            storage
+                .get(locations_name)
+                .await
+                .map_err(CliError::from)?
+                .ok_or_else(|| {
+                    CliError::from(StorageError::PresetNotFound(locations_name.to_string()))
+                })?,
+        ),
+        None => None,
+    };
+    let layout_name = layout_preset.as_ref().map_or(name, |p| p.name.as_str());
+
+    if let Some(locations) = layout_preset.as_ref().unwrap_or(&preset).locations.as_ref() {
+        let issues = validate_locations(locations);
+        let errors = issues.iter().filter(|issue| issue.is_error()).count();
+        let warnings = issues.len() - errors;
+        let blocked = if errors > 0 {
+            Some(format!(
+                "Preset '{}' has {} validation error(s)",
+                layout_name, errors
+            ))
+        } else if warnings > 0 && !args.force {
+            Some(format!(
+                "Preset '{}' has {} validation warning(s); use --force to upload anyway",
+                layout_name, warnings
+            ))
+        } else {
+            None
+        };
+
+        if let Some(reason) = blocked {
+            if json {
+                let output = serde_json::json!({
+                    "preset": layout_name,
+                    "validation": {
+                        "valid": errors == 0,
+                        "issues": issues
+                    },
+                    "uploaded": false
+                });
+                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+            } else {
+                print_validation_report(&issues);
+            }
+            return Err(CliError::Validation(reason));
+        }
+        if !json && !issues.is_empty() {
+            print_validation_report(&issues);
+            println!();
+        }
+    }
+
+    let preset = match layout_preset {
+        Some(ref locations) => merge_presets(&preset, locations, name).map_err(CliError::from)?,
+        None => preset,
+    };
 
-    let ips = if target.to_lowercase() == "all" {
+    let anchor_layout = match preset.preset_type {
+        PresetType::Locations if args.include_anchors => preset.locations.as_ref(),
+        PresetType::Full if args.include_anchors => {
+            return Err(CliError::InvalidArgument(
+                "--include-anchors only applies to locations presets".to_string(),
+            ));
+        }
+        _ => None,
+    };
+    let plan = preview_preset(&preset).map_err(CliError::Validation)?;
+    let mut overrides = preset.overrides.clone();
+    for spec in &args.overrides {
+        let (device, param_override) = parse_override(spec).map_err(CliError::InvalidArgument)?;
+        overrides.entry(device).or_default().push(param_override);
+    }
+
+    let targets: Vec<UploadTarget> = if target.to_lowercase() == "all" {
         let options = DiscoveryOptions {
             port: DISCOVERY_PORT,
             duration: Duration::from_secs(3),
         };
         let devices = discover_devices(options).await?;
-        let devices = filter_devices_by_role(devices, filter_role);
+        let devices = filter_devices_by_role(devices, args.filter_role);
 
         let devices = if preset.preset_type == PresetType::Locations {
-            devices.into_iter().filter(|d| d.role.is_tag()).collect()
+            devices
+                .into_iter()
+                .filter(|d| d.role.is_tag() || (anchor_layout.is_some() && d.role.is_anchor()))
+                .collect()
         } else {
             devices
         };
+        if let (Some(config), false) = (&preset.config, json) {
+            for issue in devices
+                .iter()
+                .filter_map(|d| check_role_matches_config(d, config))
+            {
+                eprintln!("{}: {}", "warning".yellow(), issue.message);
+            }
+        }
 
-        devices.into_iter().map(|d| d.ip).collect()
-    } else if target.contains(',') {
-        target.split(',').map(|s| s.trim().to_string()).collect()
+        devices
+            .into_iter()
+            .map(|d| UploadTarget {
+                ip: d.ip,
+                mac: Some(d.mac),
+                uwb_short: Some(d.uwb_short),
+                role: Some(d.role),
+            })
+            .collect()
     } else {
-        vec![target.to_string()]
+        target
+            .split(',')
+            .map(|s| UploadTarget {
+                ip: s.trim().to_string(),
+                mac: None,
+                uwb_short: None,
+                role: None,
+            })
+            .collect()
     };
 
-    if ips.is_empty() {
+    if targets.is_empty() {
         return Err(CliError::NoDevicesFound);
     }
 
+    if args.dry_run {
+        // Without discovery the device identity is only known once connected
+        let previews: Vec<(&UploadTarget, Vec<String>, bool)> = targets
+            .iter()
+            .map(|target| {
+                let target_plan = match (anchor_layout, &target.role, &target.uwb_short) {
+                    (Some(layout), Some(DeviceRole::AnchorTdoa), Some(short_addr)) => {
+                        anchor_plan(layout, short_addr, &plan)?
+                    }
+                    _ => Some(plan.clone()),
+                };
+                let Some(target_plan) = target_plan else {
+                    return Ok((target, Vec::new(), false));
+                };
+                let pending = target.role.is_none() && anchor_layout.is_some();
+                let (mut commands, pending) = match target.known_overrides(&overrides) {
+                    Some(set) => (target_plan.with_overrides(set).commands()?, pending),
+                    None => (target_plan.commands()?, true),
+                };
+                if args.reboot {
+                    commands.push(Commands::reboot().to_string());
+                }
+                Ok((target, commands, pending))
+            })
+            .collect::<Result<_, CliError>>()?;
+
+        if json {
+            let devices: Vec<serde_json::Value> = previews
+                .iter()
+                .map(|(target, commands, pending)| {
+                    serde_json::json!({
+                        "ip": target.ip,
+                        "commands": commands,
+                        "overridesPending": pending
+                    })
+                })
+                .collect();
+            let output = serde_json::json!({
+                "preset": name,
+                "dryRun": true,
+                "params": plan.params,
+                "devices": devices
+            });
+            println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        } else {
+            for (target, commands, pending) in &previews {
+                if commands.is_empty() {
+                    println!("{}: skipped, anchor is not in the preset", target.ip.bold());
+                    continue;
+                }
+                println!("{} ({} commands):", target.ip.bold(), commands.len());
+                for command in commands {
+                    println!("  {}", command);
+                }
+                if *pending {
+                    println!(
+                        "  (per-device overrides and anchor roles are resolved when uploading)"
+                    );
+                }
+            }
+            println!("\nDry run: nothing was sent");
+        }
+        return Ok(());
+    }
+
     let formatter = get_formatter(json);
     let mut results = Vec::new();
 
-    for ip in &ips {
-        let result = upload_preset_to_device(ip, &preset, &params, timeout).await;
+    let mut skipped = Vec::new();
+    let mut mismatches = serde_json::Map::new();
+    let mut mismatch_count = 0;
+    for target in &targets {
+        let result = upload_preset_to_device(
+            target,
+            &plan,
+            &overrides,
+            anchor_layout,
+            args.reboot,
+            args.verify,
+            timeout,
+            args.pacing_ms.map(Duration::from_millis),
+        )
+        .await;
         let success = result.is_ok();
         let message = match &result {
-            Ok(_) => "Preset uploaded".to_string(),
+            Ok(UploadOutcome::Skipped(short_addr)) => {
+                if !json {
+                    eprintln!(
+                        "{}: {} skipped, anchor {} is not in preset '{}'",
+                        "warning".yellow(),
+                        target.ip,
+                        short_addr,
+                        name
+                    );
+                }
+                skipped.push(target.ip.clone());
+                format!("Skipped: anchor {} is not in the preset", short_addr)
+            }
+            Ok(UploadOutcome::Applied {
+                mismatches: found,
+                reconnects,
+            }) => {
+                warn_mismatches(&target.ip, found, json);
+                mismatch_count += found.len();
+                if args.verify {
+                    mismatches.insert(target.ip.clone(), serde_json::json!(found));
+                }
+                let applied = if args.reboot {
+                    "Applied and rebooted"
+                } else {
+                    "Applied"
+                };
+                if found.is_empty() {
+                    format!("{}{}", applied, reconnect_note(*reconnects))
+                } else {
+                    format!(
+                        "{}, {} parameter(s) read back differently{}",
+                        applied,
+                        found.len(),
+                        reconnect_note(*reconnects)
+                    )
+                }
+            }
             Err(e) => e.to_string(),
         };
-        results.push((ip.clone(), success, message));
+        results.push((target.ip.clone(), success, message));
+    }
+
+    if let Some(secs) = args.wait_online {
+        let rebooted: Vec<String> = results
+            .iter()
+            .filter(|(ip, success, _)| *success && !skipped.contains(ip))
+            .map(|(ip, _, _)| ip.clone())
+            .collect();
+        if !json {
+            println!(
+                "Waiting up to {}s for {} device(s) to come back online...",
+                secs,
+                rebooted.len()
+            );
+        }
+        let offline = wait_for_devices(rebooted, Duration::from_secs(secs)).await?;
+        for (ip, success, message) in results.iter_mut() {
+            if offline.contains(ip) {
+                *success = false;
+                *message = "Applied but did not come back online".to_string();
+            }
+        }
     }
 
-    println!("{}", formatter.format_bulk_results(&results));
+    let at = Utc::now().to_rfc3339();
+    let applied: Vec<AppliedRecord> = targets
+        .iter()
+        .filter(|target| {
+            !skipped.contains(&target.ip)
+                && results.iter().any(|(ip, ok, _)| *ok && *ip == target.ip)
+        })
+        .map(|target| AppliedRecord {
+            mac: target.mac.clone(),
+            ip: target.ip.clone(),
+            at: at.clone(),
+        })
+        .collect();
+    for applied_name in std::iter::once(name).chain(args.with_locations.as_deref()) {
+        // Apply history is informational; the upload itself already succeeded
+        if let Err(e) = storage.record_applied(applied_name, &applied).await {
+            eprintln!(
+                "{}: could not record upload in preset '{}': {}",
+                "warning".yellow(),
+                applied_name,
+                e
+            );
+        }
+    }
+
+    let report = formatter.format_bulk_results(&results);
+    if json && args.verify {
+        let mut output: serde_json::Value = serde_json::from_str(&report).unwrap_or_default();
+        if let Some(fields) = output.as_object_mut() {
+            fields.insert("mismatches".to_string(), mismatches.into());
+        }
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else {
+        println!("{}", report);
+    }
 
     let failed_count = results.iter().filter(|(_, s, _)| !s).count();
     if failed_count == results.len() || (strict && failed_count > 0) {
@@ -331,49 +981,244 @@ async fn run_upload(
             failed: failed_count,
         });
     }
+    if strict && mismatch_count > 0 {
+        return Err(CliError::VerifyFailed(mismatch_count));
+    }
 
     Ok(())
 }
 
-async fn upload_preset_to_device(
-    ip: &str,
-    preset: &Preset,
-    params: &[(String, String, String)],
-    timeout: Duration,
-) -> Result<(), CliError> {
-    let mut conn = DeviceConnection::connect(ip, timeout).await?;
+async fn run_diff(name: &str, ip: &str, timeout: Duration, json: bool) -> Result<(), CliError> {
+    let storage = create_preset_storage()?;
+    let preset: Preset = storage
+        .get(name)
+        .await
+        .map_err(CliError::from)?
+        .ok_or_else(|| CliError::from(StorageError::PresetNotFound(name.to_string())))?;
+
+    let response = send_command(ip, Commands::backup_config(), timeout).await?;
+    let backup: serde_json::Value = parse_json_response(&response, ip)?;
+    let device = device_config_from_backup_value(backup)
+        .map_err(|e| CliError::Other(format!("Failed to parse config: {}", e)))?;
+
+    let diff = diff_preset(&preset, &device).map_err(CliError::Other)?;
 
-    for (group, name, value) in params {
-        let cmd = Commands::write_param(group, name, value);
-        conn.send_raw(&cmd).await?;
+    if json {
+        let output = serde_json::json!({
+            "preset": name,
+            "ip": ip,
+            "identical": diff.is_empty(),
+            "differences": diff,
+            "count": diff.len()
+        });
+        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+    } else if diff.is_empty() {
+        println!("Device {} matches preset '{}'.", ip, name);
+    } else {
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec!["Parameter", "Preset", "Device", "Status"]);
+        for entry in &diff {
+            let status = match entry.kind {
+                ConfigDiffKind::Changed => "changed".yellow(),
+                ConfigDiffKind::Removed => "preset only".red(),
+                ConfigDiffKind::Added => "device only".green(),
+            };
+            table.add_row(vec![
+                format!("{}.{}", entry.group, entry.name),
+                entry.left.clone().unwrap_or_default(),
+                entry.right.clone().unwrap_or_default(),
+                status.to_string(),
+            ]);
+        }
+        println!("{}", table);
+        println!(
+            "\n{} difference(s) between preset '{}' and {}",
+            diff.len(),
+            name,
+            ip
+        );
     }
 
-    if preset.preset_type == PresetType::Full {
-        let cmd = Commands::save_config_as(&preset.name);
-        conn.send_raw(&cmd).await?;
+    if diff.is_empty() {
+        Ok(())
     } else {
-        conn.send_raw(Commands::save_config()).await?;
+        Err(CliError::DifferencesFound(diff.len()))
     }
+}
 
-    Ok(())
+/// Time a device takes to go down after a reboot command
+const REBOOT_GRACE: Duration = Duration::from_secs(3);
+
+/// Device a preset is uploaded to, with its identity if discovery found it
+struct UploadTarget {
+    ip: String,
+    mac: Option<String>,
+    uwb_short: Option<String>,
+    role: Option<DeviceRole>,
 }
 
-fn preset_to_params(preset: &Preset) -> Result<Vec<(String, String, String)>, CliError> {
-    match preset.preset_type {
-        PresetType::Full => {
-            if let Some(ref config) = preset.config {
-                config_to_params(config).map_err(CliError::Other)
-            } else {
-                return Err(CliError::Other("Preset has no config data".to_string()));
-            }
+/// What an upload did to a device
+enum UploadOutcome {
+    /// Uploaded, with the parameters that read back differently and the
+    /// times the link was reopened
+    Applied {
+        mismatches: Vec<ParamMismatch>,
+        reconnects: usize,
+    },
+    /// An anchor whose short address is not in the preset
+    Skipped(String),
+}
+
+/// The plan giving the anchor with `short_addr` its own position, or `None`
+/// if the preset has no such anchor
+fn anchor_plan(
+    layout: &LocationData,
+    short_addr: &str,
+    plan: &PresetUploadPlan,
+) -> Result<Option<PresetUploadPlan>, CliError> {
+    let params = anchor_position_params(layout, short_addr).map_err(CliError::Other)?;
+    Ok(params.map(|params| PresetUploadPlan {
+        params: params.into(),
+        save_command: plan.save_command.clone(),
+    }))
+}
+
+impl UploadTarget {
+    /// The overrides for this device, or `None` if its identity must be
+    /// read from the device first
+    fn known_overrides<'a>(
+        &self,
+        overrides: &'a HashMap<String, Vec<ParamOverride>>,
+    ) -> Option<&'a [ParamOverride]> {
+        if overrides.is_empty() {
+            return Some(&[]);
         }
-        PresetType::Locations => {
-            if let Some(ref locations) = preset.locations {
-                location_to_params(locations).map_err(CliError::Other)
-            } else {
-                return Err(CliError::Other("Preset has no location data".to_string()));
+        let short_addr = self.uwb_short.as_deref()?;
+        Some(overrides_for(
+            overrides,
+            self.mac.as_deref(),
+            Some(short_addr),
+        ))
+    }
+}
+
+/// Upload `plan` to one device
+///
+/// With `anchor_layout`, TDoA anchors get only their own position from it
+/// instead of the full plan. With `verify`, parameters are read back before
+/// saving. With `pacing`, commands are sent that far apart.
+#[allow(clippy::too_many_arguments)]
+async fn upload_preset_to_device(
+    target: &UploadTarget,
+    plan: &PresetUploadPlan,
+    overrides: &HashMap<String, Vec<ParamOverride>>,
+    anchor_layout: Option<&LocationData>,
+    reboot: bool,
+    verify: bool,
+    timeout: Duration,
+    pacing: Option<Duration>,
+) -> Result<UploadOutcome, CliError> {
+    let mut conn = DeviceConnection::connect_resilient(&target.ip, timeout).await?;
+    conn.set_pacing(pacing);
+
+    let is_anchor = match (anchor_layout, &target.role) {
+        (None, _) => false,
+        (Some(_), Some(role)) => role.is_anchor(),
+        (Some(_), None) => {
+            let mode = conn.send_raw(&Commands::read_param("uwb", "mode")?).await?;
+            mode.trim()
+                .parse()
+                .is_ok_and(|mode| DeviceRole::from_mode(mode).is_anchor())
+        }
+    };
+    let short_addr = match target.uwb_short {
+        Some(ref short_addr) => Some(short_addr.clone()),
+        None if is_anchor || !overrides.is_empty() => {
+            let short_addr = conn
+                .send_raw(&Commands::read_param("uwb", "devShortAddr")?)
+                .await?;
+            Some(short_addr.trim().to_string())
+        }
+        None => None,
+    };
+
+    let anchor_plan = match (anchor_layout, short_addr.as_deref()) {
+        (Some(layout), Some(short_addr)) if is_anchor => {
+            match anchor_plan(layout, short_addr, plan)? {
+                Some(anchor_plan) => Some(anchor_plan),
+                None => return Ok(UploadOutcome::Skipped(short_addr.to_string())),
             }
         }
+        _ => None,
+    };
+    let device_overrides = overrides_for(overrides, target.mac.as_deref(), short_addr.as_deref());
+
+    let device_plan = anchor_plan
+        .as_ref()
+        .unwrap_or(plan)
+        .with_overrides(device_overrides);
+    let mismatches = if verify {
+        conn.upload_plan_verified(&device_plan).await?
+    } else {
+        conn.upload_plan(&device_plan).await?;
+        Vec::new()
+    };
+    if reboot {
+        conn.send_raw(Commands::reboot()).await?;
+    }
+
+    Ok(UploadOutcome::Applied {
+        mismatches,
+        reconnects: conn.reconnects(),
+    })
+}
+
+/// Listen for discovery heartbeats until every device in `ips` has been
+/// heard from or `timeout` passes, returning the ones that stayed silent.
+async fn wait_for_devices(
+    mut ips: Vec<String>,
+    timeout: Duration,
+) -> Result<Vec<String>, CliError> {
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    // Heartbeats sent just before the restart would count as back online
+    tokio::time::sleep(REBOOT_GRACE.min(timeout)).await;
+
+    while !ips.is_empty() {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        let options = DiscoveryOptions {
+            port: DISCOVERY_PORT,
+            duration: remaining.min(Duration::from_secs(2)),
+        };
+        let devices = discover_devices(options).await?;
+        ips.retain(|ip| !devices.iter().any(|d| d.ip == *ip));
+    }
+
+    Ok(ips)
+}
+
+/// Time since an ISO 8601 timestamp, e.g. "3d ago"
+fn format_age(timestamp: &str) -> String {
+    let Ok(then) = chrono::DateTime::parse_from_rfc3339(timestamp) else {
+        return timestamp.to_string();
+    };
+    let seconds = (Utc::now() - then.with_timezone(&Utc)).num_seconds().max(0);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400),
+    }
+}
+
+fn preset_type_from_arg(arg: PresetTypeArg) -> PresetType {
+    match arg {
+        PresetTypeArg::Full => PresetType::Full,
+        PresetTypeArg::Locations => PresetType::Locations,
     }
 }
 
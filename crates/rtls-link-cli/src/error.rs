@@ -6,7 +6,7 @@ use rtls_link_core::error::CoreError;
 use thiserror::Error;
 
 // Re-export core error types so command modules can use them via crate::error
-pub use rtls_link_core::error::{ConfigError, DeviceError, StorageError};
+pub use rtls_link_core::error::{CommandError, ConfigError, DeviceError, ParamError, StorageError};
 
 /// Exit codes for the CLI
 pub mod exit_codes {
@@ -15,6 +15,10 @@ pub mod exit_codes {
     pub const DEVICE_ERROR: i32 = 3;
     pub const INVALID_ARGS: i32 = 4;
     pub const PARTIAL_FAILURE: i32 = 5;
+    pub const NO_MESSAGES: i32 = 6;
+    pub const VALIDATION_FAILED: i32 = 7;
+    pub const DIFFERENCES_FOUND: i32 = 8;
+    pub const VERIFY_FAILED: i32 = 9;
 }
 
 /// Main error type for the CLI
@@ -35,6 +39,18 @@ pub enum CliError {
     #[error("No devices found")]
     NoDevicesFound,
 
+    #[error("No log messages received")]
+    NoLogMessages,
+
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
+    #[error("{0} difference(s) found")]
+    DifferencesFound(usize),
+
+    #[error("{0} parameter(s) did not read back as written")]
+    VerifyFailed(usize),
+
     #[error("{0}")]
     Other(String),
 }
@@ -47,6 +63,7 @@ impl CliError {
                 CoreError::Device(_) => exit_codes::DEVICE_ERROR,
                 CoreError::Storage(_) => exit_codes::GENERAL_ERROR,
                 CoreError::Config(_) => exit_codes::GENERAL_ERROR,
+                CoreError::Command(_) => exit_codes::INVALID_ARGS,
                 CoreError::Io(_) => exit_codes::GENERAL_ERROR,
                 CoreError::Other(_) => exit_codes::GENERAL_ERROR,
             },
@@ -54,6 +71,10 @@ impl CliError {
             CliError::InvalidArgument(_) => exit_codes::INVALID_ARGS,
             CliError::PartialFailure { .. } => exit_codes::PARTIAL_FAILURE,
             CliError::NoDevicesFound => exit_codes::GENERAL_ERROR,
+            CliError::NoLogMessages => exit_codes::NO_MESSAGES,
+            CliError::Validation(_) => exit_codes::VALIDATION_FAILED,
+            CliError::DifferencesFound(_) => exit_codes::DIFFERENCES_FOUND,
+            CliError::VerifyFailed(_) => exit_codes::VERIFY_FAILED,
             CliError::Other(_) => exit_codes::GENERAL_ERROR,
         }
     }
@@ -78,6 +99,12 @@ impl From<ConfigError> for CliError {
     }
 }
 
+impl From<CommandError> for CliError {
+    fn from(e: CommandError) -> Self {
+        CliError::Core(CoreError::Command(e))
+    }
+}
+
 // Clone implementation needed for bulk operations (firmware upload)
 impl Clone for CliError {
     fn clone(&self) -> Self {
@@ -90,6 +117,10 @@ impl Clone for CliError {
                 failed: *failed,
             },
             CliError::NoDevicesFound => CliError::NoDevicesFound,
+            CliError::NoLogMessages => CliError::NoLogMessages,
+            CliError::Validation(s) => CliError::Validation(s.clone()),
+            CliError::DifferencesFound(n) => CliError::DifferencesFound(*n),
+            CliError::VerifyFailed(n) => CliError::VerifyFailed(*n),
             CliError::Other(s) => CliError::Other(s.clone()),
         }
     }
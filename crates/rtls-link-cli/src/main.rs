@@ -44,12 +44,13 @@ async fn run(cli: Cli) -> Result<(), CliError> {
             commands::run_preset(args, cli.timeout, cli.json, cli.strict).await
         }
         Commands::Ota(args) => commands::run_ota(args, cli.json, cli.strict).await,
-        Commands::Logs(args) => commands::run_logs(args, cli.json).await,
+        Commands::Logs(args) => commands::run_logs(args, cli.timeout, cli.json).await,
         Commands::AnchorTelemetry(args) => {
             commands::run_anchor_telemetry(args, cli.timeout, cli.json, cli.strict).await
         }
         Commands::Cmd(args) => commands::run_cmd(args, cli.timeout, cli.json).await,
         Commands::Bulk(args) => commands::run_bulk(args, cli.timeout, cli.json, cli.strict).await,
         Commands::Calibrate(args) => commands::run_calibrate(args, cli.timeout, cli.json).await,
+        Commands::BackupData(args) => commands::run_backup_data(args, cli.json).await,
     }
 }
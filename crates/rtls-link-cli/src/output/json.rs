@@ -6,6 +6,8 @@ use serde_json::{json, Value};
 use super::OutputFormatter;
 use crate::health::DeviceHealth;
 use crate::types::Device;
+use rtls_link_core::device::mavlink::{BatchOutcome, BatchResult};
+use rtls_link_core::log_stats::LogStatsSnapshot;
 
 pub struct JsonOutput;
 
@@ -98,4 +100,69 @@ impl OutputFormatter for JsonOutput {
             }
         }))
     }
+
+    fn format_batch_results(&self, results: &[BatchResult]) -> String {
+        let items: Vec<Value> = results
+            .iter()
+            .map(|result| {
+                let status = match result.outcome {
+                    BatchOutcome::Success { .. } => "success",
+                    BatchOutcome::Timeout => "timeout",
+                    BatchOutcome::ConnectFailed { .. } => "connectFailed",
+                    BatchOutcome::CommandFailed { .. } => "commandFailed",
+                    BatchOutcome::Unreachable => "skipped",
+                };
+                let result_value = match result.response() {
+                    Some(response) => {
+                        serde_json::from_str(response).unwrap_or_else(|_| json!(response.trim()))
+                    }
+                    None => json!(result.error()),
+                };
+
+                json!({
+                    "ip": result.ip,
+                    "success": result.is_success(),
+                    "status": status,
+                    "elapsedMs": result.elapsed().map(|elapsed| elapsed.as_millis() as u64),
+                    "attempts": result.attempts,
+                    "result": result_value
+                })
+            })
+            .collect();
+
+        let success_count = results.iter().filter(|result| result.is_success()).count();
+
+        Self::to_json(&json!({
+            "results": items,
+            "summary": {
+                "total": results.len(),
+                "succeeded": success_count,
+                "failed": results.len() - success_count
+            }
+        }))
+    }
+
+    fn format_log_stats(
+        &self,
+        stats: &[(String, LogStatsSnapshot)],
+        top_tags: &[(String, u64)],
+    ) -> String {
+        let tags: Vec<Value> = top_tags
+            .iter()
+            .map(|(tag, count)| json!({ "tag": tag, "count": count }))
+            .collect();
+        let devices: Vec<Value> = stats
+            .iter()
+            .map(|(ip, snapshot)| {
+                json!({
+                    "ip": ip,
+                    "counts": snapshot.totals,
+                    "messagesPerSec": snapshot.messages_per_sec,
+                    "errorRate": snapshot.error_rate,
+                })
+            })
+            .collect();
+
+        Self::to_json(&json!({ "topTags": tags, "devices": devices }))
+    }
 }
@@ -8,6 +8,8 @@ pub use table::TableOutput;
 
 use crate::health::DeviceHealth;
 use crate::types::Device;
+use rtls_link_core::device::mavlink::BatchResult;
+use rtls_link_core::log_stats::LogStatsSnapshot;
 
 /// Output formatter trait
 pub trait OutputFormatter {
@@ -23,6 +25,16 @@ pub trait OutputFormatter {
 
     /// Format bulk operation results
     fn format_bulk_results(&self, results: &[(String, bool, String)]) -> String;
+
+    /// Format the results of one command sent to many devices, with timing
+    fn format_batch_results(&self, results: &[BatchResult]) -> String;
+
+    /// Format per-device log level statistics and the noisiest tags
+    fn format_log_stats(
+        &self,
+        stats: &[(String, LogStatsSnapshot)],
+        top_tags: &[(String, u64)],
+    ) -> String;
 }
 
 /// Get the appropriate formatter based on JSON flag
@@ -6,6 +6,8 @@ use comfy_table::{Cell, Color, ContentArrangement, Table};
 use super::OutputFormatter;
 use crate::health::{DeviceHealth, HealthLevel};
 use crate::types::Device;
+use rtls_link_core::device::mavlink::{BatchOutcome, BatchResult};
+use rtls_link_core::log_stats::LogStatsSnapshot;
 
 pub struct TableOutput;
 
@@ -190,4 +192,118 @@ impl OutputFormatter for TableOutput {
 
         format!("{}{}", table, summary)
     }
+
+    fn format_batch_results(&self, results: &[BatchResult]) -> String {
+        let show_attempts = results.iter().any(|result| result.attempts > 1);
+        let mut header = vec!["IP", "Status", "Elapsed"];
+        if show_attempts {
+            header.push("Attempts");
+        }
+        header.push("Result");
+
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(header);
+
+        for result in results {
+            let status_cell = match result.outcome {
+                BatchOutcome::Success { .. } => Cell::new("OK").fg(Color::Green),
+                BatchOutcome::Timeout => Cell::new("TIMEOUT").fg(Color::Yellow),
+                BatchOutcome::ConnectFailed { .. } => Cell::new("UNREACHABLE").fg(Color::Red),
+                BatchOutcome::CommandFailed { .. } => Cell::new("FAIL").fg(Color::Red),
+                BatchOutcome::Unreachable => Cell::new("SKIPPED").fg(Color::DarkGrey),
+            };
+            let elapsed = result
+                .elapsed()
+                .map(|elapsed| format!("{} ms", elapsed.as_millis()))
+                .unwrap_or_else(|| "-".to_string());
+            let message = match result.response() {
+                Some(response) if response.chars().count() > 100 => {
+                    format!("{}...", response.chars().take(100).collect::<String>())
+                }
+                Some(response) => response.trim().to_string(),
+                None => result.error().unwrap_or_default(),
+            };
+
+            let mut row = vec![Cell::new(&result.ip), status_cell, Cell::new(elapsed)];
+            if show_attempts {
+                row.push(Cell::new(result.attempts));
+            }
+            row.push(Cell::new(message));
+            table.add_row(row);
+        }
+
+        let success_count = results.iter().filter(|result| result.is_success()).count();
+        let summary = format!(
+            "\nSummary: {} succeeded, {} failed",
+            success_count.to_string().green(),
+            (results.len() - success_count).to_string().red()
+        );
+
+        format!("{}{}", table, summary)
+    }
+
+    fn format_log_stats(
+        &self,
+        stats: &[(String, LogStatsSnapshot)],
+        top_tags: &[(String, u64)],
+    ) -> String {
+        if stats.is_empty() {
+            return "No log messages received.".to_string();
+        }
+
+        let mut output = String::new();
+        if !top_tags.is_empty() {
+            let total: u64 = stats.iter().map(|(_, s)| s.totals.total()).sum();
+            let mut tags = Table::new();
+            tags.set_content_arrangement(ContentArrangement::Dynamic);
+            tags.set_header(vec!["Tag", "Messages", "Share %"]);
+            for (tag, count) in top_tags {
+                tags.add_row(vec![
+                    Cell::new(if tag.is_empty() { "-" } else { tag }),
+                    Cell::new(count),
+                    Cell::new(format!(
+                        "{:.1}",
+                        *count as f64 * 100.0 / total.max(1) as f64
+                    )),
+                ]);
+            }
+            output.push_str(&format!("{}\n{}\n\n", "Top tags".bold(), tags));
+        }
+
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(vec![
+            "IP", "Total", "ERROR", "WARN", "INFO", "DEBUG", "VERBOSE", "Msg/s", "Error %",
+        ]);
+
+        for (ip, snapshot) in stats {
+            let counts = &snapshot.totals;
+            let error_cell = if counts.error > 0 {
+                Cell::new(counts.error).fg(Color::Red)
+            } else {
+                Cell::new(counts.error)
+            };
+            let warn_cell = if counts.warn > 0 {
+                Cell::new(counts.warn).fg(Color::Yellow)
+            } else {
+                Cell::new(counts.warn)
+            };
+
+            table.add_row(vec![
+                Cell::new(ip),
+                Cell::new(counts.total()),
+                error_cell,
+                warn_cell,
+                Cell::new(counts.info),
+                Cell::new(counts.debug),
+                Cell::new(counts.verbose),
+                Cell::new(format!("{:.1}", snapshot.messages_per_sec)),
+                Cell::new(format!("{:.1}", snapshot.error_rate * 100.0)),
+            ]);
+        }
+
+        output.push_str(&table.to_string());
+        output
+    }
 }
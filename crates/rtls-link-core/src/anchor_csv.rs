@@ -0,0 +1,138 @@
+//! Anchor positions from survey spreadsheets.
+//!
+//! Surveyors export anchor coordinates as CSV with an `id,x,y,z` header.
+//! Columns may come in any order and extra columns are ignored. Fields are
+//! separated by commas or, as spreadsheets in many locales export them, by
+//! semicolons; with semicolons a comma is accepted as the decimal mark.
+
+use crate::types::AnchorConfig;
+
+const COLUMNS: [&str; 4] = ["id", "x", "y", "z"];
+
+/// Parse anchor rows from CSV text
+///
+/// Errors name the 1-based line and column of the offending field.
+pub fn parse_anchors_csv(content: &str) -> Result<Vec<AnchorConfig>, String> {
+    let mut lines = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty());
+
+    let (header_line, header) = lines
+        .next()
+        .ok_or_else(|| "File is empty; expected an id,x,y,z header".to_string())?;
+    let delimiter = if header.contains(';') { ';' } else { ',' };
+    let header: Vec<String> = split_fields(header, delimiter)
+        .map(str::to_ascii_lowercase)
+        .collect();
+
+    let mut positions = [0usize; 4];
+    for (slot, column) in positions.iter_mut().zip(COLUMNS) {
+        *slot = header.iter().position(|h| h == column).ok_or_else(|| {
+            format!(
+                "Line {}: missing '{}' column in header",
+                header_line, column
+            )
+        })?;
+    }
+
+    let mut anchors = Vec::new();
+    for (line_number, line) in lines {
+        let fields: Vec<&str> = split_fields(line, delimiter).collect();
+        let field = |column: usize| -> Result<&str, String> {
+            let position = positions[column];
+            match fields.get(position) {
+                Some(value) if !value.is_empty() => Ok(value),
+                _ => Err(format!(
+                    "Line {}, column {} ({}): missing value",
+                    line_number,
+                    position + 1,
+                    COLUMNS[column]
+                )),
+            }
+        };
+        let coordinate = |column: usize| -> Result<f64, String> {
+            let raw = field(column)?;
+            let normalized = if delimiter == ';' {
+                raw.replace(',', ".")
+            } else {
+                raw.to_string()
+            };
+            normalized
+                .parse::<f64>()
+                .ok()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| {
+                    format!(
+                        "Line {}, column {} ({}): '{}' is not a number",
+                        line_number,
+                        positions[column] + 1,
+                        COLUMNS[column],
+                        raw
+                    )
+                })
+        };
+
+        anchors.push(AnchorConfig {
+            id: field(0)?.to_string(),
+            x: coordinate(1)?,
+            y: coordinate(2)?,
+            z: coordinate(3)?,
+        });
+    }
+
+    if anchors.is_empty() {
+        return Err("File has a header but no anchor rows".to_string());
+    }
+    Ok(anchors)
+}
+
+fn split_fields(line: &str, delimiter: char) -> impl Iterator<Item = &str> {
+    line.split(delimiter)
+        .map(|field| field.trim().trim_matches('"').trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_comma_separated() {
+        let anchors =
+            parse_anchors_csv("id,x,y,z\n0,0,0,2.5\n1,8.25,0,2.5\n\n2,8,6,2.4\n").unwrap();
+
+        assert_eq!(anchors.len(), 3);
+        assert_eq!(anchors[1].id, "1");
+        assert_eq!(anchors[1].x, 8.25);
+        assert_eq!(anchors[2].z, 2.4);
+    }
+
+    #[test]
+    fn test_parse_semicolons_with_decimal_commas_and_reordered_columns() {
+        let content = "\u{feff}Z;Id;X;Y;Note\r\n2,5;\"A1\";0;0;door\r\n2,5;A2;8,25;0;\r\n";
+        let anchors = parse_anchors_csv(content).unwrap();
+
+        assert_eq!(anchors[0].id, "A1");
+        assert_eq!(anchors[1].x, 8.25);
+        assert_eq!(anchors[1].z, 2.5);
+    }
+
+    #[test]
+    fn test_errors_name_line_and_column() {
+        assert_eq!(
+            parse_anchors_csv("id,x,y,z\n0,0,0,2\n1,abc,0,2\n").unwrap_err(),
+            "Line 3, column 2 (x): 'abc' is not a number"
+        );
+        assert_eq!(
+            parse_anchors_csv("id,x,y,z\n0,0,0\n").unwrap_err(),
+            "Line 2, column 4 (z): missing value"
+        );
+        assert_eq!(
+            parse_anchors_csv("id,x,y\n0,0,0\n").unwrap_err(),
+            "Line 1: missing 'z' column in header"
+        );
+        assert!(parse_anchors_csv("id,x,y,z\n").is_err());
+    }
+}
@@ -570,12 +570,11 @@ async fn sample_inter_anchor_distances(
 
     while start.elapsed() < duration {
         let results = sender.send_to_all(&ips, Commands::tdoa_distances()).await;
-        for (ip, result) in results {
-            let resp = match result {
-                Ok(r) => r,
-                Err(_e) => continue,
+        for result in results {
+            let Some(resp) = result.response() else {
+                continue;
             };
-            let parsed: TdoaDistancesResponse = match parse_json_response(&resp, &ip) {
+            let parsed: TdoaDistancesResponse = match parse_json_response(resp, &result.ip) {
                 Ok(p) => p,
                 Err(_e) => continue,
             };
@@ -964,7 +963,7 @@ async fn apply_delays(endpoints: &[AnchorEndpoint], delays: &[u16], timeout_ms:
             continue;
         }
         let value = delays[ep.anchor_id as usize].to_string();
-        let cmd = Commands::write_param("uwb", "ADelay", &value);
+        let cmd = Commands::write_param("uwb", "ADelay", &value)?;
         send_command_with_retry(&ep.ip, &cmd, timeout, 1).await?;
 
         // Best-effort verify that the broadcast value updated (runtime propagation)
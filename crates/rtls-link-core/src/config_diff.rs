@@ -0,0 +1,446 @@
+//! Comparison of device configurations.
+//!
+//! Both configurations are flattened into firmware parameters with
+//! [`config_to_flat_params`], so anchors are compared slot by slot
+//! (`devId1`, `x1`, ...) rather than as a whole array. Presets are
+//! flattened into the parameters an upload would write, so comparing one
+//! against a device shows what uploading it would change.
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::config_params::{
+    config_to_flat_params, config_to_params, is_flat_anchor_key, location_to_params, ParamOptions,
+};
+use crate::types::{DeviceConfig, Preset, PresetType};
+
+type ParamTuple = (String, String, String);
+
+/// Parameters that identify one device rather than describe a setup
+const IDENTITY_PARAMS: &[(&str, &str)] =
+    &[("uwb", "devShortAddr"), ("uwb", "mavlinkTargetSystemId")];
+
+/// How [`diff_configs_with`] compares parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffOptions {
+    /// Leave out devShortAddr and mavlinkTargetSystemId, e.g. to compare
+    /// two devices of the same setup
+    pub ignore_identity: bool,
+    /// Compare values that parse as numbers numerically, so `1.50` equals `1.5`
+    pub numeric: bool,
+    /// Largest difference between two numbers still counted as equal
+    pub tolerance: f64,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignore_identity: false,
+            numeric: true,
+            tolerance: 0.0,
+        }
+    }
+}
+
+/// How a parameter differs between two configurations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigDiffKind {
+    /// Only set in the right-hand configuration
+    Added,
+    /// Only set in the left-hand configuration
+    Removed,
+    /// Set in both with different values
+    Changed,
+}
+
+/// A single differing parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiffEntry {
+    /// Parameter group (wifi, uwb, app)
+    pub group: String,
+    /// Parameter name as used by the firmware
+    pub name: String,
+    /// Value in the left-hand configuration
+    pub left: Option<String>,
+    /// Value in the right-hand configuration
+    pub right: Option<String>,
+    /// Kind of difference
+    pub kind: ConfigDiffKind,
+}
+
+/// List the parameters that differ between `a` (left) and `b` (right).
+///
+/// Entries follow the parameter order of `a`, then parameters only set in `b`.
+pub fn diff_configs(a: &DeviceConfig, b: &DeviceConfig) -> Vec<ConfigDiffEntry> {
+    diff_configs_with(a, b, &DiffOptions::default())
+}
+
+/// Like [`diff_configs`], compared as `options` describe
+pub fn diff_configs_with(
+    a: &DeviceConfig,
+    b: &DeviceConfig,
+    options: &DiffOptions,
+) -> Vec<ConfigDiffEntry> {
+    let flatten = |config: &DeviceConfig| -> Vec<ParamTuple> {
+        config_to_flat_params(config)
+            .into_iter()
+            .filter(|(group, name, _)| {
+                !options.ignore_identity
+                    || !IDENTITY_PARAMS.contains(&(group.as_str(), name.as_str()))
+            })
+            .collect()
+    };
+    diff_params(flatten(a), flatten(b), options)
+}
+
+/// List the parameters that differ between a preset (left) and the
+/// configuration read back from a device (right).
+///
+/// The preset side holds the parameters uploading it would write. The
+/// device side is limited to what the preset covers: devShortAddr is never
+/// compared, a locations preset only covers its own parameters, and anchor
+/// slots only count when the preset writes anchors. Anchor slots beyond the
+/// preset's anchors therefore show up as device-only.
+pub fn diff_preset(preset: &Preset, device: &DeviceConfig) -> Result<Vec<ConfigDiffEntry>, String> {
+    let left = match preset.preset_type {
+        PresetType::Full => preset
+            .config
+            .as_ref()
+            .ok_or("Preset has no config data")
+            .map(|config| config_to_params(config, ParamOptions::default()))?,
+        PresetType::Locations => preset
+            .locations
+            .as_ref()
+            .ok_or("Preset has no location data")
+            .map(|locations| location_to_params(locations, ParamOptions::default()))?,
+    }?;
+
+    let writes_anchors = left.iter().any(|(_, name, _)| name == "devId1");
+    let in_left = |group: &str, name: &str| left.iter().any(|(g, n, _)| g == group && n == name);
+    let right = config_to_flat_params(device)
+        .into_iter()
+        .filter(|(group, name, _)| {
+            let anchor_param =
+                group == "uwb" && (is_flat_anchor_key(name) || name == "anchorCount");
+            if anchor_param {
+                return writes_anchors;
+            }
+            match preset.preset_type {
+                PresetType::Full => !(group == "uwb" && name == "devShortAddr"),
+                PresetType::Locations => in_left(group, name),
+            }
+        })
+        .collect();
+
+    Ok(diff_params(left.into_vec(), right, &DiffOptions::default()))
+}
+
+fn diff_params(
+    left: Vec<ParamTuple>,
+    mut right: Vec<ParamTuple>,
+    options: &DiffOptions,
+) -> Vec<ConfigDiffEntry> {
+    let mut diff = Vec::new();
+
+    for (group, name, left_value) in left {
+        let position = right.iter().position(|(g, n, _)| *g == group && *n == name);
+        let right_value = position.map(|i| right.remove(i).2);
+        let kind = match &right_value {
+            None => ConfigDiffKind::Removed,
+            Some(value) if !values_match(value, &left_value, options) => ConfigDiffKind::Changed,
+            Some(_) => continue,
+        };
+        diff.push(ConfigDiffEntry {
+            group,
+            name,
+            left: Some(left_value),
+            right: right_value,
+            kind,
+        });
+    }
+
+    diff.extend(
+        right
+            .into_iter()
+            .map(|(group, name, value)| ConfigDiffEntry {
+                group,
+                name,
+                left: None,
+                right: Some(value),
+                kind: ConfigDiffKind::Added,
+            }),
+    );
+
+    diff
+}
+
+/// Compare parameter values; numerically, `100` and `100.0` are equal
+pub fn values_match(a: &str, b: &str, options: &DiffOptions) -> bool {
+    a == b
+        || options.numeric
+            && matches!(
+                (a.trim().parse::<f64>(), b.trim().parse::<f64>()),
+                (Ok(x), Ok(y)) if x == y || (x - y).abs() <= options.tolerance
+            )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AnchorConfig;
+
+    fn config(json: serde_json::Value) -> DeviceConfig {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn anchor(id: &str, x: f64) -> AnchorConfig {
+        AnchorConfig {
+            id: id.to_string(),
+            x,
+            y: 0.0,
+            z: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_identical_configs_have_no_diff() {
+        let a = config(serde_json::json!({
+            "wifi": {"mode": 1, "ssidST": "net"},
+            "uwb": {"mode": 4, "devShortAddr": "1", "channel": 5},
+            "app": {}
+        }));
+
+        assert!(diff_configs(&a, &a.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_added_removed_and_changed() {
+        let a = config(serde_json::json!({
+            "wifi": {"mode": 1, "ssidST": "net"},
+            "uwb": {"mode": 4, "devShortAddr": "1", "channel": 5},
+            "app": {}
+        }));
+        let b = config(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 4, "devShortAddr": "2", "channel": 5},
+            "app": {"led2Pin": 4}
+        }));
+
+        let diff = diff_configs(&a, &b);
+
+        assert_eq!(diff.len(), 3);
+        assert_eq!(diff[0].name, "ssidST");
+        assert_eq!(diff[0].kind, ConfigDiffKind::Removed);
+        assert_eq!(diff[0].right, None);
+        assert_eq!(diff[1].name, "devShortAddr");
+        assert_eq!(diff[1].kind, ConfigDiffKind::Changed);
+        assert_eq!(diff[1].left.as_deref(), Some("1"));
+        assert_eq!(diff[1].right.as_deref(), Some("2"));
+        assert_eq!(diff[2].group, "app");
+        assert_eq!(diff[2].kind, ConfigDiffKind::Added);
+        assert_eq!(diff[2].right.as_deref(), Some("4"));
+    }
+
+    #[test]
+    fn test_anchors_are_compared_element_wise() {
+        let mut a = config(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 4, "devShortAddr": "1"},
+            "app": {}
+        }));
+        let mut b = a.clone();
+        a.uwb.anchors = Some(vec![anchor("0", 0.0), anchor("1", 4.0)]);
+        b.uwb.anchors = Some(vec![anchor("0", 0.0), anchor("1", 4.5), anchor("2", 2.0)]);
+
+        let diff = diff_configs(&a, &b);
+        let names: Vec<&str> = diff.iter().map(|d| d.name.as_str()).collect();
+
+        assert_eq!(names, ["x2", "devId3", "x3", "y3", "z3"]);
+        assert_eq!(diff[0].left.as_deref(), Some("4"));
+        assert_eq!(diff[0].right.as_deref(), Some("4.5"));
+        assert!(diff[1..].iter().all(|d| d.kind == ConfigDiffKind::Added));
+    }
+
+    #[test]
+    fn test_numeric_strings_compare_numerically_unless_disabled() {
+        let param = |value: &str| {
+            vec![(
+                "uwb".to_string(),
+                "originAlt".to_string(),
+                value.to_string(),
+            )]
+        };
+        let exact = DiffOptions {
+            numeric: false,
+            ..DiffOptions::default()
+        };
+
+        assert!(diff_params(param("1.50"), param("1.5"), &DiffOptions::default()).is_empty());
+        assert!(diff_params(param(" 2"), param("2.0"), &DiffOptions::default()).is_empty());
+        assert_eq!(diff_params(param("1.50"), param("1.5"), &exact).len(), 1);
+        assert_eq!(
+            diff_params(param("1.5x"), param("1.5"), &DiffOptions::default()).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_float_tolerance() {
+        let a = config(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 4, "devShortAddr": "1", "rotationDegrees": 90.0},
+            "app": {}
+        }));
+        let mut b = a.clone();
+        b.uwb.rotation_degrees = Some(90.004);
+        let tolerant = DiffOptions {
+            tolerance: 0.01,
+            ..DiffOptions::default()
+        };
+
+        assert_eq!(diff_configs(&a, &b).len(), 1);
+        assert!(diff_configs_with(&a, &b, &tolerant).is_empty());
+
+        b.uwb.rotation_degrees = Some(90.02);
+        let diff = diff_configs_with(&a, &b, &tolerant);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].right.as_deref(), Some("90.02"));
+    }
+
+    #[test]
+    fn test_identity_fields_can_be_ignored() {
+        let a = config(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 4, "devShortAddr": "1", "mavlinkTargetSystemId": 1, "channel": 5},
+            "app": {}
+        }));
+        let mut b = a.clone();
+        b.uwb.dev_short_addr = "2".to_string();
+        b.uwb.mavlink_target_system_id = Some(2);
+
+        assert_eq!(diff_configs(&a, &b).len(), 2);
+        let options = DiffOptions {
+            ignore_identity: true,
+            ..DiffOptions::default()
+        };
+        assert!(diff_configs_with(&a, &b, &options).is_empty());
+
+        b.uwb.channel = Some(9);
+        let diff = diff_configs_with(&a, &b, &options);
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].name, "channel");
+    }
+
+    #[test]
+    fn test_fewer_anchors_show_as_removed() {
+        let a = tag_with_anchors(5);
+        let b = tag_with_anchors(3);
+
+        let diff = diff_configs(&a, &b);
+        let summary: Vec<(&str, ConfigDiffKind)> =
+            diff.iter().map(|d| (d.name.as_str(), d.kind)).collect();
+
+        assert_eq!(summary[0], ("anchorCount", ConfigDiffKind::Changed));
+        assert_eq!(diff[0].left.as_deref(), Some("5"));
+        assert_eq!(diff[0].right.as_deref(), Some("3"));
+        assert_eq!(summary.len(), 1 + 2 * 4);
+        assert!(summary[1..]
+            .iter()
+            .all(|&(_, kind)| kind == ConfigDiffKind::Removed));
+        assert_eq!(summary.last().unwrap().0, "z5");
+    }
+
+    fn tag_with_anchors(count: usize) -> DeviceConfig {
+        let mut device = config(serde_json::json!({
+            "wifi": {"mode": 1, "ssidST": "net"},
+            "uwb": {"mode": 4, "devShortAddr": "7", "use2DEstimator": 1,
+                    "originLat": 41.5, "originLon": 2.0, "originAlt": 100.0, "rotationDegrees": 0.0},
+            "app": {}
+        }));
+        let layout = [(0.0, 0.0), (5.0, 0.0), (5.0, 4.0), (0.0, 4.0), (2.5, 6.0)];
+        let anchors: Vec<AnchorConfig> = layout[..count]
+            .iter()
+            .enumerate()
+            .map(|(i, &(x, y))| AnchorConfig {
+                id: i.to_string(),
+                x,
+                y,
+                z: 1.5,
+            })
+            .collect();
+        device.uwb.anchor_count = Some(count as u8);
+        device.uwb.anchors = Some(anchors);
+        device
+    }
+
+    fn preset(preset_type: PresetType, device: &DeviceConfig) -> Preset {
+        Preset {
+            name: "site".to_string(),
+            description: None,
+            config: (preset_type == PresetType::Full).then(|| device.clone()),
+            locations: (preset_type == PresetType::Locations)
+                .then(|| crate::types::LocationData::from_config(device)),
+            preset_type,
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_preset_matching_device_has_no_diff() {
+        let device = tag_with_anchors(4);
+
+        assert!(diff_preset(&preset(PresetType::Full, &device), &device)
+            .unwrap()
+            .is_empty());
+        assert!(
+            diff_preset(&preset(PresetType::Locations, &device), &device)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_locations_preset_against_drifted_device() {
+        let source = tag_with_anchors(4);
+        let mut device = tag_with_anchors(5);
+        device.uwb.origin_lat = Some(41.6);
+        device.wifi.ssid_s_t = Some("other".to_string());
+
+        let diff = diff_preset(&preset(PresetType::Locations, &source), &device).unwrap();
+        let summary: Vec<(&str, ConfigDiffKind)> =
+            diff.iter().map(|d| (d.name.as_str(), d.kind)).collect();
+
+        // wifi is outside a locations preset; the fifth anchor is device-only
+        assert_eq!(
+            summary,
+            [
+                ("originLat", ConfigDiffKind::Changed),
+                ("anchorCount", ConfigDiffKind::Changed),
+                ("devId5", ConfigDiffKind::Added),
+                ("x5", ConfigDiffKind::Added),
+                ("y5", ConfigDiffKind::Added),
+                ("z5", ConfigDiffKind::Added),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_full_preset_ignores_device_identity() {
+        let source = tag_with_anchors(4);
+        let mut device = source.clone();
+        device.uwb.dev_short_addr = "9".to_string();
+        device.uwb.channel = Some(5);
+
+        let diff = diff_preset(&preset(PresetType::Full, &source), &device).unwrap();
+
+        assert_eq!(diff.len(), 1);
+        assert_eq!(diff[0].name, "channel");
+        assert_eq!(diff[0].kind, ConfigDiffKind::Added);
+    }
+}
@@ -0,0 +1,583 @@
+//! Validation of device configurations and anchor layouts.
+//!
+//! Catches out-of-range values before a config is stored or applied, so a
+//! bad value is reported up front instead of by the device rejecting
+//! writes one parameter at a time. Anchor layouts are also checked for
+//! geometry that trilateration cannot solve, which otherwise only shows up
+//! later as tags that never converge.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ValidationError;
+use crate::types::{AnchorConfig, Device, DeviceConfig, DeviceRole, LocationData};
+
+/// Anchors closer together than this are treated as the same position (m)
+const MIN_ANCHOR_SEPARATION_M: f64 = 0.01;
+
+/// Anchors within this distance of a common line are treated as collinear (m)
+const COLLINEAR_TOLERANCE_M: f64 = 0.05;
+
+/// How serious a validation issue is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    /// The device would reject the config
+    Error,
+    /// Accepted by the device, but probably not intended
+    Warning,
+}
+
+/// A problem found in a configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// How serious the issue is
+    pub severity: IssueSeverity,
+    /// Offending field, e.g. `uwb.channel` or `uwb.anchors[2].id`
+    pub field: String,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl ValidationIssue {
+    fn error(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Error,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: IssueSeverity::Warning,
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Whether this issue makes the config invalid
+    pub fn is_error(&self) -> bool {
+        self.severity == IssueSeverity::Error
+    }
+}
+
+impl From<ValidationError> for ValidationIssue {
+    fn from(error: ValidationError) -> Self {
+        let message = match &error {
+            ValidationError::OutOfRange {
+                value, min, max, ..
+            } => format!("{} is out of range ({} to {})", value, min, max),
+            ValidationError::LatLonSwapped { lat, lon } => format!(
+                "{} is out of range (-90 to 90); latitude and longitude ({}) look swapped",
+                lat, lon
+            ),
+        };
+        Self::error(error.field(), message)
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Check a configuration for invalid or suspicious values.
+///
+/// Returns every issue found; the config is valid if none is an error.
+pub fn validate_config(config: &DeviceConfig) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let uwb = &config.uwb;
+
+    check_enum(&mut issues, "wifi.mode", Some(config.wifi.mode), 0, 1);
+    check_enum(&mut issues, "uwb.mode", Some(uwb.mode), 3, 4);
+    check_enum(&mut issues, "uwb.uwbEnable", uwb.uwb_enable, 0, 1);
+    check_enum(&mut issues, "uwb.outputBackend", uwb.output_backend, 0, 1);
+    check_enum(&mut issues, "uwb.zCalcMode", uwb.z_calc_mode, 0, 2);
+    check_enum(
+        &mut issues,
+        "uwb.tdoaEstimatorMode",
+        uwb.tdoa_estimator_mode,
+        0,
+        2,
+    );
+    check_enum(
+        &mut issues,
+        "uwb.tdoaEstimatorDiag",
+        uwb.tdoa_estimator_diag,
+        0,
+        2,
+    );
+    check_enum(
+        &mut issues,
+        "uwb.tdoaMatcherPolicy",
+        uwb.tdoa_matcher_policy,
+        0,
+        1,
+    );
+    check_enum(
+        &mut issues,
+        "uwb.use2DEstimator",
+        uwb.use_2d_estimator,
+        0,
+        1,
+    );
+    check_enum(&mut issues, "uwb.channel", uwb.channel, 1, 7);
+    check_enum(&mut issues, "uwb.dwMode", uwb.dw_mode, 0, 7);
+    check_enum(&mut issues, "uwb.txPowerLevel", uwb.tx_power_level, 0, 3);
+
+    if uwb.channel == Some(6) {
+        issues.push(ValidationIssue::warning(
+            "uwb.channel",
+            "Channel 6 is not supported by the DW1000 radio",
+        ));
+    }
+
+    check_coordinate(&mut issues, "uwb.originLat", uwb.origin_lat, 90.0);
+    check_coordinate(&mut issues, "uwb.originLon", uwb.origin_lon, 180.0);
+    if let Some(alt) = uwb.origin_alt {
+        if !alt.is_finite() {
+            issues.push(ValidationIssue::error(
+                "uwb.originAlt",
+                "Must be a finite number",
+            ));
+        }
+    }
+
+    check_anchors(config, &mut issues);
+
+    issues
+}
+
+fn check_enum(issues: &mut Vec<ValidationIssue>, field: &str, value: Option<u8>, min: u8, max: u8) {
+    if let Some(value) = value {
+        if !(min..=max).contains(&value) {
+            issues.push(ValidationIssue::error(
+                field,
+                format!("{} is out of range ({}-{})", value, min, max),
+            ));
+        }
+    }
+}
+
+fn check_coordinate(
+    issues: &mut Vec<ValidationIssue>,
+    field: &str,
+    value: Option<f64>,
+    limit: f64,
+) {
+    if let Some(value) = value {
+        if !value.is_finite() || value.abs() > limit {
+            issues.push(ValidationIssue::error(
+                field,
+                format!("{} is out of range (-{} to {})", value, limit, limit),
+            ));
+        }
+    }
+}
+
+/// Check that `config` keeps `device` in the role it is broadcasting.
+///
+/// Returns a warning when, say, a tag config is about to be applied to a
+/// device currently running as an anchor. Nothing is reported when either
+/// role is unknown.
+pub fn check_role_matches_config(
+    device: &Device,
+    config: &DeviceConfig,
+) -> Option<ValidationIssue> {
+    let config_role = DeviceRole::from_mode(config.uwb.mode);
+    if device.role == DeviceRole::Unknown
+        || config_role == DeviceRole::Unknown
+        || device.role == config_role
+    {
+        return None;
+    }
+    Some(ValidationIssue::warning(
+        "uwb.mode",
+        format!(
+            "{} is running as {} but the config makes it {}",
+            device.ip, device.role, config_role
+        ),
+    ))
+}
+
+/// Check an anchor layout, e.g. from a locations preset.
+///
+/// Besides the per-anchor checks applied to configs, this looks at the
+/// geometry as a whole: anchors sharing a position, too few anchors for the
+/// estimator, and anchors that all lie on one line in the horizontal plane.
+/// Returns every issue found; the layout is valid if none is an error.
+pub fn validate_locations(locations: &LocationData) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let anchors = &locations.anchors;
+
+    if let Err(e) = locations.origin.validate() {
+        issues.push(ValidationIssue::from(e));
+    }
+    if !locations.rotation.is_finite() {
+        issues.push(ValidationIssue::error(
+            "rotation",
+            "Must be a finite number",
+        ));
+    }
+
+    check_anchor_entries(&mut issues, "anchors", anchors);
+
+    for (i, anchor) in anchors.iter().enumerate() {
+        if let Some(other) = anchors[..i]
+            .iter()
+            .find(|other| distance(anchor, other) < MIN_ANCHOR_SEPARATION_M)
+        {
+            issues.push(ValidationIssue::error(
+                format!("anchors[{}]", i),
+                format!("Same position as anchor '{}'", other.id.trim()),
+            ));
+        }
+    }
+
+    let (dimensions, recommended) = if locations.use_2d_estimator == Some(1) {
+        ("2D", 3)
+    } else {
+        ("3D", 4)
+    };
+    if anchors.len() < 2 {
+        issues.push(ValidationIssue::error(
+            "anchors",
+            format!("At least 2 anchors are required, got {}", anchors.len()),
+        ));
+    } else if anchors.len() < recommended {
+        issues.push(ValidationIssue::warning(
+            "anchors",
+            format!(
+                "{} positioning needs at least {} anchors, got {}",
+                dimensions,
+                recommended,
+                anchors.len()
+            ),
+        ));
+    }
+
+    if anchors.len() >= 3 && anchors_collinear(anchors) {
+        issues.push(ValidationIssue::error(
+            "anchors",
+            "All anchors lie on one line; positions cannot be solved",
+        ));
+    }
+
+    issues
+}
+
+fn distance(a: &AnchorConfig, b: &AnchorConfig) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+}
+
+/// Whether all anchors lie on one line in the horizontal plane
+fn anchors_collinear(anchors: &[AnchorConfig]) -> bool {
+    let first = &anchors[0];
+    let horizontal = |a: &AnchorConfig| (a.x - first.x).hypot(a.y - first.y);
+    let Some(far) = anchors
+        .iter()
+        .max_by(|a, b| horizontal(a).total_cmp(&horizontal(b)))
+    else {
+        return false;
+    };
+
+    let length = horizontal(far);
+    if length < MIN_ANCHOR_SEPARATION_M {
+        // Stacked vertically: trivially on one line
+        return true;
+    }
+    let (dx, dy) = (far.x - first.x, far.y - first.y);
+    anchors.iter().all(|a| {
+        let offset = (dx * (a.y - first.y) - dy * (a.x - first.x)).abs() / length;
+        offset < COLLINEAR_TOLERANCE_M
+    })
+}
+
+/// Per-anchor checks: empty or duplicate ids and non-finite coordinates
+fn check_anchor_entries(issues: &mut Vec<ValidationIssue>, field: &str, anchors: &[AnchorConfig]) {
+    let mut seen = HashSet::new();
+    for (i, anchor) in anchors.iter().enumerate() {
+        let id = anchor.id.trim();
+        if id.is_empty() {
+            issues.push(ValidationIssue::error(
+                format!("{}[{}].id", field, i),
+                "Anchor ID is empty",
+            ));
+        } else if !seen.insert(id) {
+            issues.push(ValidationIssue::error(
+                format!("{}[{}].id", field, i),
+                format!("Duplicate anchor ID '{}'", id),
+            ));
+        }
+        if !anchor.x.is_finite() || !anchor.y.is_finite() || !anchor.z.is_finite() {
+            issues.push(ValidationIssue::error(
+                format!("{}[{}]", field, i),
+                "Anchor coordinates must be finite numbers",
+            ));
+        }
+    }
+}
+
+fn check_anchors(config: &DeviceConfig, issues: &mut Vec<ValidationIssue>) {
+    let uwb = &config.uwb;
+    let anchors = uwb.anchors.as_deref().unwrap_or_default();
+
+    check_anchor_entries(issues, "uwb.anchors", anchors);
+
+    match (uwb.anchor_count, &uwb.anchors) {
+        (Some(count), Some(list)) if count as usize != list.len() => {
+            issues.push(ValidationIssue::error(
+                "uwb.anchorCount",
+                format!("{} does not match the {} anchors listed", count, list.len()),
+            ));
+        }
+        (Some(count), None) if count > 0 => {
+            issues.push(ValidationIssue::warning(
+                "uwb.anchorCount",
+                format!("{} anchors expected but none are listed", count),
+            ));
+        }
+        _ => {}
+    }
+
+    // Static tags cannot be applied without anchor geometry
+    if uwb.mode == 4 && uwb.dynamic_anchor_pos_enabled != Some(1) && anchors.is_empty() {
+        issues.push(ValidationIssue::warning(
+            "uwb.anchors",
+            "Tag has no anchor positions",
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GpsOrigin;
+
+    fn config(json: serde_json::Value) -> DeviceConfig {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn anchor(id: &str) -> AnchorConfig {
+        AnchorConfig {
+            id: id.to_string(),
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn anchor_config() -> DeviceConfig {
+        config(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 3, "devShortAddr": "0", "channel": 5, "txPowerLevel": 3},
+            "app": {}
+        }))
+    }
+
+    fn fields(issues: &[ValidationIssue]) -> Vec<&str> {
+        issues.iter().map(|i| i.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_config_has_no_issues() {
+        assert!(validate_config(&anchor_config()).is_empty());
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_errors() {
+        let mut config = anchor_config();
+        config.uwb.mode = 9;
+        config.uwb.channel = Some(12);
+        config.uwb.tx_power_level = Some(4);
+        config.uwb.origin_lat = Some(91.0);
+        config.uwb.origin_lon = Some(f64::NAN);
+
+        let issues = validate_config(&config);
+
+        assert!(issues.iter().all(ValidationIssue::is_error));
+        assert_eq!(
+            fields(&issues),
+            [
+                "uwb.mode",
+                "uwb.channel",
+                "uwb.txPowerLevel",
+                "uwb.originLat",
+                "uwb.originLon"
+            ]
+        );
+        assert_eq!(
+            issues[1].to_string(),
+            "uwb.channel: 12 is out of range (1-7)"
+        );
+    }
+
+    #[test]
+    fn test_role_mismatch_is_a_warning() {
+        let device = |role: &str| -> Device {
+            serde_json::from_value(serde_json::json!({
+                "ip": "10.0.0.7", "id": "rtls-7", "role": role, "mac": "AA:BB:CC:DD:EE:07",
+                "uwbShort": "7", "mavSysId": 7, "firmware": "2.3.1"
+            }))
+            .unwrap()
+        };
+        let with_mode = |mode: u8| {
+            config(serde_json::json!({
+                "wifi": {"mode": 1},
+                "uwb": {"mode": mode, "devShortAddr": "7"},
+                "app": {}
+            }))
+        };
+
+        let issue = check_role_matches_config(&device("anchor_tdoa"), &with_mode(4)).unwrap();
+        assert!(!issue.is_error());
+        assert_eq!(issue.field, "uwb.mode");
+        assert_eq!(
+            issue.message,
+            "10.0.0.7 is running as Anchor (TDoA) but the config makes it Tag (TDoA)"
+        );
+        assert!(check_role_matches_config(&device("tag_tdoa"), &with_mode(3)).is_some());
+
+        assert!(check_role_matches_config(&device("tag_tdoa"), &with_mode(4)).is_none());
+        assert!(check_role_matches_config(&device("anchor_tdoa"), &with_mode(3)).is_none());
+        assert!(check_role_matches_config(&device("unknown"), &with_mode(4)).is_none());
+        assert!(check_role_matches_config(&device("anchor_tdoa"), &with_mode(0)).is_none());
+    }
+
+    #[test]
+    fn test_anchor_checks() {
+        let mut config = anchor_config();
+        config.uwb.mode = 4;
+        config.uwb.anchors = Some(vec![anchor("0"), anchor("1"), anchor(" 1 ")]);
+        config.uwb.anchor_count = Some(4);
+
+        let issues = validate_config(&config);
+
+        assert_eq!(fields(&issues), ["uwb.anchors[2].id", "uwb.anchorCount"]);
+        assert!(issues.iter().all(ValidationIssue::is_error));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let mut config = anchor_config();
+        config.uwb.mode = 4;
+        config.uwb.channel = Some(6);
+        config.uwb.anchor_count = Some(3);
+
+        let issues = validate_config(&config);
+
+        assert_eq!(
+            fields(&issues),
+            ["uwb.channel", "uwb.anchorCount", "uwb.anchors"]
+        );
+        assert!(!issues.iter().any(ValidationIssue::is_error));
+    }
+
+    fn placed(id: &str, x: f64, y: f64, z: f64) -> AnchorConfig {
+        AnchorConfig {
+            id: id.to_string(),
+            x,
+            y,
+            z,
+        }
+    }
+
+    fn locations(anchors: Vec<AnchorConfig>) -> LocationData {
+        LocationData {
+            origin: GpsOrigin {
+                lat: 41.4036,
+                lon: 2.1744,
+                alt: 100.0,
+            },
+            rotation: 0.0,
+            anchors,
+            use_2d_estimator: None,
+        }
+    }
+
+    #[test]
+    fn test_valid_locations_have_no_issues() {
+        let layout = locations(vec![
+            placed("0", 0.0, 0.0, 1.5),
+            placed("1", 6.0, 0.0, 2.5),
+            placed("2", 6.0, 4.0, 1.5),
+            placed("3", 0.0, 4.0, 2.5),
+        ]);
+
+        assert!(validate_locations(&layout).is_empty());
+    }
+
+    #[test]
+    fn test_locations_geometry_errors() {
+        let mut layout = locations(vec![
+            placed("0", 0.0, 0.0, 1.5),
+            placed("1", 3.0, 3.0, 1.5),
+            placed("1", 3.0, 3.0, 1.5),
+            placed("3", 6.02, 6.0, 2.0),
+        ]);
+        layout.origin.lat = -95.0;
+
+        let issues = validate_locations(&layout);
+
+        assert!(issues.iter().all(ValidationIssue::is_error));
+        assert_eq!(
+            fields(&issues),
+            ["origin.lat", "anchors[2].id", "anchors[2]", "anchors"]
+        );
+        assert_eq!(issues[2].message, "Same position as anchor '1'");
+    }
+
+    #[test]
+    fn test_locations_anchor_count() {
+        let issues = validate_locations(&locations(vec![placed("0", 0.0, 0.0, 1.0)]));
+        assert_eq!(fields(&issues), ["anchors"]);
+        assert!(issues[0].is_error());
+
+        let mut layout = locations(vec![
+            placed("0", 0.0, 0.0, 1.0),
+            placed("1", 5.0, 0.0, 1.0),
+            placed("2", 0.0, 5.0, 1.0),
+        ]);
+        let issues = validate_locations(&layout);
+        assert_eq!(issues.len(), 1);
+        assert!(!issues[0].is_error());
+        assert!(issues[0].message.starts_with("3D positioning"));
+
+        layout.use_2d_estimator = Some(1);
+        assert!(validate_locations(&layout).is_empty());
+    }
+
+    #[test]
+    fn test_locations_origin_errors() {
+        let mut layout = locations(vec![
+            placed("0", 0.0, 0.0, 1.5),
+            placed("1", 6.0, 0.0, 2.5),
+            placed("2", 6.0, 4.0, 1.5),
+            placed("3", 0.0, 4.0, 2.5),
+        ]);
+        layout.origin.alt = -100_000.0;
+        let issues = validate_locations(&layout);
+        assert_eq!(fields(&issues), ["origin.alt"]);
+        assert_eq!(
+            issues[0].message,
+            "-100000 is out of range (-1000 to 10000)"
+        );
+
+        layout.origin = GpsOrigin {
+            lat: 2.1744,
+            lon: 141.4036,
+            alt: 100.0,
+        };
+        assert!(validate_locations(&layout).is_empty());
+        layout.origin.lat = 141.4036;
+        layout.origin.lon = 2.1744;
+        let issues = validate_locations(&layout);
+        assert_eq!(fields(&issues), ["origin.lat"]);
+        assert!(issues[0].message.contains("look swapped"));
+    }
+}
@@ -1,16 +1,18 @@
 //! UDP MAVLink client for device management.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, VecDeque};
 use std::io::Cursor;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
 use tokio::net::UdpSocket;
 use tokio::time::{timeout, Instant};
 
+use crate::config_diff::{values_match, DiffOptions};
+use crate::device::probe::{partition_reachable, DEVICE_HTTP_PORT};
 use crate::error::{CoreError, DeviceError};
 use crate::mavlink::params;
 use crate::mavlink::rtlslink::{
@@ -21,8 +23,13 @@ use crate::mavlink::rtlslink::{
 use crate::mavlink::types::CharArray;
 use crate::mavlink::{peek_reader::PeekReader, read_v2_msg, write_v2_msg, MavHeader};
 use crate::protocol::binary::decode_command_frame;
-use crate::protocol::commands::is_structured_response_command;
-use crate::protocol::response::is_error_response;
+use crate::protocol::commands::{is_structured_response_command, Commands, WRITE_BATCH_COMMAND};
+use crate::protocol::config_params::{ParamPlan, PresetUploadPlan};
+use crate::protocol::response::{
+    is_error_response, parse_ack, parse_firmware_info, parse_write_batch_response, write_failures,
+    CommandAck, ParamMismatch, ParamWriteResult,
+};
+use crate::types::{DeviceCapabilities, FirmwareInfo};
 
 pub const MAVLINK_MANAGEMENT_PORT: u16 = 3333;
 
@@ -31,6 +38,20 @@ const MANAGER_COMPONENT_ID: u8 = 190;
 const TARGET_SYSTEM_BROADCAST: u8 = 0;
 const TARGET_COMPONENT_BROADCAST: u8 = 0;
 const PARAM_LIST_IDLE_TIMEOUT: Duration = Duration::from_millis(250);
+/// Parameter writes of a batch that may be awaiting their ack at once,
+/// small enough not to overrun the device's UDP receive queue
+const WRITE_BATCH_WINDOW: usize = 8;
+/// Largest difference between a number written and the one read back that
+/// still counts as a match; the firmware keeps some values as floats
+const VERIFY_TOLERANCE: f64 = 1e-5;
+/// Longest wait between two attempts of a command, before jitter
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
+/// Reconnects per command of a [`DeviceConnection::connect_resilient`]
+/// connection
+pub const DEFAULT_RECONNECT_ATTEMPTS: usize = 3;
+/// Wait before each reconnect of a
+/// [`DeviceConnection::connect_resilient`] connection
+pub const DEFAULT_RECONNECT_DELAY: Duration = Duration::from_millis(500);
 
 static REQUEST_COUNTER: AtomicU32 = AtomicU32::new(1);
 
@@ -40,31 +61,146 @@ pub struct DeviceCommandResponse {
     pub raw: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub json: Option<serde_json::Value>,
+    pub ack: CommandAck,
+    /// Times the command was sent before this response came back
+    #[serde(default = "first_attempt")]
+    pub attempts: usize,
+}
+
+fn first_attempt() -> usize {
+    1
+}
+
+/// A command of a batch, optionally with its own response timeout
+///
+/// Commands without one use the connection's timeout, so a batch can give
+/// a slow command such as `save-config-as` (a flash erase) more time
+/// without raising it for every quick write.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchCommand {
+    pub command: String,
+    pub timeout: Option<Duration>,
+}
+
+impl BatchCommand {
+    pub fn with_timeout(command: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            command: command.into(),
+            timeout: Some(timeout),
+        }
+    }
+}
+
+impl From<String> for BatchCommand {
+    fn from(command: String) -> Self {
+        Self {
+            command,
+            timeout: None,
+        }
+    }
+}
+
+impl From<&str> for BatchCommand {
+    fn from(command: &str) -> Self {
+        command.to_string().into()
+    }
+}
+
+/// Outcome of one command of a batch sent with
+/// [`DeviceConnection::send_batch_continuing`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandResult {
+    /// Position of the command in the batch, from 0
+    pub index: usize,
+    pub command: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<DeviceCommandResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Not sent because the batch was cancelled
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+}
+
+impl CommandResult {
+    pub fn new(
+        index: usize,
+        command: &str,
+        result: Result<DeviceCommandResponse, CoreError>,
+    ) -> Self {
+        let (response, error) = match result {
+            Ok(response) => (Some(response), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        Self {
+            index,
+            command: command.to_string(),
+            success: error.is_none(),
+            response,
+            error,
+            cancelled: false,
+        }
+    }
+
+    /// A command left unsent because the batch was cancelled
+    pub fn cancelled(index: usize, command: &str) -> Self {
+        Self {
+            index,
+            command: command.to_string(),
+            success: false,
+            response: None,
+            error: Some("Cancelled before sending".to_string()),
+            cancelled: true,
+        }
+    }
 }
 
+/// Attach the JSON found in a command's reply, if any
+///
+/// Any reply holding a JSON object or array gets it parsed. Commands known
+/// to return structured replies must do so; for them a reply without JSON
+/// is an error.
 fn parse_command_response(
     command: &str,
     raw: String,
     device_ip: &str,
 ) -> Result<DeviceCommandResponse, CoreError> {
-    if is_structured_response_command(command) {
-        let json: serde_json::Value =
-            crate::protocol::response::parse_json_response(&raw, device_ip)
-                .map_err(CoreError::from)?;
-        Ok(DeviceCommandResponse {
-            raw,
-            json: Some(json),
-        })
-    } else {
-        Ok(DeviceCommandResponse { raw, json: None })
-    }
+    let json = crate::protocol::response::parse_json_response::<serde_json::Value>(&raw, device_ip);
+    let json = match json {
+        Ok(json) => Some(json),
+        Err(e) if is_structured_response_command(command) => return Err(e.into()),
+        Err(_) => None,
+    };
+    let ack = parse_ack(&raw);
+    Ok(DeviceCommandResponse {
+        raw,
+        json,
+        ack,
+        attempts: first_attempt(),
+    })
 }
 
 pub struct DeviceConnection {
     ip: String,
+    target: SocketAddr,
     timeout: Duration,
     socket: UdpSocket,
     sequence: u8,
+    capabilities: DeviceCapabilities,
+    reconnect_attempts: usize,
+    reconnect_delay: Duration,
+    reconnects: usize,
+    pacing: Option<Duration>,
+    sent_any: bool,
+}
+
+#[derive(Deserialize)]
+struct BatchWrite {
+    group: String,
+    name: String,
+    value: String,
 }
 
 impl DeviceConnection {
@@ -72,6 +208,15 @@ impl DeviceConnection {
         Self::connect_to_port(ip, MAVLINK_MANAGEMENT_PORT, cmd_timeout).await
     }
 
+    /// Connect with reconnecting enabled, for long unattended operations
+    /// such as applying a config, where one dropped packet should not fail
+    /// the whole run
+    pub async fn connect_resilient(ip: &str, cmd_timeout: Duration) -> Result<Self, CoreError> {
+        let mut conn = Self::connect(ip, cmd_timeout).await?;
+        conn.set_reconnect(DEFAULT_RECONNECT_ATTEMPTS, DEFAULT_RECONNECT_DELAY);
+        Ok(conn)
+    }
+
     async fn connect_to_port(
         ip: &str,
         port: u16,
@@ -80,24 +225,91 @@ impl DeviceConnection {
         let target: SocketAddr = format!("{ip}:{port}")
             .parse()
             .map_err(|e| CoreError::Other(format!("Invalid MAVLink target {ip}: {e}")))?;
-        let socket = UdpSocket::bind("0.0.0.0:0").await?;
-        socket.connect(target).await?;
+        let socket = bind_to(target).await?;
 
         Ok(Self {
             ip: ip.to_string(),
+            target,
             timeout: cmd_timeout,
             socket,
             sequence: 0,
+            capabilities: dialect_capabilities(FirmwareInfo::default()),
+            reconnect_attempts: 0,
+            reconnect_delay: DEFAULT_RECONNECT_DELAY,
+            reconnects: 0,
+            pacing: None,
+            sent_any: false,
         })
     }
 
+    /// Change how long each later command waits for a response
+    pub fn set_timeout(&mut self, cmd_timeout: Duration) {
+        self.timeout = cmd_timeout;
+    }
+
+    /// Reopen the socket and resend a command up to `attempts` times when
+    /// it times out or hits a transport error, waiting `delay` first
+    ///
+    /// Off (0 attempts) by default. Only parameter reads and writes are
+    /// resent: a lost reply to anything else, such as `delete-config` or
+    /// `save-config-as`, may mean the command already ran. Errors the device
+    /// reports are never retried.
+    pub fn set_reconnect(&mut self, attempts: usize, delay: Duration) {
+        self.reconnect_attempts = attempts;
+        self.reconnect_delay = delay;
+    }
+
+    /// Times the socket was reopened since connecting
+    pub fn reconnects(&self) -> usize {
+        self.reconnects
+    }
+
+    /// Wait `pacing` between one command's reply and sending the next, for
+    /// devices that drop frames when commands arrive back to back
+    ///
+    /// The first command is sent right away. While pacing, `write-batch` is
+    /// skipped because it pipelines several writes at once; parameters are
+    /// written one paced `write` at a time instead.
+    pub fn set_pacing(&mut self, pacing: Option<Duration>) {
+        self.pacing = pacing.filter(|pacing| !pacing.is_zero());
+    }
+
     pub async fn send_raw(&mut self, command: &str) -> Result<String, CoreError> {
+        if let Some(pacing) = self.pacing {
+            if self.sent_any {
+                tokio::time::sleep(pacing).await;
+            }
+        }
+        self.sent_any = true;
+
+        let repeatable = is_param_command(command);
+        let mut reconnects = 0;
+        loop {
+            match self.send_raw_once(command).await {
+                Err(e)
+                    if repeatable
+                        && reconnects < self.reconnect_attempts
+                        && is_transport_error(&e) =>
+                {
+                    tokio::time::sleep(self.reconnect_delay).await;
+                    self.socket = bind_to(self.target).await?;
+                    self.reconnects += 1;
+                    reconnects += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send_raw_once(&mut self, command: &str) -> Result<String, CoreError> {
         let response = if command.starts_with("readall") {
             self.handle_read_all(command).await?
         } else if command.starts_with("read ") {
             self.handle_read(command).await?
         } else if command.starts_with("write ") {
             self.handle_write(command).await?
+        } else if let Some(payload) = command.strip_prefix(WRITE_BATCH_COMMAND) {
+            self.handle_write_batch(payload).await?
         } else {
             self.handle_rtls_command(command).await?
         };
@@ -113,21 +325,235 @@ impl DeviceConnection {
     }
 
     pub async fn send(&mut self, command: &str) -> Result<DeviceCommandResponse, CoreError> {
+        let reconnects = self.reconnects;
         let raw = self.send_raw(command).await?;
-        parse_command_response(command, raw, &self.ip)
+        let mut response = parse_command_response(command, raw, &self.ip)?;
+        response.attempts = self.reconnects - reconnects + 1;
+        Ok(response)
+    }
+
+    /// Send `command`, waiting `cmd_timeout` for its response instead of
+    /// the connection's timeout when given
+    pub async fn send_with_timeout(
+        &mut self,
+        command: &str,
+        cmd_timeout: Option<Duration>,
+    ) -> Result<DeviceCommandResponse, CoreError> {
+        let Some(cmd_timeout) = cmd_timeout else {
+            return self.send(command).await;
+        };
+        let default_timeout = std::mem::replace(&mut self.timeout, cmd_timeout);
+        let result = self.send(command).await;
+        self.timeout = default_timeout;
+        result
     }
 
     pub async fn send_batch(
         &mut self,
         commands: &[String],
+    ) -> Result<Vec<DeviceCommandResponse>, CoreError> {
+        let commands: Vec<BatchCommand> = commands.iter().map(|cmd| cmd.as_str().into()).collect();
+        self.send_batch_timed(&commands).await
+    }
+
+    /// Like [`DeviceConnection::send_batch`], with a timeout per command
+    pub async fn send_batch_timed(
+        &mut self,
+        commands: &[BatchCommand],
     ) -> Result<Vec<DeviceCommandResponse>, CoreError> {
         let mut responses = Vec::with_capacity(commands.len());
         for cmd in commands {
-            responses.push(self.send(cmd).await?);
+            responses.push(self.send_with_timeout(&cmd.command, cmd.timeout).await?);
         }
         Ok(responses)
     }
 
+    /// Like [`DeviceConnection::send_batch_timed`], but sending every
+    /// command even after one fails and reporting the outcome of each
+    pub async fn send_batch_continuing(&mut self, commands: &[BatchCommand]) -> Vec<CommandResult> {
+        let mut results = Vec::with_capacity(commands.len());
+        for (index, cmd) in commands.iter().enumerate() {
+            let result = self.send_with_timeout(&cmd.command, cmd.timeout).await;
+            results.push(CommandResult::new(index, &cmd.command, result));
+        }
+        results
+    }
+
+    /// Whether the device answers a request within `grace`
+    ///
+    /// UDP keeps no connection state that would show a device has gone
+    /// away, so this asks it for its firmware details; any reply, even an
+    /// error, counts. Use it before a long batch to fail fast instead of
+    /// waiting out the command timeout.
+    pub async fn is_alive(&mut self, grace: Duration) -> bool {
+        let cmd_timeout = std::mem::replace(&mut self.timeout, grace);
+        let result = self.send_raw_once(Commands::get_firmware_info()).await;
+        self.timeout = cmd_timeout;
+        matches!(result, Ok(_) | Err(CoreError::Device(_)))
+    }
+
+    /// Read the device's firmware details
+    pub async fn firmware_info(&mut self) -> Result<FirmwareInfo, CoreError> {
+        let response = self.send_raw(Commands::get_firmware_info()).await?;
+        Ok(parse_firmware_info(&response, &self.ip)?)
+    }
+
+    /// The commands the device supports, with its firmware version
+    ///
+    /// The dialect has no command to list them, so every device is taken
+    /// to speak all of it, less any command it turned down on this
+    /// connection. The version comes from `firmware-info` and is left out
+    /// if the reply cannot be read.
+    pub async fn capabilities(&mut self) -> Result<DeviceCapabilities, CoreError> {
+        if self.capabilities.firmware_version.is_none() {
+            let response = self.send_raw(Commands::get_firmware_info()).await?;
+            self.capabilities.firmware_version = parse_firmware_info(&response, &self.ip)
+                .ok()
+                .and_then(|info| info.version);
+        }
+        Ok(self.capabilities.clone())
+    }
+
+    /// Fail with a clear message unless the device supports `command`
+    ///
+    /// Nothing is sent to the device.
+    pub fn ensure_supported(&self, command: &str) -> Result<(), CoreError> {
+        if self.capabilities.supports(command) {
+            return Ok(());
+        }
+        let name = command.split_whitespace().next().unwrap_or_default();
+        Err(CoreError::Device(DeviceError::CommandFailed {
+            ip: self.ip.clone(),
+            message: format!("Device firmware does not support {name}"),
+        }))
+    }
+
+    /// Write `params` and report the outcome of each one
+    ///
+    /// All parameters go out as one `write-batch` if the device supports
+    /// it and the connection is not paced, otherwise as one `write` each. A
+    /// parameter the device rejects does not stop the others, but losing
+    /// the device does.
+    pub async fn write_params(
+        &mut self,
+        params: &ParamPlan,
+    ) -> Result<Vec<ParamWriteResult>, CoreError> {
+        if params.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if self.pacing.is_none() && self.capabilities.supports(WRITE_BATCH_COMMAND) {
+            match self.send_raw(&Commands::write_batch(params)?).await {
+                Ok(response) => {
+                    return Ok(parse_write_batch_response(&response, params, &self.ip)?);
+                }
+                Err(e) if is_unknown_command(&e) => {
+                    self.capabilities
+                        .commands
+                        .retain(|name| name != WRITE_BATCH_COMMAND);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let mut results = Vec::with_capacity(params.len());
+        for (group, name, value) in params.iter() {
+            let error = match self
+                .send_raw(&Commands::write_param(group, name, value)?)
+                .await
+            {
+                Ok(_) => None,
+                Err(CoreError::Device(DeviceError::CommandFailed { message, .. })) => Some(message),
+                Err(e) => return Err(e),
+            };
+            results.push(ParamWriteResult::new(group, name, error));
+        }
+        Ok(results)
+    }
+
+    /// Read back each of `written` and list those not holding the value
+    /// that was written
+    ///
+    /// Values are compared as the config diff compares them, numbers
+    /// numerically. Parameters missing from the firmware parameter table
+    /// cannot be read and are skipped.
+    pub async fn verify_params(
+        &mut self,
+        written: &[(String, String, String)],
+    ) -> Result<Vec<ParamMismatch>, CoreError> {
+        let options = DiffOptions {
+            tolerance: VERIFY_TOLERANCE,
+            ..DiffOptions::default()
+        };
+        let mut mismatches = Vec::new();
+        for (group, name, value) in written {
+            if params::find_by_legacy_name(group, name).is_none() {
+                continue;
+            }
+            let read = match self.send_raw(&Commands::read_param(group, name)?).await {
+                Ok(read) => Some(read.trim().to_string()),
+                Err(CoreError::Device(DeviceError::CommandFailed { .. })) => None,
+                Err(e) => return Err(e),
+            };
+            if read
+                .as_deref()
+                .is_some_and(|read| values_match(value, read, &options))
+            {
+                continue;
+            }
+            mismatches.push(ParamMismatch {
+                group: group.clone(),
+                name: name.clone(),
+                written: value.clone(),
+                read,
+            });
+        }
+        Ok(mismatches)
+    }
+
+    /// Write every parameter of `plan`, then send its save command
+    ///
+    /// Nothing is written if the device cannot run the save command, and
+    /// nothing is saved if any write fails; the error names each parameter
+    /// that did.
+    pub async fn upload_plan(&mut self, plan: &PresetUploadPlan) -> Result<(), CoreError> {
+        self.upload(plan, false).await.map(drop)
+    }
+
+    /// Like [`DeviceConnection::upload_plan`], reading every parameter back
+    /// before saving
+    ///
+    /// Parameters that read back differently are returned; the plan is
+    /// saved regardless, as the device accepted every write.
+    pub async fn upload_plan_verified(
+        &mut self,
+        plan: &PresetUploadPlan,
+    ) -> Result<Vec<ParamMismatch>, CoreError> {
+        self.upload(plan, true).await
+    }
+
+    async fn upload(
+        &mut self,
+        plan: &PresetUploadPlan,
+        verify: bool,
+    ) -> Result<Vec<ParamMismatch>, CoreError> {
+        self.ensure_supported(&plan.save_command)?;
+        let results = self.write_params(&plan.params).await?;
+        if let Some(message) = write_failures(&results) {
+            return Err(CoreError::Device(DeviceError::CommandFailed {
+                ip: self.ip.clone(),
+                message,
+            }));
+        }
+        let mismatches = if verify {
+            self.verify_params(&plan.params).await?
+        } else {
+            Vec::new()
+        };
+        self.send_raw(&plan.save_command).await?;
+        Ok(mismatches)
+    }
+
     async fn handle_read_all(&mut self, command: &str) -> Result<String, CoreError> {
         let tokens = tokenize(command);
         let group = tokens
@@ -179,6 +605,39 @@ impl DeviceConnection {
         Ok("OK".to_string())
     }
 
+    /// Translate a `write-batch` into pipelined `PARAM_EXT_SET`s, replying
+    /// with one `{success, error}` entry per parameter
+    async fn handle_write_batch(&mut self, payload: &str) -> Result<String, CoreError> {
+        let items: Vec<BatchWrite> = serde_json::from_str(payload.trim())
+            .map_err(|e| CoreError::Other(format!("Invalid write-batch payload: {e}")))?;
+
+        let mut writes = Vec::new();
+        let mut outcomes = Vec::with_capacity(items.len());
+        for item in &items {
+            match params::find_by_legacy_name(&item.group, &item.name) {
+                Some(entry) => {
+                    writes.push((entry.id, item.value.as_str()));
+                    outcomes.push(None);
+                }
+                None => outcomes.push(Some(Err(format!(
+                    "Unsupported parameter {}.{}",
+                    item.group, item.name
+                )))),
+            }
+        }
+
+        let mut written = self.set_param_values(&writes).await?.into_iter();
+        let results = outcomes
+            .into_iter()
+            .map(|outcome| match outcome.or_else(|| written.next()) {
+                Some(Ok(())) => serde_json::json!({ "success": true }),
+                Some(Err(error)) => serde_json::json!({ "success": false, "error": error }),
+                None => serde_json::json!({ "success": false, "error": "Not written" }),
+            })
+            .collect::<Vec<_>>();
+        Ok(serde_json::Value::Array(results).to_string())
+    }
+
     async fn handle_rtls_command(&mut self, command: &str) -> Result<String, CoreError> {
         let (command_id, name) = parse_rtls_command(command).map_err(|message| {
             CoreError::Device(DeviceError::CommandFailed {
@@ -365,14 +824,7 @@ impl DeviceConnection {
     }
 
     async fn set_param_value(&mut self, id: &str, value: &str) -> Result<(), CoreError> {
-        self.send_message(MavMessage::PARAM_EXT_SET(PARAM_EXT_SET_DATA {
-            target_system: TARGET_SYSTEM_BROADCAST,
-            target_component: TARGET_COMPONENT_BROADCAST,
-            param_id: CharArray::<16>::from(id),
-            param_value: CharArray::<128>::from(value),
-            param_type: MavParamExtType::MAV_PARAM_EXT_TYPE_CUSTOM,
-        }))
-        .await?;
+        self.send_message(param_set_message(id, value)).await?;
 
         let deadline = Instant::now() + self.timeout;
         loop {
@@ -392,6 +844,61 @@ impl DeviceConnection {
         }
     }
 
+    /// Set several parameters, keeping up to [`WRITE_BATCH_WINDOW`] writes
+    /// unacknowledged at a time
+    ///
+    /// Returns one outcome per write, in order. Writes still unacknowledged
+    /// when the device stops answering fail with the timeout.
+    async fn set_param_values(
+        &mut self,
+        writes: &[(&str, &str)],
+    ) -> Result<Vec<Result<(), String>>, CoreError> {
+        let mut outcomes = vec![None; writes.len()];
+        let mut in_flight = VecDeque::new();
+        let mut next = 0;
+        let mut deadline = Instant::now() + self.timeout;
+
+        while next < writes.len() || !in_flight.is_empty() {
+            while next < writes.len() && in_flight.len() < WRITE_BATCH_WINDOW {
+                let (id, value) = writes[next];
+                self.send_message(param_set_message(id, value)).await?;
+                in_flight.push_back(next);
+                next += 1;
+                deadline = Instant::now() + self.timeout;
+            }
+
+            let ack = match self.recv_until(deadline).await {
+                Ok(MavMessage::PARAM_EXT_ACK(ack)) => ack,
+                Ok(_) => continue,
+                Err(CoreError::Other(message)) if message.contains("timed out") => {
+                    for index in in_flight.drain(..).chain(next..writes.len()) {
+                        outcomes[index] = Some(Err(message.clone()));
+                    }
+                    break;
+                }
+                Err(e) => return Err(e),
+            };
+
+            // Acks for the same parameter arrive in the order it was written
+            let id = char_array_to_string(&ack.param_id);
+            let Some(position) = in_flight.iter().position(|&i| writes[i].0 == id) else {
+                continue;
+            };
+            let index = in_flight.remove(position).unwrap_or_default();
+            outcomes[index] = Some(if ack.param_result == ParamAck::PARAM_ACK_ACCEPTED {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Parameter {id} write failed: {:?}",
+                    ack.param_result
+                ))
+            });
+            deadline = Instant::now() + self.timeout;
+        }
+
+        Ok(outcomes.into_iter().flatten().collect())
+    }
+
     async fn receive_command_response(
         &mut self,
         request_id: u32,
@@ -496,32 +1003,182 @@ pub async fn send_commands_parsed(
     conn.send_batch(commands).await
 }
 
+/// How a failed command is retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts after the first
+    pub retries: usize,
+    /// Wait before the first retry, doubled for each one after it
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(retries: usize, backoff: Duration) -> Self {
+        Self { retries, backoff }
+    }
+
+    /// Wait before retry number `retry`, counting from 0
+    ///
+    /// Up to half as long again is added at random so that devices failing
+    /// together are not retried in lockstep.
+    pub fn delay(&self, retry: usize) -> Duration {
+        let base = self
+            .backoff
+            .saturating_mul(1 << retry.min(16))
+            .min(MAX_RETRY_DELAY);
+        // Sub-second clock noise is random enough for spreading retries
+        let jitter = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.subsec_nanos());
+        base + base.mul_f64(f64::from(jitter) / 2e9)
+    }
+
+    /// Whether `error` is worth another attempt
+    ///
+    /// Timeouts and transport errors are; a device refusing the command
+    /// itself, e.g. an unknown parameter or an unsupported value, will
+    /// refuse it again.
+    pub fn is_retryable(error: &CoreError) -> bool {
+        match error {
+            CoreError::Device(DeviceError::CommandFailed { message, .. }) => {
+                let message = message.to_ascii_lowercase();
+                !(is_unknown_command(error)
+                    || message.contains("invalid param")
+                    || message.contains("unsupported parameter")
+                    || message.contains("value_unsupported")
+                    || message.contains("does not support"))
+            }
+            CoreError::Config(_) | CoreError::Command(_) | CoreError::Storage(_) => false,
+            _ => true,
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(0, Duration::from_millis(500))
+    }
+}
+
+/// Send a command, retrying failures `policy` allows
+///
+/// Returns the response and the number of attempts it took.
+pub async fn send_command_retrying(
+    ip: &str,
+    command: &str,
+    cmd_timeout: Duration,
+    policy: RetryPolicy,
+) -> Result<(String, usize), CoreError> {
+    let mut retry = 0;
+    loop {
+        match send_command(ip, command, cmd_timeout).await {
+            Ok(response) => return Ok((response, retry + 1)),
+            Err(e) if retry < policy.retries && RetryPolicy::is_retryable(&e) => {
+                tokio::time::sleep(policy.delay(retry)).await;
+                retry += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 pub async fn send_command_with_retry(
     ip: &str,
     command: &str,
     cmd_timeout: Duration,
     max_retries: usize,
 ) -> Result<String, CoreError> {
-    let mut last_error = None;
+    let policy = RetryPolicy {
+        retries: max_retries,
+        ..RetryPolicy::default()
+    };
+    send_command_retrying(ip, command, cmd_timeout, policy)
+        .await
+        .map(|(response, _)| response)
+}
 
-    for attempt in 0..=max_retries {
-        match send_command(ip, command, cmd_timeout).await {
-            Ok(response) => return Ok(response),
-            Err(e) => {
-                last_error = Some(e);
-                if attempt < max_retries {
-                    tokio::time::sleep(Duration::from_millis(500)).await;
-                }
+/// What happened when a [`BatchSender`] sent its command to one device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchOutcome {
+    /// The device answered; `elapsed` is the time the answering attempt took
+    Success { response: String, elapsed: Duration },
+    /// The device did not answer in time
+    Timeout,
+    /// The command could not be sent, e.g. an invalid address or no route
+    ConnectFailed { message: String },
+    /// The device refused the command or sent a reply that could not be read
+    CommandFailed { message: String },
+    /// Skipped without sending: the device did not answer the reachability
+    /// probe
+    Unreachable,
+}
+
+impl BatchOutcome {
+    fn new(result: Result<String, CoreError>, elapsed: Duration) -> Self {
+        match result {
+            Ok(response) => Self::Success { response, elapsed },
+            Err(CoreError::Other(message)) if message.contains("timed out") => Self::Timeout,
+            Err(CoreError::Other(message)) if message.starts_with("Invalid MAVLink target") => {
+                Self::ConnectFailed { message }
             }
+            Err(e @ CoreError::Io(_)) => Self::ConnectFailed {
+                message: e.to_string(),
+            },
+            Err(e) => Self::CommandFailed {
+                message: e.to_string(),
+            },
         }
     }
+}
 
-    Err(last_error.unwrap())
+/// Result of sending a [`BatchSender`] command to one device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult {
+    pub ip: String,
+    pub outcome: BatchOutcome,
+    /// Times the command was sent, counting retries; 0 for skipped devices
+    pub attempts: usize,
+}
+
+impl BatchResult {
+    pub fn is_success(&self) -> bool {
+        matches!(self.outcome, BatchOutcome::Success { .. })
+    }
+
+    /// The device's response, if it answered
+    pub fn response(&self) -> Option<&str> {
+        match &self.outcome {
+            BatchOutcome::Success { response, .. } => Some(response),
+            _ => None,
+        }
+    }
+
+    /// How long the answering attempt took, if the device answered
+    pub fn elapsed(&self) -> Option<Duration> {
+        match &self.outcome {
+            BatchOutcome::Success { elapsed, .. } => Some(*elapsed),
+            _ => None,
+        }
+    }
+
+    /// Why the command failed, for display
+    pub fn error(&self) -> Option<String> {
+        match &self.outcome {
+            BatchOutcome::Success { .. } => None,
+            BatchOutcome::Timeout => Some(format!("Command to {} timed out", self.ip)),
+            BatchOutcome::Unreachable => Some(format!("Skipped: {} is unreachable", self.ip)),
+            BatchOutcome::ConnectFailed { message } | BatchOutcome::CommandFailed { message } => {
+                Some(message.clone())
+            }
+        }
+    }
 }
 
 pub struct BatchSender {
     timeout: Duration,
     concurrency: usize,
+    retry: RetryPolicy,
+    probe_timeout: Option<Duration>,
 }
 
 impl BatchSender {
@@ -529,26 +1186,85 @@ impl BatchSender {
         Self {
             timeout: Duration::from_millis(timeout_ms),
             concurrency: concurrency.max(1),
+            retry: RetryPolicy::default(),
+            probe_timeout: None,
         }
     }
 
-    pub async fn send_to_all(
+    /// Retry each device's failures that `policy` allows
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
+    /// Probe every device first and skip those whose web server does not
+    /// answer within `timeout`, reporting them as
+    /// [`BatchOutcome::Unreachable`]
+    pub fn skip_unreachable(mut self, timeout: Duration) -> Self {
+        self.probe_timeout = Some(timeout);
+        self
+    }
+
+    pub async fn send_to_all(&self, ips: &[String], command: &str) -> Vec<BatchResult> {
+        self.send_to_all_with_progress(ips, command, |_| {}).await
+    }
+
+    /// Like [`BatchSender::send_to_all`], calling `on_result` as each device
+    /// answers or fails
+    pub async fn send_to_all_with_progress(
         &self,
         ips: &[String],
         command: &str,
-    ) -> Vec<(String, Result<String, CoreError>)> {
-        stream::iter(ips.iter().cloned())
-            .map(|ip| {
-                let cmd = command.to_string();
-                let timeout = self.timeout;
-                async move {
-                    let result = send_command(&ip, &cmd, timeout).await;
-                    (ip, result)
+        mut on_result: impl FnMut(&BatchResult),
+    ) -> Vec<BatchResult> {
+        let mut results = Vec::with_capacity(ips.len());
+        let reachable = match self.probe_timeout {
+            Some(probe_timeout) => {
+                let (reachable, unreachable) =
+                    partition_reachable(ips, DEVICE_HTTP_PORT, probe_timeout).await;
+                for ip in unreachable {
+                    let result = BatchResult {
+                        ip,
+                        outcome: BatchOutcome::Unreachable,
+                        attempts: 0,
+                    };
+                    on_result(&result);
+                    results.push(result);
                 }
-            })
-            .buffer_unordered(self.concurrency)
-            .collect()
-            .await
+                reachable
+            }
+            None => ips.to_vec(),
+        };
+
+        let mut pending = stream::iter(reachable)
+            .map(|ip| self.send_to_one(ip, command))
+            .buffer_unordered(self.concurrency);
+
+        while let Some(result) = pending.next().await {
+            on_result(&result);
+            results.push(result);
+        }
+        results
+    }
+
+    async fn send_to_one(&self, ip: String, command: &str) -> BatchResult {
+        let mut retry = 0;
+        loop {
+            let started = Instant::now();
+            match send_command(&ip, command, self.timeout).await {
+                Err(e) if retry < self.retry.retries && RetryPolicy::is_retryable(&e) => {
+                    tokio::time::sleep(self.retry.delay(retry)).await;
+                    retry += 1;
+                }
+                result => {
+                    return BatchResult {
+                        outcome: BatchOutcome::new(result, started.elapsed()),
+                        ip,
+                        attempts: retry + 1,
+                    };
+                }
+            }
+        }
     }
 }
 
@@ -574,6 +1290,51 @@ struct CommandResponse {
     payload: Vec<u8>,
 }
 
+fn param_set_message(id: &str, value: &str) -> MavMessage {
+    MavMessage::PARAM_EXT_SET(PARAM_EXT_SET_DATA {
+        target_system: TARGET_SYSTEM_BROADCAST,
+        target_component: TARGET_COMPONENT_BROADCAST,
+        param_id: CharArray::<16>::from(id),
+        param_value: CharArray::<128>::from(value),
+        param_type: MavParamExtType::MAV_PARAM_EXT_TYPE_CUSTOM,
+    })
+}
+
+/// Open a socket that sends to and only receives from `target`
+async fn bind_to(target: SocketAddr) -> Result<UdpSocket, CoreError> {
+    let socket = UdpSocket::bind("0.0.0.0:0").await?;
+    socket.connect(target).await?;
+    Ok(socket)
+}
+
+/// Whether `command` only reads or writes parameters, so running it twice
+/// has the same effect as running it once
+fn is_param_command(command: &str) -> bool {
+    command.starts_with("readall")
+        || command.starts_with("read ")
+        || command.starts_with("write ")
+        || command.starts_with(WRITE_BATCH_COMMAND)
+}
+
+/// Whether `error` came from the link rather than the device
+fn is_transport_error(error: &CoreError) -> bool {
+    match error {
+        CoreError::Io(_) => true,
+        CoreError::Other(message) => message.contains("timed out"),
+        _ => false,
+    }
+}
+
+/// Whether `error` is the device (or transport) rejecting a command it does
+/// not implement
+fn is_unknown_command(error: &CoreError) -> bool {
+    let CoreError::Device(DeviceError::CommandFailed { message, .. }) = error else {
+        return false;
+    };
+    let message = message.to_ascii_lowercase();
+    message.contains("unknown command") || message.contains("unsupported mavlink command")
+}
+
 fn parse_datagram(data: &[u8]) -> Result<MavMessage, String> {
     let cursor = Cursor::new(data);
     let mut reader = PeekReader::new(cursor);
@@ -613,40 +1374,91 @@ fn format_param_list(values: &[ParamValue], group_filter: Option<&str>) -> Strin
     out
 }
 
+/// Management commands of the RTLS-Link dialect, by name
+const RTLS_COMMANDS: &[(&str, RtlsCommand)] = &[
+    ("reboot", RtlsCommand::RTLS_COMMAND_REBOOT),
+    ("firmware-info", RtlsCommand::RTLS_COMMAND_FIRMWARE_INFO),
+    ("save-config", RtlsCommand::RTLS_COMMAND_SAVE_CONFIG),
+    ("load-config", RtlsCommand::RTLS_COMMAND_LOAD_CONFIG),
+    ("backup-config", RtlsCommand::RTLS_COMMAND_BACKUP_CONFIG),
+    ("list-configs", RtlsCommand::RTLS_COMMAND_LIST_CONFIGS),
+    ("toggle-led2", RtlsCommand::RTLS_COMMAND_TOGGLE_LED2),
+    ("get-led2-state", RtlsCommand::RTLS_COMMAND_GET_LED2_STATE),
+    ("tdoa-distances", RtlsCommand::RTLS_COMMAND_TDOA_DISTANCES),
+    (
+        "tdoa-anchor-stats",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_STATS,
+    ),
+    (
+        "tdoa-anchor-model-reset",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_RESET,
+    ),
+    (
+        "tdoa-anchor-model-collect-start",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_COLLECT_START,
+    ),
+    (
+        "tdoa-anchor-model-collect-status",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_COLLECT_STATUS,
+    ),
+    (
+        "tdoa-anchor-model-lock",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_LOCK,
+    ),
+    (
+        "tdoa-anchor-model-status",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_STATUS,
+    ),
+    (
+        "tdoa-anchor-model-export",
+        RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_EXPORT,
+    ),
+    (
+        "tdoa-estimator-stats-reset",
+        RtlsCommand::RTLS_COMMAND_TDOA_ESTIMATOR_STATS_RESET,
+    ),
+    (
+        "tdoa-estimator-status",
+        RtlsCommand::RTLS_COMMAND_TDOA_ESTIMATOR_STATUS,
+    ),
+    ("save-config-as", RtlsCommand::RTLS_COMMAND_SAVE_CONFIG_AS),
+    (
+        "load-config-named",
+        RtlsCommand::RTLS_COMMAND_LOAD_CONFIG_NAMED,
+    ),
+    (
+        "read-config-named",
+        RtlsCommand::RTLS_COMMAND_READ_CONFIG_NAMED,
+    ),
+    ("delete-config", RtlsCommand::RTLS_COMMAND_DELETE_CONFIG),
+];
+
+/// Commands this transport answers with parameter messages
+const PARAM_COMMANDS: &[&str] = &["readall", "read", "write", WRITE_BATCH_COMMAND];
+
+/// Capabilities of a device speaking the whole dialect
+fn dialect_capabilities(info: FirmwareInfo) -> DeviceCapabilities {
+    let commands = PARAM_COMMANDS
+        .iter()
+        .chain(RTLS_COMMANDS.iter().map(|(name, _)| name))
+        .map(|name| name.to_string())
+        .collect();
+    DeviceCapabilities {
+        protocol_version: None,
+        firmware_version: info.version,
+        commands,
+        derived: true,
+    }
+}
+
 fn parse_rtls_command(command: &str) -> Result<(RtlsCommand, Option<String>), String> {
     let tokens = tokenize(command);
     let Some(name) = tokens.first().map(String::as_str) else {
         return Err("Empty command".to_string());
     };
 
-    let command_id = match name {
-        "reboot" => RtlsCommand::RTLS_COMMAND_REBOOT,
-        "firmware-info" => RtlsCommand::RTLS_COMMAND_FIRMWARE_INFO,
-        "save-config" => RtlsCommand::RTLS_COMMAND_SAVE_CONFIG,
-        "load-config" => RtlsCommand::RTLS_COMMAND_LOAD_CONFIG,
-        "backup-config" => RtlsCommand::RTLS_COMMAND_BACKUP_CONFIG,
-        "list-configs" => RtlsCommand::RTLS_COMMAND_LIST_CONFIGS,
-        "toggle-led2" => RtlsCommand::RTLS_COMMAND_TOGGLE_LED2,
-        "get-led2-state" => RtlsCommand::RTLS_COMMAND_GET_LED2_STATE,
-        "tdoa-distances" => RtlsCommand::RTLS_COMMAND_TDOA_DISTANCES,
-        "tdoa-anchor-stats" => RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_STATS,
-        "tdoa-anchor-model-reset" => RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_RESET,
-        "tdoa-anchor-model-collect-start" => {
-            RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_COLLECT_START
-        }
-        "tdoa-anchor-model-collect-status" => {
-            RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_COLLECT_STATUS
-        }
-        "tdoa-anchor-model-lock" => RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_LOCK,
-        "tdoa-anchor-model-status" => RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_STATUS,
-        "tdoa-anchor-model-export" => RtlsCommand::RTLS_COMMAND_TDOA_ANCHOR_MODEL_EXPORT,
-        "tdoa-estimator-stats-reset" => RtlsCommand::RTLS_COMMAND_TDOA_ESTIMATOR_STATS_RESET,
-        "tdoa-estimator-status" => RtlsCommand::RTLS_COMMAND_TDOA_ESTIMATOR_STATUS,
-        "save-config-as" => RtlsCommand::RTLS_COMMAND_SAVE_CONFIG_AS,
-        "load-config-named" => RtlsCommand::RTLS_COMMAND_LOAD_CONFIG_NAMED,
-        "read-config-named" => RtlsCommand::RTLS_COMMAND_READ_CONFIG_NAMED,
-        "delete-config" => RtlsCommand::RTLS_COMMAND_DELETE_CONFIG,
-        _ => return Err(format!("Unsupported MAVLink command: {name}")),
+    let Some(&(_, command_id)) = RTLS_COMMANDS.iter().find(|(known, _)| *known == name) else {
+        return Err(format!("Unsupported MAVLink command: {name}"));
     };
 
     let named_value = match command_id {
@@ -708,6 +1520,7 @@ fn char_array_to_string<const N: usize>(value: &CharArray<N>) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mavlink::rtlslink::{PARAM_EXT_ACK_DATA, RTLS_COMMAND_RESPONSE_DATA};
 
     fn encode_message(message: MavMessage) -> Vec<u8> {
         let mut bytes = Vec::new();
@@ -734,6 +1547,141 @@ mod tests {
         })
     }
 
+    #[test]
+    fn parse_command_response_finds_json_in_any_reply() {
+        let text = parse_command_response("version", "v2.3.1".to_string(), "1.2.3.4").unwrap();
+        assert_eq!(text.raw, "v2.3.1");
+        assert!(text.json.is_none());
+        assert!(text.ack.success);
+        assert_eq!(text.ack.message.as_deref(), Some("v2.3.1"));
+
+        let readall = "[wifi]\nmode=1\n".to_string();
+        assert!(parse_command_response("readall all", readall, "1.2.3.4")
+            .unwrap()
+            .json
+            .is_none());
+
+        let prefixed = "OK\n{\"uptime\": 42, \"freeHeap\": 1024}".to_string();
+        let stats = parse_command_response("get-stats", prefixed, "1.2.3.4").unwrap();
+        assert_eq!(stats.json.unwrap()["uptime"], 42);
+
+        let array = r#"[{"name": "radio", "passed": true}]"#.to_string();
+        let checks = parse_command_response("self-test", array, "1.2.3.4").unwrap();
+        assert_eq!(checks.json.unwrap()[0]["passed"], true);
+    }
+
+    #[test]
+    fn parse_command_response_requires_json_from_structured_commands() {
+        assert!(parse_command_response("list-configs", "OK".to_string(), "1.2.3.4").is_err());
+        assert!(
+            parse_command_response("list-configs", "[]".to_string(), "1.2.3.4")
+                .unwrap()
+                .json
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn retry_policy_backs_off_and_skips_definitive_errors() {
+        let policy = RetryPolicy::new(6, Duration::from_millis(100));
+        for (retry, base_ms) in [(0, 100), (1, 200), (2, 400), (6, 5000)] {
+            let delay = policy.delay(retry);
+            let base = Duration::from_millis(base_ms);
+            assert!(
+                delay >= base && delay <= base.mul_f64(1.5),
+                "{retry}: {delay:?}"
+            );
+        }
+
+        let failed = |message: &str| {
+            CoreError::Device(DeviceError::CommandFailed {
+                ip: "1.2.3.4".to_string(),
+                message: message.to_string(),
+            })
+        };
+        assert!(RetryPolicy::is_retryable(&CoreError::Other(
+            "Command to 1.2.3.4 timed out".to_string()
+        )));
+        assert!(RetryPolicy::is_retryable(&failed("Command failed")));
+        assert!(!RetryPolicy::is_retryable(&failed("Invalid param")));
+        assert!(!RetryPolicy::is_retryable(&failed(
+            "Parameter UWB_CHANNEL write failed: PARAM_ACK_VALUE_UNSUPPORTED"
+        )));
+        assert!(!RetryPolicy::is_retryable(&failed(
+            "Unsupported MAVLink command: frobnicate"
+        )));
+    }
+
+    #[test]
+    fn batch_outcome_tells_timeouts_from_device_errors() {
+        let elapsed = Duration::from_millis(42);
+        assert_eq!(
+            BatchOutcome::new(Ok("OK".to_string()), elapsed),
+            BatchOutcome::Success {
+                response: "OK".to_string(),
+                elapsed
+            }
+        );
+        assert_eq!(
+            BatchOutcome::new(
+                Err(CoreError::Other("Command to 1.2.3.4 timed out".to_string())),
+                elapsed
+            ),
+            BatchOutcome::Timeout
+        );
+        let refused = BatchOutcome::new(
+            Err(CoreError::Device(DeviceError::CommandFailed {
+                ip: "1.2.3.4".to_string(),
+                message: "Unknown command".to_string(),
+            })),
+            elapsed,
+        );
+        assert!(matches!(refused, BatchOutcome::CommandFailed { .. }));
+        let unreachable = BatchOutcome::new(
+            Err(CoreError::Io(std::io::Error::from(
+                std::io::ErrorKind::NetworkUnreachable,
+            ))),
+            elapsed,
+        );
+        assert!(matches!(unreachable, BatchOutcome::ConnectFailed { .. }));
+    }
+
+    #[tokio::test]
+    async fn batch_sender_counts_attempts_across_retries() {
+        let sender = BatchSender::new(100, 2).with_retry(RetryPolicy::new(2, Duration::ZERO));
+        let results = sender
+            .send_to_all(&["not-an-ip".to_string()], "toggle-led")
+            .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].is_success());
+        assert!(matches!(
+            results[0].outcome,
+            BatchOutcome::ConnectFailed { .. }
+        ));
+        assert_eq!(results[0].attempts, 3);
+        assert_eq!(results[0].elapsed(), None);
+    }
+
+    #[tokio::test]
+    async fn batch_sender_skips_unreachable_devices_without_sending() {
+        let sender = BatchSender::new(100, 2).skip_unreachable(Duration::from_millis(50));
+        let mut reported = Vec::new();
+        let results = sender
+            .send_to_all_with_progress(&["not-an-ip".to_string()], "toggle-led", |result| {
+                reported.push(result.ip.clone())
+            })
+            .await;
+
+        assert_eq!(reported, vec!["not-an-ip".to_string()]);
+        assert_eq!(results[0].outcome, BatchOutcome::Unreachable);
+        assert_eq!(results[0].attempts, 0);
+        assert_eq!(
+            results[0].error().as_deref(),
+            Some("Skipped: not-an-ip is unreachable")
+        );
+    }
+
     #[test]
     fn parse_datagram_decodes_mavlink_frame() {
         let bytes = encode_message(param_value(7, 8, "WIFI_GCS_IP", "192.168.100.100"));
@@ -802,4 +1750,525 @@ mod tests {
 
         server_task.await.unwrap();
     }
+
+    fn text_chunk(
+        request_id: u32,
+        command: RtlsCommand,
+        chunk_index: u8,
+        chunk_count: u8,
+        text: &[u8],
+    ) -> MavMessage {
+        let mut payload = [0u8; 220];
+        payload[..text.len()].copy_from_slice(text);
+        MavMessage::RTLS_COMMAND_RESPONSE(RTLS_COMMAND_RESPONSE_DATA {
+            request_id,
+            command,
+            result: RtlsResult::RTLS_RESULT_ACCEPTED,
+            payload_type: RtlsPayloadType::RTLS_PAYLOAD_TYPE_TEXT,
+            chunk_index,
+            chunk_count,
+            payload_len: text.len() as u8,
+            payload,
+        })
+    }
+
+    #[tokio::test]
+    async fn send_reassembles_json_split_across_response_chunks() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let configs = (0..24)
+            .map(|i| format!(r#"{{"name":"site-{i:02}","active":{}}}"#, i == 3))
+            .collect::<Vec<_>>()
+            .join(",");
+        let reply = format!(r#"{{"configs":[{configs}]}}"#);
+        assert!(reply.len() > 2 * 220);
+
+        let server_reply = reply.clone();
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                panic!("expected RTLS_COMMAND");
+            };
+
+            let chunks = server_reply.as_bytes().chunks(220).collect::<Vec<_>>();
+            let count = chunks.len() as u8;
+            // A late chunk of an earlier request must not be mixed in
+            let stale = text_chunk(request.request_id - 1, request.command, 0, 1, b"{}");
+            server.send_to(&encode_message(stale), peer).await.unwrap();
+            for index in (0..chunks.len()).rev() {
+                let chunk = text_chunk(
+                    request.request_id,
+                    request.command,
+                    index as u8,
+                    count,
+                    chunks[index],
+                );
+                server.send_to(&encode_message(chunk), peer).await.unwrap();
+            }
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let response = conn.send("list-configs").await.unwrap();
+
+        assert_eq!(response.raw, reply);
+        let json = response.json.unwrap();
+        assert_eq!(json["configs"].as_array().unwrap().len(), 24);
+        assert_eq!(json["configs"][23]["name"], "site-23");
+
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn capabilities_read_the_firmware_version_once() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                panic!("expected RTLS_COMMAND");
+            };
+            assert_eq!(request.command, RtlsCommand::RTLS_COMMAND_FIRMWARE_INFO);
+            let info = br#"{"device":"RTLS-Link","version":"2.3.1"}"#;
+            let reply = text_chunk(request.request_id, request.command, 0, 1, info);
+            server.send_to(&encode_message(reply), peer).await.unwrap();
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let caps = conn.capabilities().await.unwrap();
+        server_task.await.unwrap();
+
+        assert!(caps.derived);
+        assert_eq!(caps.firmware_version.as_deref(), Some("2.3.1"));
+        assert!(caps.supports(WRITE_BATCH_COMMAND));
+        assert!(caps.supports("readall"));
+        assert!(caps.named_configs());
+        // Cached: no device left to answer a second probe
+        assert_eq!(conn.capabilities().await.unwrap(), caps);
+    }
+
+    #[tokio::test]
+    async fn capabilities_survive_unreadable_firmware_info() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                panic!("expected RTLS_COMMAND");
+            };
+            let reply = text_chunk(request.request_id, request.command, 0, 1, b"RTLS-Link 2.3");
+            server.send_to(&encode_message(reply), peer).await.unwrap();
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let caps = conn.capabilities().await.unwrap();
+        server_task.await.unwrap();
+
+        assert_eq!(caps.firmware_version, None);
+        assert!(caps.supports(WRITE_BATCH_COMMAND));
+        assert!(conn.ensure_supported("save-config-as -name site").is_ok());
+    }
+
+    #[tokio::test]
+    async fn is_alive_fails_fast_once_the_device_stops_answering() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                panic!("expected RTLS_COMMAND");
+            };
+            let info = br#"{"device":"RTLS-Link","version":"2.3.1"}"#;
+            let reply = text_chunk(request.request_id, request.command, 0, 1, info);
+            server.send_to(&encode_message(reply), peer).await.unwrap();
+            // Keep the port open but stop answering, as a rebooting device
+            server
+        });
+
+        let mut conn = DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(conn.is_alive(Duration::from_millis(1500)).await);
+        let _server = server_task.await.unwrap();
+
+        let started = Instant::now();
+        assert!(!conn.is_alive(Duration::from_millis(100)).await);
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(conn.timeout, Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn send_batch_timed_gives_slow_commands_their_own_timeout() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            for _ in 0..2 {
+                let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+                let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                    panic!("expected RTLS_COMMAND");
+                };
+                // Answer like a device busy erasing flash
+                tokio::time::sleep(Duration::from_millis(400)).await;
+                let saved = br#"{"success":true}"#;
+                let reply = text_chunk(request.request_id, request.command, 0, 1, saved);
+                server.send_to(&encode_message(reply), peer).await.unwrap();
+            }
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(150))
+                .await
+                .unwrap();
+        let slow = [BatchCommand::with_timeout(
+            "save-config-as -name site",
+            Duration::from_millis(1500),
+        )];
+        let responses = conn.send_batch_timed(&slow).await.unwrap();
+        assert!(responses[0].ack.success);
+        assert_eq!(conn.timeout, Duration::from_millis(150));
+
+        let err = conn
+            .send_batch(&["save-config-as -name site".to_string()])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"));
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnect_resends_a_command_lost_on_the_old_socket() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            // Drop the first request, as a flaky link would
+            let (_, first_peer) = server.recv_from(&mut buf).await.unwrap();
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            assert_ne!(peer, first_peer, "resent from a new socket");
+            let MavMessage::PARAM_EXT_REQUEST_READ(request) = parse_datagram(&buf[..len]).unwrap()
+            else {
+                panic!("expected PARAM_EXT_REQUEST_READ");
+            };
+            assert_eq!(char_array_to_string(&request.param_id), "WIFI_SSID_ST");
+            server
+                .send_to(
+                    &encode_message(param_value(3, 40, "WIFI_SSID_ST", "lab")),
+                    peer,
+                )
+                .await
+                .unwrap();
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(150))
+                .await
+                .unwrap();
+        conn.set_reconnect(1, Duration::ZERO);
+        let response = conn.send("read -group wifi -name ssidST").await.unwrap();
+
+        assert_eq!(response.attempts, 2);
+        assert_eq!(conn.reconnects(), 1);
+        server_task.await.unwrap();
+
+        // Device errors are not the link's fault and are not resent
+        let err = conn.send("read -group uwb -name bogus").await.unwrap_err();
+        assert!(err.to_string().contains("Unsupported parameter"));
+        assert_eq!(conn.reconnects(), 1);
+    }
+
+    #[tokio::test]
+    async fn reconnect_does_not_resend_config_commands() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(150))
+                .await
+                .unwrap();
+        conn.set_reconnect(1, Duration::ZERO);
+        // Only the reply may have been lost, so running it again could
+        // delete or overwrite a config twice
+        assert!(conn.send("delete-config -name site").await.is_err());
+
+        let mut buf = [0u8; 1500];
+        server.recv_from(&mut buf).await.unwrap();
+        assert_eq!(conn.reconnects(), 0);
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), server.recv_from(&mut buf))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn pacing_delays_every_command_but_the_first() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            for _ in 0..2 {
+                let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+                let MavMessage::RTLS_COMMAND(request) = parse_datagram(&buf[..len]).unwrap() else {
+                    panic!("expected RTLS_COMMAND");
+                };
+                let reply = text_chunk(request.request_id, request.command, 0, 1, b"LED toggled");
+                server.send_to(&encode_message(reply), peer).await.unwrap();
+            }
+        });
+
+        let pacing = Duration::from_millis(200);
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(500))
+                .await
+                .unwrap();
+        conn.set_pacing(Some(pacing));
+        let commands = vec![Commands::toggle_led().to_string(); 2];
+
+        let started = Instant::now();
+        conn.send_raw(&commands[0]).await.unwrap();
+        let first = started.elapsed();
+        conn.send_raw(&commands[1]).await.unwrap();
+        let second = started.elapsed();
+
+        assert!(first < pacing, "first command waited {first:?}");
+        assert!(
+            second - first >= pacing,
+            "commands {:?} apart",
+            second - first
+        );
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn upload_plan_refuses_unsupported_save_command_before_writing() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(300))
+                .await
+                .unwrap();
+        conn.capabilities = DeviceCapabilities {
+            commands: vec!["write".to_string(), "save-config".to_string()],
+            ..DeviceCapabilities::default()
+        };
+        let plan = PresetUploadPlan {
+            params: vec![("uwb".to_string(), "mode".to_string(), "1".to_string())].into(),
+            save_command: "save-config-as -name site".to_string(),
+        };
+
+        let err = conn.upload_plan(&plan).await.unwrap_err();
+        assert!(err.to_string().contains("does not support save-config-as"));
+        let mut buf = [0u8; 1500];
+        assert!(
+            timeout(Duration::from_millis(100), server.recv_from(&mut buf))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn write_params_pipelines_writes_and_reports_each_result() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let mut params = (1..=3)
+            .flat_map(|slot| {
+                ["devId", "x", "y"]
+                    .map(|name| ("uwb".to_string(), format!("{name}{slot}"), "1".to_string()))
+            })
+            .collect::<Vec<_>>();
+        params.push(("uwb".to_string(), "mode".to_string(), "99".to_string()));
+        params.push(("uwb".to_string(), "bogus".to_string(), "1".to_string()));
+        let params = ParamPlan::new(params);
+        let expected_writes = params.len() - 1;
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let mut pending = Vec::new();
+            let mut written = 0;
+            while written < expected_writes {
+                let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+                let MavMessage::PARAM_EXT_SET(set) = parse_datagram(&buf[..len]).unwrap() else {
+                    panic!("expected PARAM_EXT_SET");
+                };
+                written += 1;
+                pending.push(set);
+
+                // Only ack once a full window is in flight, or at the end
+                if pending.len() < WRITE_BATCH_WINDOW && written < expected_writes {
+                    continue;
+                }
+                for set in pending.drain(..) {
+                    let id = char_array_to_string(&set.param_id);
+                    let param_result = if id == "UWB_MODE" {
+                        ParamAck::PARAM_ACK_VALUE_UNSUPPORTED
+                    } else {
+                        ParamAck::PARAM_ACK_ACCEPTED
+                    };
+                    let ack = MavMessage::PARAM_EXT_ACK(PARAM_EXT_ACK_DATA {
+                        param_id: set.param_id,
+                        param_value: set.param_value,
+                        param_type: set.param_type,
+                        param_result,
+                    });
+                    server.send_to(&encode_message(ack), peer).await.unwrap();
+                }
+            }
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let results = conn.write_params(&params).await.unwrap();
+
+        assert_eq!(results.len(), params.len());
+        // The plan sends the mode write first
+        assert_eq!(results[0].name, "mode");
+        assert!(results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("VALUE_UNSUPPORTED"));
+        assert!(results[1..10].iter().all(|r| r.success));
+        assert_eq!(
+            results[10].error.as_deref(),
+            Some("Unsupported parameter uwb.bogus")
+        );
+
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_batch_continuing_reports_each_command_past_failures() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            let MavMessage::PARAM_EXT_REQUEST_READ(request) = parse_datagram(&buf[..len]).unwrap()
+            else {
+                panic!("expected PARAM_EXT_REQUEST_READ");
+            };
+            assert_eq!(char_array_to_string(&request.param_id), "WIFI_SSID_ST");
+            server
+                .send_to(
+                    &encode_message(param_value(3, 40, "WIFI_SSID_ST", "lab")),
+                    peer,
+                )
+                .await
+                .unwrap();
+        });
+
+        let commands = [
+            "read -group uwb -name bogus",
+            "read -group wifi -name ssidST",
+        ]
+        .map(BatchCommand::from);
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let results = conn.send_batch_continuing(&commands).await;
+
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].success);
+        assert!(results[0].response.is_none());
+        assert!(results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("Unsupported parameter uwb.bogus"));
+        assert_eq!(results[1].index, 1);
+        assert_eq!(results[1].command, commands[1].command);
+        assert!(results[1].success);
+        assert_eq!(results[1].response.as_ref().unwrap().raw, "lab");
+
+        server_task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_params_reports_values_that_read_back_differently() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+
+        let written = [
+            ("wifi", "ssidST", "warehouse-north-2"),
+            ("uwb", "x1", "1.5"),
+            ("wifi", "pswdST", "secret"),
+            ("uwb", "bogus", "1"),
+        ]
+        .map(|(group, name, value)| (group.to_string(), name.to_string(), value.to_string()));
+
+        let server_task = tokio::spawn(async move {
+            let mut buf = [0u8; 1500];
+            for _ in 0..3 {
+                let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+                let MavMessage::PARAM_EXT_REQUEST_READ(request) =
+                    parse_datagram(&buf[..len]).unwrap()
+                else {
+                    panic!("expected PARAM_EXT_REQUEST_READ");
+                };
+                let reply = match char_array_to_string(&request.param_id).as_str() {
+                    "WIFI_SSID_ST" => param_value(3, 40, "WIFI_SSID_ST", "warehouse-north-"),
+                    "UWB_A1_X" => param_value(20, 40, "UWB_A1_X", "1.500000"),
+                    id => MavMessage::PARAM_EXT_ACK(PARAM_EXT_ACK_DATA {
+                        param_id: CharArray::<16>::from(id),
+                        param_value: CharArray::<128>::from(""),
+                        param_type: MavParamExtType::MAV_PARAM_EXT_TYPE_CUSTOM,
+                        param_result: ParamAck::PARAM_ACK_FAILED,
+                    }),
+                };
+                server.send_to(&encode_message(reply), peer).await.unwrap();
+            }
+        });
+
+        let mut conn =
+            DeviceConnection::connect_to_port("127.0.0.1", port, Duration::from_millis(1500))
+                .await
+                .unwrap();
+        let mismatches = conn.verify_params(&written).await.unwrap();
+
+        assert_eq!(
+            mismatches,
+            vec![
+                ParamMismatch {
+                    group: "wifi".to_string(),
+                    name: "ssidST".to_string(),
+                    written: "warehouse-north-2".to_string(),
+                    read: Some("warehouse-north-".to_string()),
+                },
+                ParamMismatch {
+                    group: "wifi".to_string(),
+                    name: "pswdST".to_string(),
+                    written: "secret".to_string(),
+                    read: None,
+                },
+            ]
+        );
+
+        server_task.await.unwrap();
+    }
 }
@@ -1,6 +1,8 @@
 //! Device communication layer.
 //!
-//! Provides UDP MAVLink command sending and HTTP OTA firmware upload.
+//! Provides UDP MAVLink command sending, HTTP OTA firmware upload and
+//! reachability probes.
 
 pub mod mavlink;
 pub mod ota;
+pub mod probe;
@@ -7,10 +7,15 @@ use std::sync::{
 };
 use std::time::Duration;
 
+use base64::Engine;
+use bytes::Bytes;
+use futures::SinkExt;
+use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 use tokio::time::{sleep, timeout};
 
+use crate::device::probe::probe_reachable;
 use crate::error::{CoreError, DeviceError};
 
 const CONNECT_TIMEOUT_SECS: u64 = 10;
@@ -30,47 +35,148 @@ pub trait OtaProgressHandler: Send + Sync {
     fn on_error(&self, ip: &str, error: &str);
 }
 
+/// How a device's OTA endpoint is reached
+///
+/// Devices serve OTA over plain HTTP on port 80 unless configured
+/// otherwise, e.g. when fronted by a TLS reverse proxy.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OtaEndpoint {
+    /// Upload over HTTPS instead of HTTP
+    pub tls: bool,
+    /// Port to upload to, overriding one given with the IP and the
+    /// scheme's default (80 or 443)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Accept self-signed or otherwise invalid certificates, for lab setups
+    pub accept_invalid_certs: bool,
+    /// Credentials for devices that protect their web server. Never
+    /// serialized with the endpoint; callers store them separately.
+    #[serde(skip)]
+    pub auth: OtaAuth,
+}
+
+/// Credentials sent with the OTA upload
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OtaAuth {
+    #[default]
+    None,
+    Basic {
+        username: String,
+        password: String,
+    },
+    Bearer {
+        token: String,
+    },
+}
+
+impl OtaAuth {
+    /// Value for the `Authorization` header, if any
+    pub fn header_value(&self) -> Option<String> {
+        match self {
+            OtaAuth::None => None,
+            OtaAuth::Basic { username, password } => Some(format!(
+                "Basic {}",
+                base64::engine::general_purpose::STANDARD
+                    .encode(format!("{}:{}", username, password))
+            )),
+            OtaAuth::Bearer { token } => Some(format!("Bearer {}", token)),
+        }
+    }
+}
+
+// Keep secrets out of logs and error messages
+impl std::fmt::Debug for OtaAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OtaAuth::None => f.write_str("None"),
+            OtaAuth::Basic { username, .. } => f
+                .debug_struct("Basic")
+                .field("username", username)
+                .field("password", &"***")
+                .finish(),
+            OtaAuth::Bearer { .. } => f.debug_struct("Bearer").field("token", &"***").finish(),
+        }
+    }
+}
+
+impl OtaEndpoint {
+    /// Host and port to upload to for the device at `ip` (`host[:port]`)
+    fn target<'a>(&self, ip: &'a str) -> (&'a str, u16) {
+        let default_port = if self.tls { 443 } else { 80 };
+        let (host, port) = split_host_port(ip, default_port);
+        (host, self.port.unwrap_or(port))
+    }
+}
+
 /// Upload firmware data to a device via HTTP multipart POST.
 pub async fn upload_firmware(ip: &str, data: Vec<u8>, filename: &str) -> Result<(), CoreError> {
-    upload_firmware_data(ip, data, filename, None, None).await
+    upload_firmware_data(ip, &OtaEndpoint::default(), data, filename, None, None).await
 }
 
 /// Upload firmware data to a device and report transfer progress.
 pub async fn upload_firmware_with_progress<P: OtaProgressHandler>(
     ip: &str,
+    endpoint: &OtaEndpoint,
     data: Vec<u8>,
     filename: &str,
     progress: &P,
 ) -> Result<(), CoreError> {
-    upload_firmware_data(ip, data, filename, Some(progress), None).await
+    upload_firmware_data(ip, endpoint, data, filename, Some(progress), None).await
 }
 
 /// Upload firmware data to a device and allow cooperative cancellation.
 pub async fn upload_firmware_with_progress_and_cancel<P: OtaProgressHandler>(
     ip: &str,
+    endpoint: &OtaEndpoint,
     data: Vec<u8>,
     filename: &str,
     progress: &P,
     cancel: Arc<AtomicBool>,
 ) -> Result<(), CoreError> {
-    upload_firmware_data(ip, data, filename, Some(progress), Some(cancel.as_ref())).await
+    upload_firmware_data(
+        ip,
+        endpoint,
+        data,
+        filename,
+        Some(progress),
+        Some(cancel.as_ref()),
+    )
+    .await
 }
 
 /// Upload firmware to multiple devices concurrently.
 pub async fn upload_firmware_bulk<P: OtaProgressHandler>(
     ips: &[String],
+    endpoint: &OtaEndpoint,
     data: Vec<u8>,
     filename: &str,
     concurrency: usize,
     progress: &P,
 ) -> Vec<(String, Result<(), CoreError>)> {
-    upload_firmware_bulk_with_cancel(ips, data, filename, concurrency, progress, HashMap::new())
-        .await
+    let endpoints = ips
+        .iter()
+        .map(|ip| (ip.clone(), endpoint.clone()))
+        .collect();
+    upload_firmware_bulk_with_cancel(
+        ips,
+        &endpoints,
+        data,
+        filename,
+        concurrency,
+        progress,
+        HashMap::new(),
+    )
+    .await
 }
 
 /// Upload firmware to multiple devices concurrently with optional per-device cancellation.
+///
+/// Devices missing from `endpoints` are reached over plain HTTP.
 pub async fn upload_firmware_bulk_with_cancel<P: OtaProgressHandler>(
     ips: &[String],
+    endpoints: &HashMap<String, OtaEndpoint>,
     data: Vec<u8>,
     filename: &str,
     concurrency: usize,
@@ -82,15 +188,24 @@ pub async fn upload_firmware_bulk_with_cancel<P: OtaProgressHandler>(
     let concurrency = concurrency.max(1);
     let filename = filename.to_string();
     let cancel_flags = Arc::new(cancel_flags);
+    let default_endpoint = OtaEndpoint::default();
 
     let results: Vec<_> = stream::iter(ips.iter().cloned())
         .map(|ip| {
             let data = data.clone();
             let name = filename.clone();
             let cancel = cancel_flags.get(&ip).cloned();
+            let endpoint = endpoints.get(&ip).unwrap_or(&default_endpoint);
             async move {
-                let result =
-                    upload_firmware_data(&ip, data, &name, Some(progress), cancel.as_deref()).await;
+                let result = upload_firmware_data(
+                    &ip,
+                    endpoint,
+                    data,
+                    &name,
+                    Some(progress),
+                    cancel.as_deref(),
+                )
+                .await;
                 match &result {
                     Ok(()) => {
                         progress.on_complete(&ip);
@@ -109,17 +224,52 @@ pub async fn upload_firmware_bulk_with_cancel<P: OtaProgressHandler>(
     results
 }
 
+/// Probe each device's OTA endpoint, returning the devices that accept a
+/// connection within `timeout` and failed results for the rest.
+///
+/// Devices missing from `endpoints` are probed on port 80. Skipped devices
+/// fail with [`DeviceError::Offline`], so callers can tell them apart from
+/// failed uploads.
+pub async fn skip_unreachable(
+    ips: &[String],
+    endpoints: &HashMap<String, OtaEndpoint>,
+    timeout: Duration,
+) -> (Vec<String>, Vec<(String, Result<(), CoreError>)>) {
+    let default_endpoint = OtaEndpoint::default();
+    let probed = futures::future::join_all(ips.iter().map(|ip| {
+        let (host, port) = endpoints.get(ip).unwrap_or(&default_endpoint).target(ip);
+        probe_reachable(host, port, timeout)
+    }))
+    .await;
+
+    let mut reachable = Vec::new();
+    let mut skipped = Vec::new();
+    for (ip, up) in ips.iter().zip(probed) {
+        if up {
+            reachable.push(ip.clone());
+        } else {
+            let offline = DeviceError::Offline { ip: ip.clone() };
+            skipped.push((ip.clone(), Err(offline.into())));
+        }
+    }
+    (reachable, skipped)
+}
+
 /// Upload firmware data (already loaded) to a single device.
 async fn upload_firmware_data(
     ip: &str,
+    endpoint: &OtaEndpoint,
     data: Vec<u8>,
     file_name: &str,
     progress: Option<&dyn OtaProgressHandler>,
     cancel: Option<&AtomicBool>,
 ) -> Result<(), CoreError> {
     check_cancelled(ip, cancel)?;
+    if endpoint.tls {
+        return upload_firmware_https(ip, endpoint, data, file_name, progress, cancel).await;
+    }
 
-    let (host, port) = split_host_port(ip);
+    let (host, port) = endpoint.target(ip);
     let address = format!("{}:{}", host, port);
     let boundary = "----rtls-link-ota-boundary";
     let prefix = format!(
@@ -128,9 +278,14 @@ async fn upload_firmware_data(
     );
     let suffix = format!("\r\n--{}--\r\n", boundary);
     let content_length = prefix.len() + data.len() + suffix.len();
+    let authorization = endpoint
+        .auth
+        .header_value()
+        .map(|value| format!("Authorization: {}\r\n", value))
+        .unwrap_or_default();
     let request_headers = format!(
-        "POST /update HTTP/1.1\r\nHost: {}\r\nAccept: */*\r\nContent-Type: multipart/form-data; boundary={}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
-        ip, boundary, content_length
+        "POST /update HTTP/1.1\r\nHost: {}\r\nAccept: */*\r\n{}Content-Type: multipart/form-data; boundary={}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        ip, authorization, boundary, content_length
     );
 
     let mut stream = timeout(
@@ -207,8 +362,17 @@ async fn upload_firmware_data(
 
     let response_text = String::from_utf8_lossy(&response);
     let status_line = response_text.lines().next().unwrap_or_default();
-    let success = status_line.contains(" 200 ") || status_line.ends_with(" 200");
-    if !success {
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok());
+    if let Some(status @ (401 | 403)) = status {
+        return Err(CoreError::Device(DeviceError::Unauthorized {
+            ip: ip.to_string(),
+            status,
+        }));
+    }
+    if status != Some(200) {
         return Err(CoreError::Device(DeviceError::OtaFailed {
             ip: ip.to_string(),
             message: response_text.to_string(),
@@ -218,6 +382,87 @@ async fn upload_firmware_data(
     Ok(())
 }
 
+/// Upload firmware over HTTPS
+///
+/// The firmware is fed to the request in chunks so progress and
+/// cancellation work as they do over plain HTTP.
+async fn upload_firmware_https(
+    ip: &str,
+    endpoint: &OtaEndpoint,
+    data: Vec<u8>,
+    file_name: &str,
+    progress: Option<&dyn OtaProgressHandler>,
+    cancel: Option<&AtomicBool>,
+) -> Result<(), CoreError> {
+    let (host, port) = endpoint.target(ip);
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(endpoint.accept_invalid_certs)
+        .connect_timeout(Duration::from_secs(CONNECT_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(UPLOAD_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| CoreError::Other(format!("Failed to set up HTTPS client: {}", e)))?;
+
+    let total = data.len() as u64;
+    let (mut chunks, body) = futures::channel::mpsc::channel::<Result<Bytes, std::io::Error>>(1);
+    let part =
+        reqwest::multipart::Part::stream_with_length(reqwest::Body::wrap_stream(body), total)
+            .file_name(file_name.to_string())
+            .mime_str("application/octet-stream")
+            .map_err(|e| CoreError::Other(format!("Invalid firmware upload part: {}", e)))?;
+    let mut request = client
+        .post(format!("https://{}:{}/update", host, port))
+        .multipart(reqwest::multipart::Form::new().part("firmware", part));
+    if let Some(value) = endpoint.auth.header_value() {
+        request = request.header(reqwest::header::AUTHORIZATION, value);
+    }
+    let request = request.send();
+
+    let feed = async move {
+        if let Some(handler) = progress {
+            handler.on_progress(ip, 0, total);
+        }
+        let mut sent = 0u64;
+        for chunk in data.chunks(UPLOAD_CHUNK_SIZE) {
+            check_cancelled(ip, cancel)?;
+            chunks
+                .send(Ok(Bytes::copy_from_slice(chunk)))
+                .await
+                .map_err(|_| CoreError::Other(format!("HTTPS upload to {} ended early", ip)))?;
+            sent += chunk.len() as u64;
+            if let Some(handler) = progress {
+                handler.on_progress(ip, sent, total);
+            }
+        }
+        Ok::<(), CoreError>(())
+    };
+
+    let (response, fed) = tokio::join!(request, feed);
+    check_cancelled(ip, cancel)?;
+    let response =
+        response.map_err(|e| CoreError::Other(format!("HTTPS upload to {} failed: {}", ip, e)))?;
+    fed?;
+
+    let status = response.status();
+    if matches!(
+        status,
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN
+    ) {
+        return Err(CoreError::Device(DeviceError::Unauthorized {
+            ip: ip.to_string(),
+            status: status.as_u16(),
+        }));
+    }
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(CoreError::Device(DeviceError::OtaFailed {
+            ip: ip.to_string(),
+            message: format!("{} {}", status, body),
+        }));
+    }
+
+    Ok(())
+}
+
 fn check_cancelled(ip: &str, cancel: Option<&AtomicBool>) -> Result<(), CoreError> {
     if matches!(cancel, Some(flag) if flag.load(Ordering::Relaxed)) {
         return Err(CoreError::Other(format!(
@@ -255,13 +500,13 @@ async fn flush_with_timeout(stream: &mut TcpStream, ip: &str) -> Result<(), Core
         .map_err(|e| CoreError::Other(format!("HTTP upload to {} failed during flush: {}", ip, e)))
 }
 
-fn split_host_port(ip: &str) -> (&str, u16) {
+fn split_host_port(ip: &str, default_port: u16) -> (&str, u16) {
     if let Some((host, port)) = ip.rsplit_once(':') {
         if let Ok(port) = port.parse::<u16>() {
             return (host, port);
         }
     }
-    (ip, 80)
+    (ip, default_port)
 }
 
 #[cfg(test)]
@@ -287,11 +532,99 @@ mod tests {
 
     #[test]
     fn split_host_port_uses_default_port_for_plain_ip() {
-        assert_eq!(split_host_port("192.168.0.10"), ("192.168.0.10", 80));
+        assert_eq!(split_host_port("192.168.0.10", 80), ("192.168.0.10", 80));
     }
 
     #[test]
     fn split_host_port_accepts_explicit_port() {
-        assert_eq!(split_host_port("192.168.0.10:8080"), ("192.168.0.10", 8080));
+        assert_eq!(
+            split_host_port("192.168.0.10:8080", 80),
+            ("192.168.0.10", 8080)
+        );
+    }
+
+    #[test]
+    fn ota_endpoint_picks_port_by_precedence() {
+        let plain = OtaEndpoint::default();
+        assert_eq!(plain.target("192.168.0.10"), ("192.168.0.10", 80));
+        assert_eq!(plain.target("192.168.0.10:8080"), ("192.168.0.10", 8080));
+
+        let tls = OtaEndpoint {
+            tls: true,
+            ..OtaEndpoint::default()
+        };
+        assert_eq!(tls.target("192.168.0.10"), ("192.168.0.10", 443));
+
+        let custom = OtaEndpoint {
+            port: Some(8443),
+            ..tls
+        };
+        assert_eq!(custom.target("192.168.0.10:8080"), ("192.168.0.10", 8443));
+    }
+
+    #[tokio::test]
+    async fn skip_unreachable_probes_each_device_endpoint() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let ips = vec!["127.0.0.1".to_string(), "127.0.0.2".to_string()];
+        let endpoint = OtaEndpoint {
+            port: Some(port),
+            ..OtaEndpoint::default()
+        };
+        let endpoints = ips
+            .iter()
+            .map(|ip| (ip.clone(), endpoint.clone()))
+            .collect();
+
+        let (reachable, skipped) =
+            skip_unreachable(&ips, &endpoints, Duration::from_millis(500)).await;
+
+        assert_eq!(reachable, vec!["127.0.0.1".to_string()]);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].0, "127.0.0.2");
+        assert!(matches!(
+            skipped[0].1,
+            Err(CoreError::Device(DeviceError::Offline { .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn upload_sends_credentials_and_reports_rejection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ip = listener.local_addr().unwrap().to_string();
+        let device = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"--\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed mid-request");
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request).into_owned()
+        });
+
+        let endpoint = OtaEndpoint {
+            auth: OtaAuth::Basic {
+                username: "admin".to_string(),
+                password: "secret".to_string(),
+            },
+            ..OtaEndpoint::default()
+        };
+        let error = upload_firmware_data(&ip, &endpoint, vec![0xAB; 16], "fw.bin", None, None)
+            .await
+            .unwrap_err();
+
+        let request = device.await.unwrap();
+        assert!(request.contains("Authorization: Basic YWRtaW46c2VjcmV0\r\n"));
+        assert!(matches!(
+            error,
+            CoreError::Device(DeviceError::Unauthorized { status: 401, .. })
+        ));
+        assert!(!format!("{:?}", endpoint).contains("secret"));
     }
 }
@@ -0,0 +1,105 @@
+//! Reachability checks run before slow multi-device operations.
+//!
+//! A dead device otherwise costs a full command or upload timeout; a TCP
+//! connect to its web server answers the same question in a fraction of it.
+
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use tokio::net::TcpStream;
+
+/// Port of the devices' built-in web server
+pub const DEVICE_HTTP_PORT: u16 = 80;
+
+/// Time a device gets to answer a probe
+pub const DEFAULT_PROBE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Probes in flight at once in [`partition_reachable`]
+const PROBE_CONCURRENCY: usize = 32;
+
+/// Whether the host at `ip` accepts a TCP connection on `port` within
+/// `timeout`
+pub async fn probe_reachable(ip: &str, port: u16, timeout: Duration) -> bool {
+    matches!(
+        tokio::time::timeout(timeout, TcpStream::connect((ip, port))).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Probe `ips` concurrently, returning the reachable and the unreachable
+/// ones, each in their original order.
+pub async fn partition_reachable(
+    ips: &[String],
+    port: u16,
+    timeout: Duration,
+) -> (Vec<String>, Vec<String>) {
+    let probed: Vec<bool> = stream::iter(ips)
+        .map(|ip| probe_reachable(ip, port, timeout))
+        .buffered(PROBE_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut reachable = Vec::new();
+    let mut unreachable = Vec::new();
+    for (ip, up) in ips.iter().zip(probed) {
+        if up {
+            reachable.push(ip.clone());
+        } else {
+            unreachable.push(ip.clone());
+        }
+    }
+    (reachable, unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// A local port nothing listens on
+    async fn closed_port() -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().port()
+    }
+
+    #[tokio::test]
+    async fn probe_accepts_listening_host() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        assert!(probe_reachable("127.0.0.1", port, Duration::from_millis(500)).await);
+    }
+
+    #[tokio::test]
+    async fn probe_rejects_closed_port() {
+        let port = closed_port().await;
+
+        assert!(!probe_reachable("127.0.0.1", port, Duration::from_millis(500)).await);
+    }
+
+    #[tokio::test]
+    async fn probe_rejects_invalid_address() {
+        assert!(!probe_reachable("not-an-ip", DEVICE_HTTP_PORT, Duration::from_millis(50)).await);
+    }
+
+    #[tokio::test]
+    async fn partition_keeps_order_within_each_group() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // The listener is bound to 127.0.0.1 only, so 127.0.0.2 refuses
+        let ips = vec![
+            "127.0.0.2".to_string(),
+            "127.0.0.1".to_string(),
+            "not-an-ip".to_string(),
+        ];
+
+        let (reachable, unreachable) =
+            partition_reachable(&ips, port, Duration::from_millis(50)).await;
+
+        assert_eq!(reachable, vec!["127.0.0.1".to_string()]);
+        assert_eq!(
+            unreachable,
+            vec!["127.0.0.2".to_string(), "not-an-ip".to_string()]
+        );
+    }
+}
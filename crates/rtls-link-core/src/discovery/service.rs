@@ -40,6 +40,55 @@ pub fn create_reusable_socket(port: u16) -> Result<std::net::UdpSocket, std::io:
     Ok(socket.into())
 }
 
+/// Create a UDP socket that does not share its port, for listeners that
+/// must receive every datagram sent to it.
+///
+/// Unlike [`create_reusable_socket`], no reuse options are set, so binding
+/// fails with `AddrInUse` while any other socket holds `port`, including
+/// one created by [`create_reusable_socket`]. On Windows the socket is also
+/// marked SO_EXCLUSIVEADDRUSE, so another process cannot take the port over
+/// with SO_REUSEADDR.
+pub fn create_exclusive_socket(port: u16) -> Result<std::net::UdpSocket, std::io::Error> {
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+
+    #[cfg(windows)]
+    set_exclusive_address_use(&socket)?;
+
+    let addr: SocketAddr = format!("0.0.0.0:{}", port).parse().unwrap();
+    socket.bind(&addr.into())?;
+
+    socket.set_nonblocking(true)?;
+
+    Ok(socket.into())
+}
+
+#[cfg(windows)]
+fn set_exclusive_address_use(socket: &Socket) -> Result<(), std::io::Error> {
+    use std::os::windows::io::AsRawSocket;
+    use windows_sys::Win32::Networking::WinSock::{setsockopt, SOL_SOCKET, SO_REUSEADDR};
+
+    // Defined by winsock2.h as the complement of SO_REUSEADDR
+    const SO_EXCLUSIVEADDRUSE: i32 = !SO_REUSEADDR;
+
+    let enable: i32 = 1;
+    // SAFETY: the socket handle is valid for the lifetime of `socket` and
+    // `enable` outlives the call
+    let result = unsafe {
+        setsockopt(
+            socket.as_raw_socket() as _,
+            SOL_SOCKET,
+            SO_EXCLUSIVEADDRUSE,
+            &enable as *const i32 as *const u8,
+            std::mem::size_of::<i32>() as i32,
+        )
+    };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(std::io::Error::last_os_error())
+    }
+}
+
 /// Framework-agnostic discovery service.
 pub struct DiscoveryService {
     socket: UdpSocket,
@@ -137,3 +186,23 @@ impl DiscoveryService {
         Ok(device_list)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusive_socket_does_not_share_its_port() {
+        let first = create_exclusive_socket(0).unwrap();
+        let port = first.local_addr().unwrap().port();
+
+        let err = create_exclusive_socket(port).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+        let err = create_reusable_socket(port).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        // The port is free again once the first socket is dropped
+        drop(first);
+        create_exclusive_socket(port).unwrap();
+    }
+}
@@ -15,6 +15,9 @@ pub enum CoreError {
     #[error("Config error: {0}")]
     Config(#[from] ConfigError),
 
+    #[error("Command error: {0}")]
+    Command(#[from] CommandError),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -39,6 +42,9 @@ pub enum DeviceError {
 
     #[error("OTA update failed on {ip}: {message}")]
     OtaFailed { ip: String, message: String },
+
+    #[error("Device {ip} rejected the credentials (HTTP {status})")]
+    Unauthorized { ip: String, status: u16 },
 }
 
 /// Configuration errors
@@ -57,6 +63,59 @@ pub enum ConfigError {
     InvalidFile(String),
 }
 
+/// Errors building a device command, raised before anything is sent
+#[derive(Debug, Error)]
+pub enum CommandError {
+    #[error("Invalid {field} {value:?}: {reason}")]
+    InvalidArgument {
+        field: &'static str,
+        value: String,
+        reason: &'static str,
+    },
+}
+
+/// Parameter errors, raised before a value is written to a device
+#[derive(Debug, Error)]
+pub enum ParamError {
+    #[error("Unknown parameter {group}.{name}")]
+    Unknown { group: String, name: String },
+
+    #[error("{group}.{name}: {reason}")]
+    InvalidValue {
+        group: String,
+        name: String,
+        value: String,
+        reason: String,
+    },
+}
+
+/// Invalid values, caught before they are stored or written to a device
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum ValidationError {
+    #[error("{field} {value} must be between {min} and {max}")]
+    OutOfRange {
+        field: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+
+    #[error(
+        "origin.lat {lat} must be between -90 and 90; are latitude and longitude ({lon}) swapped?"
+    )]
+    LatLonSwapped { lat: f64, lon: f64 },
+}
+
+impl ValidationError {
+    /// The offending field, e.g. `origin.lat`
+    pub fn field(&self) -> &'static str {
+        match self {
+            ValidationError::OutOfRange { field, .. } => field,
+            ValidationError::LatLonSwapped { .. } => "origin.lat",
+        }
+    }
+}
+
 /// Storage errors
 #[derive(Debug, Error)]
 pub enum StorageError {
@@ -69,12 +128,24 @@ pub enum StorageError {
     #[error("Invalid preset name: {0}")]
     InvalidPresetName(String),
 
+    #[error("Invalid preset: {0}")]
+    InvalidPreset(String),
+
     #[error("Invalid name: {0}")]
     InvalidName(String),
 
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+
+    #[error("Invalid config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Invalid search query: {0}")]
+    InvalidQuery(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -96,6 +167,9 @@ pub enum AppError {
     #[error("Config not found: {0}")]
     NotFound(String),
 
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+
     #[error("JSON error: {0}")]
     Json(String),
 
@@ -104,6 +178,15 @@ pub enum AppError {
 
     #[error("Device error: {0}")]
     Device(String),
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
 }
 
 impl From<std::io::Error> for AppError {
@@ -121,6 +204,9 @@ impl From<serde_json::Error> for AppError {
 impl From<CoreError> for AppError {
     fn from(e: CoreError) -> Self {
         match e {
+            CoreError::Device(de @ DeviceError::Unauthorized { .. }) => {
+                AppError::Unauthorized(de.to_string())
+            }
             CoreError::Device(de) => AppError::Device(de.to_string()),
             CoreError::Storage(se) => match se {
                 StorageError::InvalidPresetName(msg) | StorageError::InvalidName(msg) => {
@@ -129,15 +215,26 @@ impl From<CoreError> for AppError {
                 StorageError::PresetNotFound(msg) | StorageError::NotFound(msg) => {
                     AppError::NotFound(msg)
                 }
+                StorageError::AlreadyExists(msg) => AppError::AlreadyExists(msg),
+                StorageError::InvalidConfig(msg)
+                | StorageError::InvalidQuery(msg)
+                | StorageError::InvalidPreset(msg) => AppError::InvalidInput(msg),
                 other => AppError::Io(other.to_string()),
             },
             CoreError::Config(ce) => AppError::Json(ce.to_string()),
+            CoreError::Command(ce) => AppError::InvalidInput(ce.to_string()),
             CoreError::Io(e) => AppError::Io(e.to_string()),
             CoreError::Other(msg) => AppError::Io(msg),
         }
     }
 }
 
+impl From<CommandError> for AppError {
+    fn from(e: CommandError) -> Self {
+        AppError::InvalidInput(e.to_string())
+    }
+}
+
 impl From<StorageError> for AppError {
     fn from(e: StorageError) -> Self {
         match e {
@@ -147,6 +244,10 @@ impl From<StorageError> for AppError {
             StorageError::PresetNotFound(msg) | StorageError::NotFound(msg) => {
                 AppError::NotFound(msg)
             }
+            StorageError::AlreadyExists(msg) => AppError::AlreadyExists(msg),
+            StorageError::InvalidConfig(msg)
+            | StorageError::InvalidQuery(msg)
+            | StorageError::InvalidPreset(msg) => AppError::InvalidInput(msg),
             other => AppError::Io(other.to_string()),
         }
     }
@@ -178,6 +279,16 @@ mod tests {
         assert!(format!("{}", err).contains("Device not found"));
     }
 
+    #[test]
+    fn test_unauthorized_device_error_to_app_error() {
+        let core_err = CoreError::Device(DeviceError::Unauthorized {
+            ip: "192.168.1.1".to_string(),
+            status: 401,
+        });
+        let app_err: AppError = core_err.into();
+        assert!(matches!(app_err, AppError::Unauthorized(msg) if msg.contains("HTTP 401")));
+    }
+
     #[test]
     fn test_core_error_to_app_error() {
         let core_err = CoreError::Storage(StorageError::InvalidName("bad name".to_string()));
@@ -1,8 +1,16 @@
+pub mod anchor_csv;
 pub mod calibration;
+pub mod config_diff;
+pub mod config_validation;
 pub mod device;
 pub mod discovery;
 pub mod error;
 pub mod health;
+pub mod location_template;
+pub mod log_clock;
+pub mod log_filter;
+pub mod log_packet;
+pub mod log_stats;
 pub mod mavlink;
 pub mod protocol;
 pub mod storage;
@@ -0,0 +1,372 @@
+//! Generated anchor layouts for common room shapes.
+//!
+//! Rooms are described by their floor dimensions: X runs along `width`,
+//! Y along `length`, and every anchor is mounted at `height`. Anchors are
+//! numbered counter-clockwise from the origin corner, so the first three
+//! match the `rectangular-a1x-a3y` calibration layout.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{AnchorConfig, GpsOrigin, LocationData, Preset, PresetType};
+
+/// Room shape used to place anchors
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Layout {
+    /// Four anchors, one in each corner
+    Square {
+        width: f64,
+        length: f64,
+        height: f64,
+    },
+    /// Six anchors: the corners plus the middle of both long walls
+    Rectangle {
+        width: f64,
+        length: f64,
+        height: f64,
+    },
+    /// Six anchors on the outer corners of a `width` x `length` room with a
+    /// `notch_width` x `notch_length` cut out of the far corner
+    LShape {
+        width: f64,
+        length: f64,
+        #[serde(rename = "notchWidth")]
+        notch_width: f64,
+        #[serde(rename = "notchLength")]
+        notch_length: f64,
+        height: f64,
+    },
+}
+
+impl Layout {
+    /// Number of anchors the layout places
+    pub fn anchor_count(&self) -> usize {
+        match self {
+            Layout::Square { .. } => 4,
+            Layout::Rectangle { .. } | Layout::LShape { .. } => 6,
+        }
+    }
+
+    /// Short summary of the template and dimensions, used as the preset
+    /// description
+    pub fn describe(&self) -> String {
+        match *self {
+            Layout::Square {
+                width,
+                length,
+                height,
+            } => format!(
+                "Square template: {} x {} m room, anchors at {} m",
+                width, length, height
+            ),
+            Layout::Rectangle {
+                width,
+                length,
+                height,
+            } => format!(
+                "Rectangle template: {} x {} m room, anchors at {} m",
+                width, length, height
+            ),
+            Layout::LShape {
+                width,
+                length,
+                notch_width,
+                notch_length,
+                height,
+            } => format!(
+                "L-shape template: {} x {} m room with a {} x {} m notch, anchors at {} m",
+                width, length, notch_width, notch_length, height
+            ),
+        }
+    }
+
+    fn check_dimensions(&self) -> Result<(), String> {
+        let (width, length, height) = match *self {
+            Layout::Square {
+                width,
+                length,
+                height,
+            }
+            | Layout::Rectangle {
+                width,
+                length,
+                height,
+            }
+            | Layout::LShape {
+                width,
+                length,
+                height,
+                ..
+            } => (width, length, height),
+        };
+
+        for (field, value) in [("width", width), ("length", length)] {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(format!(
+                    "Room {} must be a positive number of meters",
+                    field
+                ));
+            }
+        }
+        if !height.is_finite() || height < 0.0 {
+            return Err("Anchor height must be zero or more meters".to_string());
+        }
+
+        if let Layout::LShape {
+            notch_width,
+            notch_length,
+            ..
+        } = *self
+        {
+            if !notch_width.is_finite() || notch_width <= 0.0 || notch_width >= width {
+                return Err(
+                    "Notch width must be positive and smaller than the room width".to_string(),
+                );
+            }
+            if !notch_length.is_finite() || notch_length <= 0.0 || notch_length >= length {
+                return Err(
+                    "Notch length must be positive and smaller than the room length".to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Floor positions of the anchors, counter-clockwise from the origin
+    fn floor_positions(&self) -> Vec<(f64, f64)> {
+        match *self {
+            Layout::Square { width, length, .. } => {
+                vec![(0.0, 0.0), (width, 0.0), (width, length), (0.0, length)]
+            }
+            Layout::Rectangle { width, length, .. } if width >= length => vec![
+                (0.0, 0.0),
+                (width / 2.0, 0.0),
+                (width, 0.0),
+                (width, length),
+                (width / 2.0, length),
+                (0.0, length),
+            ],
+            Layout::Rectangle { width, length, .. } => vec![
+                (0.0, 0.0),
+                (width, 0.0),
+                (width, length / 2.0),
+                (width, length),
+                (0.0, length),
+                (0.0, length / 2.0),
+            ],
+            Layout::LShape {
+                width,
+                length,
+                notch_width,
+                notch_length,
+                ..
+            } => vec![
+                (0.0, 0.0),
+                (width, 0.0),
+                (width, length - notch_length),
+                (width - notch_width, length - notch_length),
+                (width - notch_width, length),
+                (0.0, length),
+            ],
+        }
+    }
+
+    fn height(&self) -> f64 {
+        match *self {
+            Layout::Square { height, .. }
+            | Layout::Rectangle { height, .. }
+            | Layout::LShape { height, .. } => height,
+        }
+    }
+}
+
+/// Place anchors for `layout`
+///
+/// `ids` names the anchors in placement order; when empty they are
+/// numbered from 0. The origin is left at zero for the caller to fill in.
+pub fn generate_layout(layout: Layout, ids: &[String]) -> Result<LocationData, String> {
+    layout.check_dimensions()?;
+
+    let count = layout.anchor_count();
+    let ids: Vec<String> = if ids.is_empty() {
+        (0..count).map(|i| i.to_string()).collect()
+    } else if ids.len() == count {
+        ids.iter().map(|id| id.trim().to_string()).collect()
+    } else {
+        return Err(format!(
+            "Layout places {} anchors but {} ids were given",
+            count,
+            ids.len()
+        ));
+    };
+
+    let z = layout.height();
+    let anchors = layout
+        .floor_positions()
+        .into_iter()
+        .zip(ids)
+        .map(|((x, y), id)| AnchorConfig { id, x, y, z })
+        .collect();
+
+    Ok(LocationData {
+        origin: GpsOrigin {
+            lat: 0.0,
+            lon: 0.0,
+            alt: 0.0,
+        },
+        rotation: 0.0,
+        anchors,
+        use_2d_estimator: None,
+    })
+}
+
+/// Build a locations preset named `name` from a generated layout
+pub fn preset_from_template(name: &str, layout: Layout, ids: &[String]) -> Result<Preset, String> {
+    let locations = generate_layout(layout, ids)?;
+    let now = Utc::now().to_rfc3339();
+
+    Ok(Preset {
+        name: name.to_string(),
+        description: Some(layout.describe()),
+        preset_type: PresetType::Locations,
+        config: None,
+        locations: Some(locations),
+        tags: Vec::new(),
+        overrides: Default::default(),
+        last_applied_at: None,
+        applied_to: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_validation::validate_locations;
+
+    fn positions(locations: &LocationData) -> Vec<(f64, f64, f64)> {
+        locations
+            .anchors
+            .iter()
+            .map(|anchor| (anchor.x, anchor.y, anchor.z))
+            .collect()
+    }
+
+    #[test]
+    fn test_generated_layouts_pass_validation() {
+        let layouts = [
+            Layout::Square {
+                width: 8.0,
+                length: 6.0,
+                height: 2.5,
+            },
+            Layout::Rectangle {
+                width: 4.0,
+                length: 20.0,
+                height: 3.0,
+            },
+            Layout::LShape {
+                width: 10.0,
+                length: 8.0,
+                notch_width: 4.0,
+                notch_length: 3.0,
+                height: 2.0,
+            },
+        ];
+
+        for layout in layouts {
+            let locations = generate_layout(layout, &[]).unwrap();
+            assert_eq!(locations.anchors.len(), layout.anchor_count());
+            assert!(
+                validate_locations(&locations).is_empty(),
+                "{:?} produced issues",
+                layout
+            );
+        }
+    }
+
+    #[test]
+    fn test_square_layout_positions_and_ids() {
+        let ids: Vec<String> = ["10", "11", "12", "13"].map(String::from).to_vec();
+        let locations = generate_layout(
+            Layout::Square {
+                width: 8.0,
+                length: 6.0,
+                height: 2.5,
+            },
+            &ids,
+        )
+        .unwrap();
+
+        assert_eq!(
+            positions(&locations),
+            vec![
+                (0.0, 0.0, 2.5),
+                (8.0, 0.0, 2.5),
+                (8.0, 6.0, 2.5),
+                (0.0, 6.0, 2.5)
+            ]
+        );
+        assert_eq!(locations.anchors[3].id, "13");
+
+        let preset = preset_from_template(
+            "demo-room",
+            Layout::Square {
+                width: 8.0,
+                length: 6.0,
+                height: 2.5,
+            },
+            &[],
+        )
+        .unwrap();
+        assert_eq!(preset.preset_type, PresetType::Locations);
+        assert_eq!(
+            preset.description.as_deref(),
+            Some("Square template: 8 x 6 m room, anchors at 2.5 m")
+        );
+    }
+
+    #[test]
+    fn test_rectangle_uses_the_long_walls() {
+        let locations = generate_layout(
+            Layout::Rectangle {
+                width: 4.0,
+                length: 20.0,
+                height: 3.0,
+            },
+            &[],
+        )
+        .unwrap();
+
+        assert!(positions(&locations).contains(&(0.0, 10.0, 3.0)));
+        assert!(positions(&locations).contains(&(4.0, 10.0, 3.0)));
+    }
+
+    #[test]
+    fn test_rejects_bad_dimensions_and_ids() {
+        let square = Layout::Square {
+            width: 8.0,
+            length: 0.0,
+            height: 2.5,
+        };
+        assert!(generate_layout(square, &[]).is_err());
+
+        let notch_too_big = Layout::LShape {
+            width: 10.0,
+            length: 8.0,
+            notch_width: 10.0,
+            notch_length: 3.0,
+            height: 2.0,
+        };
+        assert!(generate_layout(notch_too_big, &[]).is_err());
+
+        let square = Layout::Square {
+            width: 8.0,
+            length: 6.0,
+            height: 2.5,
+        };
+        assert!(generate_layout(square, &["1".to_string()]).is_err());
+    }
+}
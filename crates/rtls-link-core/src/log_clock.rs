@@ -0,0 +1,78 @@
+//! Wall-clock alignment for device log timestamps.
+//!
+//! Devices stamp log messages with milliseconds since boot. The offset to
+//! local wall-clock time is estimated from receive times and shared by the
+//! manager backend and CLI.
+
+/// Per-device offset between device uptime and local wall-clock time
+///
+/// Each message yields a sample `received_at - ts`, which is the true offset
+/// plus network latency. Keeping the minimum sample filters out jitter. A
+/// timestamp going backwards means the device rebooted and resets the offset.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WallClockOffset {
+    offset_ms: Option<i64>,
+    last_ts: Option<u64>,
+}
+
+impl WallClockOffset {
+    /// Create an offset with no samples yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a device timestamp and its local receive time (ms since the
+    /// epoch), returning the aligned wall-clock timestamp
+    pub fn observe(&mut self, ts: u64, received_at_ms: u64) -> u64 {
+        if self.last_ts.is_some_and(|last| ts < last) {
+            self.offset_ms = None;
+        }
+        self.last_ts = Some(ts);
+
+        let sample = received_at_ms as i64 - ts as i64;
+        let offset = self.offset_ms.map_or(sample, |current| current.min(sample));
+        self.offset_ms = Some(offset);
+
+        (ts as i64 + offset).max(0) as u64
+    }
+
+    /// Current offset estimate in milliseconds, if any samples were seen
+    pub fn offset_ms(&self) -> Option<i64> {
+        self.offset_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_sample_sets_offset() {
+        let mut clock = WallClockOffset::new();
+
+        assert_eq!(clock.observe(1_000, 1_700_000_001_000), 1_700_000_001_000);
+        assert_eq!(clock.offset_ms(), Some(1_700_000_000_000));
+    }
+
+    #[test]
+    fn test_min_filter_removes_jitter() {
+        let mut clock = WallClockOffset::new();
+        clock.observe(1_000, 1_700_000_001_050); // 50 ms latency
+        clock.observe(2_000, 1_700_000_002_010); // 10 ms latency
+
+        // A later, slower packet is aligned with the best estimate
+        assert_eq!(clock.observe(3_000, 1_700_000_003_080), 1_700_000_003_010);
+    }
+
+    #[test]
+    fn test_reboot_resets_offset() {
+        let mut clock = WallClockOffset::new();
+        clock.observe(500_000, 1_700_000_500_000);
+
+        // Device rebooted 10 s later; uptime restarts near zero
+        let wall = clock.observe(200, 1_700_000_510_000);
+
+        assert_eq!(wall, 1_700_000_510_000);
+        assert_eq!(clock.offset_ms(), Some(1_700_000_509_800));
+    }
+}
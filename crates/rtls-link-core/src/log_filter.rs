@@ -0,0 +1,232 @@
+//! Tag filtering for device log streams.
+//!
+//! Firmware tags are hierarchical (`uwb.ranging`, `uwb.tdoa`, `mavlink`), so
+//! filters are glob patterns where `*` matches any run of characters and `?`
+//! matches a single character. Matching is case-sensitive. The same globs
+//! select per-tag minimum levels in [`TagLevelFilter`].
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::LogLevel;
+
+/// Check whether `tag` matches the glob `pattern`
+pub fn tag_matches(pattern: &str, tag: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let tag: Vec<char> = tag.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern and the tag index it resumed at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < tag.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == tag[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, resumed)) => {
+                    p = star + 1;
+                    t = resumed + 1;
+                    backtrack = Some((star, resumed + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// A set of tag globs; a tag passes if it matches any of them
+///
+/// An empty filter lets every tag through.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagFilter {
+    patterns: Vec<String>,
+}
+
+impl TagFilter {
+    /// Create a filter from glob patterns, ignoring blank entries
+    pub fn new<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(Into::into)
+                .map(|p| p.trim().to_string())
+                .filter(|p| !p.is_empty())
+                .collect(),
+        }
+    }
+
+    /// The glob patterns in this filter
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Whether the filter lets every tag through
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Check whether a tag passes the filter
+    pub fn matches(&self, tag: &str) -> bool {
+        self.patterns.is_empty() || self.patterns.iter().any(|p| tag_matches(p, tag))
+    }
+}
+
+/// Minimum log level with per-tag overrides
+///
+/// A message passes if its level is at or above the minimum for its tag.
+/// The minimum comes from the most specific (longest) matching override
+/// glob, or the base level if none match. Among equally long globs the
+/// last one added wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagLevelFilter {
+    base: LogLevel,
+    overrides: Vec<(String, LogLevel)>,
+}
+
+impl TagLevelFilter {
+    /// Create a filter that applies `base` to every tag
+    pub fn new(base: LogLevel) -> Self {
+        Self {
+            base,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Add an override for tags matching `pattern`
+    pub fn with_override(mut self, pattern: impl Into<String>, level: LogLevel) -> Self {
+        self.overrides.push((pattern.into(), level));
+        self
+    }
+
+    /// Parse a `<glob>=<level>` rule, e.g. `uwb*=debug`
+    pub fn parse_rule(rule: &str) -> Result<(String, LogLevel), String> {
+        let (pattern, level) = rule
+            .rsplit_once('=')
+            .ok_or_else(|| format!("Expected <glob>=<level>, got '{}'", rule))?;
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(format!("Missing tag glob in '{}'", rule));
+        }
+        let level = LogLevel::from_str(level.trim())
+            .ok_or_else(|| format!("Invalid log level in '{}'", rule))?;
+        Ok((pattern.to_string(), level))
+    }
+
+    /// The base level used when no override matches
+    pub fn base(&self) -> LogLevel {
+        self.base
+    }
+
+    /// The override rules, in the order they were added
+    pub fn overrides(&self) -> &[(String, LogLevel)] {
+        &self.overrides
+    }
+
+    /// Minimum level for messages with `tag`
+    pub fn min_level(&self, tag: &str) -> LogLevel {
+        self.overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, (pattern, _))| tag_matches(pattern, tag))
+            .max_by_key(|(index, (pattern, _))| (pattern.chars().count(), *index))
+            .map_or(self.base, |(_, (_, level))| *level)
+    }
+
+    /// Check whether a message at `level` with `tag` passes the filter
+    pub fn allows(&self, level: LogLevel, tag: &str) -> bool {
+        level <= self.min_level(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_matches_globs() {
+        assert!(tag_matches("uwb.*", "uwb.ranging"));
+        assert!(tag_matches("uwb*", "uwb"));
+        assert!(tag_matches("*.tdoa", "uwb.tdoa"));
+        assert!(tag_matches("uwb.?doa", "uwb.tdoa"));
+        assert!(tag_matches("mavlink", "mavlink"));
+        assert!(tag_matches("*", ""));
+
+        assert!(!tag_matches("uwb.*", "uwb"));
+        assert!(!tag_matches("uwb.*", "mavlink"));
+        assert!(!tag_matches("mav", "mavlink"));
+        // `.` is literal, not a regex wildcard
+        assert!(!tag_matches("uwb.ranging", "uwbxranging"));
+    }
+
+    #[test]
+    fn test_tag_matches_backtracking() {
+        assert!(tag_matches("*a*b", "xaxxab"));
+        assert!(tag_matches("a*b*c", "abbbc"));
+        assert!(!tag_matches("a*b*c", "abbb"));
+    }
+
+    #[test]
+    fn test_tag_filter() {
+        let all = TagFilter::default();
+        assert!(all.matches("anything"));
+
+        let filter = TagFilter::new(["uwb.*", " ", "mavlink"]);
+        assert_eq!(filter.patterns().len(), 2);
+        assert!(filter.matches("uwb.tdoa"));
+        assert!(filter.matches("mavlink"));
+        assert!(!filter.matches("wifi"));
+    }
+
+    #[test]
+    fn test_tag_level_filter_precedence() {
+        let filter = TagLevelFilter::new(LogLevel::Warn)
+            .with_override("uwb*", LogLevel::Debug)
+            .with_override("uwb.tdoa", LogLevel::Error);
+
+        // Base level applies to unmatched tags
+        assert!(filter.allows(LogLevel::Warn, "wifi"));
+        assert!(!filter.allows(LogLevel::Info, "wifi"));
+
+        // Glob override lowers the threshold
+        assert!(filter.allows(LogLevel::Debug, "uwb.ranging"));
+        assert!(!filter.allows(LogLevel::Verbose, "uwb.ranging"));
+
+        // The longer, more specific glob wins over "uwb*"
+        assert_eq!(filter.min_level("uwb.tdoa"), LogLevel::Error);
+        assert!(!filter.allows(LogLevel::Warn, "uwb.tdoa"));
+    }
+
+    #[test]
+    fn test_tag_level_filter_ties_use_last_rule() {
+        let filter = TagLevelFilter::new(LogLevel::Info)
+            .with_override("uwb*", LogLevel::Debug)
+            .with_override("*tdoa", LogLevel::Error);
+
+        // Both globs are four characters long
+        assert_eq!(filter.min_level("uwbtdoa"), LogLevel::Error);
+    }
+
+    #[test]
+    fn test_parse_tag_level_rule() {
+        assert_eq!(
+            TagLevelFilter::parse_rule(" uwb* = debug"),
+            Ok(("uwb*".to_string(), LogLevel::Debug))
+        );
+        assert!(TagLevelFilter::parse_rule("uwb*").is_err());
+        assert!(TagLevelFilter::parse_rule("=debug").is_err());
+        assert!(TagLevelFilter::parse_rule("uwb*=loud").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Parsing of device log packets.
+//!
+//! Devices push log messages to the log UDP port either as binary frames
+//! or as JSON. Current firmware sends JSON objects with a string `lvl`,
+//! `msg` and `ts`, batching several into a JSON array; older builds and
+//! tools use a numeric `level`, `message` and `timestamp`. Both forms are
+//! accepted so the manager backend and CLI classify messages identically.
+
+use serde::Deserialize;
+
+use crate::protocol::binary::decode_log_message;
+use crate::types::{LogLevel, LogMessage, MessageLevel};
+
+/// JSON log message as sent by the firmware
+#[derive(Debug, Deserialize)]
+struct WireLogMessage {
+    #[serde(default, alias = "timestamp")]
+    ts: Option<u64>,
+    #[serde(default, alias = "level")]
+    lvl: Option<MessageLevel>,
+    #[serde(default)]
+    tag: String,
+    #[serde(alias = "message")]
+    msg: String,
+    #[serde(default)]
+    seq: Option<u32>,
+}
+
+/// Parse every log message contained in a UDP packet
+///
+/// Returns an error if the packet is neither a binary log frame nor JSON.
+/// Otherwise each message (one, or several for a batched array) yields an
+/// entry, with an error in the slot of any malformed element.
+pub fn parse_log_packet(data: &[u8], ip: &str) -> Result<Vec<Result<LogMessage, String>>, String> {
+    if let Ok(log) = decode_log_message(data, ip) {
+        return Ok(vec![Ok(log)]);
+    }
+
+    match serde_json::from_slice::<serde_json::Value>(data).map_err(|e| e.to_string())? {
+        serde_json::Value::Array(entries) => Ok(entries
+            .into_iter()
+            .map(|entry| parse_log_json(entry, ip))
+            .collect()),
+        json => Ok(vec![parse_log_json(json, ip)]),
+    }
+}
+
+/// Convert a single JSON log object into a [`LogMessage`]
+///
+/// A missing level defaults to INFO; the message body is required.
+pub fn parse_log_json(json: serde_json::Value, ip: &str) -> Result<LogMessage, String> {
+    if !json.is_object() {
+        return Err(format!("Expected a log object, got {}", json));
+    }
+
+    let wire: WireLogMessage = serde_json::from_value(json).map_err(|e| e.to_string())?;
+    Ok(LogMessage {
+        ip: ip.to_string(),
+        level: wire.lvl.unwrap_or(MessageLevel::Known(LogLevel::Info)),
+        tag: wire.tag,
+        message: wire.msg,
+        timestamp: wire.ts,
+        seq: wire.seq,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(data: &[u8]) -> LogMessage {
+        let mut logs = parse_log_packet(data, "10.0.0.5").unwrap();
+        assert_eq!(logs.len(), 1);
+        logs.remove(0).unwrap()
+    }
+
+    #[test]
+    fn test_parse_string_level_format() {
+        let log = parse_one(br#"{"ts":1200,"lvl":"WARN","tag":"uwb","msg":"late","seq":7}"#);
+
+        assert_eq!(log.ip, "10.0.0.5");
+        assert_eq!(log.level, MessageLevel::Known(LogLevel::Warn));
+        assert_eq!(log.tag, "uwb");
+        assert_eq!(log.message, "late");
+        assert_eq!(log.timestamp, Some(1200));
+        assert_eq!(log.seq, Some(7));
+    }
+
+    #[test]
+    fn test_parse_numeric_level_format() {
+        let log = parse_one(br#"{"timestamp":99,"level":1,"tag":"app","message":"boom"}"#);
+
+        assert_eq!(log.level, MessageLevel::Known(LogLevel::Error));
+        assert_eq!(log.message, "boom");
+        assert_eq!(log.timestamp, Some(99));
+        assert_eq!(log.seq, None);
+    }
+
+    #[test]
+    fn test_parse_defaults_and_unknown_levels() {
+        let log = parse_one(br#"{"msg":"hello"}"#);
+        assert_eq!(log.level, MessageLevel::Known(LogLevel::Info));
+        assert_eq!(log.timestamp, None);
+
+        let log = parse_one(br#"{"lvl":"TRACE","msg":"hello"}"#);
+        assert_eq!(log.level, MessageLevel::Unknown("trace".to_string()));
+    }
+
+    #[test]
+    fn test_parse_batched_array_with_malformed_entry() {
+        let logs = parse_log_packet(
+            br#"[{"lvl":"INFO","msg":"a"},{"ts":"bad"},5,{"level":4,"msg":"b"}]"#,
+            "10.0.0.5",
+        )
+        .unwrap();
+
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[0].as_ref().unwrap().message, "a");
+        assert!(logs[1].is_err());
+        assert!(logs[2].is_err());
+        assert_eq!(
+            logs[3].as_ref().unwrap().level,
+            MessageLevel::Known(LogLevel::Debug)
+        );
+    }
+
+    #[test]
+    fn test_parse_non_json_packet_is_an_error() {
+        assert!(parse_log_packet(b"rst:0x1 (POWERON)", "10.0.0.5").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Per-level log statistics for RTLS-Link device logs.
+//!
+//! Counters are updated incrementally as messages arrive so that the
+//! manager backend and CLI can report rates without rescanning buffers.
+
+use std::collections::VecDeque;
+
+use serde::Serialize;
+
+use crate::types::LogLevel;
+
+/// Length of the sliding rate window in seconds
+pub const LOG_STATS_WINDOW_SECS: u64 = 60;
+
+/// Message counts broken down by log level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelCounts {
+    pub error: u64,
+    pub warn: u64,
+    pub info: u64,
+    pub debug: u64,
+    pub verbose: u64,
+    /// Messages with no level or an unrecognized one
+    pub other: u64,
+}
+
+impl LevelCounts {
+    fn slot(&mut self, level: LogLevel) -> &mut u64 {
+        match level {
+            LogLevel::Error => &mut self.error,
+            LogLevel::Warn => &mut self.warn,
+            LogLevel::Info => &mut self.info,
+            LogLevel::Debug => &mut self.debug,
+            LogLevel::Verbose => &mut self.verbose,
+            LogLevel::None => &mut self.other,
+        }
+    }
+
+    /// Count one message at `level`
+    pub fn increment(&mut self, level: LogLevel) {
+        *self.slot(level) += 1;
+    }
+
+    /// Remove `count` messages at `level`
+    pub fn decrement(&mut self, level: LogLevel, count: u64) {
+        let slot = self.slot(level);
+        *slot = slot.saturating_sub(count);
+    }
+
+    /// Total number of messages across all levels
+    pub fn total(&self) -> u64 {
+        self.error + self.warn + self.info + self.debug + self.verbose + self.other
+    }
+
+    fn subtract(&mut self, other: &LevelCounts) {
+        self.error = self.error.saturating_sub(other.error);
+        self.warn = self.warn.saturating_sub(other.warn);
+        self.info = self.info.saturating_sub(other.info);
+        self.debug = self.debug.saturating_sub(other.debug);
+        self.verbose = self.verbose.saturating_sub(other.verbose);
+        self.other = self.other.saturating_sub(other.other);
+    }
+}
+
+/// Point-in-time view of a [`LogStatsTracker`]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStatsSnapshot {
+    /// Counts for every message currently tracked (e.g. the whole buffer)
+    pub totals: LevelCounts,
+    /// Counts for messages received in the last [`LOG_STATS_WINDOW_SECS`]
+    pub window: LevelCounts,
+    /// Length of the sliding window in seconds
+    pub window_secs: u64,
+    /// Average message rate over the window (or since the first message,
+    /// if that is more recent)
+    pub messages_per_sec: f64,
+    /// Fraction of windowed messages at ERROR level (0.0 - 1.0)
+    pub error_rate: f64,
+}
+
+/// Incremental per-level counters with a sliding one-minute window
+#[derive(Debug, Clone, Default)]
+pub struct LogStatsTracker {
+    totals: LevelCounts,
+    window: LevelCounts,
+    /// Per-second buckets inside the window, oldest first
+    buckets: VecDeque<(u64, LevelCounts)>,
+    /// Time of the first recorded message (ms)
+    first_at_ms: Option<u64>,
+}
+
+impl LogStatsTracker {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a message received at `at_ms` (milliseconds since the epoch)
+    pub fn record(&mut self, level: LogLevel, at_ms: u64) {
+        let second = at_ms / 1000;
+        self.expire(second);
+        self.first_at_ms.get_or_insert(at_ms);
+
+        self.totals.increment(level);
+        self.window.increment(level);
+        match self.buckets.back_mut() {
+            Some((bucket_second, counts)) if *bucket_second >= second => counts.increment(level),
+            _ => {
+                let mut counts = LevelCounts::default();
+                counts.increment(level);
+                self.buckets.push_back((second, counts));
+            }
+        }
+    }
+
+    /// Remove `count` messages from the totals, e.g. when they are evicted
+    /// from a buffer. The sliding window is unaffected.
+    pub fn evict(&mut self, level: LogLevel, count: u64) {
+        self.totals.decrement(level, count);
+    }
+
+    /// Reset the totals, e.g. when the underlying buffer is cleared
+    pub fn reset_totals(&mut self) {
+        self.totals = LevelCounts::default();
+    }
+
+    /// Snapshot the counters as of `now_ms`
+    pub fn snapshot(&self, now_ms: u64) -> LogStatsSnapshot {
+        let cutoff = (now_ms / 1000).saturating_sub(LOG_STATS_WINDOW_SECS);
+        let mut window = self.window;
+        for (_, counts) in self
+            .buckets
+            .iter()
+            .take_while(|(second, _)| *second <= cutoff)
+        {
+            window.subtract(counts);
+        }
+
+        let window_secs = LOG_STATS_WINDOW_SECS as f64;
+        let elapsed_secs = self
+            .first_at_ms
+            .map(|first| now_ms.saturating_sub(first) as f64 / 1000.0)
+            .unwrap_or(window_secs)
+            .clamp(1.0, window_secs);
+
+        let total = window.total();
+        LogStatsSnapshot {
+            totals: self.totals,
+            window,
+            window_secs: LOG_STATS_WINDOW_SECS,
+            messages_per_sec: total as f64 / elapsed_secs,
+            error_rate: if total == 0 {
+                0.0
+            } else {
+                window.error as f64 / total as f64
+            },
+        }
+    }
+
+    fn expire(&mut self, now_second: u64) {
+        let cutoff = now_second.saturating_sub(LOG_STATS_WINDOW_SECS);
+        while let Some((second, counts)) = self.buckets.front() {
+            if *second > cutoff {
+                break;
+            }
+            self.window.subtract(counts);
+            self.buckets.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_counts_per_level() {
+        let mut tracker = LogStatsTracker::new();
+        tracker.record(LogLevel::Info, 1_000);
+        tracker.record(LogLevel::Info, 1_500);
+        tracker.record(LogLevel::Error, 2_000);
+        tracker.record(LogLevel::None, 2_000);
+
+        let snapshot = tracker.snapshot(2_000);
+
+        assert_eq!(snapshot.totals.info, 2);
+        assert_eq!(snapshot.totals.error, 1);
+        assert_eq!(snapshot.totals.other, 1);
+        assert_eq!(snapshot.window.total(), 4);
+        assert!((snapshot.error_rate - 0.25).abs() < f64::EPSILON);
+        // Only one second has elapsed since the first message
+        assert!((snapshot.messages_per_sec - 4.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_window_expires_old_messages() {
+        let mut tracker = LogStatsTracker::new();
+        tracker.record(LogLevel::Warn, 10_000);
+        tracker.record(LogLevel::Info, 65_000);
+
+        let snapshot = tracker.snapshot(71_000);
+
+        assert_eq!(snapshot.totals.total(), 2);
+        assert_eq!(snapshot.window.total(), 1);
+        assert_eq!(snapshot.window.warn, 0);
+        assert!((snapshot.messages_per_sec - 1.0 / 60.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_evict_and_reset_only_affect_totals() {
+        let mut tracker = LogStatsTracker::new();
+        tracker.record(LogLevel::Debug, 1_000);
+        tracker.record(LogLevel::Debug, 1_000);
+        tracker.evict(LogLevel::Debug, 1);
+
+        assert_eq!(tracker.snapshot(1_000).totals.debug, 1);
+
+        tracker.reset_totals();
+        let snapshot = tracker.snapshot(1_000);
+        assert_eq!(snapshot.totals.total(), 0);
+        assert_eq!(snapshot.window.debug, 2);
+    }
+}
@@ -3,7 +3,7 @@
 use serde_json::{json, Map, Number, Value};
 
 use crate::error::{CoreError, DeviceError};
-use crate::types::{LogLevel, LogMessage};
+use crate::types::{LogLevel, LogMessage, MessageLevel};
 
 pub const FRAME_MAGIC: u16 = 0x4c52;
 pub const FRAME_VERSION: u8 = 1;
@@ -649,9 +649,10 @@ pub fn decode_log_message(data: &[u8], ip: &str) -> Result<LogMessage, String> {
     Ok(LogMessage {
         ip: ip.to_string(),
         timestamp: Some(r.u32()? as u64),
-        level: LogLevel::from_u8(r.u8()?),
+        level: MessageLevel::Known(LogLevel::from_u8(r.u8()?)),
         tag: r.string()?,
         message: r.string()?,
+        seq: None,
     })
 }
 
@@ -689,6 +690,29 @@ mod tests {
         out
     }
 
+    #[test]
+    fn decodes_firmware_info_frame() {
+        let mut payload = Vec::new();
+        for field in [
+            "RTLS-Link",
+            "2.3.1",
+            "ESP32S3_UWB",
+            "Mar  4 2026",
+            "14:02:11",
+        ] {
+            push_string(&mut payload, field);
+        }
+
+        let value = decode_command_frame(&frame(FrameType::FirmwareInfo, payload), "1.2.3.4")
+            .expect("frame decodes");
+        let info =
+            crate::protocol::response::parse_firmware_info(&value.to_string(), "1.2.3.4").unwrap();
+
+        assert_eq!(info.version.as_deref(), Some("2.3.1"));
+        assert_eq!(info.board.as_deref(), Some("ESP32S3_UWB"));
+        assert_eq!(info.build_time.as_deref(), Some("14:02:11"));
+    }
+
     #[test]
     fn decodes_tdoa_distances_frame() {
         let mut payload = vec![2, 4];
@@ -799,7 +823,7 @@ mod tests {
 
         assert_eq!(log.ip, "192.168.1.50");
         assert_eq!(log.timestamp, Some(12345));
-        assert_eq!(log.level, LogLevel::Debug);
+        assert_eq!(log.level, MessageLevel::Known(LogLevel::Debug));
         assert_eq!(log.tag, "uwb");
         assert_eq!(log.message, "measurement accepted");
     }
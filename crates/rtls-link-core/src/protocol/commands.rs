@@ -1,12 +1,21 @@
 //! Command string builders for RTLS-Link device protocol.
 //!
 //! These commands are sent over the MAVLink UDP management endpoint.
+//!
+//! Builders taking a parameter group or name, or a config name, check it
+//! against a safe character set so that no argument can be read as a
+//! separate token or command.
+
+use crate::error::CommandError;
 
 /// Commands that return structured responses.
 ///
 /// These commands may be returned by device-side binary frames and decoded into
 /// JSON by the host. The name intentionally describes the host-facing behavior
 /// without implying that the firmware builds JSON strings.
+///
+/// JSON is picked up from any reply; this list only marks the commands whose
+/// reply is an error without it.
 pub const STRUCTURED_RESPONSE_COMMANDS: &[&str] = &[
     "backup-config",
     "list-configs",
@@ -22,6 +31,90 @@ pub const STRUCTURED_RESPONSE_COMMANDS: &[&str] = &[
     "tdoa-estimator-status",
 ];
 
+/// Name of the batched parameter write command
+pub const WRITE_BATCH_COMMAND: &str = "write-batch";
+
+/// Check that a parameter group or name is a plain identifier
+fn check_identifier(field: &'static str, value: &str) -> Result<(), CommandError> {
+    let invalid = |reason| CommandError::InvalidArgument {
+        field,
+        value: value.to_string(),
+        reason,
+    };
+    if value.is_empty() {
+        return Err(invalid("must not be empty"));
+    }
+    if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(invalid("only letters, digits and underscores are allowed"));
+    }
+    Ok(())
+}
+
+/// Check that a device config name uses the same characters as local ones
+/// and cannot be mistaken for an option
+fn check_config_name(name: &str) -> Result<(), CommandError> {
+    let invalid = |reason| CommandError::InvalidArgument {
+        field: "config name",
+        value: name.to_string(),
+        reason,
+    };
+    if name.is_empty() {
+        return Err(invalid("must not be empty"));
+    }
+    if name.starts_with('-') {
+        return Err(invalid("must not start with a dash"));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err(invalid(
+            "only letters, digits, dashes and underscores are allowed",
+        ));
+    }
+    Ok(())
+}
+
+/// Check a raw command, e.g. one typed in the UI, before it is sent
+///
+/// Control characters are rejected anywhere, and the group, name and config
+/// name arguments of parameter and named-config commands get the same
+/// checks as the builders apply.
+pub fn check_command(command: &str) -> Result<(), CommandError> {
+    if command.chars().any(char::is_control) {
+        return Err(CommandError::InvalidArgument {
+            field: "command",
+            value: command.to_string(),
+            reason: "must not contain control characters",
+        });
+    }
+
+    let tokens = command.split_whitespace().collect::<Vec<_>>();
+    let arg = |key: &str| {
+        tokens
+            .windows(2)
+            .find(|pair| pair[0] == key || pair[0].strip_prefix('-') == Some(key))
+            .map(|pair| pair[1])
+    };
+    match tokens.first().copied() {
+        Some("read" | "write") => {
+            if let Some(group) = arg("-group") {
+                check_identifier("group", group)?;
+            }
+            if let Some(name) = arg("-name") {
+                check_identifier("name", name)?;
+            }
+        }
+        Some("save-config-as" | "load-config-named" | "read-config-named" | "delete-config") => {
+            if let Some(name) = arg("-name") {
+                check_config_name(name)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
 /// Check if a command is expected to return a structured response.
 pub fn is_structured_response_command(cmd: &str) -> bool {
     STRUCTURED_RESPONSE_COMMANDS
@@ -44,14 +137,31 @@ impl Commands {
     }
 
     /// Read a single parameter
-    pub fn read_param(group: &str, name: &str) -> String {
-        format!("read -group {} -name {}", group, name)
+    pub fn read_param(group: &str, name: &str) -> Result<String, CommandError> {
+        check_identifier("group", group)?;
+        check_identifier("name", name)?;
+        Ok(format!("read -group {} -name {}", group, name))
     }
 
     /// Write a parameter value
     ///
-    /// Values are properly escaped for the protocol
-    pub fn write_param(group: &str, name: &str, value: &str) -> String {
+    /// Values are properly escaped for the protocol; control characters
+    /// cannot be and are rejected.
+    pub fn write_param(group: &str, name: &str, value: &str) -> Result<String, CommandError> {
+        check_identifier("group", group)?;
+        check_identifier("name", name)?;
+        if value.chars().any(char::is_control) {
+            return Err(CommandError::InvalidArgument {
+                field: "value",
+                value: value.to_string(),
+                reason: "must not contain control characters",
+            });
+        }
+        Ok(Self::format_write(group, name, value))
+    }
+
+    /// The write command for arguments known to be valid
+    fn format_write(group: &str, name: &str, value: &str) -> String {
         let safe_value = value.replace('\\', "\\\\").replace('"', "\\\"");
         format!(
             "write -group {} -name {} -data \"{}\"",
@@ -59,6 +169,29 @@ impl Commands {
         )
     }
 
+    /// Write several parameters in one command
+    ///
+    /// The payload is a JSON array of `{group, name, value}` objects, so
+    /// values need no escaping beyond JSON's own. The reply is one result
+    /// per parameter, see [`parse_write_batch_response`].
+    ///
+    /// [`parse_write_batch_response`]: crate::protocol::response::parse_write_batch_response
+    pub fn write_batch(params: &[(String, String, String)]) -> Result<String, CommandError> {
+        let items = params
+            .iter()
+            .map(|(group, name, value)| {
+                check_identifier("group", group)?;
+                check_identifier("name", name)?;
+                Ok(serde_json::json!({ "group": group, "name": name, "value": value }))
+            })
+            .collect::<Result<Vec<_>, CommandError>>()?;
+        Ok(format!(
+            "{} {}",
+            WRITE_BATCH_COMMAND,
+            serde_json::Value::Array(items)
+        ))
+    }
+
     // ==================== Config commands ====================
 
     /// Backup current configuration.
@@ -82,23 +215,27 @@ impl Commands {
     }
 
     /// Save current config with a name.
-    pub fn save_config_as(name: &str) -> String {
-        format!("save-config-as -name {}", name)
+    pub fn save_config_as(name: &str) -> Result<String, CommandError> {
+        check_config_name(name)?;
+        Ok(format!("save-config-as -name {}", name))
     }
 
     /// Load a named configuration.
-    pub fn load_config_named(name: &str) -> String {
-        format!("load-config-named -name {}", name)
+    pub fn load_config_named(name: &str) -> Result<String, CommandError> {
+        check_config_name(name)?;
+        Ok(format!("load-config-named -name {}", name))
     }
 
     /// Read a named configuration without loading.
-    pub fn read_config_named(name: &str) -> String {
-        format!("read-config-named -name {}", name)
+    pub fn read_config_named(name: &str) -> Result<String, CommandError> {
+        check_config_name(name)?;
+        Ok(format!("read-config-named -name {}", name))
     }
 
     /// Delete a named configuration.
-    pub fn delete_config(name: &str) -> String {
-        format!("delete-config -name {}", name)
+    pub fn delete_config(name: &str) -> Result<String, CommandError> {
+        check_config_name(name)?;
+        Ok(format!("delete-config -name {}", name))
     }
 
     // ==================== Control commands ====================
@@ -123,6 +260,36 @@ impl Commands {
         "write -group uwb -name uwbEnable -data \"1\""
     }
 
+    /// Stop positioning
+    pub fn stop() -> &'static str {
+        "write -group uwb -name uwbEnable -data \"0\""
+    }
+
+    // ==================== Logging commands ====================
+
+    /// Enable or disable UDP log streaming, optionally moving it to `port`
+    ///
+    /// The firmware has no logging command, so these are parameter writes;
+    /// they take effect immediately and revert on reboot unless saved.
+    pub fn set_log_udp(enabled: bool, port: Option<u16>) -> Vec<String> {
+        let mut commands = Vec::new();
+        if let Some(port) = port {
+            commands.push(Self::format_write("wifi", "logUdpPort", &port.to_string()));
+        }
+        commands.push(Self::format_write(
+            "wifi",
+            "logUdpEnabled",
+            if enabled { "1" } else { "0" },
+        ));
+        commands
+    }
+
+    /// Enable or disable Serial logging (a parameter write, like
+    /// [`Commands::set_log_udp`])
+    pub fn set_log_serial(enabled: bool) -> String {
+        Self::format_write("wifi", "logSerialEnabled", if enabled { "1" } else { "0" })
+    }
+
     // ==================== System info commands ====================
 
     /// Get firmware version
@@ -161,7 +328,7 @@ mod tests {
     #[test]
     fn test_read_param() {
         assert_eq!(
-            Commands::read_param("wifi", "ssidST"),
+            Commands::read_param("wifi", "ssidST").unwrap(),
             "read -group wifi -name ssidST"
         );
     }
@@ -169,7 +336,7 @@ mod tests {
     #[test]
     fn test_write_param() {
         assert_eq!(
-            Commands::write_param("wifi", "ssidST", "MyNetwork"),
+            Commands::write_param("wifi", "ssidST", "MyNetwork").unwrap(),
             "write -group wifi -name ssidST -data \"MyNetwork\""
         );
     }
@@ -177,15 +344,113 @@ mod tests {
     #[test]
     fn test_write_param_escaping() {
         assert_eq!(
-            Commands::write_param("wifi", "ssidST", "Test\"Network"),
+            Commands::write_param("wifi", "ssidST", "Test\"Network").unwrap(),
             "write -group wifi -name ssidST -data \"Test\\\"Network\""
         );
         assert_eq!(
-            Commands::write_param("wifi", "pswdST", "pass\\word"),
+            Commands::write_param("wifi", "pswdST", "pass\\word").unwrap(),
             "write -group wifi -name pswdST -data \"pass\\\\word\""
         );
     }
 
+    #[test]
+    fn test_write_batch() {
+        let params = vec![
+            (
+                "wifi".to_string(),
+                "ssidST".to_string(),
+                "My \"Lab\"".to_string(),
+            ),
+            ("uwb".to_string(), "mode".to_string(), "4".to_string()),
+        ];
+        let command = Commands::write_batch(&params).unwrap();
+        assert_eq!(
+            command,
+            r#"write-batch [{"group":"wifi","name":"ssidST","value":"My \"Lab\""},{"group":"uwb","name":"mode","value":"4"}]"#
+        );
+
+        let payload = command.strip_prefix("write-batch ").unwrap();
+        let items: serde_json::Value = serde_json::from_str(payload).unwrap();
+        assert_eq!(items[0]["value"], "My \"Lab\"");
+    }
+
+    #[test]
+    fn test_builders_reject_unsafe_arguments() {
+        assert!(Commands::read_param("uwb", "x1; reboot").is_err());
+        assert!(Commands::read_param("uwb", "").is_err());
+        assert!(Commands::read_param("-group", "x1").is_err());
+        assert!(Commands::write_param("uwb", "x1 -data", "1").is_err());
+        assert!(Commands::write_param("wifi", "ssidST", "lab\nreboot").is_err());
+        assert!(Commands::write_param("uwb", "dev_id1", "1").is_ok());
+
+        let params = vec![("uwb".to_string(), "mode\"".to_string(), "4".to_string())];
+        assert!(Commands::write_batch(&params).is_err());
+
+        assert_eq!(
+            Commands::save_config_as("site-a_2").unwrap(),
+            "save-config-as -name site-a_2"
+        );
+        assert!(Commands::save_config_as("-name").is_err());
+        assert!(Commands::load_config_named("lab config").is_err());
+        assert!(Commands::read_config_named("lab\"").is_err());
+        assert!(Commands::delete_config("").is_err());
+
+        let err = Commands::read_param("uwb", "x1; reboot").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid name \"x1; reboot\": only letters, digits and underscores are allowed"
+        );
+    }
+
+    #[test]
+    fn test_check_command() {
+        assert!(check_command("write -group wifi -name ssidST -data \"My Lab\"").is_ok());
+        assert!(check_command("read --group uwb --name x1").is_ok());
+        assert!(check_command("load-config-named -name site-a").is_ok());
+        assert!(check_command("reboot").is_ok());
+
+        assert!(check_command("read -group uwb -name x1;reboot").is_err());
+        assert!(check_command("write --group wifi; -name ssidST -data 1").is_err());
+        assert!(check_command("delete-config -name -x").is_err());
+        assert!(check_command("version\nreboot").is_err());
+    }
+
+    #[test]
+    fn test_start_stop() {
+        assert_eq!(
+            Commands::start(),
+            "write -group uwb -name uwbEnable -data \"1\""
+        );
+        assert_eq!(
+            Commands::stop(),
+            "write -group uwb -name uwbEnable -data \"0\""
+        );
+    }
+
+    #[test]
+    fn test_set_log_udp() {
+        assert_eq!(
+            Commands::set_log_udp(false, None),
+            vec!["write -group wifi -name logUdpEnabled -data \"0\""]
+        );
+        // The port changes first so no logs go to the old one
+        assert_eq!(
+            Commands::set_log_udp(true, Some(3334)),
+            vec![
+                "write -group wifi -name logUdpPort -data \"3334\"",
+                "write -group wifi -name logUdpEnabled -data \"1\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_log_serial() {
+        assert_eq!(
+            Commands::set_log_serial(true),
+            "write -group wifi -name logSerialEnabled -data \"1\""
+        );
+    }
+
     #[test]
     fn test_is_structured_response_command() {
         assert!(is_structured_response_command("backup-config"));
@@ -1,26 +1,57 @@
 //! Configuration to parameter conversion.
 //!
 //! Converts DeviceConfig to an array of [group, name, value] tuples
-//! for uploading to devices via write commands.
+//! for uploading to devices via write commands, in the order described by
+//! [`order_params`].
 //!
-//! IMPORTANT: devShortAddr is intentionally skipped to preserve device identity.
+//! IMPORTANT: devShortAddr is skipped by default to preserve device identity.
 
-use crate::types::{AnchorConfig, DeviceConfig, LocationData};
+use std::collections::HashMap;
+use std::ops::Deref;
+
+use serde::Serialize;
+
+use crate::error::CommandError;
+use crate::protocol::commands::Commands;
+use crate::storage::migration::upgrade_config_value;
+use crate::types::{AnchorConfig, DeviceConfig, LocationData, ParamOverride, Preset, PresetType};
 
 const MAX_CONFIGURABLE_ANCHORS: usize = 8;
 const LEGACY_3D_MIN_ANCHORS: usize = 4;
 const ROBUST_3D_MIN_ANCHORS: usize = 6;
+/// Anchors further than this from the origin along any axis, in meters,
+/// are taken to be typos
+const MAX_ANCHOR_COORDINATE_M: f64 = 10_000.0;
 
 /// Parse a firmware `backup-config` payload into a DeviceConfig.
 ///
 /// Firmware stores anchor geometry as flat `uwb.devIdN/xN/yN/zN` fields.
 /// The manager stores anchors as `uwb.anchors`, so rebuild that array before
 /// saving or uploading the config again.
+///
+/// Payloads that do not parse as they are, such as those of older firmware
+/// releases with numbers as strings, no `app` group or anchors keyed by
+/// index, are upgraded the way stored configs are and parsed again. If that
+/// fails too, the original error is returned.
 pub fn device_config_from_backup_value(
+    mut value: serde_json::Value,
+) -> serde_json::Result<DeviceConfig> {
+    match device_config_from_backup_value_strict(value.clone()) {
+        Err(e) if upgrade_config_value(&mut value) => {
+            device_config_from_backup_value_strict(value).map_err(|_| e)
+        }
+        result => result,
+    }
+}
+
+/// Parse a `backup-config` payload without upgrading older payload shapes
+pub fn device_config_from_backup_value_strict(
     value: serde_json::Value,
 ) -> serde_json::Result<DeviceConfig> {
     let mut config: DeviceConfig = serde_json::from_value(value.clone())?;
     rebuild_flat_anchors(&mut config, &value).map_err(backup_parse_error)?;
+    // Flat anchor fields land in `extra`; `anchors` is authoritative now
+    config.uwb.extra.retain(|name, _| !is_flat_anchor_key(name));
     Ok(config)
 }
 
@@ -137,6 +168,15 @@ fn has_flat_anchor_fields(uwb: &serde_json::Map<String, serde_json::Value>) -> b
     })
 }
 
+/// Whether `name` is a firmware flat anchor field (`devId1`, `x1`, ...)
+pub(crate) fn is_flat_anchor_key(name: &str) -> bool {
+    ["devId", "x", "y", "z"].iter().any(|prefix| {
+        name.strip_prefix(prefix)
+            .and_then(|idx| idx.parse::<usize>().ok())
+            .is_some_and(|idx| (1..=MAX_CONFIGURABLE_ANCHORS).contains(&idx))
+    })
+}
+
 fn value_to_f64(value: Option<&serde_json::Value>) -> Option<f64> {
     value.and_then(|v| {
         v.as_f64()
@@ -190,6 +230,72 @@ fn normalize_anchor_config_id_value(value: Option<&serde_json::Value>) -> Option
 
 type ParamTuple = (String, String, String);
 
+/// A real-valued or boolean parameter, formatted the way the firmware
+/// parses it
+///
+/// Numbers are rounded to a fixed number of decimals per kind, trailing
+/// zeros are dropped and negative zero is written as `0`, so the same value
+/// always produces the same string. Integers are written with `to_string`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamValue {
+    /// Latitude or longitude in degrees, 7 decimals (about 1 cm)
+    Degrees(f64),
+    /// A length or altitude in meters, 3 decimals (1 mm)
+    Meters(f64),
+    /// Any other real number, such as an angle or threshold, 3 decimals
+    Real(f64),
+    /// A flag, `1` or `0`
+    Flag(bool),
+}
+
+impl ParamValue {
+    fn decimals(&self) -> usize {
+        match self {
+            ParamValue::Degrees(_) => 7,
+            ParamValue::Meters(_) | ParamValue::Real(_) => 3,
+            ParamValue::Flag(_) => 0,
+        }
+    }
+}
+
+impl std::fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = match *self {
+            ParamValue::Flag(flag) => return write!(f, "{}", u8::from(flag)),
+            ParamValue::Degrees(v) | ParamValue::Meters(v) | ParamValue::Real(v) => v,
+        };
+        let text = format!("{:.*}", self.decimals(), value);
+        let text = if text.contains('.') {
+            text.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &text
+        };
+        f.write_str(if text == "-0" { "0" } else { text })
+    }
+}
+
+/// Options for [`config_to_params`] and [`location_to_params`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParamOptions {
+    /// Also write devShortAddr, e.g. to clone a device including its identity
+    pub include_short_addr: bool,
+    /// Zero the anchor slots after the written anchors, up to `anchor_slots`,
+    /// so a device shrinking from 6 to 4 anchors keeps no stale 5 and 6
+    pub clear_extra_anchors: bool,
+    /// Number of anchor slots cleared by `clear_extra_anchors`
+    pub anchor_slots: usize,
+}
+
+impl Default for ParamOptions {
+    fn default() -> Self {
+        Self {
+            include_short_addr: false,
+            clear_extra_anchors: false,
+            anchor_slots: MAX_CONFIGURABLE_ANCHORS,
+        }
+    }
+}
+
 fn valid_anchor_entries(anchors: &[AnchorConfig]) -> Result<Vec<(String, &AnchorConfig)>, String> {
     if anchors.len() > MAX_CONFIGURABLE_ANCHORS {
         return Err(format!(
@@ -218,6 +324,14 @@ fn valid_anchor_entries(anchors: &[AnchorConfig]) -> Result<Vec<(String, &Anchor
         if !anchor.x.is_finite() || !anchor.y.is_finite() || !anchor.z.is_finite() {
             return Err("Anchor coordinates must be finite numbers".to_string());
         }
+        if [anchor.x, anchor.y, anchor.z]
+            .iter()
+            .any(|v| v.abs() >= MAX_ANCHOR_COORDINATE_M)
+        {
+            return Err(format!(
+                "Anchor {id} coordinates must be within 10 km of the origin"
+            ));
+        }
 
         seen[index] = true;
         valid.push((id, anchor));
@@ -367,9 +481,49 @@ fn validate_dynamic_tag_anchor_requirements(config: &DeviceConfig) -> Result<(),
     Ok(())
 }
 
+/// Push devId/x/y/z for the anchor at 1-based firmware slot `idx`
+fn push_anchor_params(params: &mut Vec<ParamTuple>, idx: usize, id: &str, anchor: &AnchorConfig) {
+    params.push(("uwb".to_string(), format!("devId{}", idx), id.to_string()));
+    for (axis, value) in [("x", anchor.x), ("y", anchor.y), ("z", anchor.z)] {
+        params.push((
+            "uwb".to_string(),
+            format!("{}{}", axis, idx),
+            ParamValue::Meters(value).to_string(),
+        ));
+    }
+}
+
+/// Append parameters kept in a group's `extra` map, sorted by name.
+///
+/// Values are converted on a best-effort basis: strings as-is, numbers in
+/// JSON notation and booleans as [`ParamValue::Flag`]. Nulls, arrays and objects cannot be
+/// written as parameters and are skipped.
+fn append_extra_params(
+    params: &mut Vec<ParamTuple>,
+    group: &str,
+    extra: &HashMap<String, serde_json::Value>,
+) {
+    let mut names: Vec<&String> = extra.keys().collect();
+    names.sort();
+
+    for name in names {
+        if group == "uwb" && is_flat_anchor_key(name) {
+            continue;
+        }
+        let value = match &extra[name] {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Number(n) => n.to_string(),
+            serde_json::Value::Bool(b) => ParamValue::Flag(*b).to_string(),
+            _ => continue,
+        };
+        params.push((group.to_string(), name.clone(), value));
+    }
+}
+
 fn append_anchor_params(
     params: &mut Vec<ParamTuple>,
     anchors: &[AnchorConfig],
+    options: ParamOptions,
 ) -> Result<(), String> {
     let anchors = valid_anchor_entries(anchors)?;
     if anchors.is_empty() {
@@ -377,15 +531,18 @@ fn append_anchor_params(
     }
 
     for (i, (anchor_id, anchor)) in anchors.iter().enumerate() {
-        let idx = i + 1; // 1-indexed in firmware
-        params.push((
-            "uwb".to_string(),
-            format!("devId{}", idx),
-            anchor_id.clone(),
-        ));
-        params.push(("uwb".to_string(), format!("x{}", idx), anchor.x.to_string()));
-        params.push(("uwb".to_string(), format!("y{}", idx), anchor.y.to_string()));
-        params.push(("uwb".to_string(), format!("z{}", idx), anchor.z.to_string()));
+        push_anchor_params(params, i + 1, anchor_id, anchor);
+    }
+    if options.clear_extra_anchors {
+        for idx in anchors.len() + 1..=options.anchor_slots {
+            for name in ["devId", "x", "y", "z"] {
+                params.push((
+                    "uwb".to_string(),
+                    format!("{}{}", name, idx),
+                    "0".to_string(),
+                ));
+            }
+        }
     }
     params.push((
         "uwb".to_string(),
@@ -395,11 +552,79 @@ fn append_anchor_params(
     Ok(())
 }
 
-/// Convert a DeviceConfig to parameter tuples.
+/// Put parameter writes into the order the firmware needs them in
 ///
-/// Each tuple is (group, name, value).
-/// Note: devShortAddr is intentionally skipped to preserve device identity.
-pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String> {
+/// 1. `uwb.mode` comes first, since switching modes resets other UWB
+///    parameters on some firmware versions.
+/// 2. The rest is grouped as wifi, uwb, app, then any other group, keeping
+///    the order within each group, e.g. the use2DEstimator placement
+///    around the anchors.
+/// 3. `uwb.anchorCount` comes right before the first anchor slot, since
+///    some firmware clears the slots when it is written.
+pub fn order_params(params: &mut Vec<ParamTuple>) {
+    let rank = |(group, name, _): &ParamTuple| match (group.as_str(), name.as_str()) {
+        ("uwb", "mode") => 0,
+        ("wifi", _) => 1,
+        ("uwb", _) => 2,
+        ("app", _) => 3,
+        _ => 4,
+    };
+    params.sort_by_key(rank);
+
+    let count = params
+        .iter()
+        .position(|(group, name, _)| group == "uwb" && name == "anchorCount");
+    let first_slot = params
+        .iter()
+        .position(|(group, name, _)| group == "uwb" && is_flat_anchor_key(name));
+    if let (Some(count), Some(first_slot)) = (count, first_slot) {
+        if first_slot < count {
+            let param = params.remove(count);
+            params.insert(first_slot, param);
+        }
+    }
+}
+
+/// Parameter writes, always in the order of [`order_params`]
+///
+/// Whatever list a plan is built from, including one with overrides
+/// appended, it is reordered on the way in, so code uploading it cannot
+/// send the writes in an order the firmware mishandles.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct ParamPlan(Vec<ParamTuple>);
+
+impl ParamPlan {
+    pub fn new(mut params: Vec<ParamTuple>) -> Self {
+        order_params(&mut params);
+        Self(params)
+    }
+
+    pub fn into_vec(self) -> Vec<ParamTuple> {
+        self.0
+    }
+}
+
+impl From<Vec<ParamTuple>> for ParamPlan {
+    fn from(params: Vec<ParamTuple>) -> Self {
+        Self::new(params)
+    }
+}
+
+impl Deref for ParamPlan {
+    type Target = [ParamTuple];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Convert a DeviceConfig to the parameter writes that apply it.
+///
+/// Each write is (group, name, value), in the order of [`order_params`].
+/// Note: devShortAddr is skipped to preserve device identity unless
+/// `options.include_short_addr` is set.
+pub fn config_to_params(config: &DeviceConfig, options: ParamOptions) -> Result<ParamPlan, String> {
     let mut params = Vec::new();
 
     // WiFi params
@@ -458,9 +683,17 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
             v.to_string(),
         ));
     }
+    append_extra_params(&mut params, "wifi", &config.wifi.extra);
 
     // UWB params
-    // NOTE: devShortAddr intentionally skipped - preserved per-device
+    // NOTE: devShortAddr skipped by default - preserved per-device
+    if options.include_short_addr && !config.uwb.dev_short_addr.trim().is_empty() {
+        params.push((
+            "uwb".to_string(),
+            "devShortAddr".to_string(),
+            config.uwb.dev_short_addr.trim().to_string(),
+        ));
+    }
 
     // Flatten anchors array to devId1/x1/y1/z1, devId2/x2/y2/z2, etc.
     let dynamic_anchors_enabled = config.uwb.dynamic_anchor_pos_enabled == Some(1);
@@ -487,7 +720,7 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
                 return Err("Anchor geometry required for TAG_TDOA configs".to_string());
             } else {
                 validate_static_tag_anchor_requirements(config, anchors)?;
-                append_anchor_params(&mut params, anchors)?;
+                append_anchor_params(&mut params, anchors, options)?;
             }
         } else if let Some(v) = config.uwb.anchor_count {
             if v == 0 {
@@ -501,13 +734,25 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
     }
 
     if let Some(v) = config.uwb.origin_lat {
-        params.push(("uwb".to_string(), "originLat".to_string(), v.to_string()));
+        params.push((
+            "uwb".to_string(),
+            "originLat".to_string(),
+            ParamValue::Degrees(v).to_string(),
+        ));
     }
     if let Some(v) = config.uwb.origin_lon {
-        params.push(("uwb".to_string(), "originLon".to_string(), v.to_string()));
+        params.push((
+            "uwb".to_string(),
+            "originLon".to_string(),
+            ParamValue::Degrees(v).to_string(),
+        ));
     }
     if let Some(v) = config.uwb.origin_alt {
-        params.push(("uwb".to_string(), "originAlt".to_string(), v.to_string()));
+        params.push((
+            "uwb".to_string(),
+            "originAlt".to_string(),
+            ParamValue::Meters(v).to_string(),
+        ));
     }
     if let Some(v) = config.uwb.mavlink_target_system_id {
         params.push((
@@ -534,7 +779,7 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
         params.push((
             "uwb".to_string(),
             "rtlsBeaconTdoaSigmaFloorM".to_string(),
-            v.to_string(),
+            ParamValue::Meters(v).to_string(),
         ));
     }
     if let Some(v) = config.uwb.rtls_beacon_tdoa_physical_guard_enable {
@@ -548,14 +793,14 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
         params.push((
             "uwb".to_string(),
             "rtlsBeaconTdoaPhysicalGuardMarginM".to_string(),
-            v.to_string(),
+            ParamValue::Meters(v).to_string(),
         ));
     }
     if let Some(v) = config.uwb.rotation_degrees {
         params.push((
             "uwb".to_string(),
             "rotationDegrees".to_string(),
-            v.to_string(),
+            ParamValue::Real(v).to_string(),
         ));
     }
     if let Some(v) = config.uwb.z_calc_mode {
@@ -600,7 +845,7 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
         params.push((
             "uwb".to_string(),
             "rmseThreshold".to_string(),
-            v.to_string(),
+            ParamValue::Real(v).to_string(),
         ));
     }
     if let Some(v) = config.uwb.tdoa_estimator_mode {
@@ -669,20 +914,26 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
             v.to_string(),
         ));
     }
-    // ESP32S3-only; direct edits may still use it, but bulk config uploads must
-    // not fail on ESP32 devices that do not advertise this parameter.
+    // tdoaMatcherPolicy is skipped: it is ESP32S3-only; direct edits may still
+    // use it, but bulk config uploads must not fail on ESP32 devices that do
+    // not advertise this parameter.
+
     // Dynamic anchor positioning (TDoA tags only)
     if let Some(v) = config.uwb.anchor_layout {
         params.push(("uwb".to_string(), "anchorLayout".to_string(), v.to_string()));
     }
     if let Some(v) = config.uwb.anchor_height {
-        params.push(("uwb".to_string(), "anchorHeight".to_string(), v.to_string()));
+        params.push((
+            "uwb".to_string(),
+            "anchorHeight".to_string(),
+            ParamValue::Meters(v).to_string(),
+        ));
     }
     if let Some(v) = config.uwb.anchor_plane_separation {
         params.push((
             "uwb".to_string(),
             "anchorPlaneSeparation".to_string(),
-            v.to_string(),
+            ParamValue::Meters(v).to_string(),
         ));
     }
     if let Some(v) = config.uwb.anchor_pos_locked {
@@ -715,6 +966,7 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
             ));
         }
     }
+    append_extra_params(&mut params, "uwb", &config.uwb.extra);
     params.push((
         "uwb".to_string(),
         "mode".to_string(),
@@ -731,66 +983,317 @@ pub fn config_to_params(config: &DeviceConfig) -> Result<Vec<ParamTuple>, String
     if let Some(v) = config.app.led2_state {
         params.push(("app".to_string(), "led2State".to_string(), v.to_string()));
     }
+    append_extra_params(&mut params, "app", &config.app.extra);
+
+    Ok(ParamPlan::new(params))
+}
+
+/// Flatten every set field of a DeviceConfig into parameter tuples.
+///
+/// Unlike [`config_to_params`] nothing is validated or skipped: devShortAddr
+/// is included and anchors are flattened as stored, so any saved config can
+/// be described (e.g. for diffs). Fields are sorted by name within each
+/// group, followed by the anchors.
+pub fn config_to_flat_params(config: &DeviceConfig) -> Vec<ParamTuple> {
+    let mut params = Vec::new();
+    let groups = [
+        ("wifi", serde_json::to_value(&config.wifi)),
+        ("uwb", serde_json::to_value(&config.uwb)),
+        ("app", serde_json::to_value(&config.app)),
+    ];
+
+    for (group, value) in groups {
+        let Ok(serde_json::Value::Object(fields)) = value else {
+            continue;
+        };
+        for (name, value) in fields {
+            let value = match value {
+                serde_json::Value::Null => continue,
+                serde_json::Value::String(s) => s,
+                // Anchors are flattened element-wise below
+                serde_json::Value::Array(_) if name == "anchors" => continue,
+                other => other.to_string(),
+            };
+            params.push((group.to_string(), name, value));
+        }
+    }
 
-    Ok(params)
+    for (i, anchor) in config.uwb.anchors.iter().flatten().enumerate() {
+        push_anchor_params(&mut params, i + 1, &anchor.id, anchor);
+    }
+
+    params
 }
 
-/// Convert LocationData to parameter tuples.
+/// Convert LocationData to the parameter writes that apply it.
 ///
 /// This is used for location-only presets and only includes:
 /// - Origin (lat, lon, alt)
-/// - Rotation
+/// - Rotation, normalized into [0, 360) degrees
 /// - Anchors
-pub fn location_to_params(location: &LocationData) -> Result<Vec<ParamTuple>, String> {
+///
+/// The origin must be a valid GPS position with a plausible altitude and
+/// anchors must lie within 10 km of it; anything else is rejected.
+pub fn location_to_params(
+    location: &LocationData,
+    options: ParamOptions,
+) -> Result<ParamPlan, String> {
     let mut params = Vec::new();
     let use_2d_estimator = location.use_2d_estimator.unwrap_or(1);
 
-    // Origin
+    push_origin_params(&mut params, location)?;
+
+    // Anchors
+    if location.anchors.is_empty() {
+        return Err("Location preset must include anchor geometry".to_string());
+    }
+    validate_tag_anchor_requirements_for_estimator(&location.anchors, use_2d_estimator, None)?;
+    if use_2d_estimator != 0 {
+        params.push((
+            "uwb".to_string(),
+            "use2DEstimator".to_string(),
+            use_2d_estimator.to_string(),
+        ));
+    }
+    append_anchor_params(&mut params, &location.anchors, options)?;
+    if use_2d_estimator == 0 {
+        params.push((
+            "uwb".to_string(),
+            "use2DEstimator".to_string(),
+            use_2d_estimator.to_string(),
+        ));
+    }
+
+    Ok(ParamPlan::new(params))
+}
+
+/// Convert location data to the parameters a TDoA anchor needs to know its
+/// own position.
+///
+/// The anchor is matched by its UWB short address against the anchor ids,
+/// both normalized the same way (`3` matches `0003` and `"3"`). Only the
+/// origin, rotation and the anchor's own `devIdN`/`xN`/`yN`/`zN` slot, at
+/// the index a full upload would use, are written. Returns `Ok(None)` if
+/// no anchor in `location` has that address.
+pub fn anchor_position_params(
+    location: &LocationData,
+    short_addr: &str,
+) -> Result<Option<Vec<ParamTuple>>, String> {
+    let anchors = valid_anchor_entries(&location.anchors)?;
+    let Some(own_id) = normalize_anchor_config_id(short_addr) else {
+        return Ok(None);
+    };
+    let Some((index, (id, anchor))) = anchors
+        .iter()
+        .enumerate()
+        .find(|(_, (id, _))| *id == own_id)
+    else {
+        return Ok(None);
+    };
+
+    let mut params = Vec::new();
+    push_origin_params(&mut params, location)?;
+    push_anchor_params(&mut params, index + 1, id, anchor);
+    order_params(&mut params);
+    Ok(Some(params))
+}
+
+fn push_origin_params(params: &mut Vec<ParamTuple>, location: &LocationData) -> Result<(), String> {
+    location.origin.validate().map_err(|e| e.to_string())?;
+    let rotation = normalize_rotation(location.rotation)
+        .ok_or_else(|| "Rotation must be a finite number".to_string())?;
+
     params.push((
         "uwb".to_string(),
         "originLat".to_string(),
-        location.origin.lat.to_string(),
+        ParamValue::Degrees(location.origin.lat).to_string(),
     ));
     params.push((
         "uwb".to_string(),
         "originLon".to_string(),
-        location.origin.lon.to_string(),
+        ParamValue::Degrees(location.origin.lon).to_string(),
     ));
     params.push((
         "uwb".to_string(),
         "originAlt".to_string(),
-        location.origin.alt.to_string(),
+        ParamValue::Meters(location.origin.alt).to_string(),
     ));
 
-    // Rotation
     params.push((
         "uwb".to_string(),
         "rotationDegrees".to_string(),
-        location.rotation.to_string(),
+        ParamValue::Real(rotation).to_string(),
     ));
+    Ok(())
+}
 
-    // Anchors
-    if location.anchors.is_empty() {
-        return Err("Location preset must include anchor geometry".to_string());
+/// Map a rotation in degrees into [0, 360), or `None` if it is not finite
+fn normalize_rotation(degrees: f64) -> Option<f64> {
+    if !degrees.is_finite() {
+        return None;
     }
-    validate_tag_anchor_requirements_for_estimator(&location.anchors, use_2d_estimator, None)?;
-    if use_2d_estimator != 0 {
-        params.push((
-            "uwb".to_string(),
-            "use2DEstimator".to_string(),
-            use_2d_estimator.to_string(),
-        ));
+    let normalized = degrees.rem_euclid(360.0);
+    // Tiny negative angles round up to 360; adding 0.0 turns -0.0 into 0
+    Some(if normalized >= 360.0 {
+        0.0
+    } else {
+        normalized + 0.0
+    })
+}
+
+/// Everything an upload of a preset sends to a device, in order
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetUploadPlan {
+    /// Parameter writes as [group, name, value]
+    pub params: ParamPlan,
+    /// Command persisting the written parameters
+    pub save_command: String,
+}
+
+impl PresetUploadPlan {
+    /// The same plan with one device's overrides applied
+    pub fn with_overrides(&self, overrides: &[ParamOverride]) -> Self {
+        Self {
+            params: apply_overrides(&self.params, overrides).into(),
+            save_command: self.save_command.clone(),
+        }
     }
-    append_anchor_params(&mut params, &location.anchors)?;
-    if use_2d_estimator == 0 {
-        params.push((
-            "uwb".to_string(),
-            "use2DEstimator".to_string(),
-            use_2d_estimator.to_string(),
-        ));
+
+    /// The raw device commands, writes first and the save last
+    pub fn commands(&self) -> Result<Vec<String>, CommandError> {
+        self.params
+            .iter()
+            .map(|(group, name, value)| Commands::write_param(group, name, value))
+            .chain(std::iter::once(Ok(self.save_command.clone())))
+            .collect()
+    }
+}
+
+/// Apply per-device overrides to a parameter list
+///
+/// Each override replaces the value of the matching parameter, or drops it
+/// when the override has no value; the last override for a parameter wins.
+/// Overrides with a value for parameters the list does not write are
+/// appended.
+pub fn apply_overrides(params: &[ParamTuple], overrides: &[ParamOverride]) -> Vec<ParamTuple> {
+    let find = |group: &str, name: &str| {
+        overrides
+            .iter()
+            .rev()
+            .find(|o| o.group == group && o.name == name)
+    };
+
+    let mut result: Vec<ParamTuple> = params
+        .iter()
+        .filter_map(|(group, name, value)| match find(group, name) {
+            Some(o) => o
+                .value
+                .as_ref()
+                .map(|v| (group.clone(), name.clone(), v.clone())),
+            None => Some((group.clone(), name.clone(), value.clone())),
+        })
+        .collect();
+
+    for (index, o) in overrides.iter().enumerate() {
+        let superseded = overrides[index + 1..]
+            .iter()
+            .any(|later| later.group == o.group && later.name == o.name);
+        let written = params.iter().any(|(g, n, _)| *g == o.group && *n == o.name);
+        if let (Some(value), false, false) = (&o.value, superseded, written) {
+            result.push((o.group.clone(), o.name.clone(), value.clone()));
+        }
     }
+    result
+}
+
+/// The overrides in `overrides` that apply to a device
+///
+/// Keys are matched against the MAC address (ignoring case) and the UWB
+/// short address; a MAC match takes precedence.
+pub fn overrides_for<'a>(
+    overrides: &'a HashMap<String, Vec<ParamOverride>>,
+    mac: Option<&str>,
+    short_addr: Option<&str>,
+) -> &'a [ParamOverride] {
+    let by_mac = mac.and_then(|mac| {
+        overrides
+            .iter()
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case(mac.trim()))
+    });
+    let by_short = || {
+        short_addr.and_then(|short| overrides.iter().find(|(key, _)| key.trim() == short.trim()))
+    };
+    by_mac
+        .or_else(by_short)
+        .map_or(&[], |(_, set)| set.as_slice())
+}
 
-    Ok(params)
+/// Parse a `GROUP:DEVICE:NAME=VALUE` override, or `GROUP:DEVICE:NAME` to
+/// skip the parameter
+///
+/// DEVICE is a MAC address or UWB short address and may itself contain
+/// colons.
+pub fn parse_override(spec: &str) -> Result<(String, ParamOverride), String> {
+    let (target, value) = match spec.split_once('=') {
+        Some((target, value)) => (target, Some(value.to_string())),
+        None => (spec, None),
+    };
+    let invalid = || format!("Expected GROUP:DEVICE:NAME[=VALUE], got '{}'", spec);
+    let (rest, name) = target.rsplit_once(':').ok_or_else(invalid)?;
+    let (group, device) = rest.split_once(':').ok_or_else(invalid)?;
+    let (group, device, name) = (group.trim(), device.trim(), name.trim());
+    if group.is_empty() || device.is_empty() || name.is_empty() {
+        return Err(invalid());
+    }
+
+    Ok((
+        device.to_string(),
+        ParamOverride {
+            group: group.to_string(),
+            name: name.to_string(),
+            value,
+        },
+    ))
+}
+
+/// Work out the writes and save command uploading `preset` performs
+///
+/// Full presets are saved on the device as a named config; location
+/// presets only update the active one. Anchor slots beyond the preset's
+/// anchors are zeroed so none are left over from a larger layout.
+pub fn preview_preset(preset: &Preset) -> Result<PresetUploadPlan, String> {
+    let options = ParamOptions {
+        clear_extra_anchors: true,
+        ..ParamOptions::default()
+    };
+    let (params, save_command) = match preset.preset_type {
+        PresetType::Full => {
+            let config = preset
+                .config
+                .as_ref()
+                .ok_or_else(|| "Full preset must include config data".to_string())?;
+            (
+                config_to_params(config, options)?,
+                Commands::save_config_as(&preset.name).map_err(|e| e.to_string())?,
+            )
+        }
+        PresetType::Locations => {
+            let locations = preset
+                .locations
+                .as_ref()
+                .ok_or_else(|| "Location preset must include location data".to_string())?;
+            (
+                location_to_params(locations, options)?,
+                Commands::save_config().to_string(),
+            )
+        }
+    };
+
+    Ok(PresetUploadPlan {
+        params,
+        save_command,
+    })
 }
 
 #[cfg(test)]
@@ -816,6 +1319,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -859,10 +1363,12 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         }
     }
@@ -883,6 +1389,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -951,14 +1458,16 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: Some(2),
                 led2_state: Some(0),
+                extra: Default::default(),
             },
         };
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
 
         // Check that devShortAddr is NOT in the params
         assert!(!params.iter().any(|(_, n, _)| n == "devShortAddr"));
@@ -1031,6 +1540,125 @@ mod tests {
             .any(|(g, n, v)| g == "uwb" && n == "tdoaAnchorTelemetryPort" && v == "3335"));
     }
 
+    /// Config with every field set, so a field `config_to_params` does not
+    /// write shows up in `config_to_params_writes_every_config_field`. Give
+    /// new fields a value here.
+    fn full_device_config() -> DeviceConfig {
+        DeviceConfig {
+            wifi: WifiConfig {
+                mode: 1,
+                ssid_a_p: Some("rtls-ap".to_string()),
+                pswd_a_p: Some("ap-password".to_string()),
+                ssid_s_t: Some("TestNetwork".to_string()),
+                pswd_s_t: Some("password123".to_string()),
+                gcs_ip: Some("192.168.1.1".to_string()),
+                udp_port: Some(14550),
+                enable_web_server: Some(1),
+                enable_uart_bridge: Some(0),
+                log_udp_port: Some(3334),
+                log_serial_enabled: Some(1),
+                log_udp_enabled: Some(1),
+                extra: Default::default(),
+            },
+            uwb: UwbConfig {
+                mode: 4,
+                uwb_enable: Some(1),
+                dev_short_addr: "9".to_string(),
+                anchor_count: Some(4),
+                anchors: Some(
+                    [(0.0, 0.0), (3.0, 0.0), (3.0, 4.0), (0.0, 4.0)]
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &(x, y))| AnchorConfig {
+                            id: i.to_string(),
+                            x,
+                            y,
+                            z: 1.5,
+                        })
+                        .collect(),
+                ),
+                origin_lat: Some(41.4036),
+                origin_lon: Some(2.1744),
+                origin_alt: Some(100.0),
+                mavlink_target_system_id: Some(1),
+                output_backend: Some(1),
+                rtls_beacon_age_bias_ms: Some(2),
+                rtls_beacon_tdoa_sigma_floor_m: Some(0.25),
+                rtls_beacon_tdoa_physical_guard_enable: Some(1),
+                rtls_beacon_tdoa_physical_guard_margin_m: Some(1.0),
+                rotation_degrees: Some(15.0),
+                z_calc_mode: Some(1),
+                rf_forward_enable: Some(1),
+                rf_forward_sensor_id: Some(7),
+                rf_forward_orientation: Some(25),
+                rf_forward_preserve_src_ids: Some(1),
+                enable_cov_matrix: Some(1),
+                rmse_threshold: Some(0.8),
+                tdoa_estimator_mode: Some(1),
+                tdoa_estimator_diag: Some(1),
+                channel: Some(5),
+                dw_mode: Some(0),
+                tx_power_level: Some(3),
+                smart_power_enable: Some(0),
+                tdoa_slot_count: Some(6),
+                tdoa_slot_duration_us: Some(1500),
+                tdoa_anchor_telemetry_enable: Some(1),
+                tdoa_anchor_telemetry_interval_ms: Some(1000),
+                tdoa_anchor_telemetry_port: Some(3335),
+                tdoa_matcher_policy: Some(1),
+                dynamic_anchor_pos_enabled: Some(0),
+                anchor_layout: Some(1),
+                anchor_height: Some(1.2),
+                anchor_plane_separation: Some(2.0),
+                anchor_pos_locked: Some(3),
+                distance_avg_samples: Some(50),
+                use_2d_estimator: Some(1),
+                extra: Default::default(),
+            },
+            app: AppConfig {
+                led2_pin: Some(2),
+                led2_state: Some(0),
+                extra: Default::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn config_to_params_writes_every_config_field() {
+        // Fields written under other names, or deliberately not at all
+        const NOT_WRITTEN: &[&str] = &["anchors", "tdoaMatcherPolicy"];
+
+        let config = full_device_config();
+        let options = ParamOptions {
+            include_short_addr: true,
+            ..ParamOptions::default()
+        };
+        let params = config_to_params(&config, options).unwrap();
+
+        let value = serde_json::to_value(&config).unwrap();
+        for group in ["wifi", "uwb", "app"] {
+            for name in value[group].as_object().unwrap().keys() {
+                if NOT_WRITTEN.contains(&name.as_str()) {
+                    continue;
+                }
+                assert!(
+                    params.iter().any(|(g, n, _)| g == group && n == name),
+                    "{}.{} is never written by config_to_params",
+                    group,
+                    name
+                );
+            }
+        }
+        for (group, name, _) in params.iter() {
+            assert!(
+                crate::protocol::param_registry::lookup(group, name).is_some(),
+                "{}.{} is missing from the parameter registry",
+                group,
+                name
+            );
+        }
+    }
+
     #[test]
     fn device_config_from_backup_value_rebuilds_flat_anchors() {
         let raw = serde_json::json!({
@@ -1075,7 +1703,7 @@ mod tests {
         assert_eq!(anchors[1].y, 4.25);
         assert_eq!(anchors[1].z, 0.0);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         assert!(params
             .iter()
             .any(|(g, n, v)| g == "uwb" && n == "devId1" && v == "0"));
@@ -1084,6 +1712,194 @@ mod tests {
             .any(|(g, n, v)| g == "uwb" && n == "x2" && v == "3"));
     }
 
+    #[test]
+    fn config_to_params_writes_unknown_fields() {
+        let config: DeviceConfig = serde_json::from_value(serde_json::json!({
+            "wifi": { "mode": 1, "futureFlag": true },
+            "uwb": {
+                "mode": 3,
+                "devShortAddr": "2",
+                "futureParam": 7,
+                "futureGain": 1.25,
+                "futureTable": [1, 2],
+                "futureNote": null
+            },
+            "app": { "futureLabel": "hall" }
+        }))
+        .unwrap();
+
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
+        let has = |group: &str, name: &str, value: &str| {
+            params
+                .iter()
+                .any(|(g, n, v)| g == group && n == name && v == value)
+        };
+        assert!(has("wifi", "futureFlag", "1"));
+        assert!(has("uwb", "futureParam", "7"));
+        assert!(has("uwb", "futureGain", "1.25"));
+        assert!(has("app", "futureLabel", "hall"));
+        assert!(!params.iter().any(|(_, n, _)| n == "futureTable"));
+        assert!(!params.iter().any(|(_, n, _)| n == "futureNote"));
+
+        // The mode switch goes first so it cannot reset unknown UWB params
+        let position = |name: &str| params.iter().position(|(g, n, _)| g == "uwb" && n == name);
+        assert_eq!(position("mode"), Some(0));
+        assert!(position("mode") < position("futureParam"));
+    }
+
+    #[test]
+    fn config_to_params_orders_mode_first_and_anchor_count_before_slots() {
+        let config: DeviceConfig = serde_json::from_value(serde_json::json!({
+            "wifi": { "mode": 1 },
+            "uwb": {
+                "mode": 4,
+                "devShortAddr": "2",
+                "anchors": [
+                    { "id": "0", "x": 0.0, "y": 0.0, "z": 1.5 },
+                    { "id": "1", "x": 3.0, "y": 0.0, "z": 1.5 },
+                    { "id": "2", "x": 0.0, "y": 4.0, "z": 1.5 },
+                    { "id": "3", "x": 3.0, "y": 4.0, "z": 2.5 }
+                ]
+            },
+            "app": { "futureLabel": "hall" }
+        }))
+        .unwrap();
+
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
+        assert_eq!(params[0], ("uwb".into(), "mode".into(), "4".into()));
+
+        let groups: Vec<&str> = params[1..].iter().map(|(g, _, _)| g.as_str()).collect();
+        let mut grouped = groups.clone();
+        grouped.sort_by_key(|g| ["wifi", "uwb", "app"].iter().position(|o| o == g));
+        assert_eq!(groups, grouped);
+
+        let position = |name: &str| params.iter().position(|(g, n, _)| g == "uwb" && n == name);
+        let count = position("anchorCount").unwrap();
+        assert_eq!(params[count].2, "4");
+        assert_eq!(position("devId1"), Some(count + 1));
+    }
+
+    #[test]
+    fn param_plan_reorders_appended_writes() {
+        let plan = ParamPlan::new(vec![
+            ("app".into(), "futureLabel".into(), "hall".into()),
+            ("uwb".into(), "devId1".into(), "0".into()),
+            ("uwb".into(), "anchorCount".into(), "1".into()),
+            ("wifi".into(), "mode".into(), "1".into()),
+            ("uwb".into(), "mode".into(), "4".into()),
+        ]);
+
+        let names: Vec<&str> = plan.iter().map(|(_, n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            ["mode", "mode", "anchorCount", "devId1", "futureLabel"]
+        );
+        assert_eq!(plan[0].0, "uwb");
+        assert_eq!(plan[1].0, "wifi");
+    }
+
+    /// `backup-config` payloads of three firmware releases holding the same
+    /// settings: numbers as strings without an `app` group (1.2), anchors
+    /// keyed by index (1.4) and the current shape (2.0)
+    const BACKUP_FIXTURES: [(&str, &str); 3] = [
+        (
+            "1.2",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/backup_config_fw_1_2.json"
+            )),
+        ),
+        (
+            "1.4",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/backup_config_fw_1_4.json"
+            )),
+        ),
+        (
+            "2.0",
+            include_str!(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/tests/fixtures/backup_config_fw_2_0.json"
+            )),
+        ),
+    ];
+
+    #[test]
+    fn backup_payloads_of_older_firmware_parse_and_round_trip() {
+        let mut expected_params = None;
+        for (version, payload) in BACKUP_FIXTURES {
+            let raw: serde_json::Value = serde_json::from_str(payload).unwrap();
+            assert_eq!(
+                device_config_from_backup_value_strict(raw.clone()).is_ok(),
+                version == "2.0",
+                "{version}"
+            );
+
+            let config = device_config_from_backup_value(raw).unwrap();
+            assert_eq!(config.wifi.udp_port, Some(14550), "{version}");
+            assert_eq!(config.uwb.mode, 4, "{version}");
+            assert_eq!(config.uwb.channel, Some(5), "{version}");
+            assert_eq!(config.uwb.origin_lat, Some(41.3851), "{version}");
+            let anchors = config.uwb.anchors.as_ref().unwrap();
+            assert_eq!(anchors.len(), 4, "{version}");
+            assert_eq!((anchors[1].id.as_str(), anchors[1].x), ("1", 6.5));
+
+            // Saved and read back, the config is unchanged
+            let saved = serde_json::to_value(&config).unwrap();
+            let reread = device_config_from_backup_value_strict(saved.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&reread).unwrap(), saved, "{version}");
+
+            // Every release yields the same writes, apart from the app group
+            // 1.2 lacks and 2.0's extra param
+            let mut params = config_to_params(&reread, ParamOptions::default())
+                .unwrap()
+                .into_vec();
+            params.retain(|(group, name, _)| group != "app" && name != "rtlsBeaconAgeBiasMs");
+            let expected = expected_params.get_or_insert_with(|| params.clone());
+            assert_eq!(&params, expected, "{version}");
+        }
+    }
+
+    #[test]
+    fn device_config_from_backup_value_keeps_only_unknown_extras() {
+        let raw = serde_json::json!({
+            "wifi": { "mode": 1 },
+            "uwb": {
+                "mode": 4,
+                "devShortAddr": "7",
+                "anchorCount": 4,
+                "devId1": "3030",
+                "x1": 0,
+                "y1": 0,
+                "z1": 0,
+                "devId2": 1,
+                "x2": 3,
+                "y2": 0,
+                "z2": 0,
+                "devId3": 2,
+                "x3": 0,
+                "y3": 4,
+                "z3": 0,
+                "devId4": 3,
+                "x4": 3,
+                "y4": 4,
+                "z4": 0,
+                "futureParam": 7
+            },
+            "app": {}
+        });
+
+        let config = device_config_from_backup_value(raw).unwrap();
+        assert_eq!(config.uwb.extra.len(), 1);
+        assert_eq!(config.uwb.extra["futureParam"], 7);
+
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
+        let dev_ids: Vec<_> = params.iter().filter(|(_, n, _)| n == "devId1").collect();
+        assert_eq!(dev_ids.len(), 1);
+        assert_eq!(dev_ids[0].2, "0");
+    }
+
     #[test]
     fn device_config_from_backup_value_rebuilds_eight_flat_anchors() {
         let mut uwb = serde_json::json!({
@@ -1117,7 +1933,7 @@ mod tests {
         assert_eq!(anchors[7].y, 8.5);
         assert_eq!(anchors[7].z, -8.0);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         assert!(params
             .iter()
             .any(|(g, n, v)| g == "uwb" && n == "anchorCount" && v == "8"));
@@ -1325,6 +2141,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -1393,14 +2210,16 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         };
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
 
         assert!(params
             .iter()
@@ -1423,8 +2242,8 @@ mod tests {
             .iter()
             .position(|(g, n, _)| g == "uwb" && n == "mode")
             .unwrap();
-        assert!(anchor_count_pos > dev_id_2_pos);
-        assert!(mode_pos > anchor_count_pos);
+        assert!(anchor_count_pos < dev_id_2_pos);
+        assert!(mode_pos < anchor_count_pos);
     }
 
     #[test]
@@ -1465,7 +2284,7 @@ mod tests {
             use_2d_estimator: Some(1),
         };
 
-        let params = location_to_params(&location).unwrap();
+        let params = location_to_params(&location, ParamOptions::default()).unwrap();
 
         assert!(params
             .iter()
@@ -1487,7 +2306,7 @@ mod tests {
             .iter()
             .position(|(g, n, _)| g == "uwb" && n == "devId2")
             .unwrap();
-        assert!(anchor_count_pos > dev_id_2_pos);
+        assert!(anchor_count_pos < dev_id_2_pos);
     }
 
     #[test]
@@ -1517,7 +2336,7 @@ mod tests {
         };
 
         assert_eq!(
-            location_to_params(&location).unwrap_err(),
+            location_to_params(&location, ParamOptions::default()).unwrap_err(),
             "2D TAG_TDOA static geometry requires at least 4 anchors"
         );
     }
@@ -1573,7 +2392,7 @@ mod tests {
         };
 
         assert_eq!(
-            location_to_params(&location).unwrap_err(),
+            location_to_params(&location, ParamOptions::default()).unwrap_err(),
             "3D TAG_TDOA static geometry requires non-coplanar anchors"
         );
     }
@@ -1628,7 +2447,7 @@ mod tests {
             use_2d_estimator: Some(0),
         };
 
-        let params = location_to_params(&location).unwrap();
+        let params = location_to_params(&location, ParamOptions::default()).unwrap();
         let anchor_count_pos = params
             .iter()
             .position(|(g, n, _)| g == "uwb" && n == "anchorCount")
@@ -1656,6 +2475,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -1699,15 +2519,17 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         };
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor geometry required when anchorCount is set"
         );
     }
@@ -1717,7 +2539,7 @@ mod tests {
         let config = minimal_device_config(Some(0), None);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor count must be positive when set"
         );
     }
@@ -1727,7 +2549,7 @@ mod tests {
         let config = minimal_device_config(None, None);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor geometry required for TAG_TDOA configs"
         );
     }
@@ -1739,7 +2561,7 @@ mod tests {
         config.uwb.use_2d_estimator = Some(0);
         config.uwb.anchor_plane_separation = Some(2.0);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
 
         assert!(params
             .iter()
@@ -1763,7 +2585,7 @@ mod tests {
         config.uwb.dynamic_anchor_pos_enabled = Some(1);
         config.uwb.use_2d_estimator = Some(1);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         let param_index = |name: &str| params.iter().position(|(_, n, _)| n == name).unwrap();
 
         assert!(param_index("use2DEstimator") < param_index("dynamicAnchorPosEnabled"));
@@ -1778,13 +2600,13 @@ mod tests {
         config.uwb.use_2d_estimator = Some(0);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "3D dynamic anchors require a positive anchor plane separation"
         );
 
         config.uwb.anchor_plane_separation = Some(0.0);
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "3D dynamic anchors require a positive anchor plane separation"
         );
     }
@@ -1817,7 +2639,7 @@ mod tests {
         config.uwb.use_2d_estimator = Some(1);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "2D TAG_TDOA static geometry requires at least 4 anchors"
         );
 
@@ -1847,7 +2669,7 @@ mod tests {
         config.uwb.use_2d_estimator = Some(0);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "3D TAG_TDOA static geometry requires at least 6 anchors"
         );
     }
@@ -1886,7 +2708,7 @@ mod tests {
         config.uwb.use_2d_estimator = Some(0);
         config.uwb.tdoa_estimator_mode = Some(0);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         assert!(params
             .iter()
             .any(|(g, n, v)| g == "uwb" && n == "anchorCount" && v == "4"));
@@ -1940,7 +2762,7 @@ mod tests {
         );
         config.uwb.use_2d_estimator = Some(0);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         assert!(params
             .iter()
             .any(|(g, n, v)| g == "uwb" && n == "anchorCount" && v == "6"));
@@ -1988,7 +2810,7 @@ mod tests {
         );
         config.uwb.use_2d_estimator = Some(1);
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
         let anchor_count_pos = params
             .iter()
             .position(|(g, n, _)| g == "uwb" && n == "anchorCount")
@@ -2046,17 +2868,253 @@ mod tests {
         config.uwb.use_2d_estimator = Some(0);
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "3D TAG_TDOA static geometry requires non-coplanar anchors"
         );
     }
 
+    #[test]
+    fn preview_preset_lists_writes_then_the_save_command() {
+        let mut config = minimal_device_config(None, None);
+        config.uwb.mode = 3;
+        let mut preset = Preset {
+            name: "anchors-v2".to_string(),
+            description: None,
+            preset_type: PresetType::Full,
+            config: Some(config.clone()),
+            locations: None,
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        let plan = preview_preset(&preset).unwrap();
+        assert_eq!(
+            plan.params,
+            config_to_params(&config, ParamOptions::default()).unwrap()
+        );
+        assert_eq!(plan.save_command, "save-config-as -name anchors-v2");
+        let commands = plan.commands().unwrap();
+        assert_eq!(commands.len(), plan.params.len() + 1);
+        assert!(commands[0].starts_with("write -group "));
+        assert_eq!(commands.last().unwrap(), "save-config-as -name anchors-v2");
+
+        preset.preset_type = PresetType::Locations;
+        assert!(preview_preset(&preset).is_err());
+    }
+
+    fn anchor_layout() -> LocationData {
+        LocationData {
+            origin: GpsOrigin {
+                lat: 41.5,
+                lon: 2.25,
+                alt: 120.0,
+            },
+            rotation: 15.0,
+            anchors: ["0", "1", "2", "3"]
+                .iter()
+                .enumerate()
+                .map(|(i, id)| AnchorConfig {
+                    id: id.to_string(),
+                    x: i as f64 * 2.0,
+                    y: 1.0,
+                    z: 2.5,
+                })
+                .collect(),
+            use_2d_estimator: None,
+        }
+    }
+
+    #[test]
+    fn anchor_position_params_writes_origin_and_the_matching_slot_only() {
+        let params = anchor_position_params(&anchor_layout(), "2")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            params,
+            vec![
+                param("uwb", "originLat", "41.5"),
+                param("uwb", "originLon", "2.25"),
+                param("uwb", "originAlt", "120"),
+                param("uwb", "rotationDegrees", "15"),
+                param("uwb", "devId3", "2"),
+                param("uwb", "x3", "4"),
+                param("uwb", "y3", "1"),
+                param("uwb", "z3", "2.5"),
+            ]
+        );
+    }
+
+    #[test]
+    fn clear_extra_anchors_zeroes_slots_left_over_from_a_larger_set() {
+        let mut location = anchor_layout();
+        location.anchors[1].y = 3.0;
+        let cleared = |params: &[ParamTuple]| -> Vec<String> {
+            params
+                .iter()
+                .filter(|(_, _, v)| v == "0")
+                .filter(|(_, n, _)| n.ends_with(['5', '6', '7', '8']))
+                .map(|(_, n, _)| n.clone())
+                .collect()
+        };
+
+        let params = location_to_params(&location, ParamOptions::default()).unwrap();
+        assert!(cleared(&params).is_empty());
+
+        // Shrinking from 6 anchors to 4
+        let options = ParamOptions {
+            clear_extra_anchors: true,
+            anchor_slots: 6,
+            ..ParamOptions::default()
+        };
+        let params = location_to_params(&location, options).unwrap();
+        assert_eq!(
+            cleared(&params),
+            ["devId5", "x5", "y5", "z5", "devId6", "x6", "y6", "z6"]
+        );
+        let count = params
+            .iter()
+            .position(|(_, n, _)| n == "anchorCount")
+            .unwrap();
+        assert_eq!(params[count].2, "4");
+        assert!(params.iter().position(|(_, n, _)| n == "devId1").unwrap() > count);
+
+        let mut config = minimal_device_config(Some(4), Some(location.anchors.clone()));
+        config.uwb.use_2d_estimator = Some(1);
+        let params = config_to_params(&config, options).unwrap();
+        assert_eq!(cleared(&params).len(), 8);
+    }
+
+    #[test]
+    fn preview_preset_clears_anchor_slots_up_to_the_firmware_maximum() {
+        let mut location = anchor_layout();
+        location.anchors[1].y = 3.0;
+        let preset = Preset {
+            name: "hall".to_string(),
+            description: None,
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(location),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        };
+
+        let plan = preview_preset(&preset).unwrap();
+        assert!(plan
+            .params
+            .iter()
+            .any(|(_, n, v)| n == "devId8" && v == "0"));
+        assert!(!plan.params.iter().any(|(_, n, _)| n == "devId9"));
+    }
+
+    #[test]
+    fn anchor_position_params_matches_encoded_short_addresses() {
+        let location = anchor_layout();
+        let by_hex = anchor_position_params(&location, "0033").unwrap().unwrap();
+        assert!(by_hex.contains(&param("uwb", "devId4", "3")));
+
+        assert_eq!(anchor_position_params(&location, "7").unwrap(), None);
+        assert_eq!(anchor_position_params(&location, "").unwrap(), None);
+
+        let mut duplicated = location;
+        duplicated.anchors[1].id = "0".to_string();
+        assert!(anchor_position_params(&duplicated, "0").is_err());
+    }
+
+    fn param(group: &str, name: &str, value: &str) -> ParamTuple {
+        (group.to_string(), name.to_string(), value.to_string())
+    }
+
+    fn param_override(group: &str, name: &str, value: Option<&str>) -> ParamOverride {
+        ParamOverride {
+            group: group.to_string(),
+            name: name.to_string(),
+            value: value.map(String::from),
+        }
+    }
+
+    #[test]
+    fn apply_overrides_replaces_skips_and_appends() {
+        let params = vec![
+            param("uwb", "mode", "4"),
+            param("uwb", "mavlinkTargetSystemId", "1"),
+            param("wifi", "gcsIp", "10.0.0.1"),
+        ];
+        let overrides = vec![
+            param_override("uwb", "mavlinkTargetSystemId", Some("5")),
+            param_override("uwb", "mavlinkTargetSystemId", Some("7")),
+            param_override("wifi", "gcsIp", None),
+            param_override("uwb", "rfForwardSensorId", Some("3")),
+        ];
+
+        assert_eq!(
+            apply_overrides(&params, &overrides),
+            vec![
+                param("uwb", "mode", "4"),
+                param("uwb", "mavlinkTargetSystemId", "7"),
+                param("uwb", "rfForwardSensorId", "3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn overrides_for_matches_mac_then_short_address() {
+        let mut overrides = HashMap::new();
+        overrides.insert(
+            "AA:BB:CC:DD:EE:01".to_string(),
+            vec![param_override("uwb", "mavlinkTargetSystemId", Some("9"))],
+        );
+        overrides.insert(
+            "5".to_string(),
+            vec![param_override("uwb", "mavlinkTargetSystemId", None)],
+        );
+
+        let by_mac = overrides_for(&overrides, Some("aa:bb:cc:dd:ee:01"), Some("5"));
+        assert_eq!(by_mac[0].value.as_deref(), Some("9"));
+        let by_short = overrides_for(&overrides, Some("AA:BB:CC:DD:EE:02"), Some("5"));
+        assert_eq!(by_short[0].value, None);
+
+        // A device without overrides gets the base parameters unchanged
+        let none = overrides_for(&overrides, Some("AA:BB:CC:DD:EE:02"), Some("6"));
+        assert!(none.is_empty());
+        let params = vec![param("uwb", "mavlinkTargetSystemId", "1")];
+        assert_eq!(apply_overrides(&params, none), params);
+    }
+
+    #[test]
+    fn parse_override_accepts_short_and_mac_targets() {
+        assert_eq!(
+            parse_override("uwb:5:mavlinkTargetSystemId=7"),
+            Ok((
+                "5".to_string(),
+                param_override("uwb", "mavlinkTargetSystemId", Some("7"))
+            ))
+        );
+        assert_eq!(
+            parse_override("wifi:AA:BB:CC:DD:EE:01:gcsIp"),
+            Ok((
+                "AA:BB:CC:DD:EE:01".to_string(),
+                param_override("wifi", "gcsIp", None)
+            ))
+        );
+        assert!(parse_override("mavlinkTargetSystemId=7").is_err());
+        assert!(parse_override("uwb::mavlinkTargetSystemId=7").is_err());
+    }
+
     #[test]
     fn config_to_params_allows_anchor_mode_without_tag_geometry() {
         let mut config = minimal_device_config(None, None);
         config.uwb.mode = 3;
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
 
         assert!(params
             .iter()
@@ -2064,12 +3122,36 @@ mod tests {
         assert!(!params.iter().any(|(_, n, _)| n == "anchorCount"));
     }
 
+    #[test]
+    fn config_to_params_writes_short_addr_only_when_asked() {
+        let mut config = minimal_device_config(None, None);
+        config.uwb.mode = 3;
+        config.uwb.dev_short_addr = "5".to_string();
+
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
+        assert!(!params.iter().any(|(_, n, _)| n == "devShortAddr"));
+
+        let options = ParamOptions {
+            include_short_addr: true,
+            ..ParamOptions::default()
+        };
+        let params = config_to_params(&config, options).unwrap();
+        assert_eq!(
+            params
+                .iter()
+                .filter(|(g, n, _)| g == "uwb" && n == "devShortAddr")
+                .map(|(_, _, v)| v.as_str())
+                .collect::<Vec<_>>(),
+            vec!["5"]
+        );
+    }
+
     #[test]
     fn config_to_params_allows_anchor_mode_zero_count_empty_geometry() {
         let mut config = minimal_device_config(Some(0), Some(vec![]));
         config.uwb.mode = 3;
 
-        let params = config_to_params(&config).unwrap();
+        let params = config_to_params(&config, ParamOptions::default()).unwrap();
 
         assert!(params
             .iter()
@@ -2090,7 +3172,7 @@ mod tests {
         );
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor count must be positive when set"
         );
     }
@@ -2100,7 +3182,7 @@ mod tests {
         let config = minimal_device_config(None, Some(vec![]));
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor geometry required for TAG_TDOA configs"
         );
     }
@@ -2121,6 +3203,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -2164,15 +3247,17 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         };
 
         assert_eq!(
-            config_to_params(&config).unwrap_err(),
+            config_to_params(&config, ParamOptions::default()).unwrap_err(),
             "Anchor geometry required when anchorCount is set"
         );
     }
@@ -2216,7 +3301,7 @@ mod tests {
         };
 
         assert_eq!(
-            location_to_params(&location).unwrap_err(),
+            location_to_params(&location, ParamOptions::default()).unwrap_err(),
             "Anchor IDs must be contiguous from 0"
         );
     }
@@ -2235,8 +3320,140 @@ mod tests {
         };
 
         assert_eq!(
-            location_to_params(&location).unwrap_err(),
+            location_to_params(&location, ParamOptions::default()).unwrap_err(),
             "Location preset must include anchor geometry"
         );
     }
+
+    #[test]
+    fn param_values_have_fixed_precision_without_trailing_zeros() {
+        let cases = [
+            (ParamValue::Degrees(0.1 + 0.2), "0.3"),
+            (ParamValue::Degrees(2.174356), "2.174356"),
+            (ParamValue::Degrees(-0.123456789), "-0.1234568"),
+            (ParamValue::Degrees(41.0), "41"),
+            (ParamValue::Meters(0.0), "0"),
+            (ParamValue::Meters(-0.0), "0"),
+            (ParamValue::Meters(-0.0001), "0"),
+            (ParamValue::Meters(1.5), "1.5"),
+            (ParamValue::Meters(1.23456), "1.235"),
+            (ParamValue::Meters(120.0), "120"),
+            (ParamValue::Meters(-3.1), "-3.1"),
+            (ParamValue::Real(0.8), "0.8"),
+            (ParamValue::Real(1e-9), "0"),
+            (ParamValue::Real(270.0), "270"),
+            (ParamValue::Flag(true), "1"),
+            (ParamValue::Flag(false), "0"),
+        ];
+
+        for (value, expected) in cases {
+            assert_eq!(value.to_string(), expected, "{value:?}");
+        }
+    }
+
+    #[test]
+    fn config_and_location_params_share_value_formatting() {
+        let mut location = anchor_layout();
+        location.origin.lat = 41.1 + 0.3;
+        location.origin.alt = 0.0;
+        location.rotation = 12.34567;
+        location.anchors[1].x = 2.0004;
+        location.anchors[2].y = 1.10000000001;
+
+        let mut config: DeviceConfig = serde_json::from_value(serde_json::json!({
+            "wifi": {"mode": 1},
+            "uwb": {"mode": 4, "devShortAddr": "1", "use2DEstimator": 1, "flag": true},
+            "app": {}
+        }))
+        .unwrap();
+        location.apply_to(&mut config);
+
+        let from_location = location_to_params(&location, ParamOptions::default()).unwrap();
+        let from_config = config_to_params(&config, ParamOptions::default()).unwrap();
+        let value = |params: &[ParamTuple], name: &str| {
+            params
+                .iter()
+                .find(|(_, n, _)| n == name)
+                .map(|(_, _, v)| v.clone())
+                .unwrap()
+        };
+
+        for params in [&from_location, &from_config] {
+            assert_eq!(value(params, "originLat"), "41.4");
+            assert_eq!(value(params, "originLon"), "2.25");
+            assert_eq!(value(params, "originAlt"), "0");
+            assert_eq!(value(params, "rotationDegrees"), "12.346");
+            assert_eq!(value(params, "x2"), "2");
+            assert_eq!(value(params, "y3"), "1.1");
+            assert_eq!(value(params, "z1"), "2.5");
+        }
+        assert_eq!(value(&from_config, "flag"), "1");
+    }
+
+    #[test]
+    fn location_to_params_normalizes_rotation() {
+        let mut location = anchor_layout();
+        let rotation_of = |location: &LocationData| {
+            location_to_params(location, ParamOptions::default())
+                .unwrap()
+                .into_vec()
+                .into_iter()
+                .find(|(_, name, _)| name == "rotationDegrees")
+                .unwrap()
+                .2
+        };
+
+        for (degrees, expected) in [
+            (540.0, "180"),
+            (-90.0, "270"),
+            (360.0, "0"),
+            (-0.0, "0"),
+            (-1e-15, "0"),
+            (15.5, "15.5"),
+        ] {
+            location.rotation = degrees;
+            assert_eq!(rotation_of(&location), expected, "{degrees}");
+        }
+
+        location.rotation = f64::NAN;
+        assert_eq!(
+            location_to_params(&location, ParamOptions::default()).unwrap_err(),
+            "Rotation must be a finite number"
+        );
+    }
+
+    #[test]
+    fn location_to_params_rejects_implausible_coordinates() {
+        let check = |change: &dyn Fn(&mut LocationData)| {
+            let mut location = anchor_layout();
+            change(&mut location);
+            location_to_params(&location, ParamOptions::default()).unwrap_err()
+        };
+
+        assert_eq!(
+            check(&|l| l.origin.lat = 191.0),
+            "origin.lat 191 must be between -90 and 90"
+        );
+        assert_eq!(
+            check(&|l| l.origin.lon = -180.5),
+            "origin.lon -180.5 must be between -180 and 180"
+        );
+        assert_eq!(
+            check(&|l| l.origin.alt = -9999.0),
+            "origin.alt -9999 must be between -1000 and 10000"
+        );
+        assert!(check(&|l| l.origin.lat = f64::NAN).starts_with("origin.lat NaN"));
+        assert_eq!(
+            check(&|l| l.anchors[1].x = 12_000.0),
+            "Anchor 1 coordinates must be within 10 km of the origin"
+        );
+        assert!(anchor_position_params(
+            &LocationData {
+                rotation: f64::INFINITY,
+                ..anchor_layout()
+            },
+            "0"
+        )
+        .is_err());
+    }
 }
@@ -5,4 +5,5 @@
 pub mod binary;
 pub mod commands;
 pub mod config_params;
+pub mod param_registry;
 pub mod response;
@@ -0,0 +1,517 @@
+//! Registry of known device parameters.
+//!
+//! Describes every wifi/uwb/app parameter the firmware understands: its
+//! type, the values it accepts and whether it only takes effect after a
+//! reboot. Single writes are checked against it before they are sent, since
+//! the device only answers a bad value with an opaque error string.
+
+use std::net::Ipv4Addr;
+
+use serde::Serialize;
+
+use crate::error::ParamError;
+
+/// Number of anchor slots (`devId1`..`devId8`, `x1`..`x8`, ...)
+const ANCHOR_SLOTS: u8 = 8;
+
+/// Value type and accepted values of a parameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ParamKind {
+    /// `0` or `1`
+    Bool,
+    /// Integer within `min..=max`
+    Int { min: i64, max: i64 },
+    /// Finite number, optionally bounded
+    Float { min: Option<f64>, max: Option<f64> },
+    /// One of a fixed set of integer codes
+    Enum { options: &'static [EnumOption] },
+    /// Free text of at most `max_len` characters
+    #[serde(rename_all = "camelCase")]
+    Text { max_len: usize },
+    /// IPv4 address
+    Ip,
+}
+
+/// One accepted value of an enum parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct EnumOption {
+    pub value: i64,
+    pub label: &'static str,
+}
+
+/// A known device parameter
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamSpec {
+    /// Parameter group (wifi, uwb, app)
+    pub group: &'static str,
+    /// Firmware parameter name, without the slot number for slotted params
+    pub name: &'static str,
+    /// Value type and accepted values
+    pub kind: ParamKind,
+    /// For per-anchor params, the number of slots; `x` covers `x1`..`xN`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slots: Option<u8>,
+    /// Whether the device must reboot for a new value to take effect
+    pub requires_reboot: bool,
+    /// Human-readable description
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    const fn new(
+        group: &'static str,
+        name: &'static str,
+        kind: ParamKind,
+        description: &'static str,
+    ) -> Self {
+        Self {
+            group,
+            name,
+            kind,
+            slots: None,
+            requires_reboot: false,
+            description,
+        }
+    }
+
+    const fn reboot(mut self) -> Self {
+        self.requires_reboot = true;
+        self
+    }
+
+    const fn slotted(mut self, slots: u8) -> Self {
+        self.slots = Some(slots);
+        self
+    }
+
+    /// Whether `name` refers to this parameter
+    fn matches(&self, name: &str) -> bool {
+        match self.slots {
+            None => self.name == name,
+            Some(slots) => name
+                .strip_prefix(self.name)
+                .filter(|slot| slot.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|slot| slot.parse::<u8>().ok())
+                .is_some_and(|slot| (1..=slots).contains(&slot)),
+        }
+    }
+
+    /// Check `value` against the accepted values, describing the problem
+    fn check(&self, value: &str) -> Result<(), String> {
+        let trimmed = value.trim();
+        match self.kind {
+            ParamKind::Bool => match trimmed {
+                "0" | "1" => Ok(()),
+                _ => Err("must be 0 or 1".to_string()),
+            },
+            ParamKind::Int { min, max } => {
+                let parsed: i64 = trimmed
+                    .parse()
+                    .map_err(|_| "must be an integer".to_string())?;
+                if (min..=max).contains(&parsed) {
+                    Ok(())
+                } else {
+                    Err(format!("{} is out of range ({}-{})", parsed, min, max))
+                }
+            }
+            ParamKind::Float { min, max } => {
+                let parsed = trimmed
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| "must be a finite number".to_string())?;
+                match (min, max) {
+                    (Some(min), _) if parsed < min => Err(format!("must be at least {}", min)),
+                    (_, Some(max)) if parsed > max => Err(format!("must be at most {}", max)),
+                    _ => Ok(()),
+                }
+            }
+            ParamKind::Enum { options } => {
+                let parsed = trimmed.parse::<i64>().ok();
+                if options.iter().any(|option| Some(option.value) == parsed) {
+                    Ok(())
+                } else {
+                    let accepted: Vec<String> = options
+                        .iter()
+                        .map(|option| format!("{}={}", option.value, option.label))
+                        .collect();
+                    Err(format!("must be one of {}", accepted.join(", ")))
+                }
+            }
+            ParamKind::Text { max_len } => {
+                if value.chars().count() <= max_len {
+                    Ok(())
+                } else {
+                    Err(format!("longer than {} characters", max_len))
+                }
+            }
+            ParamKind::Ip => trimmed
+                .parse::<Ipv4Addr>()
+                .map(|_| ())
+                .map_err(|_| "must be an IPv4 address".to_string()),
+        }
+    }
+}
+
+const BOOL: ParamKind = ParamKind::Bool;
+const PORT: ParamKind = ParamKind::Int { min: 1, max: 65535 };
+const U8: ParamKind = ParamKind::Int { min: 0, max: 255 };
+const ANY_FLOAT: ParamKind = ParamKind::Float {
+    min: None,
+    max: None,
+};
+const NON_NEGATIVE: ParamKind = ParamKind::Float {
+    min: Some(0.0),
+    max: None,
+};
+const SSID: ParamKind = ParamKind::Text { max_len: 32 };
+const PASSWORD: ParamKind = ParamKind::Text { max_len: 63 };
+const SHORT_ADDR: ParamKind = ParamKind::Text { max_len: 4 };
+
+const fn int(min: i64, max: i64) -> ParamKind {
+    ParamKind::Int { min, max }
+}
+
+const fn float(min: f64, max: f64) -> ParamKind {
+    ParamKind::Float {
+        min: Some(min),
+        max: Some(max),
+    }
+}
+
+const fn options(options: &'static [EnumOption]) -> ParamKind {
+    ParamKind::Enum { options }
+}
+
+const fn option(value: i64, label: &'static str) -> EnumOption {
+    EnumOption { value, label }
+}
+
+const PARAMS: &[ParamSpec] = &[
+    // ==================== wifi ====================
+    ParamSpec::new(
+        "wifi",
+        "mode",
+        options(&[option(0, "AP"), option(1, "Station")]),
+        "WiFi mode",
+    )
+    .reboot(),
+    ParamSpec::new("wifi", "ssidAP", SSID, "Access point SSID").reboot(),
+    ParamSpec::new("wifi", "pswdAP", PASSWORD, "Access point password").reboot(),
+    ParamSpec::new("wifi", "ssidST", SSID, "Station SSID").reboot(),
+    ParamSpec::new("wifi", "pswdST", PASSWORD, "Station password").reboot(),
+    ParamSpec::new("wifi", "gcsIp", ParamKind::Ip, "Ground control station IP").reboot(),
+    ParamSpec::new("wifi", "udpPort", PORT, "UDP port for MAVLink").reboot(),
+    ParamSpec::new("wifi", "enableWebServer", BOOL, "Enable web server").reboot(),
+    ParamSpec::new("wifi", "enableUartBridge", BOOL, "Enable UART bridge").reboot(),
+    ParamSpec::new("wifi", "logUdpPort", PORT, "UDP port for log streaming"),
+    ParamSpec::new("wifi", "logSerialEnabled", BOOL, "Serial logging enabled"),
+    ParamSpec::new("wifi", "logUdpEnabled", BOOL, "UDP log streaming enabled"),
+    // ==================== uwb ====================
+    ParamSpec::new(
+        "uwb",
+        "mode",
+        options(&[option(3, "TDoA anchor"), option(4, "TDoA tag")]),
+        "UWB mode",
+    )
+    .reboot(),
+    ParamSpec::new("uwb", "uwbEnable", BOOL, "UWB backend enabled"),
+    ParamSpec::new("uwb", "devShortAddr", SHORT_ADDR, "UWB short address").reboot(),
+    ParamSpec::new(
+        "uwb",
+        "anchorCount",
+        int(0, ANCHOR_SLOTS as i64),
+        "Number of anchors",
+    ),
+    ParamSpec::new("uwb", "devId", SHORT_ADDR, "Anchor short address").slotted(ANCHOR_SLOTS),
+    ParamSpec::new("uwb", "x", ANY_FLOAT, "Anchor X position (m)").slotted(ANCHOR_SLOTS),
+    ParamSpec::new("uwb", "y", ANY_FLOAT, "Anchor Y position (m)").slotted(ANCHOR_SLOTS),
+    ParamSpec::new("uwb", "z", ANY_FLOAT, "Anchor Z position (m)").slotted(ANCHOR_SLOTS),
+    ParamSpec::new(
+        "uwb",
+        "originLat",
+        float(-90.0, 90.0),
+        "GPS origin latitude",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "originLon",
+        float(-180.0, 180.0),
+        "GPS origin longitude",
+    ),
+    ParamSpec::new("uwb", "originAlt", ANY_FLOAT, "GPS origin altitude (m)"),
+    ParamSpec::new(
+        "uwb",
+        "rotationDegrees",
+        ANY_FLOAT,
+        "Coordinate rotation (degrees)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "mavlinkTargetSystemId",
+        U8,
+        "MAVLink target system ID",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "outputBackend",
+        options(&[option(0, "MAVLink"), option(1, "RTLSLink Beacon")]),
+        "Position output backend",
+    )
+    .reboot(),
+    ParamSpec::new(
+        "uwb",
+        "rtlsBeaconAgeBiasMs",
+        U8,
+        "Safety bias added to TDoA age estimates (ms)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rtlsBeaconTdoaSigmaFloorM",
+        NON_NEGATIVE,
+        "Minimum TDoA one-sigma error reported (m)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rtlsBeaconTdoaPhysicalGuardEnable",
+        BOOL,
+        "Drop physically impossible TDoA samples",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rtlsBeaconTdoaPhysicalGuardMarginM",
+        NON_NEGATIVE,
+        "Allowed range difference beyond the anchor baseline (m)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "zCalcMode",
+        options(&[
+            option(0, "None"),
+            option(1, "Rangefinder"),
+            option(2, "UWB"),
+        ]),
+        "Z calculation mode",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rfForwardEnable",
+        BOOL,
+        "Forward rangefinder DISTANCE_SENSOR",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rfForwardSensorId",
+        U8,
+        "Rangefinder sensor ID override (255 = preserve source)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rfForwardOrientation",
+        U8,
+        "Rangefinder orientation override (255 = preserve source)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rfForwardPreserveSrcIds",
+        BOOL,
+        "Preserve source sysid/compid when forwarding",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "enableCovMatrix",
+        BOOL,
+        "Send position covariance matrix",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "rmseThreshold",
+        NON_NEGATIVE,
+        "RMSE threshold for position validity (m)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "tdoaEstimatorMode",
+        options(&[
+            option(0, "Legacy"),
+            option(1, "Robust"),
+            option(2, "Compare"),
+        ]),
+        "3D TDoA estimator mode",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "tdoaEstimatorDiag",
+        options(&[
+            option(0, "Off"),
+            option(1, "Summary"),
+            option(2, "Selected rows"),
+        ]),
+        "TDoA estimator diagnostics level",
+    ),
+    ParamSpec::new("uwb", "channel", int(1, 7), "UWB channel").reboot(),
+    ParamSpec::new("uwb", "dwMode", int(0, 7), "DW1000 mode index").reboot(),
+    ParamSpec::new("uwb", "txPowerLevel", int(0, 3), "TX power level").reboot(),
+    ParamSpec::new("uwb", "smartPowerEnable", BOOL, "Smart power enabled").reboot(),
+    ParamSpec::new(
+        "uwb",
+        "tdoaSlotCount",
+        int(0, 8),
+        "TDMA active slots per frame (0 = legacy)",
+    )
+    .reboot(),
+    ParamSpec::new(
+        "uwb",
+        "tdoaSlotDurationUs",
+        int(0, 65535),
+        "TDMA slot duration (us, 0 = legacy)",
+    )
+    .reboot(),
+    ParamSpec::new(
+        "uwb",
+        "tdoaAnchorTelemetryEnable",
+        BOOL,
+        "Periodic anchor stats telemetry",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "tdoaAnchorTelemetryIntervalMs",
+        int(250, 60000),
+        "Anchor stats telemetry interval (ms)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "tdoaAnchorTelemetryPort",
+        PORT,
+        "Anchor stats telemetry UDP port",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "tdoaMatcherPolicy",
+        options(&[option(0, "Youngest"), option(1, "Random")]),
+        "TDoA tag matcher policy",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "dynamicAnchorPosEnabled",
+        BOOL,
+        "Dynamic anchor positioning",
+    ),
+    ParamSpec::new("uwb", "anchorLayout", U8, "Dynamic anchor layout"),
+    ParamSpec::new(
+        "uwb",
+        "anchorHeight",
+        ANY_FLOAT,
+        "Lower-plane anchor height (m)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "anchorPlaneSeparation",
+        NON_NEGATIVE,
+        "Distance between dynamic anchor planes (m)",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "anchorPosLocked",
+        U8,
+        "Bitmask of anchors with locked positions",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "distanceAvgSamples",
+        int(1, 65535),
+        "Distance samples to average",
+    ),
+    ParamSpec::new(
+        "uwb",
+        "use2DEstimator",
+        options(&[option(0, "3D"), option(1, "2D")]),
+        "Position estimator mode",
+    ),
+    // ==================== app ====================
+    ParamSpec::new(
+        "app",
+        "led2Pin",
+        int(0, 65535),
+        "LED 2 GPIO pin (65535 = unset)",
+    )
+    .reboot(),
+    ParamSpec::new("app", "led2State", BOOL, "LED 2 state"),
+];
+
+/// All known parameters
+pub fn param_registry() -> &'static [ParamSpec] {
+    PARAMS
+}
+
+/// Look up a parameter, e.g. `("uwb", "channel")` or `("uwb", "x3")`
+pub fn lookup(group: &str, name: &str) -> Option<&'static ParamSpec> {
+    PARAMS
+        .iter()
+        .find(|spec| spec.group == group && spec.matches(name))
+}
+
+/// Check a value before it is written to a device.
+///
+/// Fails for parameters not in the registry as well as for values the
+/// parameter does not accept.
+pub fn validate_param(group: &str, name: &str, value: &str) -> Result<(), ParamError> {
+    let spec = lookup(group, name).ok_or_else(|| ParamError::Unknown {
+        group: group.to_string(),
+        name: name.to_string(),
+    })?;
+    spec.check(value)
+        .map_err(|reason| ParamError::InvalidValue {
+            group: group.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            reason,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(lookup("uwb", "channel").unwrap().name, "channel");
+        assert!(lookup("uwb", "channel").unwrap().requires_reboot);
+        assert_eq!(lookup("uwb", "x3").unwrap().name, "x");
+        assert_eq!(lookup("uwb", "devId8").unwrap().name, "devId");
+        assert!(lookup("uwb", "x9").is_none());
+        assert!(lookup("uwb", "x0").is_none());
+        assert!(lookup("uwb", "x").is_none());
+        assert!(lookup("wifi", "channel").is_none());
+        assert_eq!(lookup("wifi", "mode").unwrap().group, "wifi");
+    }
+
+    #[test]
+    fn test_validate_param() {
+        assert!(validate_param("uwb", "channel", "5").is_ok());
+        assert!(validate_param("uwb", "mode", "4").is_ok());
+        assert!(validate_param("uwb", "originLat", "41.39").is_ok());
+        assert!(validate_param("wifi", "gcsIp", "192.168.4.2").is_ok());
+        assert!(validate_param("wifi", "ssidST", "").is_ok());
+        assert!(validate_param("app", "led2State", "1").is_ok());
+
+        let err = validate_param("uwb", "channel", "42").unwrap_err();
+        assert_eq!(err.to_string(), "uwb.channel: 42 is out of range (1-7)");
+        assert!(validate_param("uwb", "mode", "2").is_err());
+        assert!(validate_param("uwb", "originLat", "91").is_err());
+        assert!(validate_param("uwb", "x2", "NaN").is_err());
+        assert!(validate_param("wifi", "gcsIp", "192.168.4").is_err());
+        assert!(validate_param("wifi", "udpPort", "0").is_err());
+        assert!(validate_param("wifi", "enableWebServer", "yes").is_err());
+        assert!(validate_param("wifi", "ssidST", &"a".repeat(33)).is_err());
+    }
+
+    #[test]
+    fn test_unknown_param() {
+        let err = validate_param("uwb", "notAParam", "1").unwrap_err();
+        assert!(matches!(err, ParamError::Unknown { .. }));
+        assert_eq!(err.to_string(), "Unknown parameter uwb.notAParam");
+    }
+}
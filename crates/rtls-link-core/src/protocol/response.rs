@@ -1,6 +1,9 @@
 //! Response parsing utilities for device protocol.
 
+use serde::{Deserialize, Serialize};
+
 use crate::error::DeviceError;
+use crate::types::FirmwareInfo;
 
 /// Parse a JSON response from device output.
 ///
@@ -88,8 +91,26 @@ impl ParsedResponse {
     }
 }
 
+/// Phrases marking a plain-text reply as fine even though it mentions errors
+pub const BENIGN_RESPONSES: &[&str] = &["errors cleared", "no error"];
+
+/// Leading words of a plain-text error reply
+const ERROR_WORDS: &[&str] = &["error", "fail", "failed", "failure", "invalid"];
+
 /// Check if a command response indicates an error
+///
+/// JSON replies are judged by their `success` and `error` fields; a reply
+/// counts as JSON if it starts with a JSON value or everything after its
+/// leading text is one. Plain text is an error when it contains `error:` or
+/// its first line starts with an error word such as `Failed` or `Invalid`;
+/// a mere mention, as in `failover disabled`, is not.
 pub fn is_error_response(response: &str) -> Option<String> {
+    is_error_response_with(response, BENIGN_RESPONSES)
+}
+
+/// Like [`is_error_response`], but plain-text replies containing any of the
+/// lowercase `benign` phrases are never errors
+pub fn is_error_response_with(response: &str, benign: &[&str]) -> Option<String> {
     // Check JSON first so successful ACKs like {"success":true,"error":null}
     // are not misclassified by the text-level "error" checks below.
     let obj_start = response.find('{');
@@ -102,7 +123,19 @@ pub fn is_error_response(response: &str) -> Option<String> {
     };
 
     if let Some(start) = start {
-        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&response[start..]) {
+        let rest = &response[start..];
+        let json = if response[..start].trim().is_empty() {
+            // Only the first value counts; the device may append text after it
+            serde_json::Deserializer::from_str(rest)
+                .into_iter::<serde_json::Value>()
+                .next()
+                .and_then(Result::ok)
+        } else {
+            // After leading text a bracket may just be part of the message,
+            // as in `Error: expected [0, 1]`
+            serde_json::from_str::<serde_json::Value>(rest).ok()
+        };
+        if let Some(json) = json {
             if json.get("success").and_then(|v| v.as_bool()) == Some(true) {
                 return None;
             }
@@ -127,57 +160,321 @@ pub fn is_error_response(response: &str) -> Option<String> {
         }
     }
 
-    let lower = response.to_lowercase();
-    if lower.contains("error:") {
-        if let Some(pos) = lower.find("error:") {
-            let msg = response[pos + 6..].trim();
-            return Some(msg.to_string());
-        }
+    // ASCII lowercasing keeps byte offsets valid for slicing `response`
+    let lower = response.to_ascii_lowercase();
+    if benign.iter().any(|phrase| lower.contains(phrase)) {
+        return None;
+    }
+    if let Some(pos) = lower.find("error:") {
+        let msg = response[pos + 6..].trim();
+        return Some(msg.to_string());
     }
 
-    let looks_like_text_error = (lower.contains("error")
-        || lower.contains("fail")
-        || lower.contains("invalid")
-        || lower.contains("not found"))
-        && !lower.contains("success");
-
-    if looks_like_text_error {
+    let first_line = lower.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let first_word = first_line
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .unwrap_or_default();
+    if ERROR_WORDS.contains(&first_word) || first_line.starts_with("not found") {
         return Some(response.trim().to_string());
     }
 
     None
 }
 
+/// A command's outcome as the device reported it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandAck {
+    /// Whether the device accepted the command
+    pub success: bool,
+    /// The error, or a status the device sent; bare acknowledgements
+    /// such as `OK` carry none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// JSON the reply carried, without its `success`, `message` and
+    /// `error` fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<serde_json::Value>,
+}
+
+/// Plain-text replies that only acknowledge a command, lowercase
+const BARE_ACKS: &[&str] = &["ok", "done", "success"];
+
+/// Read a command reply as an acknowledgement
+///
+/// Success follows [`is_error_response`], so JSON `{success, message}`
+/// replies and plain-text errors are judged the same way everywhere.
+pub fn parse_ack(response: &str) -> CommandAck {
+    let error = is_error_response(response);
+    let (message, payload) = match parse_json_response::<serde_json::Value>(response, "") {
+        Ok(mut json) => {
+            let message = json
+                .get("message")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let payload = match json.as_object_mut() {
+                Some(fields) => {
+                    for key in ["success", "message", "error"] {
+                        fields.remove(key);
+                    }
+                    (!fields.is_empty()).then_some(json)
+                }
+                None => Some(json),
+            };
+            (message, payload)
+        }
+        Err(_) => {
+            let text = response.trim();
+            let word = text.trim_end_matches(['.', '!']).to_ascii_lowercase();
+            let message =
+                (!text.is_empty() && !BARE_ACKS.contains(&word.as_str())).then(|| text.to_string());
+            (message, None)
+        }
+    };
+
+    CommandAck {
+        success: error.is_none(),
+        message: error.or(message),
+        payload,
+    }
+}
+
+/// Parse a `firmware-info` reply
+pub fn parse_firmware_info(response: &str, device_ip: &str) -> Result<FirmwareInfo, DeviceError> {
+    parse_json_response(response, device_ip)
+}
+
+/// Outcome of one parameter of a batched write
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamWriteResult {
+    pub group: String,
+    pub name: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ParamWriteResult {
+    pub fn new(group: &str, name: &str, error: Option<String>) -> Self {
+        Self {
+            group: group.to_string(),
+            name: name.to_string(),
+            success: error.is_none(),
+            error,
+        }
+    }
+}
+
+/// A parameter that reads back differently from what was written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamMismatch {
+    pub group: String,
+    pub name: String,
+    /// Value sent to the device
+    pub written: String,
+    /// Value read back, or `None` if the device would not return one
+    pub read: Option<String>,
+}
+
+impl std::fmt::Display for ParamMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.read {
+            Some(read) => write!(
+                f,
+                "{}.{}: wrote {:?}, reads back {:?}",
+                self.group, self.name, self.written, read
+            ),
+            None => write!(
+                f,
+                "{}.{}: wrote {:?}, could not be read back",
+                self.group, self.name, self.written
+            ),
+        }
+    }
+}
+
+/// Parse the reply to a `write-batch` of `params`
+///
+/// The reply is a JSON array with one entry per parameter, in order: either
+/// a bare boolean or an object with `success` and an optional `error`.
+pub fn parse_write_batch_response(
+    response: &str,
+    params: &[(String, String, String)],
+    device_ip: &str,
+) -> Result<Vec<ParamWriteResult>, DeviceError> {
+    let items: Vec<serde_json::Value> = parse_json_response(response, device_ip)?;
+    if items.len() != params.len() {
+        return Err(DeviceError::InvalidResponse {
+            ip: device_ip.to_string(),
+            message: format!(
+                "Batch write returned {} results for {} parameters",
+                items.len(),
+                params.len()
+            ),
+        });
+    }
+
+    params
+        .iter()
+        .zip(items)
+        .map(|((group, name, _), item)| {
+            let success = item
+                .as_bool()
+                .or_else(|| item.get("success").and_then(|v| v.as_bool()))
+                .ok_or_else(|| DeviceError::InvalidResponse {
+                    ip: device_ip.to_string(),
+                    message: format!("Batch write result for {group}.{name} has no success flag"),
+                })?;
+            let error = (!success).then(|| {
+                item.get("error")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("Write failed")
+                    .to_string()
+            });
+            Ok(ParamWriteResult::new(group, name, error))
+        })
+        .collect()
+}
+
+/// Summarize the failed writes in `results`, or `None` if all succeeded
+pub fn write_failures(results: &[ParamWriteResult]) -> Option<String> {
+    let failed = results
+        .iter()
+        .filter_map(|r| {
+            r.error
+                .as_ref()
+                .map(|error| format!("{}.{} ({})", r.group, r.name, error))
+        })
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} of {} parameters failed: {}",
+        failed.len(),
+        results.len(),
+        failed.join(", ")
+    ))
+}
+
+/// Parameters parsed from a readall response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReadallResponse {
+    /// Parameters as (group, name, value), in response order
+    pub params: Vec<(String, String, String)>,
+    /// Lines that were neither blank, a comment, a group header nor a
+    /// parameter
+    pub skipped: Vec<SkippedLine>,
+}
+
+/// A readall line that could not be parsed
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedLine {
+    /// One-based line number
+    pub line: usize,
+    pub text: String,
+    pub reason: &'static str,
+}
+
 /// Parse a readall response into key-value pairs
-pub fn parse_readall_response(response: &str) -> Vec<(String, String, String)> {
-    let mut params = Vec::new();
+///
+/// Values may be quoted with `"` or `'`, keeping any `=`, `#`, `;` or
+/// surrounding spaces inside; `\"` and `\\` are unescaped in double quotes.
+/// A `#` or `;` starting a line or following whitespace outside quotes
+/// begins a comment. Malformed lines are skipped and reported.
+pub fn parse_readall_response(response: &str) -> ReadallResponse {
+    let mut result = ReadallResponse::default();
     let mut current_group = String::new();
 
-    for line in response.lines() {
-        let line = line.trim();
+    for (index, raw_line) in response.lines().enumerate() {
+        let line = strip_comment(raw_line).trim();
+        let mut skip = |reason| {
+            result.skipped.push(SkippedLine {
+                line: index + 1,
+                text: raw_line.to_string(),
+                reason,
+            })
+        };
 
         if line.is_empty() {
             continue;
         }
 
         // Check for group header (e.g., "[wifi]")
-        if line.starts_with('[') && line.ends_with(']') {
-            current_group = line[1..line.len() - 1].to_string();
+        if let Some(group) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let group = group.trim();
+            if group.is_empty() {
+                skip("empty group name");
+            } else {
+                current_group = group.to_string();
+            }
+            continue;
+        }
+
+        let Some((name, value)) = line.split_once('=') else {
+            skip("expected name=value");
             continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            skip("missing parameter name");
+            continue;
+        }
+        if current_group.is_empty() {
+            skip("parameter outside a group");
+            continue;
+        }
+        match unquote(value.trim()) {
+            Some(value) => result
+                .params
+                .push((current_group.clone(), name.to_string(), value)),
+            None => skip("malformed quoted value"),
         }
+    }
 
-        // Parse key=value pairs
-        if let Some(eq_pos) = line.find('=') {
-            let name = line[..eq_pos].trim().to_string();
-            let value = line[eq_pos + 1..].trim().to_string();
+    result
+}
 
-            if !current_group.is_empty() {
-                params.push((current_group.clone(), name, value));
-            }
+/// `line` without its comment, if any
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut after_space = true;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '#' || c == ';') && after_space => return &line[..i],
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None => {}
         }
+        after_space = c.is_whitespace();
     }
+    line
+}
 
-    params
+/// The value of a possibly quoted string, or `None` if a quote is not
+/// closed or is followed by more than whitespace
+fn unquote(value: &str) -> Option<String> {
+    let Some(quote) = value.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+        return Some(value.to_string());
+    };
+
+    let mut out = String::new();
+    let mut chars = value[1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quote == '"' => out.push(chars.next()?),
+            c if c == quote => return chars.as_str().trim().is_empty().then_some(out),
+            c => out.push(c),
+        }
+    }
+    None
 }
 
 #[cfg(test)]
@@ -230,13 +527,149 @@ mod tests {
         assert!(
             is_error_response(r#"{"success": true, "error": null, "message": "OK"}"#).is_none()
         );
+        assert!(is_error_response("Invalid value for channel").is_some());
+        assert!(is_error_response("OK\nwrite error: flash busy").is_some());
+        assert!(is_error_response("{\"success\": false}\nDone").is_some());
+        assert!(is_error_response(r#"ack {"success": false}"#).is_some());
+    }
+
+    #[test]
+    fn test_is_error_response_with_bracketed_text() {
+        assert!(is_error_response("Error: expected [0, 1], got 7").is_some());
+        assert!(is_error_response("Invalid value: range [0, 1] exceeded").is_some());
+        assert!(is_error_response("Failed: {busy}").is_some());
+        assert!(is_error_response("channel set [5]").is_none());
+    }
+
+    #[test]
+    fn test_is_error_response_ignores_mentions() {
+        assert!(is_error_response("log level set, previous errors cleared").is_none());
+        assert!(is_error_response("failover disabled").is_none());
+        assert!(is_error_response("Errors: none").is_none());
+        assert!(is_error_response("rxFailed counter reset").is_none());
+        assert!(is_error_response("No errors").is_none());
+        assert!(is_error_response("Telemetry on, 0 invalid frames").is_none());
+        assert!(is_error_response("{\"success\": true}\nsee error log").is_none());
+    }
+
+    #[test]
+    fn test_is_error_response_with_benign_phrases() {
+        assert!(is_error_response("Error count reset").is_some());
+        assert!(is_error_response_with("Error count reset", &["error count reset"]).is_none());
+        assert!(is_error_response_with("Error: busy", &[]).is_some());
+    }
+
+    #[test]
+    fn test_parse_ack() {
+        for bare in ["OK", "ok\n", "Done.", ""] {
+            let ack = parse_ack(bare);
+            assert!(ack.success, "{bare:?}");
+            assert_eq!(ack.message, None);
+            assert_eq!(ack.payload, None);
+        }
+
+        let ack = parse_ack("Configuration saved");
+        assert!(ack.success);
+        assert_eq!(ack.message.as_deref(), Some("Configuration saved"));
+
+        let ack = parse_ack("Error: config not found");
+        assert!(!ack.success);
+        assert_eq!(ack.message.as_deref(), Some("config not found"));
+
+        let ack = parse_ack(r#"{"success": false, "message": "busy", "retryIn": 5}"#);
+        assert!(!ack.success);
+        assert_eq!(ack.message.as_deref(), Some("busy"));
+        assert_eq!(ack.payload, Some(serde_json::json!({"retryIn": 5})));
+
+        let ack = parse_ack(r#"{"success": true, "error": null, "led2State": true}"#);
+        assert!(ack.success);
+        assert_eq!(ack.message, None);
+        assert_eq!(ack.payload, Some(serde_json::json!({"led2State": true})));
+
+        let ack = parse_ack(r#"{"success": true}"#);
+        assert_eq!(ack.payload, None);
+
+        let ack = parse_ack(r#"[{"name": "site"}]"#);
+        assert!(ack.success);
+        assert_eq!(ack.payload, Some(serde_json::json!([{"name": "site"}])));
+    }
+
+    #[test]
+    fn test_parse_firmware_info() {
+        let response = r#"{"device":"RTLS-Link","version":"2.3.1","board":"MAKERFABS_ESP32_UWB","buildDate":"Mar  4 2026","buildTime":"14:02:11"}"#;
+
+        let info = parse_firmware_info(response, "192.168.1.1").unwrap();
+        assert_eq!(info.device.as_deref(), Some("RTLS-Link"));
+        assert_eq!(info.version.as_deref(), Some("2.3.1"));
+        assert_eq!(info.board.as_deref(), Some("MAKERFABS_ESP32_UWB"));
+        assert_eq!(info.build_date.as_deref(), Some("Mar  4 2026"));
+        assert_eq!(info.build_time.as_deref(), Some("14:02:11"));
+        assert_eq!(info.free_heap, None);
+        assert!(info.extra.is_empty());
+    }
+
+    #[test]
+    fn test_parse_firmware_info_lenient() {
+        let response = "OK\n{\"version\": 2, \"partition\": \"app1\", \"freeHeap\": \"182344\", \"sketchSize\": 1245184, \"board\": null, \"chipRevision\": 3}";
+
+        let info = parse_firmware_info(response, "192.168.1.1").unwrap();
+        assert_eq!(info.version.as_deref(), Some("2"));
+        assert_eq!(info.partition.as_deref(), Some("app1"));
+        assert_eq!(info.free_heap, Some(182344));
+        assert_eq!(info.sketch_size, Some(1245184));
+        assert_eq!(info.board, None);
+        assert_eq!(info.device, None);
+        assert_eq!(info.extra["chipRevision"], 3);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["freeHeap"], 182344);
+        assert_eq!(json["chipRevision"], 3);
+        assert!(json.get("board").is_none());
+
+        assert!(parse_firmware_info("OK", "192.168.1.1").is_err());
+    }
+
+    #[test]
+    fn test_parse_write_batch_response() {
+        let params = vec![
+            ("wifi".to_string(), "mode".to_string(), "1".to_string()),
+            ("uwb".to_string(), "mode".to_string(), "9".to_string()),
+            ("uwb".to_string(), "channel".to_string(), "5".to_string()),
+        ];
+        let response = r#"[true, {"success": false, "error": "out of range"}, {"success": true}]"#;
+
+        let results = parse_write_batch_response(response, &params, "192.168.1.1").unwrap();
+        assert!(results[0].success);
+        assert_eq!(
+            results[1],
+            ParamWriteResult::new("uwb", "mode", Some("out of range".into()))
+        );
+        assert!(results[2].success);
+        assert_eq!(
+            write_failures(&results).unwrap(),
+            "1 of 3 parameters failed: uwb.mode (out of range)"
+        );
+        assert!(write_failures(&[results[0].clone()]).is_none());
+    }
+
+    #[test]
+    fn test_parse_write_batch_response_rejects_mismatched_results() {
+        let params = vec![("wifi".to_string(), "mode".to_string(), "1".to_string())];
+
+        assert!(parse_write_batch_response("[true, true]", &params, "192.168.1.1").is_err());
+        assert!(
+            parse_write_batch_response(r#"[{"error": null}]"#, &params, "192.168.1.1").is_err()
+        );
+        assert!(parse_write_batch_response("OK", &params, "192.168.1.1").is_err());
     }
 
     #[test]
     fn test_parse_readall_response() {
         let response = "\n[wifi]\nmode=1\nssidST=TestNetwork\npswdST=password123\n\n[uwb]\nmode=4\ndevShortAddr=1\n";
 
-        let params = parse_readall_response(response);
+        let parsed = parse_readall_response(response);
+        assert!(parsed.skipped.is_empty());
+        let params = parsed.params;
         assert_eq!(params.len(), 5);
         assert_eq!(
             params[0],
@@ -254,5 +687,54 @@ mod tests {
             params[3],
             ("uwb".to_string(), "mode".to_string(), "4".to_string())
         );
+
+        let response = concat!(
+            "# readall all\n",
+            "stray line\n",
+            "orphan=1\n",
+            "[wifi]\n",
+            "  mode = 1 ; 0=AP, 1=STA\n",
+            "ssidST=\"Lab #2\"\n",
+            "pswdST=\"a=b;c \\\"q\\\" \"   # quoted\n",
+            "gcsIp=192.168.1.10#no-space\n",
+            "apPass='it;s'\n",
+            "bad=\"unterminated\n",
+            "=5\n",
+            "[app]\n",
+            "\n",
+            "[]\n",
+            "[uwb]\n",
+            "devShortAddr=\n",
+        );
+
+        let parsed = parse_readall_response(response);
+        let param = |i: usize| {
+            let (group, name, value) = &parsed.params[i];
+            (group.as_str(), name.as_str(), value.as_str())
+        };
+        assert_eq!(parsed.params.len(), 6);
+        assert_eq!(param(0), ("wifi", "mode", "1"));
+        assert_eq!(param(1), ("wifi", "ssidST", "Lab #2"));
+        assert_eq!(param(2), ("wifi", "pswdST", "a=b;c \"q\" "));
+        assert_eq!(param(3), ("wifi", "gcsIp", "192.168.1.10#no-space"));
+        assert_eq!(param(4), ("wifi", "apPass", "it;s"));
+        assert_eq!(param(5), ("uwb", "devShortAddr", ""));
+
+        let skipped = parsed
+            .skipped
+            .iter()
+            .map(|s| (s.line, s.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (2, "expected name=value"),
+                (3, "parameter outside a group"),
+                (10, "malformed quoted value"),
+                (11, "missing parameter name"),
+                (14, "empty group name"),
+            ]
+        );
+        assert_eq!(parsed.skipped[0].text, "stray line");
     }
 }
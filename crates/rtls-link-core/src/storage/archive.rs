@@ -0,0 +1,510 @@
+//! Whole-data archives for moving local storage between machines.
+//!
+//! An archive is a gzip-compressed tar file holding `manifest.json` and the
+//! configs and presets listed by [`ConfigStorage`] and [`PresetStorage`].
+//! Revision history is not included. Only plain files are written and read, so the small
+//! ustar subset needed is implemented here.
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use super::config::{check_config, find_name_ignoring_case, parse_config_file};
+use super::preset::validate_contents;
+use super::{ConfigLabels, ConfigStorage, PresetStorage};
+use crate::error::StorageError;
+use crate::types::{DeviceConfig, Preset};
+
+/// Format marker identifying data archives
+const ARCHIVE_FORMAT: &str = "rtls-link-data";
+
+/// Current archive format version
+const ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const CONFIGS_DIR: &str = "configs";
+const PRESETS_DIR: &str = "presets";
+
+const BLOCK_SIZE: usize = 512;
+
+/// Largest entry accepted on import
+const MAX_ENTRY_SIZE: usize = 4 * 1024 * 1024;
+
+/// Description of an archive, stored as its `manifest.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataArchiveManifest {
+    /// Always `rtls-link-data`
+    pub format: String,
+    /// Archive format version
+    pub version: u32,
+    /// Application that wrote the archive
+    pub app_version: String,
+    /// Export timestamp (ISO 8601)
+    pub exported_at: String,
+    /// Names of the archived configurations
+    pub configs: Vec<String>,
+    /// Names of the archived presets
+    pub presets: Vec<String>,
+}
+
+/// Outcome of [`import_all`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataImportReport {
+    /// Configurations written
+    pub configs: Vec<String>,
+    /// Presets written
+    pub presets: Vec<String>,
+    /// Entries left alone because they already exist (`configs/<name>`, ...)
+    pub skipped: Vec<String>,
+}
+
+/// Write every config and preset under `data_dir` to an archive at `path`.
+///
+/// Only entries the storage listings accept are archived; files that fail
+/// to parse or carry an invalid name are left out, so the archive can
+/// always be imported again.
+pub async fn export_all(
+    data_dir: &Path,
+    path: &Path,
+    app_version: &str,
+) -> Result<DataArchiveManifest, StorageError> {
+    let config_dir = data_dir.join(CONFIGS_DIR);
+    let config_storage = ConfigStorage::new(config_dir.clone())?;
+    let preset_storage = PresetStorage::new(data_dir.join(PRESETS_DIR))?;
+
+    let mut configs = Vec::new();
+    for info in config_storage.list().await? {
+        let content = fs::read(config_dir.join(format!("{}.json", info.name))).await?;
+        configs.push((info.name, content));
+    }
+    let mut presets = Vec::new();
+    for info in preset_storage.list(None, None).await?.presets {
+        // Presets are listed by their recorded name, which may not match a file
+        if let Ok(Some(preset)) = preset_storage.get(&info.name).await {
+            presets.push((info.name, serde_json::to_vec_pretty(&preset)?));
+        }
+    }
+
+    let manifest = DataArchiveManifest {
+        format: ARCHIVE_FORMAT.to_string(),
+        version: ARCHIVE_VERSION,
+        app_version: app_version.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        configs: configs.iter().map(|(name, _)| name.clone()).collect(),
+        presets: presets.iter().map(|(name, _)| name.clone()).collect(),
+    };
+
+    let mtime = chrono::Utc::now().timestamp().max(0) as u64;
+    let mut tar = Vec::new();
+    append_entry(
+        &mut tar,
+        MANIFEST_ENTRY,
+        &serde_json::to_vec_pretty(&manifest)?,
+        mtime,
+    );
+    for (dir, files) in [(CONFIGS_DIR, &configs), (PRESETS_DIR, &presets)] {
+        for (name, content) in files {
+            append_entry(&mut tar, &format!("{}/{}.json", dir, name), content, mtime);
+        }
+    }
+    tar.extend_from_slice(&[0; BLOCK_SIZE * 2]);
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&tar)?;
+    fs::write(path, encoder.finish()?).await?;
+
+    Ok(manifest)
+}
+
+/// Restore the configs and presets of an archive into `data_dir`.
+///
+/// Every entry is checked (name, JSON and contents) before anything is
+/// written, so a bad archive leaves storage untouched. Entries are saved
+/// through [`ConfigStorage`] and [`PresetStorage`], so replaced configs keep
+/// their revision history. Existing entries, matched ignoring case, are kept
+/// unless `overwrite` is set.
+pub async fn import_all(
+    data_dir: &Path,
+    path: &Path,
+    overwrite: bool,
+) -> Result<DataImportReport, StorageError> {
+    let config_dir = data_dir.join(CONFIGS_DIR);
+    let preset_dir = data_dir.join(PRESETS_DIR);
+    let config_storage = ConfigStorage::new(config_dir.clone())?;
+    let preset_storage = PresetStorage::new(preset_dir.clone())?;
+
+    let mut gz = Vec::new();
+    GzDecoder::new(fs::read(path).await?.as_slice())
+        .read_to_end(&mut gz)
+        .map_err(|e| invalid(format!("Not a data archive: {}", e)))?;
+
+    let mut manifest = None;
+    let mut configs: Vec<(String, ConfigLabels, DeviceConfig)> = Vec::new();
+    let mut presets: Vec<Preset> = Vec::new();
+    for (entry, content) in read_entries(&gz)? {
+        if entry == MANIFEST_ENTRY {
+            manifest = Some(serde_json::from_slice::<DataArchiveManifest>(&content)?);
+            continue;
+        }
+
+        let (dir, name) = entry
+            .strip_suffix(".json")
+            .and_then(|stem| stem.split_once('/'))
+            .ok_or_else(|| invalid(format!("Unexpected archive entry '{}'", entry)))?;
+        let text = std::str::from_utf8(&content)
+            .map_err(|_| invalid(format!("'{}' is not UTF-8", entry)))?;
+        match dir {
+            CONFIGS_DIR => {
+                config_storage.validate_name(name)?;
+                let (_, labels, config) = parse_config_file(text)?;
+                check_config(&config)?;
+                configs.push((name.to_string(), labels, config));
+            }
+            PRESETS_DIR => {
+                preset_storage.validate_name(name)?;
+                let mut preset = serde_json::from_str::<Preset>(text)?;
+                // The entry name is authoritative, as for stored files
+                preset.name = name.to_string();
+                validate_contents(&preset)?;
+                presets.push(preset);
+            }
+            _ => return Err(invalid(format!("Unexpected archive entry '{}'", entry))),
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid("Archive has no manifest".to_string()))?;
+    if manifest.format != ARCHIVE_FORMAT || manifest.version > ARCHIVE_VERSION {
+        return Err(invalid(format!(
+            "Unsupported archive {} v{} (supported: {} v{})",
+            manifest.format, manifest.version, ARCHIVE_FORMAT, ARCHIVE_VERSION
+        )));
+    }
+
+    let mut report = DataImportReport::default();
+    for (name, labels, config) in configs {
+        let taken = find_name_ignoring_case(&config_dir, &name).await?.is_some();
+        if taken && !overwrite {
+            report.skipped.push(format!("{}/{}", CONFIGS_DIR, name));
+            continue;
+        }
+        config_storage
+            .save_with_labels(&name, &config, &labels, true)
+            .await?;
+        report.configs.push(name);
+    }
+    for preset in presets {
+        let taken = find_name_ignoring_case(&preset_dir, &preset.name)
+            .await?
+            .is_some();
+        if taken && !overwrite {
+            report
+                .skipped
+                .push(format!("{}/{}", PRESETS_DIR, preset.name));
+            continue;
+        }
+        preset_storage.replace(&preset).await?;
+        report.presets.push(preset.name);
+    }
+
+    Ok(report)
+}
+
+fn invalid(message: String) -> StorageError {
+    StorageError::Serialization(serde_json::Error::custom(message))
+}
+
+/// Append a regular file to a tar stream (paths must be under 100 bytes)
+fn append_entry(tar: &mut Vec<u8>, path: &str, content: &[u8], mtime: u64) {
+    let mut header = [0u8; BLOCK_SIZE];
+    header[..path.len()].copy_from_slice(path.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{:011o}\0", content.len()).as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime).as_bytes());
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with its own field filled with spaces
+    header[148..156].copy_from_slice(b"        ");
+    let checksum: u32 = header.iter().map(|&b| b as u32).sum();
+    header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+
+    tar.extend_from_slice(&header);
+    tar.extend_from_slice(content);
+    tar.resize(tar.len().next_multiple_of(BLOCK_SIZE), 0);
+}
+
+/// Split a tar stream into (path, content) pairs for its regular files
+fn read_entries(tar: &[u8]) -> Result<Vec<(String, Vec<u8>)>, StorageError> {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    while offset + BLOCK_SIZE <= tar.len() {
+        let header = &tar[offset..offset + BLOCK_SIZE];
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+
+        let stored_sum = parse_octal(&header[148..156])?;
+        let actual_sum: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| if (148..156).contains(&i) { b' ' } else { b } as u64)
+            .sum();
+        if stored_sum != actual_sum {
+            return Err(invalid("Corrupt archive header".to_string()));
+        }
+
+        let name_len = header[..100].iter().position(|&b| b == 0).unwrap_or(100);
+        let path = std::str::from_utf8(&header[..name_len])
+            .map_err(|_| invalid("Archive entry name is not UTF-8".to_string()))?
+            .to_string();
+        let size = parse_octal(&header[124..136])? as usize;
+        if size > MAX_ENTRY_SIZE {
+            return Err(invalid(format!("Archive entry '{}' is too large", path)));
+        }
+
+        let start = offset + BLOCK_SIZE;
+        let end = start + size;
+        if end > tar.len() {
+            return Err(invalid("Truncated archive".to_string()));
+        }
+        if matches!(header[156], b'0' | 0) {
+            entries.push((path, tar[start..end].to_vec()));
+        }
+        offset = start + size.next_multiple_of(BLOCK_SIZE);
+    }
+
+    Ok(entries)
+}
+
+fn parse_octal(field: &[u8]) -> Result<u64, StorageError> {
+    let text: String = field
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect();
+    u64::from_str_radix(text.trim(), 8).map_err(|_| invalid("Corrupt archive header".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AnchorConfig, LocationData, PresetType};
+
+    fn stored_config(name: &str) -> String {
+        serde_json::json!({
+            "meta": {"name": name, "createdAt": "2024-01-01T00:00:00Z", "updatedAt": "2024-01-01T00:00:00Z"},
+            "config": {"wifi": {"mode": 1}, "uwb": {"mode": 3, "devShortAddr": "0"}, "app": {}}
+        })
+        .to_string()
+    }
+
+    fn preset(name: &str) -> Preset {
+        Preset {
+            name: name.to_string(),
+            description: None,
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(LocationData {
+                origin: crate::types::GpsOrigin {
+                    lat: 1.0,
+                    lon: 2.0,
+                    alt: 3.0,
+                },
+                rotation: 0.0,
+                anchors: [
+                    (0.0, 0.0, 1.0),
+                    (4.0, 0.0, 2.0),
+                    (4.0, 4.0, 1.0),
+                    (0.0, 4.0, 2.0),
+                ]
+                .iter()
+                .enumerate()
+                .map(|(i, &(x, y, z))| AnchorConfig {
+                    id: i.to_string(),
+                    x,
+                    y,
+                    z,
+                })
+                .collect(),
+                use_2d_estimator: None,
+            }),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_round_trip() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("configs/history/hall")).unwrap();
+        std::fs::write(
+            source.path().join("configs/hall.json"),
+            stored_config("hall"),
+        )
+        .unwrap();
+        std::fs::write(
+            source
+                .path()
+                .join("configs/history/hall/20240101T000000000Z.json"),
+            stored_config("hall"),
+        )
+        .unwrap();
+        PresetStorage::new(source.path().join("presets"))
+            .unwrap()
+            .save(&preset("site"))
+            .await
+            .unwrap();
+
+        let archive = source.path().join("data.tar.gz");
+        let manifest = export_all(source.path(), &archive, "test 1.0")
+            .await
+            .unwrap();
+        assert_eq!(manifest.configs, ["hall"]);
+        assert_eq!(manifest.presets, ["site"]);
+
+        let target = tempfile::tempdir().unwrap();
+        let report = import_all(target.path(), &archive, false).await.unwrap();
+        assert_eq!(report.configs, ["hall"]);
+        assert_eq!(report.presets, ["site"]);
+
+        let configs = ConfigStorage::new(target.path().join("configs")).unwrap();
+        assert_eq!(
+            configs.read("hall").await.unwrap().unwrap().config.uwb.mode,
+            3
+        );
+        let presets = PresetStorage::new(target.path().join("presets")).unwrap();
+        assert!(presets.get("site").await.unwrap().is_some());
+
+        // Existing entries are skipped unless overwriting
+        let report = import_all(target.path(), &archive, false).await.unwrap();
+        assert!(report.configs.is_empty());
+        assert_eq!(report.skipped, ["configs/hall", "presets/site"]);
+        let report = import_all(target.path(), &archive, true).await.unwrap();
+        assert_eq!(report.configs, ["hall"]);
+        // The replaced version is kept as a revision
+        assert_eq!(configs.list_revisions("hall").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_export_leaves_out_unlisted_files() {
+        let source = tempfile::tempdir().unwrap();
+        let config_dir = source.path().join("configs");
+        let preset_dir = source.path().join("presets");
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::create_dir_all(&preset_dir).unwrap();
+        std::fs::write(config_dir.join("hall.json"), stored_config("hall")).unwrap();
+        std::fs::write(config_dir.join("broken.json"), "{not json").unwrap();
+        std::fs::write(config_dir.join("bad name.json"), stored_config("bad name")).unwrap();
+        std::fs::write(preset_dir.join("broken.json"), "{not json").unwrap();
+
+        let archive = source.path().join("data.tar.gz");
+        let manifest = export_all(source.path(), &archive, "test 1.0")
+            .await
+            .unwrap();
+        assert_eq!(manifest.configs, ["hall"]);
+        assert!(manifest.presets.is_empty());
+
+        let target = tempfile::tempdir().unwrap();
+        let report = import_all(target.path(), &archive, false).await.unwrap();
+        assert_eq!(report.configs, ["hall"]);
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_names_taken_ignoring_case() {
+        let source = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(source.path().join("configs")).unwrap();
+        std::fs::write(
+            source.path().join("configs/hall.json"),
+            stored_config("hall"),
+        )
+        .unwrap();
+        let archive = source.path().join("data.tar.gz");
+        export_all(source.path(), &archive, "test 1.0")
+            .await
+            .unwrap();
+
+        let target = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(target.path().join("configs")).unwrap();
+        std::fs::write(
+            target.path().join("configs/HALL.json"),
+            stored_config("HALL"),
+        )
+        .unwrap();
+
+        let report = import_all(target.path(), &archive, false).await.unwrap();
+        assert!(report.configs.is_empty());
+        assert_eq!(report.skipped, ["configs/hall"]);
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_bad_entries_before_writing() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = DataArchiveManifest {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            app_version: "test".to_string(),
+            exported_at: "2024-01-01T00:00:00Z".to_string(),
+            configs: Vec::new(),
+            presets: Vec::new(),
+        };
+
+        for (entry, content) in [
+            ("configs/../escape.json", stored_config("escape")),
+            ("configs/broken.json", "{not json".to_string()),
+            ("other/file.json", "{}".to_string()),
+        ] {
+            let mut tar = Vec::new();
+            append_entry(
+                &mut tar,
+                MANIFEST_ENTRY,
+                &serde_json::to_vec(&manifest).unwrap(),
+                0,
+            );
+            append_entry(
+                &mut tar,
+                "configs/good.json",
+                stored_config("good").as_bytes(),
+                0,
+            );
+            append_entry(&mut tar, entry, content.as_bytes(), 0);
+            tar.extend_from_slice(&[0; BLOCK_SIZE * 2]);
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(&tar).unwrap();
+            let archive = dir.path().join("bad.tar.gz");
+            std::fs::write(&archive, encoder.finish().unwrap()).unwrap();
+
+            let target = dir.path().join("target");
+            assert!(
+                import_all(&target, &archive, true).await.is_err(),
+                "{}",
+                entry
+            );
+            assert!(!target.join("configs/good.json").exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_non_archive() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        assert!(import_all(dir.path(), &path, false).await.is_err());
+    }
+}
@@ -1,11 +1,26 @@
 //! Configuration storage service.
 //!
-//! Provides file-based storage for device configurations.
+//! Provides file-based storage for device configurations. Each file holds
+//! `{ "meta": { name, createdAt, updatedAt, description }, "config": ... }`
+//! so timestamps survive copies and filesystems without creation times.
+//! Legacy files containing a bare `DeviceConfig` are migrated on first read.
+//! Configs written in older shapes (e.g. `wifi.mode` as a name) are
+//! upgraded when read and flagged as migrated.
+//!
+//! Overwritten versions are kept as `history/<name>/<timestamp>.json`.
 
+use super::migration::upgrade_config_value;
+use crate::config_validation::validate_config;
 use crate::error::StorageError;
-use crate::types::{DeviceConfig, LocalConfig, LocalConfigInfo};
-use regex::Regex;
-use std::path::PathBuf;
+use crate::protocol::config_params::config_to_flat_params;
+use crate::types::{
+    ConfigRevision, ConfigSearchMatch, ConfigSearchResult, ConfigSummary, DeviceConfig,
+    LocalConfig, LocalConfigInfo,
+};
+use regex::{Regex, RegexBuilder};
+use serde::de::Error as _;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 /// Regex for valid config names: alphanumeric, dash, underscore only
@@ -14,6 +29,272 @@ const NAME_PATTERN: &str = r"^[a-zA-Z0-9_-]+$";
 /// Maximum name length
 const MAX_NAME_LENGTH: usize = 64;
 
+/// Default number of previous versions kept per configuration
+pub const DEFAULT_MAX_REVISIONS: usize = 5;
+
+/// Subdirectory holding previous versions
+const HISTORY_DIR: &str = "history";
+
+/// Format marker identifying exported configuration files
+const EXPORT_FORMAT: &str = "rtls-link-config";
+
+/// Current version of the export file layout
+const EXPORT_VERSION: u32 = 1;
+
+/// Self-describing configuration file written by [`ConfigStorage::export`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigExport {
+    /// Always `rtls-link-config`
+    pub format: String,
+    /// Export file layout version
+    pub version: u32,
+    /// Name the configuration was stored under
+    pub name: String,
+    /// Export timestamp (ISO 8601)
+    pub exported_at: String,
+    /// Application that wrote the file
+    pub app_version: String,
+    /// Description and tags
+    #[serde(flatten)]
+    pub labels: ConfigLabels,
+    /// Device configuration data
+    pub config: DeviceConfig,
+}
+
+/// Search query over the parameters of a configuration.
+///
+/// Configs are flattened with [`config_to_flat_params`]; a parameter
+/// matches if its `group.name` or value contains the query, ignoring case.
+/// Regex queries are matched the same way.
+#[derive(Debug, Clone)]
+pub struct ConfigQuery {
+    pattern: Regex,
+}
+
+impl ConfigQuery {
+    /// Build a query from a substring, or a regular expression if `regex` is set.
+    pub fn new(query: &str, regex: bool) -> Result<Self, StorageError> {
+        if query.is_empty() {
+            return Err(StorageError::InvalidQuery("query is empty".to_string()));
+        }
+        let pattern = if regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let pattern = RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map_err(|e| StorageError::InvalidQuery(e.to_string()))?;
+        Ok(Self { pattern })
+    }
+
+    /// The parameters of `config` matching the query, in parameter order.
+    pub fn matches(&self, config: &DeviceConfig) -> Vec<ConfigSearchMatch> {
+        config_to_flat_params(config)
+            .into_iter()
+            .filter(|(group, name, value)| {
+                self.pattern.is_match(&format!("{}.{}", group, name))
+                    || self.pattern.is_match(value)
+            })
+            .map(|(group, name, value)| ConfigSearchMatch { group, name, value })
+            .collect()
+    }
+}
+
+/// Description and tags attached to a stored configuration.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfigLabels {
+    /// Free-form description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Labels such as `site:hallA` or `channel:5`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl ConfigLabels {
+    /// Create labels, trimming whitespace and dropping blank or repeated tags
+    pub fn new<I, S>(description: Option<&str>, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self {
+            description: description
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string),
+            tags: normalize_tags(tags),
+        }
+    }
+}
+
+/// Trim tags, dropping blank and repeated ones
+pub(super) fn normalize_tags<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut unique: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.as_ref().trim();
+        if !tag.is_empty() && !unique.iter().any(|t| t == tag) {
+            unique.push(tag.to_string());
+        }
+    }
+    unique
+}
+
+/// Metadata stored alongside each configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigMeta {
+    name: String,
+    /// Creation timestamp (ISO 8601)
+    created_at: String,
+    /// Last update timestamp (ISO 8601)
+    updated_at: String,
+    #[serde(flatten)]
+    labels: ConfigLabels,
+}
+
+impl ConfigMeta {
+    fn new(name: &str, now: String) -> Self {
+        Self {
+            name: name.to_string(),
+            created_at: now.clone(),
+            updated_at: now,
+            labels: ConfigLabels::default(),
+        }
+    }
+}
+
+/// On-disk format of a stored configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredConfig {
+    meta: ConfigMeta,
+    config: DeviceConfig,
+    /// Whether the config had to be upgraded from a legacy shape when read
+    #[serde(skip)]
+    migrated: bool,
+}
+
+impl StoredConfig {
+    /// Whether a JSON document uses the stored layout rather than a bare config
+    fn matches(value: &serde_json::Value) -> bool {
+        value.get("meta").is_some() && value.get("config").is_some()
+    }
+
+    /// Listing entry for this configuration
+    fn info(self) -> LocalConfigInfo {
+        LocalConfigInfo {
+            summary: Some(ConfigSummary::of(&self.config)),
+            name: self.meta.name,
+            created_at: self.meta.created_at,
+            updated_at: self.meta.updated_at,
+            description: self.meta.labels.description,
+            tags: self.meta.labels.tags,
+        }
+    }
+}
+
+/// Name of the stored entry in `dir` matching `name` ignoring case,
+/// preferring an exact match.
+///
+/// Case-insensitive filesystems (Windows, macOS) would otherwise let `SiteA`
+/// silently replace `sitea`.
+pub(super) async fn find_name_ignoring_case(
+    dir: &Path,
+    name: &str,
+) -> Result<Option<String>, StorageError> {
+    let mut found = None;
+    let mut entries = fs::read_dir(dir).await.map_err(StorageError::Io)?;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        match path.file_stem().and_then(|s| s.to_str()) {
+            Some(stem) if stem == name => return Ok(Some(stem.to_string())),
+            Some(stem) if stem.eq_ignore_ascii_case(name) => found = Some(stem.to_string()),
+            _ => {}
+        }
+    }
+    Ok(found)
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339()
+}
+
+/// Reject configs failing [`validate_config`] with errors.
+pub(super) fn check_config(config: &DeviceConfig) -> Result<(), StorageError> {
+    let errors: Vec<String> = validate_config(config)
+        .into_iter()
+        .filter(|issue| issue.is_error())
+        .map(|issue| issue.to_string())
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(StorageError::InvalidConfig(errors.join("; ")))
+    }
+}
+
+/// Parse a bare `DeviceConfig`, upgrading legacy shapes if strict parsing fails.
+///
+/// Returns the config and whether it had to be upgraded. If the upgraded
+/// document still does not parse, the original error is returned.
+fn parse_device_config(mut value: serde_json::Value) -> Result<(DeviceConfig, bool), StorageError> {
+    match serde_json::from_value(value.clone()) {
+        Ok(config) => Ok((config, false)),
+        Err(e) if upgrade_config_value(&mut value) => serde_json::from_value(value)
+            .map(|config| (config, true))
+            .map_err(|_| StorageError::Serialization(e)),
+        Err(e) => Err(StorageError::Serialization(e)),
+    }
+}
+
+/// Parse an exported file, a stored config file, or a bare `DeviceConfig`
+/// (e.g. a device backup).
+///
+/// Returns the recorded name and labels for exported and stored files.
+pub(crate) fn parse_config_file(
+    content: &str,
+) -> Result<(Option<String>, ConfigLabels, DeviceConfig), StorageError> {
+    let mut value: serde_json::Value = serde_json::from_str(content)?;
+    if StoredConfig::matches(&value) {
+        let meta: ConfigMeta = serde_json::from_value(value["meta"].take())?;
+        let (config, _) = parse_device_config(value["config"].take())?;
+        return Ok((Some(meta.name), meta.labels, config));
+    }
+    if value.get("format").and_then(|f| f.as_str()) != Some(EXPORT_FORMAT) {
+        let (config, _) = parse_device_config(value)?;
+        return Ok((None, ConfigLabels::default(), config));
+    }
+
+    let export: ConfigExport = serde_json::from_value(value)?;
+    if export.version > EXPORT_VERSION {
+        return Err(StorageError::Serialization(serde_json::Error::custom(
+            format!(
+                "Unsupported export version {} (newest supported is {})",
+                export.version, EXPORT_VERSION
+            ),
+        )));
+    }
+    Ok((Some(export.name), export.labels, export.config))
+}
+
+/// Read the device configuration from any file [`ConfigStorage::import`]
+/// accepts, without storing it.
+pub async fn read_config_file(path: &Path) -> Result<DeviceConfig, StorageError> {
+    let content = fs::read_to_string(path).await.map_err(StorageError::Io)?;
+    let (_, _, config) = parse_config_file(&content)?;
+    Ok(config)
+}
+
 /// Configuration storage service.
 ///
 /// Takes a `PathBuf` in the constructor so each consumer (Tauri, CLI) can
@@ -21,6 +302,8 @@ const MAX_NAME_LENGTH: usize = 64;
 pub struct ConfigStorage {
     config_dir: PathBuf,
     name_regex: Regex,
+    max_revisions: usize,
+    rewrite_migrated: bool,
 }
 
 impl ConfigStorage {
@@ -31,10 +314,27 @@ impl ConfigStorage {
         Ok(Self {
             config_dir: dir,
             name_regex: Regex::new(NAME_PATTERN).unwrap(),
+            max_revisions: DEFAULT_MAX_REVISIONS,
+            rewrite_migrated: false,
         })
     }
 
-    fn validate_name(&self, name: &str) -> Result<(), StorageError> {
+    /// Set how many previous versions are kept per configuration (0 keeps none).
+    pub fn with_max_revisions(mut self, max_revisions: usize) -> Self {
+        self.max_revisions = max_revisions;
+        self
+    }
+
+    /// Rewrite files upgraded from a legacy shape as soon as they are read.
+    ///
+    /// Off by default, so [`LocalConfig::migrated`] keeps flagging such
+    /// configs until they are saved again.
+    pub fn with_migration_rewrite(mut self, rewrite: bool) -> Self {
+        self.rewrite_migrated = rewrite;
+        self
+    }
+
+    pub(crate) fn validate_name(&self, name: &str) -> Result<(), StorageError> {
         if name.is_empty() {
             return Err(StorageError::InvalidName(
                 "Name cannot be empty".to_string(),
@@ -62,7 +362,186 @@ impl ConfigStorage {
         self.config_dir.join(format!("{}.json", name))
     }
 
+    fn history_dir(&self, name: &str) -> PathBuf {
+        self.config_dir.join(HISTORY_DIR).join(name)
+    }
+
+    fn revision_path(&self, name: &str, timestamp: &str) -> Result<PathBuf, StorageError> {
+        if timestamp.is_empty()
+            || !timestamp
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            return Err(StorageError::InvalidName(format!(
+                "Invalid revision '{}'",
+                timestamp
+            )));
+        }
+        Ok(self.history_dir(name).join(format!("{}.json", timestamp)))
+    }
+
+    /// Move the current file for `name` into its history, pruning old revisions.
+    async fn archive(&self, name: &str, path: &Path) -> Result<(), StorageError> {
+        if !path.exists() {
+            return Ok(());
+        }
+        if self.max_revisions == 0 {
+            return fs::remove_file(path).await.map_err(StorageError::Io);
+        }
+
+        let dir = self.history_dir(name);
+        fs::create_dir_all(&dir).await.map_err(StorageError::Io)?;
+
+        let mut stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3fZ").to_string();
+        if let Some(latest) = self.revision_stamps(name).await?.into_iter().next() {
+            if latest >= stamp {
+                // Same millisecond (or the clock went back): sort after the latest
+                let (base, count) = match latest.split_once('-') {
+                    Some((base, count)) => (base, count.parse::<u32>().unwrap_or(0)),
+                    None => (latest.as_str(), 0),
+                };
+                stamp = format!("{}-{:03}", base, count + 1);
+            }
+        }
+        fs::rename(path, dir.join(format!("{}.json", stamp)))
+            .await
+            .map_err(StorageError::Io)?;
+
+        let revisions = self.revision_stamps(name).await?;
+        for stale in revisions.iter().skip(self.max_revisions) {
+            fs::remove_file(dir.join(format!("{}.json", stale)))
+                .await
+                .map_err(StorageError::Io)?;
+        }
+
+        Ok(())
+    }
+
+    /// Revision timestamps for `name`, newest first
+    async fn revision_stamps(&self, name: &str) -> Result<Vec<String>, StorageError> {
+        let dir = self.history_dir(name);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut stamps = Vec::new();
+        let mut entries = fs::read_dir(&dir).await.map_err(StorageError::Io)?;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                stamps.push(stem.to_string());
+            }
+        }
+
+        // Timestamps are fixed width, so they sort chronologically
+        stamps.sort_by(|a, b| b.cmp(a));
+        Ok(stamps)
+    }
+
+    /// List previous versions of a configuration, newest first.
+    pub async fn list_revisions(&self, name: &str) -> Result<Vec<ConfigRevision>, StorageError> {
+        self.validate_name(name)?;
+
+        let mut revisions = Vec::new();
+        for timestamp in self.revision_stamps(name).await? {
+            let path = self.revision_path(name, &timestamp)?;
+            let Ok(stored) = self.load(name, &path).await else {
+                continue;
+            };
+            revisions.push(ConfigRevision {
+                timestamp,
+                updated_at: stored.meta.updated_at,
+            });
+        }
+
+        Ok(revisions)
+    }
+
+    /// Make a previous version the current one.
+    ///
+    /// The version being replaced is itself kept as a revision, so a
+    /// restore can be undone.
+    pub async fn restore_revision(&self, name: &str, timestamp: &str) -> Result<(), StorageError> {
+        self.validate_name(name)?;
+
+        let path = self.revision_path(name, timestamp)?;
+        if !path.exists() {
+            return Err(StorageError::NotFound(format!(
+                "{} revision {}",
+                name, timestamp
+            )));
+        }
+
+        let revision = self.load(name, &path).await?;
+        self.save_with_labels(name, &revision.config, &revision.meta.labels, true)
+            .await
+    }
+
+    /// Load a stored configuration, migrating a legacy bare `DeviceConfig`
+    /// file to the current format on first access.
+    ///
+    /// Configs upgraded from an older shape are only written back if
+    /// [`Self::with_migration_rewrite`] is set. The file name is
+    /// authoritative for the name.
+    async fn load(&self, name: &str, path: &Path) -> Result<StoredConfig, StorageError> {
+        let content = fs::read_to_string(path).await.map_err(StorageError::Io)?;
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+
+        if StoredConfig::matches(&value) {
+            let mut meta: ConfigMeta = serde_json::from_value(value["meta"].take())?;
+            let (config, migrated) = parse_device_config(value["config"].take())?;
+            meta.name = name.to_string();
+            let stored = StoredConfig {
+                meta,
+                config,
+                migrated,
+            };
+            if migrated && self.rewrite_migrated {
+                self.write(path, &stored).await?;
+            }
+            return Ok(stored);
+        }
+
+        // Legacy files only have filesystem timestamps; capture them once
+        let (config, migrated) = parse_device_config(value)?;
+        let metadata = fs::metadata(path).await.map_err(StorageError::Io)?;
+        let updated_at = metadata
+            .modified()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+            .unwrap_or_else(|_| now());
+        let created_at = metadata
+            .created()
+            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
+            .unwrap_or_else(|_| updated_at.clone());
+
+        let stored = StoredConfig {
+            meta: ConfigMeta {
+                name: name.to_string(),
+                created_at,
+                updated_at,
+                labels: ConfigLabels::default(),
+            },
+            config,
+            migrated,
+        };
+        if !migrated || self.rewrite_migrated {
+            self.write(path, &stored).await?;
+        }
+
+        Ok(stored)
+    }
+
+    async fn write(&self, path: &Path, stored: &StoredConfig) -> Result<(), StorageError> {
+        let content = serde_json::to_string_pretty(stored).map_err(StorageError::Serialization)?;
+        fs::write(path, content).await.map_err(StorageError::Io)
+    }
+
     /// List all saved configurations.
+    ///
+    /// Files that cannot be parsed are skipped.
     pub async fn list(&self) -> Result<Vec<LocalConfigInfo>, StorageError> {
         let mut configs = Vec::new();
         let mut entries = fs::read_dir(&self.config_dir)
@@ -85,30 +564,66 @@ impl ConfigStorage {
                 continue;
             }
 
-            let metadata = fs::metadata(&path).await.map_err(StorageError::Io)?;
+            let Ok(stored) = self.load(&name, &path).await else {
+                continue;
+            };
 
-            let created_at = metadata
-                .created()
-                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
-                .unwrap_or_default();
+            configs.push(stored.info());
+        }
 
-            let updated_at = metadata
-                .modified()
-                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
-                .unwrap_or_default();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
 
-            configs.push(LocalConfigInfo {
-                name,
-                created_at,
-                updated_at,
-            });
+        Ok(configs)
+    }
+
+    /// Get the listing entry for a single configuration.
+    pub async fn info(&self, name: &str) -> Result<Option<LocalConfigInfo>, StorageError> {
+        self.validate_name(name)?;
+
+        let path = self.get_path(name);
+        if !path.exists() {
+            return Ok(None);
         }
 
-        configs.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Some(self.load(name, &path).await?.info()))
+    }
 
+    /// List the saved configurations carrying `tag`.
+    pub async fn list_with_tag(&self, tag: &str) -> Result<Vec<LocalConfigInfo>, StorageError> {
+        let mut configs = self.list().await?;
+        configs.retain(|info| info.tags.iter().any(|t| t == tag));
         Ok(configs)
     }
 
+    /// Search saved configurations by parameter name and value.
+    ///
+    /// See [`ConfigQuery`] for how `query` and `regex` are interpreted.
+    /// Configs without matches are left out.
+    pub async fn search(
+        &self,
+        query: &str,
+        regex: bool,
+    ) -> Result<Vec<ConfigSearchResult>, StorageError> {
+        let query = ConfigQuery::new(query, regex)?;
+
+        let mut results = Vec::new();
+        for info in self.list().await? {
+            let Ok(Some(local)) = self.read(&info.name).await else {
+                continue;
+            };
+
+            let matches = query.matches(&local.config);
+            if !matches.is_empty() {
+                results.push(ConfigSearchResult {
+                    name: info.name,
+                    matches,
+                });
+            }
+        }
+
+        Ok(results)
+    }
+
     /// Read a configuration by name.
     pub async fn read(&self, name: &str) -> Result<Option<LocalConfig>, StorageError> {
         self.validate_name(name)?;
@@ -119,40 +634,99 @@ impl ConfigStorage {
             return Ok(None);
         }
 
-        let content = fs::read_to_string(&path).await.map_err(StorageError::Io)?;
-        let config: DeviceConfig =
-            serde_json::from_str(&content).map_err(StorageError::Serialization)?;
-
-        let metadata = fs::metadata(&path).await.map_err(StorageError::Io)?;
-
-        let created_at = metadata
-            .created()
-            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
-            .unwrap_or_default();
-
-        let updated_at = metadata
-            .modified()
-            .map(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339())
-            .unwrap_or_default();
+        let stored = self.load(name, &path).await?;
 
         Ok(Some(LocalConfig {
             name: name.to_string(),
-            created_at,
-            updated_at,
-            config,
+            created_at: stored.meta.created_at,
+            updated_at: stored.meta.updated_at,
+            description: stored.meta.labels.description,
+            tags: stored.meta.labels.tags,
+            config: stored.config,
+            migrated: stored.migrated,
         }))
     }
 
     /// Save a configuration.
-    pub async fn save(&self, name: &str, config: &DeviceConfig) -> Result<(), StorageError> {
+    ///
+    /// Fails with [`StorageError::AlreadyExists`] if the name, ignoring case,
+    /// is taken unless `overwrite` is set. Overwriting an entry whose name
+    /// differs only by case moves that entry into its history. Overwriting
+    /// keeps the original creation time, description and tags. Configs
+    /// failing [`validate_config`] with errors are rejected.
+    pub async fn save(
+        &self,
+        name: &str,
+        config: &DeviceConfig,
+        overwrite: bool,
+    ) -> Result<(), StorageError> {
+        self.save_inner(name, config, None, overwrite).await
+    }
+
+    /// Save a configuration, replacing its description and tags.
+    pub async fn save_with_labels(
+        &self,
+        name: &str,
+        config: &DeviceConfig,
+        labels: &ConfigLabels,
+        overwrite: bool,
+    ) -> Result<(), StorageError> {
+        self.save_inner(name, config, Some(labels), overwrite).await
+    }
+
+    /// Replace the description and tags of an existing configuration.
+    pub async fn set_labels(&self, name: &str, labels: &ConfigLabels) -> Result<(), StorageError> {
+        let local = self
+            .read(name)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(name.to_string()))?;
+        self.save_with_labels(name, &local.config, labels, true)
+            .await
+    }
+
+    async fn save_inner(
+        &self,
+        name: &str,
+        config: &DeviceConfig,
+        labels: Option<&ConfigLabels>,
+        overwrite: bool,
+    ) -> Result<(), StorageError> {
         self.validate_name(name)?;
 
         let path = self.get_path(name);
-        let content = serde_json::to_string_pretty(config).map_err(StorageError::Serialization)?;
+        let stored = find_name_ignoring_case(&self.config_dir, name).await?;
+        if let Some(stored) = stored.as_ref().filter(|_| !overwrite) {
+            return Err(StorageError::AlreadyExists(stored.clone()));
+        }
 
-        fs::write(&path, content).await.map_err(StorageError::Io)?;
+        check_config(config)?;
 
-        Ok(())
+        let now = now();
+        let mut meta = match self.load(name, &path).await {
+            Ok(existing) => ConfigMeta {
+                updated_at: now,
+                ..existing.meta
+            },
+            // New config, or an unreadable file being replaced
+            Err(_) => ConfigMeta::new(name, now),
+        };
+        if let Some(labels) = labels {
+            meta.labels = labels.clone();
+        }
+
+        if let Some(stored) = stored.filter(|stored| stored != name) {
+            self.archive(&stored, &self.get_path(&stored)).await?;
+        }
+        self.archive(name, &path).await?;
+        self.write(
+            &path,
+            &StoredConfig {
+                meta,
+                config: config.clone(),
+                migrated: false,
+            },
+        )
+        .await
     }
 
     /// Delete a configuration.
@@ -167,8 +741,178 @@ impl ConfigStorage {
 
         fs::remove_file(&path).await.map_err(StorageError::Io)?;
 
+        let history = self.history_dir(name);
+        if history.exists() {
+            fs::remove_dir_all(&history)
+                .await
+                .map_err(StorageError::Io)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rename a configuration.
+    ///
+    /// Its revision history moves with it.
+    ///
+    /// The file is moved rather than rewritten, so its creation time is kept.
+    /// Fails if `new_name`, ignoring case, is already taken by another
+    /// config; a rename that only changes case is allowed.
+    pub async fn rename(&self, old_name: &str, new_name: &str) -> Result<(), StorageError> {
+        self.validate_name(old_name)?;
+        self.validate_name(new_name)?;
+
+        let old_path = self.get_path(old_name);
+        if !old_path.exists() {
+            return Err(StorageError::NotFound(old_name.to_string()));
+        }
+
+        if old_name == new_name {
+            return Ok(());
+        }
+
+        if let Some(stored) = find_name_ignoring_case(&self.config_dir, new_name).await? {
+            if stored != old_name {
+                return Err(StorageError::AlreadyExists(stored));
+            }
+        }
+
+        let new_path = self.get_path(new_name);
+        fs::rename(&old_path, &new_path)
+            .await
+            .map_err(StorageError::Io)?;
+
+        let old_history = self.history_dir(old_name);
+        let new_history = self.history_dir(new_name);
+        if old_history.exists() {
+            // On a case-insensitive filesystem a case-only rename sees its
+            // own history under the new name
+            if new_history.exists() && !old_name.eq_ignore_ascii_case(new_name) {
+                // Leftovers of a deleted config with the new name
+                fs::remove_dir_all(&new_history)
+                    .await
+                    .map_err(StorageError::Io)?;
+            }
+            fs::rename(&old_history, &new_history)
+                .await
+                .map_err(StorageError::Io)?;
+        }
+
+        // Keep the stored name in step with the file name
+        let stored = self.load(new_name, &new_path).await?;
+        self.write(&new_path, &stored).await
+    }
+
+    /// Write a configuration to `path` as a self-describing export file.
+    pub async fn export(
+        &self,
+        name: &str,
+        path: &Path,
+        app_version: &str,
+    ) -> Result<(), StorageError> {
+        let local = self
+            .read(name)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(name.to_string()))?;
+
+        let export = ConfigExport {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            name: local.name,
+            exported_at: now(),
+            app_version: app_version.to_string(),
+            labels: ConfigLabels {
+                description: local.description,
+                tags: local.tags,
+            },
+            config: local.config,
+        };
+        let content = serde_json::to_string_pretty(&export).map_err(StorageError::Serialization)?;
+
+        fs::write(path, content).await.map_err(StorageError::Io)?;
+
         Ok(())
     }
+
+    /// Import a configuration from an export file or a bare config JSON.
+    ///
+    /// The name is `name` if given, else the name recorded in the export,
+    /// else the file stem. Fails if the name is already taken unless
+    /// `overwrite` is set. Returns the name the configuration was saved as.
+    pub async fn import(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        overwrite: bool,
+    ) -> Result<String, StorageError> {
+        let content = fs::read_to_string(path).await.map_err(StorageError::Io)?;
+        let (exported_name, labels, config) = parse_config_file(&content)?;
+
+        let name = name
+            .map(str::to_string)
+            .or(exported_name)
+            .or_else(|| {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .map(str::to_string)
+            })
+            .ok_or_else(|| {
+                StorageError::InvalidName(format!(
+                    "Cannot derive a config name from {}",
+                    path.display()
+                ))
+            })?;
+        self.validate_name(&name)?;
+
+        self.save_with_labels(&name, &config, &labels, overwrite)
+            .await?;
+
+        Ok(name)
+    }
+
+    /// Copy a configuration under a new name.
+    ///
+    /// The copy gets fresh created/updated timestamps. Fails if `target`,
+    /// ignoring case, is already taken unless `overwrite` is set; an entry
+    /// whose name differs only by case is then moved into its history.
+    pub async fn duplicate(
+        &self,
+        source: &str,
+        target: &str,
+        overwrite: bool,
+    ) -> Result<(), StorageError> {
+        self.validate_name(source)?;
+        self.validate_name(target)?;
+
+        let source_path = self.get_path(source);
+        if !source_path.exists() {
+            return Err(StorageError::NotFound(source.to_string()));
+        }
+
+        if source.eq_ignore_ascii_case(target) {
+            return Err(StorageError::AlreadyExists(source.to_string()));
+        }
+        let stored = find_name_ignoring_case(&self.config_dir, target).await?;
+        if let Some(stored) = stored.as_ref().filter(|_| !overwrite) {
+            return Err(StorageError::AlreadyExists(stored.clone()));
+        }
+
+        let source = self.load(source, &source_path).await?;
+        let copy = StoredConfig {
+            meta: ConfigMeta {
+                labels: source.meta.labels,
+                ..ConfigMeta::new(target, now())
+            },
+            config: source.config,
+            migrated: false,
+        };
+        if let Some(stored) = stored.filter(|stored| stored != target) {
+            self.archive(&stored, &self.get_path(&stored)).await?;
+        }
+        let target_path = self.get_path(target);
+        self.archive(target, &target_path).await?;
+        self.write(&target_path, &copy).await
+    }
 }
 
 #[cfg(test)]
@@ -197,6 +941,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -240,10 +985,12 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         }
     }
@@ -253,7 +1000,7 @@ mod tests {
         let (storage, _tmp) = create_test_storage();
         let config = make_config();
 
-        storage.save("test-config", &config).await.unwrap();
+        storage.save("test-config", &config, false).await.unwrap();
 
         let loaded = storage.read("test-config").await.unwrap();
         assert!(loaded.is_some());
@@ -267,13 +1014,24 @@ mod tests {
         let (storage, _tmp) = create_test_storage();
         let config = make_config();
 
-        storage.save("alpha", &config).await.unwrap();
-        storage.save("beta", &config).await.unwrap();
+        storage.save("alpha", &config, false).await.unwrap();
+        storage.save("beta", &config, false).await.unwrap();
 
         let configs = storage.list().await.unwrap();
         assert_eq!(configs.len(), 2);
         assert_eq!(configs[0].name, "alpha");
         assert_eq!(configs[1].name, "beta");
+
+        let summary = configs[0].summary.as_ref().unwrap();
+        assert_eq!(summary.uwb_mode, 4);
+        assert_eq!(summary.wifi_mode, 1);
+        assert_eq!(summary.anchor_count, 0);
+        assert!(!summary.has_origin);
+
+        let info = storage.info("beta").await.unwrap().unwrap();
+        assert_eq!(info.name, "beta");
+        assert_eq!(info.summary, configs[1].summary);
+        assert!(storage.info("gamma").await.unwrap().is_none());
     }
 
     #[tokio::test]
@@ -281,13 +1039,599 @@ mod tests {
         let (storage, _tmp) = create_test_storage();
         let config = make_config();
 
-        storage.save("to-delete", &config).await.unwrap();
+        storage.save("to-delete", &config, false).await.unwrap();
         assert!(storage.read("to-delete").await.unwrap().is_some());
 
         storage.delete("to-delete").await.unwrap();
         assert!(storage.read("to-delete").await.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_rename() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save("old", &make_config(), false).await.unwrap();
+
+        storage.rename("old", "new").await.unwrap();
+
+        assert!(storage.read("old").await.unwrap().is_none());
+        let renamed = storage.read("new").await.unwrap().unwrap();
+        assert_eq!(renamed.name, "new");
+        assert_eq!(renamed.config.wifi.mode, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rename_errors() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save("first", &make_config(), false).await.unwrap();
+        storage.save("second", &make_config(), false).await.unwrap();
+
+        assert!(matches!(
+            storage.rename("first", "second").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.rename("first", "../escape").await,
+            Err(StorageError::InvalidName(_))
+        ));
+        assert!(matches!(
+            storage.rename("missing", "third").await,
+            Err(StorageError::NotFound(_))
+        ));
+
+        // Failed renames leave both configs in place
+        assert!(storage.read("first").await.unwrap().is_some());
+        assert!(storage.read("second").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate() {
+        let (storage, _tmp) = create_test_storage();
+        let mut config = make_config();
+        storage.save("source", &config, false).await.unwrap();
+        config.wifi.mode = 0;
+        storage.save("existing", &config, false).await.unwrap();
+
+        storage.duplicate("source", "copy", false).await.unwrap();
+        assert_eq!(
+            storage
+                .read("copy")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .wifi
+                .mode,
+            1
+        );
+        assert!(storage.read("source").await.unwrap().is_some());
+
+        assert!(matches!(
+            storage.duplicate("source", "existing", false).await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        storage.duplicate("source", "existing", true).await.unwrap();
+        assert_eq!(
+            storage
+                .read("existing")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .wifi
+                .mode,
+            1
+        );
+
+        assert!(matches!(
+            storage.duplicate("missing", "other", false).await,
+            Err(StorageError::NotFound(_))
+        ));
+        assert!(matches!(
+            storage.duplicate("source", "bad name", false).await,
+            Err(StorageError::InvalidName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import() {
+        let (storage, _tmp) = create_test_storage();
+        let outside = tempfile::tempdir().unwrap();
+        storage.save("office", &make_config(), false).await.unwrap();
+        let path = outside.path().join("shared.json");
+
+        storage.export("office", &path, "test 1.0").await.unwrap();
+        let export: ConfigExport =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(export.format, EXPORT_FORMAT);
+        assert_eq!(export.name, "office");
+        assert_eq!(export.app_version, "test 1.0");
+        assert_eq!(read_config_file(&path).await.unwrap().uwb.mode, 4);
+
+        // The exported name collides with the original
+        assert!(matches!(
+            storage.import(&path, None, false).await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert_eq!(
+            storage
+                .import(&path, Some("office-copy"), false)
+                .await
+                .unwrap(),
+            "office-copy"
+        );
+        assert_eq!(storage.import(&path, None, true).await.unwrap(), "office");
+        assert!(matches!(
+            storage.import(&path, Some("bad name"), false).await,
+            Err(StorageError::InvalidName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_import_bare_config_uses_file_stem() {
+        let (storage, _tmp) = create_test_storage();
+        let outside = tempfile::tempdir().unwrap();
+        let path = outside.path().join("backup-01.json");
+        std::fs::write(&path, serde_json::to_string(&make_config()).unwrap()).unwrap();
+
+        assert_eq!(
+            storage.import(&path, None, false).await.unwrap(),
+            "backup-01"
+        );
+        assert_eq!(
+            storage
+                .read("backup-01")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .uwb
+                .mode,
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_invalid_payload() {
+        let (storage, _tmp) = create_test_storage();
+        let outside = tempfile::tempdir().unwrap();
+        let path = outside.path().join("broken.json");
+
+        std::fs::write(&path, r#"{"wifi": {"mode": "x"}}"#).unwrap();
+        assert!(matches!(
+            storage.import(&path, None, false).await,
+            Err(StorageError::Serialization(_))
+        ));
+
+        let future = serde_json::json!({
+            "format": EXPORT_FORMAT,
+            "version": EXPORT_VERSION + 1,
+            "name": "future",
+            "exportedAt": "2030-01-01T00:00:00Z",
+            "appVersion": "test",
+            "config": make_config(),
+        });
+        std::fs::write(&path, future.to_string()).unwrap();
+        assert!(storage.import(&path, None, false).await.is_err());
+        assert!(storage.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_save_keeps_created_at() {
+        let (storage, tmp) = create_test_storage();
+        storage.save("kept", &make_config(), false).await.unwrap();
+        let first = storage.read("kept").await.unwrap().unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        storage.save("kept", &make_config(), true).await.unwrap();
+        let second = storage.read("kept").await.unwrap().unwrap();
+
+        assert_eq!(second.created_at, first.created_at);
+        assert_ne!(second.updated_at, first.updated_at);
+
+        let raw: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(tmp.path().join("kept.json")).unwrap())
+                .unwrap();
+        assert_eq!(raw["meta"]["name"], "kept");
+        assert_eq!(raw["meta"]["createdAt"], first.created_at.as_str());
+        assert_eq!(raw["config"]["wifi"]["mode"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_labels() {
+        let (storage, _tmp) = create_test_storage();
+        let labels = ConfigLabels::new(
+            Some(" Hall A, channel 5 "),
+            ["site:hallA", " ", "channel:5", "site:hallA"],
+        );
+        assert_eq!(labels.tags, vec!["site:hallA", "channel:5"]);
+
+        storage
+            .save_with_labels("hall-a", &make_config(), &labels, false)
+            .await
+            .unwrap();
+        storage.save("hall-b", &make_config(), false).await.unwrap();
+
+        // A plain save keeps the labels
+        storage.save("hall-a", &make_config(), true).await.unwrap();
+        let loaded = storage.read("hall-a").await.unwrap().unwrap();
+        assert_eq!(loaded.description.as_deref(), Some("Hall A, channel 5"));
+        assert_eq!(loaded.tags, labels.tags);
+
+        let tagged = storage.list_with_tag("site:hallA").await.unwrap();
+        assert_eq!(tagged.len(), 1);
+        assert_eq!(tagged[0].name, "hall-a");
+
+        storage
+            .set_labels("hall-b", &ConfigLabels::new(None, ["site:hallA"]))
+            .await
+            .unwrap();
+        assert_eq!(storage.list_with_tag("site:hallA").await.unwrap().len(), 2);
+        assert!(matches!(
+            storage.set_labels("missing", &labels).await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_legacy_file_is_migrated() {
+        let (storage, tmp) = create_test_storage();
+        let path = tmp.path().join("legacy.json");
+        std::fs::write(&path, serde_json::to_string(&make_config()).unwrap()).unwrap();
+
+        let listed = storage.list().await.unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(!listed[0].created_at.is_empty());
+
+        let raw: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(raw["meta"]["name"], "legacy");
+        assert_eq!(raw["meta"]["createdAt"], listed[0].created_at.as_str());
+
+        let loaded = storage.read("legacy").await.unwrap().unwrap();
+        assert_eq!(loaded.created_at, listed[0].created_at);
+        assert_eq!(loaded.config.uwb.mode, 4);
+    }
+
+    #[tokio::test]
+    async fn test_rename_updates_stored_name() {
+        let (storage, tmp) = create_test_storage();
+        storage.save("before", &make_config(), false).await.unwrap();
+        let created_at = storage.read("before").await.unwrap().unwrap().created_at;
+
+        storage.rename("before", "after").await.unwrap();
+
+        let raw: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(tmp.path().join("after.json")).unwrap())
+                .unwrap();
+        assert_eq!(raw["meta"]["name"], "after");
+        assert_eq!(raw["meta"]["createdAt"], created_at.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_save_requires_overwrite_for_existing_name() {
+        let (storage, _tmp) = create_test_storage();
+        let mut config = make_config();
+        storage.save("tuned", &config, false).await.unwrap();
+
+        config.uwb.channel = Some(5);
+        assert!(matches!(
+            storage.save("tuned", &config, false).await,
+            Err(StorageError::AlreadyExists(name)) if name == "tuned"
+        ));
+        assert_eq!(
+            storage
+                .read("tuned")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .uwb
+                .channel,
+            None
+        );
+
+        storage.save("tuned", &config, true).await.unwrap();
+        assert_eq!(
+            storage
+                .read("tuned")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .uwb
+                .channel,
+            Some(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_save_rejects_names_differing_only_by_case() {
+        let (storage, _tmp) = create_test_storage();
+        let config = make_config();
+        storage.save("sitea", &config, false).await.unwrap();
+
+        assert!(matches!(
+            storage.save("SiteA", &config, false).await,
+            Err(StorageError::AlreadyExists(name)) if name == "sitea"
+        ));
+
+        storage.save("SiteA", &config, true).await.unwrap();
+        let names: Vec<String> = storage
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, ["SiteA"]);
+        assert_eq!(storage.list_revisions("sitea").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rename_and_duplicate_compare_names_ignoring_case() {
+        let (storage, _tmp) = create_test_storage();
+        let config = make_config();
+        storage.save("sitea", &config, false).await.unwrap();
+        storage.save("x", &config, false).await.unwrap();
+
+        assert!(matches!(
+            storage.rename("x", "SITEA").await,
+            Err(StorageError::AlreadyExists(name)) if name == "sitea"
+        ));
+        assert!(matches!(
+            storage.duplicate("x", "SITEA", false).await,
+            Err(StorageError::AlreadyExists(name)) if name == "sitea"
+        ));
+
+        // A case-only rename keeps the config
+        storage.rename("sitea", "SiteA").await.unwrap();
+        let names: Vec<String> = storage
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, ["SiteA", "x"]);
+        assert_eq!(storage.read("SiteA").await.unwrap().unwrap().name, "SiteA");
+    }
+
+    #[tokio::test]
+    async fn test_save_rejects_invalid_config() {
+        let (storage, _tmp) = create_test_storage();
+        let mut config = make_config();
+        config.uwb.channel = Some(12);
+
+        assert!(matches!(
+            storage.save("bad", &config, false).await,
+            Err(StorageError::InvalidConfig(_))
+        ));
+        assert!(storage.read("bad").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_keeps_revisions() {
+        let (storage, _tmp) = create_test_storage();
+        let storage = storage.with_max_revisions(2);
+        let mut config = make_config();
+
+        for channel in 1..=4 {
+            config.uwb.channel = Some(channel);
+            storage.save("site", &config, true).await.unwrap();
+        }
+
+        // Saves 1-3 were replaced; only the two newest are kept
+        let revisions = storage.list_revisions("site").await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        assert!(revisions[0].timestamp > revisions[1].timestamp);
+
+        storage
+            .restore_revision("site", &revisions[1].timestamp)
+            .await
+            .unwrap();
+        let restored = storage.read("site").await.unwrap().unwrap();
+        assert_eq!(restored.config.uwb.channel, Some(2));
+
+        // The replaced version can be restored in turn
+        let revisions = storage.list_revisions("site").await.unwrap();
+        assert_eq!(revisions.len(), 2);
+        storage
+            .restore_revision("site", &revisions[0].timestamp)
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .read("site")
+                .await
+                .unwrap()
+                .unwrap()
+                .config
+                .uwb
+                .channel,
+            Some(4)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_revision_errors() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save("site", &make_config(), false).await.unwrap();
+
+        assert!(storage.list_revisions("site").await.unwrap().is_empty());
+        assert!(matches!(
+            storage
+                .restore_revision("site", "20240101T000000000Z")
+                .await,
+            Err(StorageError::NotFound(_))
+        ));
+        assert!(matches!(
+            storage.restore_revision("site", "../../escape").await,
+            Err(StorageError::InvalidName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_history_follows_rename_and_delete() {
+        let (storage, tmp) = create_test_storage();
+        storage.save("before", &make_config(), false).await.unwrap();
+        storage.save("before", &make_config(), true).await.unwrap();
+
+        storage.rename("before", "after").await.unwrap();
+        assert!(storage.list_revisions("before").await.unwrap().is_empty());
+        assert_eq!(storage.list_revisions("after").await.unwrap().len(), 1);
+        assert_eq!(storage.list().await.unwrap().len(), 1);
+
+        storage.delete("after").await.unwrap();
+        assert!(!tmp.path().join("history").join("after").exists());
+    }
+
+    #[tokio::test]
+    async fn test_zero_revisions_keeps_no_history() {
+        let (storage, tmp) = create_test_storage();
+        let storage = storage.with_max_revisions(0);
+        storage.save("site", &make_config(), false).await.unwrap();
+        storage.save("site", &make_config(), true).await.unwrap();
+
+        assert!(storage.list_revisions("site").await.unwrap().is_empty());
+        assert!(!tmp.path().join("history").exists());
+        assert!(storage.read("site").await.unwrap().is_some());
+    }
+
+    const LEGACY_NAMED_MODES: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/legacy_named_modes.json"
+    ));
+    const LEGACY_STRING_PARAMS: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/legacy_string_params.json"
+    ));
+
+    #[tokio::test]
+    async fn test_read_migrates_legacy_named_modes() {
+        let (storage, tmp) = create_test_storage();
+        let path = tmp.path().join("anchor.json");
+        std::fs::write(&path, LEGACY_NAMED_MODES).unwrap();
+
+        let local = storage.read("anchor").await.unwrap().unwrap();
+        assert!(local.migrated);
+        assert_eq!(local.config.wifi.mode, 0);
+        assert_eq!(local.config.uwb.mode, 3);
+        assert_eq!(local.config.uwb.dev_short_addr, "3");
+        assert_eq!(local.config.uwb.anchor_count, Some(4));
+
+        // Left on disk as-is until saved again
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), LEGACY_NAMED_MODES);
+        assert_eq!(storage.list().await.unwrap().len(), 1);
+
+        storage.save("anchor", &local.config, true).await.unwrap();
+        assert!(!storage.read("anchor").await.unwrap().unwrap().migrated);
+    }
+
+    #[tokio::test]
+    async fn test_read_migrates_legacy_string_params() {
+        let (storage, tmp) = create_test_storage();
+        let storage = storage.with_migration_rewrite(true);
+        std::fs::write(tmp.path().join("hall.json"), LEGACY_STRING_PARAMS).unwrap();
+
+        let local = storage.read("hall").await.unwrap().unwrap();
+        assert!(local.migrated);
+        assert_eq!(local.description.as_deref(), Some("Hall A tag"));
+        assert_eq!(local.created_at, "2024-03-01T10:00:00+00:00");
+        assert_eq!(local.config.wifi.mode, 1);
+        assert_eq!(local.config.wifi.udp_port, Some(14550));
+        assert_eq!(local.config.wifi.enable_web_server, Some(1));
+        assert_eq!(local.config.uwb.channel, Some(5));
+        assert_eq!(local.config.uwb.origin_lat, Some(41.3851));
+        assert_eq!(local.config.app.led2_state, Some(0));
+        let anchors = local.config.uwb.anchors.as_ref().unwrap();
+        assert_eq!(anchors[1].id, "2");
+        assert_eq!(anchors[1].x, 6.5);
+
+        // Rewritten in the current shape on first read
+        let again = storage.read("hall").await.unwrap().unwrap();
+        assert!(!again.migrated);
+        assert_eq!(again.created_at, local.created_at);
+        assert_eq!(again.config.uwb.channel, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_unmigratable_file_still_fails() {
+        let (storage, tmp) = create_test_storage();
+        std::fs::write(
+            tmp.path().join("broken.json"),
+            r#"{"wifi":{"mode":"MESH"},"uwb":{"mode":4,"devShortAddr":"1"}}"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            storage.read("broken").await,
+            Err(StorageError::Serialization(_))
+        ));
+        assert!(storage.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_fields_survive_round_trip() {
+        let (storage, _tmp) = create_test_storage();
+        let mut config = make_config();
+        config.uwb.mode = 3;
+        config
+            .uwb
+            .extra
+            .insert("futureParam".to_string(), serde_json::json!(7));
+        storage.save("future", &config, false).await.unwrap();
+
+        let loaded = storage.read("future").await.unwrap().unwrap();
+        assert_eq!(loaded.config.uwb.extra["futureParam"], 7);
+
+        let params = crate::protocol::config_params::config_to_params(
+            &loaded.config,
+            crate::protocol::config_params::ParamOptions::default(),
+        )
+        .unwrap();
+        assert!(params
+            .iter()
+            .any(|(g, n, v)| g == "uwb" && n == "futureParam" && v == "7"));
+    }
+
+    #[tokio::test]
+    async fn test_search_matches_names_and_values() {
+        let (storage, _tmp) = create_test_storage();
+        let mut hall = make_config();
+        hall.wifi.gcs_ip = Some("192.168.8.10".to_string());
+        storage.save("hall", &hall, false).await.unwrap();
+        storage.save("lab", &make_config(), false).await.unwrap();
+
+        let results = storage.search("192.168.8.10", false).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "hall");
+        assert_eq!(
+            results[0].matches,
+            vec![ConfigSearchMatch {
+                group: "wifi".to_string(),
+                name: "gcsIp".to_string(),
+                value: "192.168.8.10".to_string(),
+            }]
+        );
+
+        // Substring queries are literal and case-insensitive
+        assert!(storage.search("192x168", false).await.unwrap().is_empty());
+        let by_name = storage.search("WIFI.SSIDST", false).await.unwrap();
+        assert_eq!(by_name.len(), 2);
+
+        let by_regex = storage.search(r"^192\.168\.8\.\d+$", true).await.unwrap();
+        assert_eq!(by_regex.len(), 1);
+
+        assert!(matches!(
+            storage.search("(", true).await,
+            Err(StorageError::InvalidQuery(_))
+        ));
+        assert!(matches!(
+            storage.search("", false).await,
+            Err(StorageError::InvalidQuery(_))
+        ));
+    }
+
     #[test]
     fn test_validate_name() {
         let (storage, _tmp) = create_test_storage();
@@ -0,0 +1,233 @@
+//! Upgrades for configuration files written by older app versions, and for
+//! `backup-config` payloads from older firmware releases.
+//!
+//! Files are parsed strictly first; only documents that fail are passed
+//! through [`upgrade_config_value`], which rewrites the legacy shapes we
+//! know about in place:
+//!
+//! - `wifi.mode` and `uwb.mode` stored as names (`"AP"`, `"TDOA_TAG"`)
+//! - numeric parameters stored as strings or booleans (`"channel": "5"`,
+//!   `"enableWebServer": true`), as in hand-edited firmware dumps
+//! - `devShortAddr` and anchor ids stored as numbers, anchor coordinates
+//!   stored as strings
+//! - anchors stored as an object keyed by index (`{"0": {...}}`) instead of
+//!   an array
+//! - a missing `app` group
+//!
+//! Only parameters known to the firmware parameter table are coerced, so
+//! fields this version does not know about pass through untouched.
+
+use serde_json::{Map, Value};
+
+use crate::mavlink::params::find_by_legacy_name;
+
+/// Parameters holding text rather than numbers
+const STRING_PARAMS: &[(&str, &str)] = &[
+    ("wifi", "ssidAP"),
+    ("wifi", "pswdAP"),
+    ("wifi", "ssidST"),
+    ("wifi", "pswdST"),
+    ("wifi", "gcsIp"),
+    ("uwb", "devShortAddr"),
+];
+
+/// Rewrite legacy shapes in a bare `DeviceConfig` document.
+///
+/// Returns whether anything was changed.
+pub(crate) fn upgrade_config_value(config: &mut Value) -> bool {
+    let Some(groups) = config.as_object_mut() else {
+        return false;
+    };
+
+    let mut changed = false;
+    if !groups.contains_key("app") {
+        groups.insert("app".to_string(), Value::Object(Map::new()));
+        changed = true;
+    }
+
+    for (group, fields) in groups.iter_mut() {
+        let Some(fields) = fields.as_object_mut() else {
+            continue;
+        };
+        for (name, value) in fields.iter_mut() {
+            changed |= upgrade_field(group, name, value);
+        }
+    }
+
+    changed
+}
+
+fn upgrade_field(group: &str, name: &str, value: &mut Value) -> bool {
+    match (group, name) {
+        ("wifi", "mode") => replace_named(value, wifi_mode_by_name),
+        ("uwb", "mode") => replace_named(value, uwb_mode_by_name),
+        ("uwb", "anchors") => upgrade_anchors(value),
+        _ if STRING_PARAMS.contains(&(group, name)) => to_string_value(value),
+        _ if find_by_legacy_name(group, name).is_some() => to_number_value(value),
+        _ => false,
+    }
+}
+
+fn wifi_mode_by_name(name: &str) -> Option<u8> {
+    match name.to_ascii_uppercase().as_str() {
+        "AP" | "ACCESS_POINT" => Some(0),
+        "STA" | "STATION" => Some(1),
+        _ => None,
+    }
+}
+
+fn uwb_mode_by_name(name: &str) -> Option<u8> {
+    match name.to_ascii_uppercase().as_str() {
+        "TDOA_ANCHOR" | "ANCHOR_TDOA" => Some(3),
+        "TDOA_TAG" | "TAG_TDOA" => Some(4),
+        _ => None,
+    }
+}
+
+/// Replace a mode name with its number, falling back to numeric coercion
+fn replace_named(value: &mut Value, by_name: fn(&str) -> Option<u8>) -> bool {
+    match value.as_str().and_then(|s| by_name(s.trim())) {
+        Some(mode) => {
+            *value = Value::from(mode);
+            true
+        }
+        None => to_number_value(value),
+    }
+}
+
+fn upgrade_anchors(anchors: &mut Value) -> bool {
+    let mut changed = false;
+    if let Some(by_index) = anchors.as_object() {
+        let mut entries: Vec<(usize, Value)> = match by_index
+            .iter()
+            .map(|(key, anchor)| Some((key.trim().parse().ok()?, anchor.clone())))
+            .collect()
+        {
+            Some(entries) => entries,
+            None => return false,
+        };
+        entries.sort_by_key(|(index, _)| *index);
+        *anchors = Value::Array(entries.into_iter().map(|(_, anchor)| anchor).collect());
+        changed = true;
+    }
+    let Some(anchors) = anchors.as_array_mut() else {
+        return changed;
+    };
+
+    for anchor in anchors.iter_mut().filter_map(Value::as_object_mut) {
+        for (key, value) in anchor.iter_mut() {
+            changed |= match key.as_str() {
+                "id" => to_string_value(value),
+                "x" | "y" | "z" => to_number_value(value),
+                _ => false,
+            };
+        }
+    }
+    changed
+}
+
+fn to_number_value(value: &mut Value) -> bool {
+    let number = match value {
+        Value::Bool(b) => Value::from(u8::from(*b)),
+        Value::String(s) => {
+            let s = s.trim();
+            if let Ok(n) = s.parse::<u64>() {
+                Value::from(n)
+            } else if let Some(n) = s.parse::<f64>().ok().filter(|n| n.is_finite()) {
+                Value::from(n)
+            } else {
+                return false;
+            }
+        }
+        _ => return false,
+    };
+    *value = number;
+    true
+}
+
+fn to_string_value(value: &mut Value) -> bool {
+    if !value.is_number() {
+        return false;
+    }
+    *value = Value::String(value.to_string());
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DeviceConfig;
+
+    #[test]
+    fn test_upgrade_named_modes_and_string_numbers() {
+        let mut value = serde_json::json!({
+            "wifi": { "mode": "Station", "udpPort": "14550", "enableWebServer": true, "gcsIp": "10.0.0.2" },
+            "uwb": { "mode": "TDOA_TAG", "devShortAddr": 7, "channel": "5", "originLat": "41.5" }
+        });
+
+        assert!(upgrade_config_value(&mut value));
+        let config: DeviceConfig = serde_json::from_value(value).unwrap();
+
+        assert_eq!(config.wifi.mode, 1);
+        assert_eq!(config.wifi.udp_port, Some(14550));
+        assert_eq!(config.wifi.enable_web_server, Some(1));
+        assert_eq!(config.uwb.mode, 4);
+        assert_eq!(config.uwb.dev_short_addr, "7");
+        assert_eq!(config.uwb.channel, Some(5));
+        assert_eq!(config.uwb.origin_lat, Some(41.5));
+        assert!(config.app.led2_pin.is_none());
+    }
+
+    #[test]
+    fn test_upgrade_leaves_current_and_unknown_fields_alone() {
+        let mut value = serde_json::json!({
+            "wifi": { "mode": 0, "ssidAP": "1234" },
+            "uwb": { "mode": 3, "devShortAddr": "1", "futureParam": "5" },
+            "app": {}
+        });
+        let original = value.clone();
+
+        assert!(!upgrade_config_value(&mut value));
+        assert_eq!(value, original);
+    }
+
+    #[test]
+    fn test_upgrade_anchors_keyed_by_index() {
+        let mut value = serde_json::json!({
+            "wifi": { "mode": 1 },
+            "uwb": {
+                "mode": 4,
+                "devShortAddr": "1",
+                "anchors": {
+                    "10": { "id": 3, "x": 1, "y": 0, "z": 2 },
+                    "2": { "id": "2", "x": "0.5", "y": 0, "z": 2 }
+                }
+            },
+            "app": {}
+        });
+
+        assert!(upgrade_config_value(&mut value));
+        let config: DeviceConfig = serde_json::from_value(value).unwrap();
+        let anchors = config.uwb.anchors.unwrap();
+        assert_eq!(anchors[0].id, "2");
+        assert_eq!(anchors[0].x, 0.5);
+        assert_eq!(anchors[1].id, "3");
+
+        let mut value = serde_json::json!({ "uwb": { "anchors": { "first": {} } } });
+        let original = value.clone();
+        upgrade_config_value(&mut value);
+        assert_eq!(value["uwb"], original["uwb"]);
+    }
+
+    #[test]
+    fn test_unrecognized_mode_name_is_left_for_the_parser() {
+        let mut value = serde_json::json!({
+            "wifi": { "mode": "MESH" },
+            "uwb": { "mode": 4, "devShortAddr": "1" },
+            "app": {}
+        });
+
+        assert!(!upgrade_config_value(&mut value));
+        assert!(serde_json::from_value::<DeviceConfig>(value).is_err());
+    }
+}
@@ -1,10 +1,13 @@
 //! Storage services for presets and configurations.
 
+pub mod archive;
 pub mod config;
+pub(crate) mod migration;
 pub mod preset;
 
-pub use config::ConfigStorage;
-pub use preset::PresetStorage;
+pub use archive::{export_all, import_all, DataArchiveManifest, DataImportReport};
+pub use config::{read_config_file, ConfigExport, ConfigLabels, ConfigQuery, ConfigStorage};
+pub use preset::{merge_presets, PresetStorage};
 
 /// Get the default data directory for RTLS-Link tools.
 ///
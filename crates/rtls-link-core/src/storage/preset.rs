@@ -2,10 +2,14 @@
 //!
 //! Provides file-based storage for presets (both full configs and location-only).
 
+use super::config::{find_name_ignoring_case, normalize_tags};
+use crate::config_validation::{validate_config, validate_locations};
 use crate::error::StorageError;
-use crate::types::{Preset, PresetInfo, PresetType};
+use crate::types::{
+    AppliedRecord, LocationData, Preset, PresetInfo, PresetList, PresetProblem, PresetType,
+};
 use regex::Regex;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 /// Regex for valid preset names: alphanumeric, dash, underscore only
@@ -14,6 +18,12 @@ const NAME_PATTERN: &str = r"^[a-zA-Z0-9_-]+$";
 /// Maximum name length
 const MAX_NAME_LENGTH: usize = 64;
 
+/// Subdirectory that corrupt preset files are moved into
+const CORRUPT_DIR: &str = "corrupt";
+
+/// Number of devices remembered in a preset's `applied_to`
+const MAX_APPLIED_RECORDS: usize = 32;
+
 /// Preset storage service.
 ///
 /// Takes a `PathBuf` in the constructor so each consumer (Tauri, CLI) can
@@ -34,7 +44,7 @@ impl PresetStorage {
         })
     }
 
-    fn validate_name(&self, name: &str) -> Result<(), StorageError> {
+    pub(crate) fn validate_name(&self, name: &str) -> Result<(), StorageError> {
         if name.is_empty() {
             return Err(StorageError::InvalidPresetName(
                 "Name cannot be empty".to_string(),
@@ -62,9 +72,77 @@ impl PresetStorage {
         self.preset_dir.join(format!("{}.json", name))
     }
 
-    /// List all saved presets.
-    pub async fn list(&self) -> Result<Vec<PresetInfo>, StorageError> {
+    /// List saved presets, optionally only those of `preset_type` and
+    /// carrying `tag`.
+    ///
+    /// Files that cannot be read or parsed are reported in `problems`
+    /// regardless of the filter.
+    pub async fn list(
+        &self,
+        preset_type: Option<PresetType>,
+        tag: Option<&str>,
+    ) -> Result<PresetList, StorageError> {
+        let (presets, problems) = self.scan().await?;
+        let mut presets: Vec<PresetInfo> = presets
+            .into_iter()
+            .filter(|preset| {
+                !(preset_type
+                    .as_ref()
+                    .is_some_and(|t| *t != preset.preset_type)
+                    || tag.is_some_and(|tag| !preset.tags.iter().any(|t| t == tag)))
+            })
+            .map(|preset| PresetInfo {
+                name: preset.name,
+                preset_type: preset.preset_type,
+                description: preset.description,
+                tags: preset.tags,
+                last_applied_at: preset.last_applied_at,
+                created_at: preset.created_at,
+                updated_at: preset.updated_at,
+            })
+            .collect();
+
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(PresetList { presets, problems })
+    }
+
+    /// Move every corrupt preset file into the `corrupt/` subdirectory so
+    /// it no longer shows up as a problem.
+    ///
+    /// Returns the files that were moved. A file whose name is already taken
+    /// in `corrupt/` gets a numeric suffix.
+    pub async fn move_corrupt(&self) -> Result<Vec<PresetProblem>, StorageError> {
+        let (_, problems) = self.scan().await?;
+        if problems.is_empty() {
+            return Ok(problems);
+        }
+
+        let corrupt_dir = self.preset_dir.join(CORRUPT_DIR);
+        fs::create_dir_all(&corrupt_dir)
+            .await
+            .map_err(StorageError::Io)?;
+
+        for problem in &problems {
+            let source = self.preset_dir.join(&problem.file);
+            let mut target = corrupt_dir.join(&problem.file);
+            let mut suffix = 1;
+            while target.exists() {
+                target = corrupt_dir.join(format!("{}.{}", problem.file, suffix));
+                suffix += 1;
+            }
+            fs::rename(&source, &target)
+                .await
+                .map_err(StorageError::Io)?;
+        }
+
+        Ok(problems)
+    }
+
+    /// Read every preset file, splitting out the ones that fail to load.
+    async fn scan(&self) -> Result<(Vec<Preset>, Vec<PresetProblem>), StorageError> {
         let mut presets = Vec::new();
+        let mut problems = Vec::new();
         let mut entries = fs::read_dir(&self.preset_dir)
             .await
             .map_err(StorageError::Io)?;
@@ -72,29 +150,26 @@ impl PresetStorage {
         while let Ok(Some(entry)) = entries.next_entry().await {
             let path = entry.path();
 
-            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            if path.extension().and_then(|s| s.to_str()) != Some("json") || !path.is_file() {
                 continue;
             }
 
-            match fs::read_to_string(&path).await {
-                Ok(content) => {
-                    if let Ok(preset) = serde_json::from_str::<Preset>(&content) {
-                        presets.push(PresetInfo {
-                            name: preset.name,
-                            preset_type: preset.preset_type,
-                            description: preset.description,
-                            created_at: preset.created_at,
-                            updated_at: preset.updated_at,
-                        });
-                    }
-                }
-                Err(_) => continue,
+            let loaded = match fs::read_to_string(&path).await {
+                Ok(content) => serde_json::from_str::<Preset>(&content).map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            match loaded {
+                Ok(preset) => presets.push(preset),
+                Err(error) => problems.push(PresetProblem {
+                    file: entry.file_name().to_string_lossy().into_owned(),
+                    error,
+                }),
             }
         }
 
-        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        problems.sort_by(|a, b| a.file.cmp(&b.file));
 
-        Ok(presets)
+        Ok((presets, problems))
     }
 
     /// Read a preset by name.
@@ -114,33 +189,92 @@ impl PresetStorage {
     }
 
     /// Save a preset.
+    ///
+    /// Overwriting an existing preset keeps its `created_at` and apply
+    /// history and refreshes `updated_at`. Tags are trimmed and
+    /// de-duplicated. Fails if another
+    /// preset has the same name apart from case; see [`PresetStorage::replace`].
     pub async fn save(&self, preset: &Preset) -> Result<(), StorageError> {
+        self.save_inner(preset, false).await
+    }
+
+    /// Save a preset, replacing any preset whose name differs only by case.
+    pub async fn replace(&self, preset: &Preset) -> Result<(), StorageError> {
+        self.save_inner(preset, true).await
+    }
+
+    async fn save_inner(&self, preset: &Preset, overwrite: bool) -> Result<(), StorageError> {
         self.validate_name(&preset.name)?;
+        validate_contents(preset)?;
 
-        // Validate preset data based on type
-        match preset.preset_type {
-            PresetType::Full => {
-                if preset.config.is_none() {
-                    return Err(StorageError::InvalidPresetName(
-                        "Full preset must include config data".to_string(),
-                    ));
-                }
+        let stored = self.stored_name(&preset.name).await?;
+        if let Some(stored) = stored.as_ref().filter(|s| **s != preset.name) {
+            if !overwrite {
+                return Err(StorageError::AlreadyExists(stored.clone()));
             }
-            PresetType::Locations => {
-                if preset.locations.is_none() {
-                    return Err(StorageError::InvalidPresetName(
-                        "Locations preset must include location data".to_string(),
-                    ));
-                }
+        }
+
+        let mut preset = Preset {
+            tags: normalize_tags(&preset.tags),
+            ..preset.clone()
+        };
+        if let Some(stored) = stored {
+            // A missing or unreadable preset is simply replaced
+            if let Ok(Some(existing)) = self.get(&stored).await {
+                preset.created_at = existing.created_at;
+                preset.updated_at = chrono::Utc::now().to_rfc3339();
+                preset.last_applied_at = existing.last_applied_at;
+                preset.applied_to = existing.applied_to;
+            }
+            if stored != preset.name {
+                fs::remove_file(self.get_path(&stored))
+                    .await
+                    .map_err(StorageError::Io)?;
             }
         }
 
-        let path = self.get_path(&preset.name);
-        let content = serde_json::to_string_pretty(preset).map_err(StorageError::Serialization)?;
+        self.write(&self.get_path(&preset.name), &preset).await
+    }
 
-        fs::write(&path, content).await.map_err(StorageError::Io)?;
+    /// Write a preset to `path` so it can be shared and imported elsewhere.
+    pub async fn export(&self, name: &str, path: &Path) -> Result<(), StorageError> {
+        let preset = self.get_existing(name).await?;
+        self.write(path, &preset).await
+    }
 
-        Ok(())
+    /// Import a preset file written by [`PresetStorage::export`].
+    ///
+    /// The name is `name` if given, else the one recorded in the file. Fails
+    /// if the name, ignoring case, is already taken unless `overwrite` is
+    /// set. Returns the name the preset was saved as.
+    pub async fn import(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        overwrite: bool,
+    ) -> Result<String, StorageError> {
+        let content = fs::read_to_string(path).await.map_err(StorageError::Io)?;
+        let mut preset = parse_preset_file(&content)?;
+
+        if let Some(name) = name.filter(|name| *name != preset.name) {
+            preset.name = name.to_string();
+            preset.updated_at = chrono::Utc::now().to_rfc3339();
+        }
+        self.validate_name(&preset.name)?;
+        validate_contents(&preset)?;
+
+        match self.stored_name(&preset.name).await? {
+            Some(stored) if !overwrite => return Err(StorageError::AlreadyExists(stored)),
+            Some(stored) if stored != preset.name => {
+                fs::remove_file(self.get_path(&stored))
+                    .await
+                    .map_err(StorageError::Io)?;
+            }
+            _ => {}
+        }
+        self.write(&self.get_path(&preset.name), &preset).await?;
+
+        Ok(preset.name)
     }
 
     /// Delete a preset.
@@ -158,12 +292,322 @@ impl PresetStorage {
         Ok(())
     }
 
-    /// Check if a preset exists.
-    pub fn exists(&self, name: &str) -> bool {
-        self.validate_name(name).is_ok() && self.get_path(name).exists()
+    /// Rename a preset.
+    ///
+    /// The embedded name is updated and `updated_at` refreshed. Fails if
+    /// `new_name`, ignoring case, is already taken by another preset; a
+    /// rename that only changes case is allowed.
+    pub async fn rename(&self, old_name: &str, new_name: &str) -> Result<(), StorageError> {
+        let mut preset = self.get_existing(old_name).await?;
+        self.validate_name(new_name)?;
+
+        if old_name == new_name {
+            return Ok(());
+        }
+
+        if let Some(stored) = self.stored_name(new_name).await? {
+            if stored != old_name {
+                return Err(StorageError::AlreadyExists(stored));
+            }
+        }
+
+        preset.name = new_name.to_string();
+        preset.updated_at = chrono::Utc::now().to_rfc3339();
+        let new_path = self.get_path(new_name);
+        if old_name.eq_ignore_ascii_case(new_name) {
+            // On a case-insensitive filesystem both names are the same file
+            fs::rename(self.get_path(old_name), &new_path)
+                .await
+                .map_err(StorageError::Io)?;
+            return self.write(&new_path, &preset).await;
+        }
+
+        self.write(&new_path, &preset).await?;
+        fs::remove_file(self.get_path(old_name))
+            .await
+            .map_err(StorageError::Io)
+    }
+
+    /// Copy a preset under a new name.
+    ///
+    /// The copy gets fresh created/updated timestamps and no apply history.
+    /// Fails if `target`, ignoring case, is already taken.
+    pub async fn duplicate(&self, source: &str, target: &str) -> Result<(), StorageError> {
+        let mut preset = self.get_existing(source).await?;
+        self.validate_name(target)?;
+
+        if let Some(stored) = self.stored_name(target).await? {
+            return Err(StorageError::AlreadyExists(stored));
+        }
+        let target_path = self.get_path(target);
+
+        let now = chrono::Utc::now().to_rfc3339();
+        preset.name = target.to_string();
+        preset.created_at = now.clone();
+        preset.updated_at = now;
+        preset.last_applied_at = None;
+        preset.applied_to.clear();
+        self.write(&target_path, &preset).await
+    }
+
+    /// Convert a preset to another type, returning the converted preset.
+    ///
+    /// Only Full to Locations is supported: the anchor layout is extracted
+    /// from the config. The result replaces the preset unless `new_name` is
+    /// given, in which case it is saved alongside under that name.
+    pub async fn convert(
+        &self,
+        name: &str,
+        target_type: PresetType,
+        new_name: Option<&str>,
+    ) -> Result<Preset, StorageError> {
+        let preset = self.get_existing(name).await?;
+
+        let config = match (&preset.preset_type, target_type) {
+            (PresetType::Full, PresetType::Locations) => preset.config.as_ref(),
+            (PresetType::Locations, PresetType::Full) => {
+                return Err(StorageError::InvalidPreset(format!(
+                    "Preset '{}' holds only anchor locations and cannot become a full preset",
+                    name
+                )));
+            }
+            (current, _) => {
+                return Err(StorageError::InvalidPreset(format!(
+                    "Preset '{}' is already a {} preset",
+                    name, current
+                )));
+            }
+        };
+        let locations = config.map(LocationData::from_config).ok_or_else(|| {
+            StorageError::InvalidPreset(format!("Preset '{}' has no config data", name))
+        })?;
+        if locations.anchors.is_empty() {
+            return Err(StorageError::InvalidPreset(format!(
+                "Preset '{}' has no anchors to extract",
+                name
+            )));
+        }
+
+        let target = new_name.unwrap_or(name);
+        self.validate_name(target)?;
+        if target != name && self.exists(target).await {
+            return Err(StorageError::AlreadyExists(target.to_string()));
+        }
+
+        let now = chrono::Utc::now().to_rfc3339();
+        let converted = Preset {
+            name: target.to_string(),
+            description: preset.description,
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(locations),
+            tags: preset.tags,
+            overrides: preset.overrides,
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: if target == name {
+                preset.created_at
+            } else {
+                now.clone()
+            },
+            updated_at: now,
+        };
+        self.save(&converted).await?;
+
+        Ok(converted)
+    }
+
+    /// Record successful uploads of a preset.
+    ///
+    /// Each device keeps only its latest record, matched by MAC address if
+    /// known and IP otherwise, and only the 32 most recent devices are
+    /// kept. `updated_at` is left alone since the preset
+    /// itself has not changed.
+    pub async fn record_applied(
+        &self,
+        name: &str,
+        records: &[AppliedRecord],
+    ) -> Result<(), StorageError> {
+        let Some(latest) = records.iter().map(|r| r.at.as_str()).max() else {
+            return Ok(());
+        };
+        let mut preset = self.get_existing(name).await?;
+
+        preset.last_applied_at = Some(latest.to_string());
+        for record in records {
+            preset
+                .applied_to
+                .retain(|existing| !same_device(existing, record));
+            preset.applied_to.push(record.clone());
+        }
+        let excess = preset.applied_to.len().saturating_sub(MAX_APPLIED_RECORDS);
+        preset.applied_to.drain(..excess);
+
+        self.write(&self.get_path(name), &preset).await
+    }
+
+    /// Combine the device settings of a full preset with the anchor layout
+    /// of a locations preset and save the result as `output_name`.
+    ///
+    /// See [`merge_presets`].
+    pub async fn merge(
+        &self,
+        full_name: &str,
+        locations_name: &str,
+        output_name: &str,
+    ) -> Result<Preset, StorageError> {
+        self.validate_name(output_name)?;
+        let full = self.get_existing(full_name).await?;
+        let locations = self.get_existing(locations_name).await?;
+        if self.exists(output_name).await {
+            return Err(StorageError::AlreadyExists(output_name.to_string()));
+        }
+
+        let merged = merge_presets(&full, &locations, output_name)?;
+        self.save(&merged).await?;
+
+        Ok(merged)
+    }
+
+    async fn get_existing(&self, name: &str) -> Result<Preset, StorageError> {
+        self.get(name)
+            .await?
+            .ok_or_else(|| StorageError::PresetNotFound(name.to_string()))
+    }
+
+    async fn write(&self, path: &Path, preset: &Preset) -> Result<(), StorageError> {
+        let content = serde_json::to_string_pretty(preset).map_err(StorageError::Serialization)?;
+        fs::write(path, content).await.map_err(StorageError::Io)
+    }
+
+    /// Name of the stored preset matching `name` ignoring case, if any.
+    async fn stored_name(&self, name: &str) -> Result<Option<String>, StorageError> {
+        find_name_ignoring_case(&self.preset_dir, name).await
+    }
+
+    /// Check if a preset exists under `name`, ignoring case.
+    pub async fn exists(&self, name: &str) -> bool {
+        self.validate_name(name).is_ok() && matches!(self.stored_name(name).await, Ok(Some(_)))
+    }
+}
+
+fn same_device(a: &AppliedRecord, b: &AppliedRecord) -> bool {
+    match (&a.mac, &b.mac) {
+        (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+        _ => a.ip == b.ip,
     }
 }
 
+/// Check that a preset carries the data its type requires.
+///
+/// Anchor layouts failing [`validate_locations`] with errors are rejected.
+pub(super) fn validate_contents(preset: &Preset) -> Result<(), StorageError> {
+    match (&preset.preset_type, &preset.locations) {
+        (PresetType::Full, _) if preset.config.is_none() => Err(StorageError::InvalidPreset(
+            "Full preset must include config data".to_string(),
+        )),
+        (PresetType::Locations, None) => Err(StorageError::InvalidPreset(
+            "Locations preset must include location data".to_string(),
+        )),
+        (PresetType::Locations, Some(locations)) => {
+            let errors: Vec<String> = validate_locations(locations)
+                .into_iter()
+                .filter(|issue| issue.is_error())
+                .map(|issue| issue.to_string())
+                .collect();
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                Err(StorageError::InvalidPreset(errors.join("; ")))
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Build a full preset named `output_name` from `full` with the anchors,
+/// origin and rotation of `locations`.
+///
+/// Fails if the presets are not of those types or the combined config has
+/// validation errors.
+pub fn merge_presets(
+    full: &Preset,
+    locations: &Preset,
+    output_name: &str,
+) -> Result<Preset, StorageError> {
+    let mut config = match (&full.preset_type, &full.config) {
+        (PresetType::Full, Some(config)) => config.clone(),
+        _ => {
+            return Err(StorageError::InvalidPreset(format!(
+                "Preset '{}' is not a full preset",
+                full.name
+            )))
+        }
+    };
+    let layout = match (&locations.preset_type, &locations.locations) {
+        (PresetType::Locations, Some(layout)) => layout,
+        _ => {
+            return Err(StorageError::InvalidPreset(format!(
+                "Preset '{}' is not a locations preset",
+                locations.name
+            )))
+        }
+    };
+    layout.apply_to(&mut config);
+
+    let errors: Vec<String> = validate_config(&config)
+        .into_iter()
+        .filter(|issue| issue.is_error())
+        .map(|issue| issue.to_string())
+        .collect();
+    if !errors.is_empty() {
+        return Err(StorageError::InvalidPreset(errors.join("; ")));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    Ok(Preset {
+        name: output_name.to_string(),
+        description: Some(format!(
+            "Settings from '{}' with locations from '{}'",
+            full.name, locations.name
+        )),
+        preset_type: PresetType::Full,
+        config: Some(config),
+        locations: None,
+        tags: normalize_tags(full.tags.iter().chain(&locations.tags)),
+        overrides: full.overrides.clone(),
+        last_applied_at: None,
+        applied_to: Vec::new(),
+        created_at: now.clone(),
+        updated_at: now,
+    })
+}
+
+/// Parse a shared preset file, explaining what is wrong if it is not one.
+fn parse_preset_file(content: &str) -> Result<Preset, StorageError> {
+    let value: serde_json::Value = serde_json::from_str(content)
+        .map_err(|e| StorageError::InvalidPreset(format!("Not valid JSON: {}", e)))?;
+    let Some(fields) = value.as_object() else {
+        return Err(StorageError::InvalidPreset(
+            "Expected a JSON object".to_string(),
+        ));
+    };
+
+    if !fields.contains_key("type") {
+        let message = if ["format", "meta", "wifi", "uwb"]
+            .iter()
+            .any(|key| fields.contains_key(*key))
+        {
+            "File is a configuration, not a preset; import it as a config instead"
+        } else {
+            "Missing preset type"
+        };
+        return Err(StorageError::InvalidPreset(message.to_string()));
+    }
+
+    serde_json::from_value(value).map_err(|e| StorageError::InvalidPreset(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,6 +640,7 @@ mod tests {
                     log_udp_port: None,
                     log_serial_enabled: None,
                     log_udp_enabled: None,
+                    extra: Default::default(),
                 },
                 uwb: UwbConfig {
                     mode: 4,
@@ -239,13 +684,19 @@ mod tests {
                     anchor_pos_locked: None,
                     distance_avg_samples: None,
                     use_2d_estimator: None,
+                    extra: Default::default(),
                 },
                 app: AppConfig {
                     led2_pin: None,
                     led2_state: None,
+                    extra: Default::default(),
                 },
             }),
             locations: None,
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         }
@@ -264,14 +715,32 @@ mod tests {
                     alt: 100.0,
                 },
                 rotation: 0.0,
-                anchors: vec![AnchorConfig {
-                    id: "0".to_string(),
-                    x: 0.0,
-                    y: 0.0,
-                    z: 1.5,
-                }],
+                anchors: vec![
+                    AnchorConfig {
+                        id: "0".to_string(),
+                        x: 0.0,
+                        y: 0.0,
+                        z: 1.5,
+                    },
+                    AnchorConfig {
+                        id: "1".to_string(),
+                        x: 5.0,
+                        y: 0.0,
+                        z: 1.5,
+                    },
+                    AnchorConfig {
+                        id: "2".to_string(),
+                        x: 5.0,
+                        y: 4.0,
+                        z: 2.5,
+                    },
+                ],
                 use_2d_estimator: Some(1),
             }),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         }
@@ -298,12 +767,175 @@ mod tests {
         storage.save(&make_full_preset("alpha")).await.unwrap();
         storage.save(&make_location_preset("beta")).await.unwrap();
 
-        let presets = storage.list().await.unwrap();
+        let presets = storage.list(None, None).await.unwrap().presets;
         assert_eq!(presets.len(), 2);
         assert_eq!(presets[0].name, "alpha");
         assert_eq!(presets[1].name, "beta");
     }
 
+    #[tokio::test]
+    async fn test_list_filters_by_type_and_tag() {
+        let (storage, _tmp) = create_test_storage();
+        let mut hall = make_location_preset("hall");
+        hall.tags = vec![" siteA ".to_string(), "siteA".to_string(), "".to_string()];
+        storage.save(&hall).await.unwrap();
+        let mut full = make_full_preset("hall-full");
+        full.tags = vec!["siteA".to_string()];
+        storage.save(&full).await.unwrap();
+        storage.save(&make_location_preset("yard")).await.unwrap();
+
+        let saved = storage.get("hall").await.unwrap().unwrap();
+        assert_eq!(saved.tags, vec!["siteA"]);
+
+        let names = |list: PresetList| -> Vec<String> {
+            list.presets.into_iter().map(|p| p.name).collect()
+        };
+        assert_eq!(
+            names(
+                storage
+                    .list(Some(PresetType::Locations), None)
+                    .await
+                    .unwrap()
+            ),
+            ["hall", "yard"]
+        );
+        assert_eq!(
+            names(storage.list(None, Some("siteA")).await.unwrap()),
+            ["hall", "hall-full"]
+        );
+        assert_eq!(
+            names(
+                storage
+                    .list(Some(PresetType::Locations), Some("siteA"))
+                    .await
+                    .unwrap()
+            ),
+            ["hall"]
+        );
+        assert!(storage
+            .list(None, Some("siteB"))
+            .await
+            .unwrap()
+            .presets
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_reports_and_moves_corrupt_files() {
+        let (storage, tmp) = create_test_storage();
+        storage.save(&make_full_preset("good")).await.unwrap();
+        std::fs::write(tmp.path().join("broken.json"), "{\"name\": ").unwrap();
+        std::fs::write(tmp.path().join("notes.txt"), "ignored").unwrap();
+
+        let list = storage.list(None, None).await.unwrap();
+        assert_eq!(list.presets.len(), 1);
+        assert_eq!(list.problems.len(), 1);
+        assert_eq!(list.problems[0].file, "broken.json");
+        assert!(!list.problems[0].error.is_empty());
+
+        // Problems are reported even when the filter excludes everything
+        let filtered = storage.list(None, Some("siteB")).await.unwrap();
+        assert!(filtered.presets.is_empty());
+        assert_eq!(filtered.problems.len(), 1);
+
+        let moved = storage.move_corrupt().await.unwrap();
+        assert_eq!(moved.len(), 1);
+        assert!(tmp.path().join("corrupt").join("broken.json").exists());
+        assert!(storage.list(None, None).await.unwrap().problems.is_empty());
+
+        std::fs::write(tmp.path().join("broken.json"), "[]").unwrap();
+        storage.move_corrupt().await.unwrap();
+        assert!(tmp.path().join("corrupt").join("broken.json.1").exists());
+        assert!(storage.move_corrupt().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_names_differing_only_by_case_collide() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_full_preset("sitea")).await.unwrap();
+        storage.save(&make_full_preset("other")).await.unwrap();
+
+        assert!(storage.exists("SITEA").await);
+        assert!(matches!(
+            storage.save(&make_full_preset("SiteA")).await,
+            Err(StorageError::AlreadyExists(name)) if name == "sitea"
+        ));
+        assert!(matches!(
+            storage.duplicate("other", "OTHER").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.rename("other", "SiteA").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+
+        // Changing only the case of a preset's own name is fine
+        storage.rename("other", "Other").await.unwrap();
+        assert!(storage.get("Other").await.unwrap().is_some());
+
+        storage.replace(&make_full_preset("SiteA")).await.unwrap();
+        let list = storage.list(None, None).await.unwrap();
+        let names: Vec<&str> = list.presets.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Other", "SiteA"]);
+    }
+
+    #[tokio::test]
+    async fn test_record_applied_keeps_latest_per_device() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_full_preset("site")).await.unwrap();
+        let record = |mac: Option<&str>, ip: &str, at: &str| AppliedRecord {
+            mac: mac.map(str::to_string),
+            ip: ip.to_string(),
+            at: at.to_string(),
+        };
+
+        storage
+            .record_applied(
+                "site",
+                &[
+                    record(Some("AA:BB"), "10.0.0.5", "2024-03-01T10:00:00Z"),
+                    record(None, "10.0.0.6", "2024-03-01T10:00:01Z"),
+                ],
+            )
+            .await
+            .unwrap();
+        // Same MAC at a new address replaces the earlier record
+        storage
+            .record_applied(
+                "site",
+                &[record(Some("aa:bb"), "10.0.0.9", "2024-03-02T09:00:00Z")],
+            )
+            .await
+            .unwrap();
+
+        let preset = storage.get("site").await.unwrap().unwrap();
+        assert_eq!(preset.updated_at, "2024-01-01T00:00:00Z");
+        assert_eq!(
+            preset.last_applied_at.as_deref(),
+            Some("2024-03-02T09:00:00Z")
+        );
+        let ips: Vec<&str> = preset.applied_to.iter().map(|r| r.ip.as_str()).collect();
+        assert_eq!(ips, ["10.0.0.6", "10.0.0.9"]);
+
+        // Saving new settings keeps the history
+        storage.save(&make_full_preset("site")).await.unwrap();
+        let preset = storage.get("site").await.unwrap().unwrap();
+        assert_eq!(preset.applied_to.len(), 2);
+        let listed = storage.list(None, None).await.unwrap().presets;
+        assert_eq!(
+            listed[0].last_applied_at.as_deref(),
+            Some("2024-03-02T09:00:00Z")
+        );
+
+        let many: Vec<AppliedRecord> = (0..40)
+            .map(|i| record(None, &format!("10.1.0.{}", i), "2024-04-01T00:00:00Z"))
+            .collect();
+        storage.record_applied("site", &many).await.unwrap();
+        let preset = storage.get("site").await.unwrap().unwrap();
+        assert_eq!(preset.applied_to.len(), MAX_APPLIED_RECORDS);
+        assert_eq!(preset.applied_to.last().unwrap().ip, "10.1.0.39");
+    }
+
     #[tokio::test]
     async fn test_delete() {
         let (storage, _tmp) = create_test_storage();
@@ -315,6 +947,258 @@ mod tests {
         assert!(storage.get("to-delete").await.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn test_convert_full_to_locations() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_full_preset("no-anchors")).await.unwrap();
+        assert!(matches!(
+            storage
+                .convert("no-anchors", PresetType::Locations, None)
+                .await,
+            Err(StorageError::InvalidPreset(msg)) if msg.contains("no anchors")
+        ));
+
+        let layout = make_location_preset("unused").locations.unwrap();
+        let mut full = make_full_preset("backup");
+        let config = full.config.as_mut().unwrap();
+        config.uwb.anchors = Some(layout.anchors.clone());
+        config.uwb.origin_lat = Some(layout.origin.lat);
+        config.uwb.use_2d_estimator = Some(1);
+        storage.save(&full).await.unwrap();
+
+        let copy = storage
+            .convert("backup", PresetType::Locations, Some("backup-layout"))
+            .await
+            .unwrap();
+        assert_eq!(copy.name, "backup-layout");
+        assert_eq!(
+            storage.get("backup").await.unwrap().unwrap().preset_type,
+            PresetType::Full
+        );
+        let saved = storage.get("backup-layout").await.unwrap().unwrap();
+        let locations = saved.locations.unwrap();
+        assert_eq!(locations.anchors.len(), 3);
+        assert_eq!(locations.origin.lat, layout.origin.lat);
+        assert_eq!(locations.use_2d_estimator, Some(1));
+
+        let converted = storage
+            .convert("backup", PresetType::Locations, None)
+            .await
+            .unwrap();
+        assert!(converted.config.is_none());
+        let saved = storage.get("backup").await.unwrap().unwrap();
+        assert_eq!(saved.preset_type, PresetType::Locations);
+        assert_eq!(saved.created_at, "2024-01-01T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_convert_rejects_unsupported_targets() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_location_preset("hall")).await.unwrap();
+        storage.save(&make_full_preset("other")).await.unwrap();
+
+        assert!(matches!(
+            storage.convert("hall", PresetType::Full, None).await,
+            Err(StorageError::InvalidPreset(msg)) if msg.contains("cannot become a full preset")
+        ));
+        assert!(matches!(
+            storage.convert("hall", PresetType::Locations, None).await,
+            Err(StorageError::InvalidPreset(msg)) if msg.contains("already")
+        ));
+        assert!(matches!(
+            storage
+                .convert("missing", PresetType::Locations, None)
+                .await,
+            Err(StorageError::PresetNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_merge() {
+        let (storage, _tmp) = create_test_storage();
+        let mut full = make_full_preset("release-1");
+        full.tags = vec!["fw".to_string()];
+        storage.save(&full).await.unwrap();
+        let mut site = make_location_preset("hall-a");
+        site.tags = vec!["site:hallA".to_string(), "fw".to_string()];
+        storage.save(&site).await.unwrap();
+
+        let merged = storage
+            .merge("release-1", "hall-a", "hall-a-release-1")
+            .await
+            .unwrap();
+        assert_eq!(merged.preset_type, PresetType::Full);
+        assert_eq!(merged.tags, vec!["fw", "site:hallA"]);
+
+        let saved = storage.get("hall-a-release-1").await.unwrap().unwrap();
+        let config = saved.config.unwrap();
+        assert_eq!(config.wifi.ssid_s_t.as_deref(), Some("Test"));
+        assert_eq!(config.uwb.anchors.as_ref().map(Vec::len), Some(3));
+        assert_eq!(config.uwb.anchor_count, Some(3));
+        assert_eq!(config.uwb.origin_lat, Some(41.4036));
+        assert_eq!(config.uwb.rotation_degrees, Some(0.0));
+        assert_eq!(config.uwb.use_2d_estimator, Some(1));
+
+        assert!(matches!(
+            storage
+                .merge("release-1", "hall-a", "hall-a-release-1")
+                .await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.merge("hall-a", "release-1", "swapped").await,
+            Err(StorageError::InvalidPreset(_))
+        ));
+        assert!(matches!(
+            storage.merge("release-1", "missing", "other").await,
+            Err(StorageError::PresetNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_save_rejects_degenerate_layout() {
+        let (storage, _tmp) = create_test_storage();
+        let mut preset = make_location_preset("line");
+        let locations = preset.locations.as_mut().unwrap();
+        locations.anchors[2].y = 0.0;
+
+        match storage.save(&preset).await {
+            Err(StorageError::InvalidPreset(msg)) => assert!(msg.contains("one line"), "{msg}"),
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert!(!storage.exists("line").await);
+    }
+
+    #[tokio::test]
+    async fn test_overwrite_keeps_created_at() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_full_preset("site")).await.unwrap();
+
+        let mut replacement = make_location_preset("site");
+        replacement.created_at = "2025-06-01T00:00:00Z".to_string();
+        replacement.updated_at = "2025-06-01T00:00:00Z".to_string();
+        storage.save(&replacement).await.unwrap();
+
+        let saved = storage.get("site").await.unwrap().unwrap();
+        assert_eq!(saved.preset_type, PresetType::Locations);
+        assert_eq!(saved.created_at, "2024-01-01T00:00:00Z");
+        assert_ne!(saved.updated_at, "2025-06-01T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import() {
+        let (storage, tmp) = create_test_storage();
+        storage.save(&make_location_preset("hall")).await.unwrap();
+        let file = tmp.path().join("shared.json");
+
+        storage.export("hall", &file).await.unwrap();
+        storage.delete("hall").await.unwrap();
+
+        assert_eq!(storage.import(&file, None, false).await.unwrap(), "hall");
+        let imported = storage.get("hall").await.unwrap().unwrap();
+        assert_eq!(imported.preset_type, PresetType::Locations);
+        assert_eq!(imported.created_at, "2024-01-01T00:00:00Z");
+
+        assert!(matches!(
+            storage.import(&file, None, false).await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        storage.import(&file, None, true).await.unwrap();
+
+        let name = storage.import(&file, Some("hall-2"), false).await.unwrap();
+        assert_eq!(name, "hall-2");
+        assert_eq!(storage.get("hall-2").await.unwrap().unwrap().name, "hall-2");
+
+        assert!(matches!(
+            storage.import(&file, Some("bad name"), false).await,
+            Err(StorageError::InvalidPresetName(_))
+        ));
+        assert!(matches!(
+            storage.export("missing", &file).await,
+            Err(StorageError::PresetNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_schema_mismatches() {
+        let (storage, tmp) = create_test_storage();
+        let file = tmp.path().join("shared.json");
+
+        let cases = [
+            (r#"{"wifi": {}, "uwb": {}, "app": {}}"#, "configuration"),
+            (r#"{"name": "x"}"#, "Missing preset type"),
+            (r#"[1, 2]"#, "JSON object"),
+            (
+                r#"{"name": "x", "type": "locations", "createdAt": "", "updatedAt": ""}"#,
+                "location data",
+            ),
+        ];
+        for (content, expected) in cases {
+            std::fs::write(&file, content).unwrap();
+            match storage.import(&file, None, false).await {
+                Err(StorageError::InvalidPreset(msg)) => {
+                    assert!(msg.contains(expected), "{msg}")
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rename() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_full_preset("siteA-tmp")).await.unwrap();
+        storage.save(&make_location_preset("siteB")).await.unwrap();
+
+        storage.rename("siteA-tmp", "siteA-final").await.unwrap();
+
+        assert!(!storage.exists("siteA-tmp").await);
+        let renamed = storage.get("siteA-final").await.unwrap().unwrap();
+        assert_eq!(renamed.name, "siteA-final");
+        assert_eq!(renamed.created_at, "2024-01-01T00:00:00Z");
+        assert_ne!(renamed.updated_at, "2024-01-01T00:00:00Z");
+
+        assert!(matches!(
+            storage.rename("siteA-final", "siteB").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.rename("missing", "other").await,
+            Err(StorageError::PresetNotFound(_))
+        ));
+        assert!(matches!(
+            storage.rename("siteB", "bad name").await,
+            Err(StorageError::InvalidPresetName(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate() {
+        let (storage, _tmp) = create_test_storage();
+        storage.save(&make_location_preset("hall")).await.unwrap();
+
+        storage.duplicate("hall", "hall-copy").await.unwrap();
+
+        let original = storage.get("hall").await.unwrap().unwrap();
+        let copy = storage.get("hall-copy").await.unwrap().unwrap();
+        assert_eq!(copy.name, "hall-copy");
+        assert_eq!(copy.preset_type, PresetType::Locations);
+        assert_ne!(copy.created_at, original.created_at);
+        assert_eq!(
+            copy.locations.unwrap().anchors.len(),
+            original.locations.unwrap().anchors.len()
+        );
+
+        assert!(matches!(
+            storage.duplicate("hall", "hall-copy").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+        assert!(matches!(
+            storage.duplicate("hall", "hall").await,
+            Err(StorageError::AlreadyExists(_))
+        ));
+    }
+
     #[test]
     fn test_validate_name() {
         let (storage, _tmp) = create_test_storage();
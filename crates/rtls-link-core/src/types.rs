@@ -4,9 +4,12 @@
 //! and the CLI tool. They mirror the TypeScript definitions in `shared/types.ts`
 //! and are serialized/deserialized using serde.
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::error::ValidationError;
 use crate::health::DeviceHealth;
 
 // ==================== Device Types ====================
@@ -120,6 +123,24 @@ impl DeviceRole {
         }
     }
 
+    /// Role of a firmware `uwb.mode` value; modes without a role are `Unknown`
+    pub fn from_mode(mode: u8) -> Self {
+        match mode {
+            3 => DeviceRole::AnchorTdoa,
+            4 => DeviceRole::TagTdoa,
+            _ => DeviceRole::Unknown,
+        }
+    }
+
+    /// Firmware `uwb.mode` value of this role, if it has one
+    pub fn to_mode(&self) -> Option<u8> {
+        match self {
+            DeviceRole::AnchorTdoa => Some(3),
+            DeviceRole::TagTdoa => Some(4),
+            DeviceRole::Unknown => None,
+        }
+    }
+
     /// Check if role is an anchor type
     pub fn is_anchor(&self) -> bool {
         matches!(self, DeviceRole::AnchorTdoa)
@@ -146,6 +167,141 @@ impl std::fmt::Display for DeviceRole {
     }
 }
 
+/// Firmware details from the `firmware-info` command.
+///
+/// Firmware builds report different subsets of these, so every field is
+/// optional and a field of an unexpected type reads as missing. Fields not
+/// listed here are kept in `extra`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareInfo {
+    /// Device name, e.g. `RTLS-Link`
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub device: Option<String>,
+    /// Firmware version
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub version: Option<String>,
+    /// Board the firmware was built for
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub board: Option<String>,
+    /// Build date, e.g. `Mar  4 2026`
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub build_date: Option<String>,
+    /// Build time, e.g. `14:02:11`
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub build_time: Option<String>,
+    /// OTA partition the firmware is running from
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub partition: Option<String>,
+    /// Free heap in bytes
+    #[serde(
+        default,
+        deserialize_with = "lenient_u64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub free_heap: Option<u64>,
+    /// Size of the firmware image in bytes
+    #[serde(
+        default,
+        deserialize_with = "lenient_u64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub sketch_size: Option<u64>,
+    /// Fields not known to this version
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Commands a device understands
+///
+/// The MAVLink dialect has no command reporting them, so connections
+/// derive the set from the dialect itself and take the firmware version
+/// from `firmware-info`; `derived` marks such a set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    /// Management protocol version, if the device reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<u32>,
+    /// Firmware version
+    #[serde(
+        default,
+        deserialize_with = "lenient_string",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub firmware_version: Option<String>,
+    /// Supported command names, e.g. `write-batch`
+    #[serde(default)]
+    pub commands: Vec<String>,
+    /// Whether the set was derived rather than reported by the device
+    #[serde(default)]
+    pub derived: bool,
+}
+
+impl DeviceCapabilities {
+    /// Whether the device understands `command`; only its first word counts
+    pub fn supports(&self, command: &str) -> bool {
+        let name = command.split_whitespace().next().unwrap_or_default();
+        self.commands.iter().any(|known| known == name)
+    }
+
+    /// Whether the device can save, load, read and delete named configs
+    pub fn named_configs(&self) -> bool {
+        [
+            "save-config-as",
+            "load-config-named",
+            "read-config-named",
+            "delete-config",
+        ]
+        .iter()
+        .all(|command| self.supports(command))
+    }
+}
+
+/// A string, or a number written as one; anything else is `None`
+fn lenient_string<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(text) => Some(text),
+        serde_json::Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    })
+}
+
+/// A non-negative integer, or a string holding one; anything else is `None`
+fn lenient_u64<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::Number(number) => number.as_u64(),
+        serde_json::Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    })
+}
+
 // ==================== Configuration Types ====================
 
 /// Complete device configuration.
@@ -160,25 +316,48 @@ pub struct DeviceConfig {
 }
 
 /// WiFi network configuration.
+///
+/// The SSID, password and GCS keys are spelled out as the firmware names
+/// them; their aliases load files that spelled them otherwise.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct WifiConfig {
     /// WiFi mode: 0 = AP, 1 = Station
     pub mode: u8,
     /// Access Point SSID
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "ssidAP",
+        alias = "ssidAp",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub ssid_a_p: Option<String>,
     /// Access Point password
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "pswdAP",
+        alias = "pswdAp",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub pswd_a_p: Option<String>,
     /// Station mode SSID
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "ssidST",
+        alias = "ssidSt",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub ssid_s_t: Option<String>,
     /// Station mode password
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "pswdST",
+        alias = "pswdSt",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub pswd_s_t: Option<String>,
     /// Ground Control Station IP
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        rename = "gcsIp",
+        alias = "gcsIP",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub gcs_ip: Option<String>,
     /// UDP port for MAVLink
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -198,6 +377,9 @@ pub struct WifiConfig {
     /// Whether UDP log streaming is enabled at runtime
     #[serde(skip_serializing_if = "Option::is_none")]
     pub log_udp_enabled: Option<u8>,
+    /// Parameters not known to this version, kept so they survive a round trip
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// UWB and positioning configuration.
@@ -329,6 +511,9 @@ pub struct UwbConfig {
         skip_serializing_if = "Option::is_none"
     )]
     pub use_2d_estimator: Option<u8>,
+    /// Parameters not known to this version, kept so they survive a round trip
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 /// Single anchor configuration.
@@ -354,6 +539,9 @@ pub struct AppConfig {
     /// LED 2 state (0 or 1)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub led2_state: Option<u8>,
+    /// Parameters not known to this version, kept so they survive a round trip
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
 }
 
 // ==================== Local Config Storage Types ====================
@@ -368,6 +556,49 @@ pub struct LocalConfigInfo {
     pub created_at: String,
     /// Last update timestamp (ISO 8601)
     pub updated_at: String,
+    /// Free-form description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Labels such as `site:hallA`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Key settings of the stored config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<ConfigSummary>,
+}
+
+/// Key settings of a stored configuration, shown in listings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSummary {
+    /// UWB mode: 3=TDOA_ANCHOR, 4=TDOA_TAG
+    pub uwb_mode: u8,
+    /// WiFi mode: 0 = AP, 1 = Station
+    pub wifi_mode: u8,
+    /// Number of anchors listed (or `anchorCount` if none are)
+    pub anchor_count: usize,
+    /// UWB channel, if set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u8>,
+    /// Whether a GPS origin is set
+    pub has_origin: bool,
+}
+
+impl ConfigSummary {
+    /// Summarize a device configuration
+    pub fn of(config: &DeviceConfig) -> Self {
+        let uwb = &config.uwb;
+        Self {
+            uwb_mode: uwb.mode,
+            wifi_mode: config.wifi.mode,
+            anchor_count: match &uwb.anchors {
+                Some(anchors) => anchors.len(),
+                None => uwb.anchor_count.unwrap_or(0) as usize,
+            },
+            channel: uwb.channel,
+            has_origin: uwb.origin_lat.is_some() && uwb.origin_lon.is_some(),
+        }
+    }
 }
 
 /// Full local configuration including device config.
@@ -380,8 +611,49 @@ pub struct LocalConfig {
     pub created_at: String,
     /// Last update timestamp (ISO 8601)
     pub updated_at: String,
+    /// Free-form description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Labels such as `site:hallA`
+    #[serde(default)]
+    pub tags: Vec<String>,
     /// Device configuration data
     pub config: DeviceConfig,
+    /// Whether the stored file was upgraded from a legacy shape when read
+    #[serde(default)]
+    pub migrated: bool,
+}
+
+/// A previous version of a locally stored configuration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigRevision {
+    /// Revision identifier, the time it was replaced (e.g. `20240102T030405123Z`)
+    pub timestamp: String,
+    /// When this version was saved (ISO 8601)
+    pub updated_at: String,
+}
+
+/// A parameter of a stored configuration matching a search query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSearchMatch {
+    /// Parameter group (`wifi`, `uwb`, `app`)
+    pub group: String,
+    /// Parameter name
+    pub name: String,
+    /// Parameter value
+    pub value: String,
+}
+
+/// A stored configuration with the parameters matching a search query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSearchResult {
+    /// Configuration name
+    pub name: String,
+    /// Matching parameters, in parameter order
+    pub matches: Vec<ConfigSearchMatch>,
 }
 
 // ==================== Preset Types ====================
@@ -416,6 +688,52 @@ pub struct GpsOrigin {
     pub alt: f64,
 }
 
+/// Origin altitudes in meters that any site on land falls within
+const ORIGIN_ALT_RANGE_M: std::ops::RangeInclusive<f64> = -1_000.0..=10_000.0;
+
+impl GpsOrigin {
+    /// Build an origin, failing if it does not pass [`GpsOrigin::validate`]
+    pub fn new(lat: f64, lon: f64, alt: f64) -> Result<Self, ValidationError> {
+        let origin = Self { lat, lon, alt };
+        origin.validate()?;
+        Ok(origin)
+    }
+
+    /// Check that latitude and longitude are in range and the altitude is
+    /// within -1 km to 10 km
+    ///
+    /// A latitude that is out of range while the longitude would be a valid
+    /// latitude is reported as the two being swapped, the usual mistake.
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let check = |field, value: f64, range: std::ops::RangeInclusive<f64>| {
+            if range.contains(&value) {
+                Ok(())
+            } else {
+                Err(ValidationError::OutOfRange {
+                    field,
+                    value,
+                    min: *range.start(),
+                    max: *range.end(),
+                })
+            }
+        };
+
+        if self.lat.is_finite()
+            && self.lat.abs() > 90.0
+            && self.lat.abs() <= 180.0
+            && self.lon.abs() <= 90.0
+        {
+            return Err(ValidationError::LatLonSwapped {
+                lat: self.lat,
+                lon: self.lon,
+            });
+        }
+        check("origin.lat", self.lat, -90.0..=90.0)?;
+        check("origin.lon", self.lon, -180.0..=180.0)?;
+        check("origin.alt", self.alt, ORIGIN_ALT_RANGE_M)
+    }
+}
+
 /// Location data for a preset.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -436,6 +754,42 @@ pub struct LocationData {
     pub use_2d_estimator: Option<u8>,
 }
 
+impl LocationData {
+    /// Extract the anchor layout from a device configuration
+    ///
+    /// Unset origin and rotation values default to zero.
+    pub fn from_config(config: &DeviceConfig) -> Self {
+        let uwb = &config.uwb;
+        Self {
+            origin: GpsOrigin {
+                lat: uwb.origin_lat.unwrap_or(0.0),
+                lon: uwb.origin_lon.unwrap_or(0.0),
+                alt: uwb.origin_alt.unwrap_or(0.0),
+            },
+            rotation: uwb.rotation_degrees.unwrap_or(0.0),
+            anchors: uwb.anchors.clone().unwrap_or_default(),
+            use_2d_estimator: uwb.use_2d_estimator,
+        }
+    }
+
+    /// Replace the anchor layout, origin and rotation of a device
+    /// configuration with this one
+    ///
+    /// The estimator mode is only changed if this layout sets one.
+    pub fn apply_to(&self, config: &mut DeviceConfig) {
+        let uwb = &mut config.uwb;
+        uwb.anchors = Some(self.anchors.clone());
+        uwb.anchor_count = u8::try_from(self.anchors.len()).ok();
+        uwb.origin_lat = Some(self.origin.lat);
+        uwb.origin_lon = Some(self.origin.lon);
+        uwb.origin_alt = Some(self.origin.alt);
+        uwb.rotation_degrees = Some(self.rotation);
+        if self.use_2d_estimator.is_some() {
+            uwb.use_2d_estimator = self.use_2d_estimator;
+        }
+    }
+}
+
 /// Unified preset that can be either full config or locations only.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -454,12 +808,51 @@ pub struct Preset {
     /// Location data (for type = Locations)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub locations: Option<LocationData>,
+    /// Labels such as `site:hallA`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Per-device parameter changes, keyed by MAC address or UWB short
+    /// address
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub overrides: HashMap<String, Vec<ParamOverride>>,
+    /// When the preset was last uploaded to a device (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_applied_at: Option<String>,
+    /// Devices the preset was most recently uploaded to, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub applied_to: Vec<AppliedRecord>,
     /// Creation timestamp (ISO 8601)
     pub created_at: String,
     /// Last update timestamp (ISO 8601)
     pub updated_at: String,
 }
 
+/// A successful upload of a preset to one device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedRecord {
+    /// Device MAC address, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mac: Option<String>,
+    /// Device IP address at the time of the upload
+    pub ip: String,
+    /// Upload timestamp (ISO 8601)
+    pub at: String,
+}
+
+/// Change to one parameter when a preset is uploaded to a particular device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParamOverride {
+    /// Parameter group, e.g. `uwb`
+    pub group: String,
+    /// Parameter name, e.g. `mavlinkTargetSystemId`
+    pub name: String,
+    /// Value to write instead; `None` leaves the parameter untouched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
 /// Metadata for a preset (without the full config data).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -472,12 +865,39 @@ pub struct PresetInfo {
     /// Optional description
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Labels such as `site:hallA`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// When the preset was last uploaded to a device (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_applied_at: Option<String>,
     /// Creation timestamp (ISO 8601)
     pub created_at: String,
     /// Last update timestamp (ISO 8601)
     pub updated_at: String,
 }
 
+/// A file in the preset directory that could not be read as a preset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetProblem {
+    /// File name within the preset directory
+    pub file: String,
+    /// Why the file was skipped
+    pub error: String,
+}
+
+/// Saved presets along with any files that had to be skipped.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetList {
+    /// Presets matching the filter, sorted by name
+    pub presets: Vec<PresetInfo>,
+    /// Unreadable or corrupt preset files
+    #[serde(default)]
+    pub problems: Vec<PresetProblem>,
+}
+
 // ==================== Log Types ====================
 
 /// Log level for filtering
@@ -534,13 +954,117 @@ impl std::fmt::Display for LogLevel {
     }
 }
 
+/// Level of a received log message
+///
+/// Wraps [`LogLevel`] but keeps level strings it does not recognize instead
+/// of discarding them. Serialized as a lowercase string; deserialized from
+/// either a string or the numeric firmware level. Orders from most to least
+/// severe, with `none` after `verbose` and unknown levels after all known
+/// ones, so threshold filtering is `level <= MessageLevel::from(threshold)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageLevel {
+    Known(LogLevel),
+    Unknown(String),
+}
+
+impl Ord for MessageLevel {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self, other) {
+            (MessageLevel::Unknown(a), MessageLevel::Unknown(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for MessageLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl MessageLevel {
+    /// Position in severity order; `LogLevel` itself ranks `None` first
+    fn rank(&self) -> u8 {
+        match self {
+            MessageLevel::Known(LogLevel::None) => LogLevel::Verbose as u8 + 1,
+            MessageLevel::Known(level) => *level as u8,
+            MessageLevel::Unknown(_) => u8::MAX,
+        }
+    }
+
+    /// Parse a level string, accepting any casing and common aliases
+    pub fn parse(s: &str) -> Self {
+        match LogLevel::from_str(s.trim()) {
+            Some(level) => MessageLevel::Known(level),
+            None => MessageLevel::Unknown(s.trim().to_lowercase()),
+        }
+    }
+
+    /// The recognized level, if any
+    pub fn level(&self) -> Option<LogLevel> {
+        match self {
+            MessageLevel::Known(level) => Some(*level),
+            MessageLevel::Unknown(_) => None,
+        }
+    }
+
+    /// Lowercase string form (e.g. "warn")
+    pub fn as_str(&self) -> &str {
+        match self {
+            MessageLevel::Known(level) => match level {
+                LogLevel::Verbose => "verbose",
+                LogLevel::Debug => "debug",
+                LogLevel::Info => "info",
+                LogLevel::Warn => "warn",
+                LogLevel::Error => "error",
+                LogLevel::None => "none",
+            },
+            MessageLevel::Unknown(raw) => raw,
+        }
+    }
+}
+
+impl From<LogLevel> for MessageLevel {
+    fn from(level: LogLevel) -> Self {
+        MessageLevel::Known(level)
+    }
+}
+
+impl std::fmt::Display for MessageLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Serialize for MessageLevel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for MessageLevel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum RawLevel {
+            Numeric(u8),
+            Text(String),
+        }
+
+        Ok(match RawLevel::deserialize(deserializer)? {
+            RawLevel::Numeric(value) => MessageLevel::Known(LogLevel::from_u8(value)),
+            RawLevel::Text(text) => MessageLevel::parse(&text),
+        })
+    }
+}
+
 /// A single log message from a device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LogMessage {
     /// Source device IP
     pub ip: String,
-    /// Log level
-    pub level: LogLevel,
+    /// Log level (unrecognized levels are kept as-is)
+    pub level: MessageLevel,
     /// Log tag/component
     pub tag: String,
     /// Log message content
@@ -548,6 +1072,9 @@ pub struct LogMessage {
     /// Timestamp (if provided by device)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<u64>,
+    /// Per-device sequence number (if provided by device)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u32>,
 }
 
 #[cfg(test)]
@@ -614,6 +1141,86 @@ mod tests {
         assert!(!DeviceRole::AnchorTdoa.is_tag());
     }
 
+    #[test]
+    fn test_gps_origin_validation() {
+        assert!(GpsOrigin::new(41.4036, 2.1744, 100.0).is_ok());
+        assert!(GpsOrigin::new(-90.0, 180.0, -1000.0).is_ok());
+
+        let err = |lat, lon, alt| GpsOrigin::new(lat, lon, alt).unwrap_err();
+        assert_eq!(err(41.4, 2.17, 10_001.0).field(), "origin.alt");
+        assert_eq!(err(41.4, 180.5, 0.0).field(), "origin.lon");
+        assert_eq!(err(f64::NAN, 2.17, 0.0).field(), "origin.lat");
+        assert_eq!(
+            err(412.0, 2.17, 0.0).to_string(),
+            "origin.lat 412 must be between -90 and 90"
+        );
+        assert_eq!(
+            err(141.4, 2.17, 0.0),
+            ValidationError::LatLonSwapped {
+                lat: 141.4,
+                lon: 2.17
+            }
+        );
+    }
+
+    #[test]
+    fn test_wifi_config_keys() {
+        let wifi: WifiConfig = serde_json::from_value(serde_json::json!({
+            "mode": 1,
+            "ssidAP": "ap",
+            "pswdAP": "ap-pass",
+            "ssidST": "net",
+            "pswdST": "net-pass",
+            "gcsIp": "192.168.1.10"
+        }))
+        .unwrap();
+
+        let json = serde_json::to_value(&wifi).unwrap();
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            ["gcsIp", "mode", "pswdAP", "pswdST", "ssidAP", "ssidST"]
+        );
+
+        let legacy: WifiConfig = serde_json::from_value(serde_json::json!({
+            "mode": 1,
+            "ssidAp": "ap",
+            "pswdAp": "ap-pass",
+            "ssidSt": "net",
+            "pswdSt": "net-pass",
+            "gcsIP": "192.168.1.10"
+        }))
+        .unwrap();
+        assert_eq!(serde_json::to_value(&legacy).unwrap(), json);
+        assert!(legacy.extra.is_empty());
+    }
+
+    #[test]
+    fn test_device_role_modes() {
+        for mode in 0..=u8::MAX {
+            let role = DeviceRole::from_mode(mode);
+            match mode {
+                3 => assert_eq!(role, DeviceRole::AnchorTdoa),
+                4 => assert_eq!(role, DeviceRole::TagTdoa),
+                _ => assert_eq!(role, DeviceRole::Unknown, "mode {mode}"),
+            }
+            if let Some(back) = role.to_mode() {
+                assert_eq!(back, mode);
+            }
+        }
+
+        for role in [DeviceRole::AnchorTdoa, DeviceRole::TagTdoa] {
+            assert_eq!(DeviceRole::from_mode(role.to_mode().unwrap()), role);
+        }
+        assert_eq!(DeviceRole::Unknown.to_mode(), None);
+    }
+
     #[test]
     fn test_device_role_display() {
         assert_eq!(format!("{}", DeviceRole::AnchorTdoa), "Anchor (TDoA)");
@@ -636,6 +1243,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: UwbConfig {
                 mode: 4,
@@ -698,10 +1306,12 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: AppConfig {
                 led2_pin: Some(2),
                 led2_state: Some(0),
+                extra: Default::default(),
             },
         };
 
@@ -753,10 +1363,15 @@ mod tests {
             name: "test-config".to_string(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-02T00:00:00Z".to_string(),
+            description: None,
+            tags: vec!["site:hallA".to_string()],
+            summary: None,
         };
 
         let json = serde_json::to_string(&info).unwrap();
         assert!(json.contains("\"createdAt\":"));
+        assert!(json.contains("\"tags\":[\"site:hallA\"]"));
+        assert!(!json.contains("description"));
         assert!(json.contains("\"updatedAt\":"));
     }
 
@@ -778,4 +1393,53 @@ mod tests {
         assert_eq!(LogLevel::Info.as_str(), "INFO");
         assert_eq!(format!("{}", LogLevel::Error), "ERROR");
     }
+
+    #[test]
+    fn test_message_level_parse_and_serialize() {
+        assert_eq!(
+            MessageLevel::parse("WARNING"),
+            MessageLevel::Known(LogLevel::Warn)
+        );
+        assert_eq!(
+            MessageLevel::parse("Warn"),
+            MessageLevel::Known(LogLevel::Warn)
+        );
+        assert_eq!(
+            MessageLevel::parse("TRACE"),
+            MessageLevel::Unknown("trace".to_string())
+        );
+
+        assert_eq!(
+            serde_json::to_string(&MessageLevel::Known(LogLevel::Error)).unwrap(),
+            "\"error\""
+        );
+        assert_eq!(
+            serde_json::from_str::<MessageLevel>("\"INFO\"").unwrap(),
+            MessageLevel::Known(LogLevel::Info)
+        );
+        assert_eq!(
+            serde_json::from_str::<MessageLevel>("4").unwrap(),
+            MessageLevel::Known(LogLevel::Debug)
+        );
+    }
+
+    #[test]
+    fn test_message_level_ordering() {
+        let threshold = MessageLevel::from(LogLevel::Info);
+
+        assert!(MessageLevel::from(LogLevel::Error) <= threshold);
+        assert!(MessageLevel::from(LogLevel::Info) <= threshold);
+        assert!(MessageLevel::from(LogLevel::Debug) > threshold);
+        assert!(MessageLevel::parse("trace") > MessageLevel::from(LogLevel::Verbose));
+    }
+
+    #[test]
+    fn test_message_level_none_is_least_severe_known_level() {
+        let none = MessageLevel::from(LogLevel::None);
+
+        assert!(none > MessageLevel::from(LogLevel::Verbose));
+        assert!(none > MessageLevel::from(LogLevel::Info));
+        assert!(none < MessageLevel::parse("trace"));
+        assert!(none > MessageLevel::from(LogLevel::Error));
+    }
 }
@@ -11,6 +11,12 @@ export const Commands = {
     return `write -group ${group} -name ${name} -data "${safeValue}"`;
   },
 
+  // One result per parameter, in order
+  writeBatch: (params: [group: string, name: string, value: string | number][]) =>
+    `write-batch ${JSON.stringify(
+      params.map(([group, name, value]) => ({ group, name, value: String(value) })),
+    )}`,
+
   // Config commands
   backupConfig: () => 'backup-config',
   saveConfig: () => 'save-config',
@@ -26,6 +32,15 @@ export const Commands = {
   getLedState: () => 'get-led2-state',
   reboot: () => 'reboot',
   start: () => 'write -group uwb -name uwbEnable -data "1"',
+  stop: () => 'write -group uwb -name uwbEnable -data "0"',
+
+  // Logging: parameter writes that revert on reboot unless saved
+  setLogUdp: (enabled: boolean, port?: number): string[] => [
+    ...(port !== undefined ? [Commands.writeParam('wifi', 'logUdpPort', port)] : []),
+    Commands.writeParam('wifi', 'logUdpEnabled', enabled ? 1 : 0),
+  ],
+  setLogSerial: (enabled: boolean): string =>
+    Commands.writeParam('wifi', 'logSerialEnabled', enabled ? 1 : 0),
 
   // System info
   getVersion: () => 'version',
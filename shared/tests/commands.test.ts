@@ -17,6 +17,11 @@ describe('Commands', () => {
       .toBe('write -group uwb -name mode -data "4"');
   });
 
+  it('builds writeBatch command with JSON-escaped values', () => {
+    expect(Commands.writeBatch([['wifi', 'ssidST', 'My "Lab"'], ['uwb', 'mode', 4]]))
+      .toBe('write-batch [{"group":"wifi","name":"ssidST","value":"My \\"Lab\\""},{"group":"uwb","name":"mode","value":"4"}]');
+  });
+
   it('builds anchor stats command', () => {
     expect(Commands.tdoaAnchorStats()).toBe('tdoa-anchor-stats');
   });
@@ -24,6 +29,21 @@ describe('Commands', () => {
   it('builds estimator status command', () => {
     expect(Commands.tdoaEstimatorStatus()).toBe('tdoa-estimator-status');
   });
+
+  it('builds stop command', () => {
+    expect(Commands.stop()).toBe('write -group uwb -name uwbEnable -data "0"');
+  });
+
+  it('builds logging commands as parameter writes', () => {
+    expect(Commands.setLogUdp(false))
+      .toEqual(['write -group wifi -name logUdpEnabled -data "0"']);
+    expect(Commands.setLogUdp(true, 3334)).toEqual([
+      'write -group wifi -name logUdpPort -data "3334"',
+      'write -group wifi -name logUdpEnabled -data "1"',
+    ]);
+    expect(Commands.setLogSerial(true))
+      .toBe('write -group wifi -name logSerialEnabled -data "1"');
+  });
 });
 
 describe('isStructuredResponseCommand', () => {
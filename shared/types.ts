@@ -92,6 +92,7 @@ export interface WifiConfig {
   logUdpPort?: number;      // UDP port for log streaming (default: 3334)
   logSerialEnabled?: 0 | 1; // Runtime: Serial logging enabled
   logUdpEnabled?: 0 | 1;    // Runtime: UDP log streaming enabled
+  [param: string]: unknown; // Parameters from newer firmware, passed through unchanged
 }
 
 export interface UwbConfig {
@@ -142,6 +143,7 @@ export interface UwbConfig {
   distanceAvgSamples?: number;      // Number of samples to average (default: 50)
   // Position estimator configuration
   use2DEstimator?: 0 | 1;           // 0=3D Newton-Raphson, 1=2D (XY with fixed Z, default)
+  [param: string]: unknown;         // Parameters from newer firmware, passed through unchanged
 }
 
 export interface AnchorConfig {
@@ -154,6 +156,7 @@ export interface AnchorConfig {
 export interface AppConfig {
   led2Pin?: number;
   led2State?: 0 | 1;
+  [param: string]: unknown; // Parameters from newer firmware, passed through unchanged
 }
 
 export interface CommandResult {
@@ -167,10 +170,86 @@ export interface LocalConfigInfo {
   name: string;
   createdAt: string;  // ISO date string
   updatedAt: string;  // ISO date string
+  description?: string;
+  tags: string[];     // e.g. "site:hallA"
+  summary?: ConfigSummary;
+}
+
+export interface ConfigSummary {
+  uwbMode: number;    // 3=TDOA_ANCHOR, 4=TDOA_TAG
+  wifiMode: number;   // 0=AP, 1=Station
+  anchorCount: number;
+  channel?: number;
+  hasOrigin: boolean;
 }
 
 export interface LocalConfig extends LocalConfigInfo {
   config: DeviceConfig;
+  migrated?: boolean;     // upgraded from a legacy file shape when read
+}
+
+export type ConfigDiffKind = 'added' | 'removed' | 'changed';
+
+export interface ConfigDiffEntry {
+  group: string;
+  name: string;           // firmware parameter name, anchors as devId1/x1/...
+  left?: string | null;
+  right?: string | null;
+  kind: ConfigDiffKind;
+}
+
+export interface ConfigSearchMatch {
+  group: string;
+  name: string;
+  value: string;
+}
+
+export interface ConfigSearchResult {
+  name: string;
+  matches: ConfigSearchMatch[];
+}
+
+export interface ValidationIssue {
+  severity: 'error' | 'warning';
+  field: string;      // e.g. "uwb.channel" or "uwb.anchors[2].id"
+  message: string;
+}
+
+export type ParamKind =
+  | { type: 'bool' }
+  | { type: 'int'; min: number; max: number }
+  | { type: 'float'; min: number | null; max: number | null }
+  | { type: 'enum'; options: { value: number; label: string }[] }
+  | { type: 'text'; maxLen: number }
+  | { type: 'ip' };
+
+export interface ParamSpec {
+  group: string;      // "wifi", "uwb" or "app"
+  name: string;       // firmware name, without the slot number if slotted
+  kind: ParamKind;
+  slots?: number;     // per-anchor params: `x` covers x1..x{slots}
+  requiresReboot: boolean;
+  description: string;
+}
+
+export interface DataArchiveManifest {
+  format: string;     // "rtls-link-data"
+  version: number;
+  appVersion: string;
+  exportedAt: string; // ISO date string
+  configs: string[];
+  presets: string[];
+}
+
+export interface DataImportReport {
+  configs: string[];
+  presets: string[];
+  skipped: string[];  // e.g. "configs/hallA"
+}
+
+export interface ConfigRevision {
+  timestamp: string;  // revision id, e.g. "20240102T030405123Z"
+  updatedAt: string;  // ISO date string
 }
 
 // Unified Preset types
@@ -193,18 +272,62 @@ export interface Preset {
   type: PresetType;
   config?: DeviceConfig;      // For type='full'
   locations?: LocationData;   // For type='locations'
+  tags?: string[];            // Labels such as 'site:hallA'
+  overrides?: Record<string, ParamOverride[]>;  // keyed by MAC or UWB short address
+  lastAppliedAt?: string;
+  appliedTo?: AppliedRecord[];  // most recent upload per device, oldest first
   createdAt: string;
   updatedAt: string;
 }
 
+// A successful upload of a preset to one device
+export interface AppliedRecord {
+  mac?: string;
+  ip: string;
+  at: string;
+}
+
+// Per-device change applied when a preset is uploaded
+export interface ParamOverride {
+  group: string;
+  name: string;
+  value?: string;  // omitted to skip the parameter
+}
+
+// Room templates for generated location presets (dimensions in meters)
+export type LayoutTemplate =
+  | { kind: 'square'; width: number; length: number; height: number }
+  | { kind: 'rectangle'; width: number; length: number; height: number }
+  | {
+      kind: 'lShape';
+      width: number;
+      length: number;
+      notchWidth: number;
+      notchLength: number;
+      height: number;
+    };
+
 export interface PresetInfo {
   name: string;
   type: PresetType;
   description?: string;
+  tags: string[];
+  lastAppliedAt?: string;
   createdAt: string;
   updatedAt: string;
 }
 
+// A preset file that could not be read or parsed
+export interface PresetProblem {
+  file: string;
+  error: string;
+}
+
+export interface PresetList {
+  presets: PresetInfo[];
+  problems: PresetProblem[];
+}
+
 // Bulk operation result
 export interface BulkOperationResult {
   ip: string;
@@ -213,14 +336,105 @@ export interface BulkOperationResult {
   error?: string;
 }
 
+// Log level as serialized by the backend (lowercase)
+export type LogLevelName = 'error' | 'warn' | 'info' | 'debug' | 'verbose' | 'none' | (string & {});
+
 // Log message from device (received via UDP)
 export interface LogMessage {
   deviceIp: string;       // Source device IP
   ts: number;             // Device timestamp (ms)
-  lvl: string;            // Log level (ERROR, WARN, INFO, DEBUG, VERBOSE)
+  lvl: LogLevelName;      // Log level, lowercase (unrecognized levels pass through)
   tag: string;            // Module/file tag
   msg: string;            // Log message content
   receivedAt: number;     // Local receive timestamp (ms)
+  seq?: number;           // Per-device sequence number (gap detection)
+  wallTs?: number;        // Device timestamp aligned to wall-clock time (ms since epoch)
+  repeatCount: number;    // Identical consecutive messages collapsed into this entry
+}
+
+// Stream and buffer summary per device (get_active_log_streams)
+export interface LogStreamInfo {
+  deviceIp: string;
+  active: boolean;               // Logs are forwarded to the frontend
+  bufferedCount: number;         // Messages currently buffered
+  lastMessageAt: number | null;  // Local receive timestamp of latest message (ms)
+  droppedCount: number;          // Messages evicted from a full buffer
+  paused: boolean;               // Log emissions are globally paused
+  tagFilters: string[];          // Tag globs applied to emitted logs (empty = all)
+  autoStarted: boolean;          // Stream was started automatically on discovery
+}
+
+// Automatic log streams for newly discovered devices (set_auto_stream_logs)
+export interface AutoStreamStatus {
+  enabled: boolean;
+  roles: DeviceRole[] | null;    // Roles that are auto-streamed (null = all)
+  autoStarted: string[];         // Device IPs whose streams were auto-started
+}
+
+// Buffered log search (search_buffered_logs)
+export interface LogSearchOptions {
+  deviceIp?: string;        // Limit to one device (default: all buffers)
+  regex?: boolean;          // Treat query as a regex instead of a substring
+  minLevel?: LogLevelName;  // Only this level or more severe
+  tags?: string[];          // Tag globs
+  maxResults?: number;      // Capped at 1000
+}
+
+export interface LogSearchMatch {
+  deviceIp: string;
+  index: number;            // Position in the device buffer (0 = oldest)
+  log: LogMessage;
+}
+
+export interface LogSearchResult {
+  matches: LogSearchMatch[];
+  truncated: boolean;
+}
+
+// Payload of the device-log-activity event (devices without an open terminal)
+export interface LogActivity {
+  deviceIp: string;
+  level: LogLevelName;      // Most severe level since the last notification
+  count: number;            // Messages buffered since the last notification
+}
+
+// Log forwarding to an external collector (set_log_forwarding)
+export type LogForwardFormat = 'ndjson' | 'syslog';
+
+export interface LogForwardingStatus {
+  enabled: boolean;
+  target: string | null;
+  format: LogForwardFormat | null;
+  forwarded: number;        // Messages sent to the collector
+  dropped: number;          // Dropped because the queue was full
+  sendErrors: number;
+}
+
+// Message counts per log level
+export interface LevelCounts {
+  error: number;
+  warn: number;
+  info: number;
+  debug: number;
+  verbose: number;
+  other: number;
+}
+
+// Per-device log statistics (get_log_stats)
+export interface DeviceLogStats {
+  deviceIp: string;
+  lostMessages: number;     // Detected through sequence number gaps
+  totals: LevelCounts;      // Over the whole buffer
+  window: LevelCounts;      // Over the last windowSecs seconds
+  windowSecs: number;
+  messagesPerSec: number;
+  errorRate: number;        // Fraction of windowed messages at ERROR (0-1)
+}
+
+export interface LogStats {
+  parseFailures: number;   // Packets or batch entries discarded as unparseable
+  rawMessages: number;     // Plain-text lines stored with level 'raw'
+  devices: DeviceLogStats[];
 }
 
 // Log level helpers
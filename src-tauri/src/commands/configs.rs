@@ -2,20 +2,28 @@
 
 use crate::config_storage::ConfigStorageService;
 use crate::error::AppError;
-use crate::types::{DeviceConfig, LocalConfig, LocalConfigInfo};
+use crate::types::{
+    ConfigRevision, ConfigSearchResult, DeviceConfig, LocalConfig, LocalConfigInfo,
+};
+use rtls_link_core::config_diff::ConfigDiffEntry;
+use rtls_link_core::config_validation::{validate_config, ValidationIssue};
 use rtls_link_core::device::mavlink::send_command_parsed;
 use rtls_link_core::protocol::commands::Commands;
 use rtls_link_core::protocol::config_params::device_config_from_backup_value;
+use rtls_link_core::protocol::param_registry::{param_registry, ParamSpec};
+use rtls_link_core::storage::{read_config_file, ConfigLabels};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::State;
 
-/// List all saved configurations.
+/// List saved configurations, optionally only those carrying `tag`.
 #[tauri::command]
 pub async fn list_configs(
+    tag: Option<String>,
     config_service: State<'_, Arc<ConfigStorageService>>,
 ) -> Result<Vec<LocalConfigInfo>, AppError> {
-    config_service.list().await
+    config_service.list(tag.as_deref()).await
 }
 
 /// Get a specific configuration by name.
@@ -27,14 +35,30 @@ pub async fn get_config(
     config_service.read(&name).await
 }
 
-/// Save a configuration.
+/// Save a configuration, returning any validation warnings.
+///
+/// Configs with validation errors are rejected, as is an existing name
+/// unless `overwrite` is set. Passing a description or tags replaces both;
+/// omitting them keeps the existing ones.
 #[tauri::command]
 pub async fn save_config(
     name: String,
     config: DeviceConfig,
+    description: Option<String>,
+    tags: Option<Vec<String>>,
+    overwrite: Option<bool>,
     config_service: State<'_, Arc<ConfigStorageService>>,
-) -> Result<bool, AppError> {
-    config_service.save(&name, config).await
+) -> Result<Vec<ValidationIssue>, AppError> {
+    let warnings = validate_config(&config)
+        .into_iter()
+        .filter(|issue| !issue.is_error())
+        .collect();
+    let labels = (description.is_some() || tags.is_some())
+        .then(|| ConfigLabels::new(description.as_deref(), tags.unwrap_or_default()));
+    config_service
+        .save(&name, config, labels, overwrite.unwrap_or(false))
+        .await?;
+    Ok(warnings)
 }
 
 /// Delete a configuration.
@@ -46,14 +70,123 @@ pub async fn delete_config(
     config_service.delete(&name).await
 }
 
-/// Backup current config from a device and save it locally.
+/// Rename a configuration.
 #[tauri::command]
-pub async fn backup_device_config_to_local(
+pub async fn rename_config(
+    old_name: String,
+    new_name: String,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<bool, AppError> {
+    config_service.rename(&old_name, &new_name).await
+}
+
+/// Copy a configuration under a new name.
+#[tauri::command]
+pub async fn duplicate_config(
+    source: String,
+    target: String,
+    overwrite: Option<bool>,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<bool, AppError> {
+    config_service
+        .duplicate(&source, &target, overwrite.unwrap_or(false))
+        .await
+}
+
+/// Export a configuration to a portable file.
+#[tauri::command]
+pub async fn export_config(
+    name: String,
+    path: PathBuf,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<bool, AppError> {
+    config_service.export(&name, &path).await
+}
+
+/// Import a configuration file, returning the name it was saved as.
+#[tauri::command]
+pub async fn import_config(
+    path: PathBuf,
+    name: Option<String>,
+    overwrite: Option<bool>,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<String, AppError> {
+    config_service
+        .import(&path, name.as_deref(), overwrite.unwrap_or(false))
+        .await
+}
+
+/// Compare two saved configurations parameter by parameter.
+#[tauri::command]
+pub async fn diff_configs(
+    name_a: String,
+    name_b: String,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<Vec<ConfigDiffEntry>, AppError> {
+    config_service.diff(&name_a, &name_b).await
+}
+
+/// Check a configuration file before it is imported or applied.
+///
+/// Returns every issue found; the file is not stored.
+#[tauri::command]
+pub async fn validate_config_file(path: String) -> Result<Vec<ValidationIssue>, AppError> {
+    let config = read_config_file(Path::new(&path)).await?;
+    Ok(validate_config(&config))
+}
+
+/// Get the known device parameters with their types and accepted values.
+#[tauri::command]
+pub async fn get_param_registry() -> Result<Vec<ParamSpec>, AppError> {
+    Ok(param_registry().to_vec())
+}
+
+/// Search saved configurations by parameter name and value.
+///
+/// `query` is a case-insensitive substring, or a regular expression when
+/// `regex` is set.
+#[tauri::command]
+pub async fn search_configs(
+    query: String,
+    regex: Option<bool>,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<Vec<ConfigSearchResult>, AppError> {
+    config_service.search(&query, regex.unwrap_or(false)).await
+}
+
+/// List previous versions of a configuration, newest first.
+#[tauri::command]
+pub async fn list_config_revisions(
+    name: String,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<Vec<ConfigRevision>, AppError> {
+    config_service.list_revisions(&name).await
+}
+
+/// Restore a previous version of a configuration.
+///
+/// The version being replaced is kept as a revision.
+#[tauri::command]
+pub async fn restore_config_revision(
+    name: String,
+    timestamp: String,
+    config_service: State<'_, Arc<ConfigStorageService>>,
+) -> Result<bool, AppError> {
+    config_service.restore_revision(&name, &timestamp).await
+}
+
+/// Back up the current config of a device straight into local storage.
+///
+/// The backup is fetched, parsed and validated in the backend; an existing
+/// config with the same name is only replaced if `overwrite` is set.
+#[tauri::command]
+pub async fn backup_device_to_config(
     ip: String,
     name: String,
+    overwrite: Option<bool>,
     timeout_ms: Option<u64>,
     config_service: State<'_, Arc<ConfigStorageService>>,
-) -> Result<bool, AppError> {
+) -> Result<LocalConfigInfo, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
     let response = send_command_parsed(&ip, Commands::backup_config(), timeout)
         .await
@@ -62,7 +195,10 @@ pub async fn backup_device_config_to_local(
         .json
         .ok_or_else(|| AppError::Json("No JSON found in backup-config response".to_string()))?;
     let config = device_config_from_backup_value(json).map_err(AppError::from)?;
-    config_service.save(&name, config).await
+    config_service
+        .save(&name, config, None, overwrite.unwrap_or(false))
+        .await?;
+    config_service.info(&name).await
 }
 
 #[cfg(test)]
@@ -0,0 +1,37 @@
+//! Whole-data backup Tauri commands.
+//!
+//! Archives cover the `configs/` and `presets/` directories of the app data dir.
+
+use crate::config_storage::APP_VERSION;
+use crate::error::AppError;
+use rtls_link_core::storage::{self, DataArchiveManifest, DataImportReport};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+fn data_dir(app_handle: &AppHandle) -> Result<PathBuf, AppError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::Io(format!("Failed to get app data dir: {}", e)))
+}
+
+/// Write all local configs and presets to a single archive.
+#[tauri::command]
+pub async fn export_all(
+    path: PathBuf,
+    app_handle: AppHandle,
+) -> Result<DataArchiveManifest, AppError> {
+    Ok(storage::export_all(&data_dir(&app_handle)?, &path, APP_VERSION).await?)
+}
+
+/// Restore configs and presets from an archive.
+///
+/// Existing entries are kept unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_all(
+    path: PathBuf,
+    overwrite: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<DataImportReport, AppError> {
+    Ok(storage::import_all(&data_dir(&app_handle)?, &path, overwrite.unwrap_or(false)).await?)
+}
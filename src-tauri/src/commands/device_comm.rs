@@ -13,17 +13,28 @@ use std::sync::{
 use std::time::Duration;
 
 use crate::error::AppError;
+use crate::preset_storage::PresetStorageService;
+use crate::settings::SettingsService;
 use crate::state::AppState;
-use crate::types::{DeviceConfig, Preset, PresetType};
+use crate::types::{
+    AppliedRecord, DeviceCapabilities, DeviceConfig, FirmwareInfo, ParamOverride, Preset,
+};
 use rtls_link_core::calibration::{calibrate_anchors, AnchorCalibrationConfig, CalibrationRun};
 use rtls_link_core::device::mavlink::{
-    send_command_parsed, send_commands_parsed, DeviceCommandResponse, DeviceConnection,
+    BatchCommand, BatchOutcome, BatchResult, BatchSender, CommandResult, DeviceCommandResponse,
+    DeviceConnection, RetryPolicy,
 };
 use rtls_link_core::device::ota::{
-    upload_firmware_bulk_with_cancel, upload_firmware_with_progress_and_cancel, OtaProgressHandler,
+    self, upload_firmware_bulk_with_cancel, upload_firmware_with_progress_and_cancel, OtaAuth,
+    OtaEndpoint, OtaProgressHandler,
+};
+use rtls_link_core::device::probe::{partition_reachable, DEFAULT_PROBE_TIMEOUT, DEVICE_HTTP_PORT};
+use rtls_link_core::error::{CommandError, CoreError};
+use rtls_link_core::protocol::commands::{check_command, Commands};
+use rtls_link_core::protocol::config_params::{
+    config_to_params, overrides_for, preview_preset, ParamOptions, ParamPlan, PresetUploadPlan,
 };
-use rtls_link_core::protocol::commands::Commands;
-use rtls_link_core::protocol::config_params::{config_to_params, location_to_params};
+use rtls_link_core::protocol::response::ParamMismatch;
 use tauri::{AppHandle, Emitter, State};
 use tokio::sync::RwLock;
 
@@ -32,25 +43,28 @@ struct TauriOtaProgress {
     app_handle: AppHandle,
 }
 
-struct OtaCancellationGuard {
-    ip: String,
+type CancellationFlags = Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>;
+
+/// Removes a cancellation flag from its map once the work it covers ends
+struct CancellationGuard {
+    key: String,
     cancel: Arc<AtomicBool>,
-    active_cancellations: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    active_cancellations: CancellationFlags,
 }
 
-impl Drop for OtaCancellationGuard {
+impl Drop for CancellationGuard {
     fn drop(&mut self) {
-        let ip = self.ip.clone();
+        let key = self.key.clone();
         let cancel = self.cancel.clone();
         let active_cancellations = self.active_cancellations.clone();
         tauri::async_runtime::spawn(async move {
             let mut active_cancellations = active_cancellations.write().await;
             let should_remove = active_cancellations
-                .get(&ip)
+                .get(&key)
                 .map(|current| Arc::ptr_eq(current, &cancel))
                 .unwrap_or(false);
             if should_remove {
-                active_cancellations.remove(&ip);
+                active_cancellations.remove(&key);
             }
         });
     }
@@ -62,6 +76,65 @@ pub struct DeviceOperationResult {
     pub ip: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Parameters that read back differently, for verified uploads
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<ParamMismatch>,
+    /// The operation was cancelled before this device's part completed
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub cancelled: bool,
+    /// Times the link to the device dropped and was reopened
+    #[serde(skip_serializing_if = "is_zero")]
+    pub reconnects: usize,
+    /// The device did not answer the reachability probe and was left alone
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+}
+
+impl DeviceOperationResult {
+    fn cancelled(ip: String, message: String) -> Self {
+        Self {
+            cancelled: true,
+            ..Self::failed(ip, message)
+        }
+    }
+
+    fn failed(ip: String, message: String) -> Self {
+        Self {
+            ip,
+            success: false,
+            error: Some(message),
+            mismatches: Vec::new(),
+            cancelled: false,
+            reconnects: 0,
+            skipped: false,
+        }
+    }
+
+    fn skipped(ip: String) -> Self {
+        let message = unreachable_message(&ip);
+        Self {
+            skipped: true,
+            ..Self::failed(ip, message)
+        }
+    }
+}
+
+fn unreachable_message(ip: &str) -> String {
+    format!("Skipped: {} is unreachable", ip)
+}
+
+/// The devices among `ips` that do not answer a reachability probe, or
+/// none when `skip_unreachable` is off
+async fn unreachable_devices(ips: &[String], skip_unreachable: bool) -> Vec<String> {
+    if !skip_unreachable {
+        return Vec::new();
+    }
+    let (_, unreachable) = partition_reachable(ips, DEVICE_HTTP_PORT, DEFAULT_PROBE_TIMEOUT).await;
+    unreachable
+}
+
+fn is_zero(count: &usize) -> bool {
+    *count == 0
 }
 
 fn emit_operation_progress(
@@ -86,12 +159,35 @@ fn emit_operation_progress(
     );
 }
 
+/// Work for one device in a bulk operation
+#[derive(Clone)]
+enum DeviceBatch {
+    /// Send these commands
+    Commands(Vec<String>),
+    /// Upload a plan, applying the overrides matching the device and
+    /// reading every parameter back if the flag is set
+    Preset(Arc<PresetUploadPlan>, Arc<PresetOverrides>, bool),
+}
+
+type PresetOverrides = HashMap<String, Vec<ParamOverride>>;
+
+/// Run each device's batch, `concurrency` devices at a time
+///
+/// Once `cancel` is set, devices not yet started are reported as cancelled,
+/// and devices already running stop before their next command and report
+/// how far they got. With `skip_unreachable`, devices that do not answer a reachability probe
+/// are reported as skipped without being contacted. `pacing` spaces out the
+/// commands sent to each device; devices still run concurrently.
+#[allow(clippy::too_many_arguments)]
 async fn run_device_batches(
     ips: Vec<String>,
-    command_batches: Vec<Vec<String>>,
+    command_batches: Vec<DeviceBatch>,
     timeout: Duration,
+    pacing: Option<Duration>,
     concurrency: usize,
+    skip_unreachable: bool,
     operation_id: String,
+    cancel: Arc<AtomicBool>,
     app_handle: AppHandle,
 ) -> Vec<DeviceOperationResult> {
     let total = ips.len();
@@ -99,15 +195,56 @@ async fn run_device_batches(
     let mut results = Vec::with_capacity(total);
     let concurrency = concurrency.max(1);
 
-    let work: Vec<(String, Vec<String>)> = ips.into_iter().zip(command_batches).collect();
+    let unreachable = unreachable_devices(&ips, skip_unreachable).await;
+    let mut work: Vec<(String, DeviceBatch)> = ips.into_iter().zip(command_batches).collect();
+    work.retain(|(ip, _)| !unreachable.contains(ip));
+    for ip in unreachable {
+        completed += 1;
+        emit_operation_progress(
+            &app_handle,
+            &operation_id,
+            completed,
+            total,
+            Some(&ip),
+            Some(false),
+            Some(&unreachable_message(&ip)),
+        );
+        results.push(DeviceOperationResult::skipped(ip));
+    }
 
     for chunk in work.chunks(concurrency) {
+        if cancel.load(Ordering::Relaxed) {
+            for (ip, _) in chunk {
+                completed += 1;
+                let message = "Cancelled before starting".to_string();
+                emit_operation_progress(
+                    &app_handle,
+                    &operation_id,
+                    completed,
+                    total,
+                    Some(ip),
+                    Some(false),
+                    Some(&message),
+                );
+                results.push(DeviceOperationResult::cancelled(ip.clone(), message));
+            }
+            continue;
+        }
+
         let mut join_set = tokio::task::JoinSet::new();
         let mut task_ips = HashMap::new();
-        for (ip, commands) in chunk.iter().cloned() {
+        for (ip, batch) in chunk.iter().cloned() {
             let ip_for_error = ip.clone();
+            let cancel = cancel.clone();
             let handle = join_set.spawn(async move {
-                let result = send_commands_parsed(&ip, &commands, timeout).await;
+                let result = match batch {
+                    DeviceBatch::Commands(commands) => {
+                        send_commands(&ip, &commands, timeout, pacing, &cancel).await
+                    }
+                    DeviceBatch::Preset(plan, overrides, verify) => {
+                        send_preset(&ip, &plan, &overrides, verify, timeout, pacing, &cancel).await
+                    }
+                };
                 (ip, result)
             });
             task_ips.insert(handle.id(), ip_for_error);
@@ -134,52 +271,179 @@ async fn run_device_batches(
                         Some(false),
                         Some(&message),
                     );
-                    results.push(DeviceOperationResult {
-                        ip,
-                        success: false,
-                        error: Some(message),
-                    });
+                    results.push(DeviceOperationResult::failed(ip, message));
                     continue;
                 }
             };
 
             completed += 1;
-            let success = result.is_ok();
-            let error = result.err().map(|e| e.to_string());
+            let result = match result {
+                Ok(DeviceOutcome::Applied {
+                    mismatches,
+                    reconnects,
+                }) => DeviceOperationResult {
+                    ip,
+                    success: true,
+                    error: None,
+                    mismatches,
+                    cancelled: false,
+                    reconnects,
+                    skipped: false,
+                },
+                Ok(DeviceOutcome::Cancelled(message)) => {
+                    DeviceOperationResult::cancelled(ip, message)
+                }
+                Err(e) => DeviceOperationResult::failed(ip, e.to_string()),
+            };
             emit_operation_progress(
                 &app_handle,
                 &operation_id,
                 completed,
                 total,
-                Some(&ip),
-                Some(success),
-                error.as_deref(),
+                Some(&result.ip),
+                Some(result.success),
+                result.error.as_deref(),
             );
-            results.push(DeviceOperationResult { ip, success, error });
+            results.push(result);
         }
     }
 
     results
 }
 
-fn write_commands_from_params(params: Vec<(String, String, String)>) -> Vec<String> {
+/// What one device's part of a bulk operation did
+enum DeviceOutcome {
+    /// Completed, with the parameters that read back differently and the
+    /// times the link was reopened
+    Applied {
+        mismatches: Vec<ParamMismatch>,
+        reconnects: usize,
+    },
+    /// Stopped by cancellation; the message says how far it got
+    Cancelled(String),
+}
+
+/// Send commands over one connection, stopping before the next command
+/// once `cancel` is set
+///
+/// Raw batches can hold commands such as `delete-config` that must not run
+/// twice, so the connection does not reconnect.
+async fn send_commands(
+    ip: &str,
+    commands: &[String],
+    timeout: Duration,
+    pacing: Option<Duration>,
+    cancel: &AtomicBool,
+) -> Result<DeviceOutcome, CoreError> {
+    let mut conn = DeviceConnection::connect(ip, timeout).await?;
+    conn.set_pacing(pacing);
+    for (index, command) in commands.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(DeviceOutcome::Cancelled(format!(
+                "Cancelled after {} of {} commands",
+                index,
+                commands.len()
+            )));
+        }
+        conn.send_raw(command).await?;
+    }
+    Ok(DeviceOutcome::Applied {
+        mismatches: Vec::new(),
+        reconnects: conn.reconnects(),
+    })
+}
+
+/// Upload a preset plan over one reconnecting connection
+///
+/// See [`write_plan`] for how cancellation and `verify` are handled.
+async fn send_preset(
+    ip: &str,
+    plan: &PresetUploadPlan,
+    overrides: &PresetOverrides,
+    verify: bool,
+    timeout: Duration,
+    pacing: Option<Duration>,
+    cancel: &AtomicBool,
+) -> Result<DeviceOutcome, CoreError> {
+    let mut conn = DeviceConnection::connect_resilient(ip, timeout).await?;
+    conn.set_pacing(pacing);
+    let plan = plan_for_device(&mut conn, plan, overrides).await?;
+    write_plan(&mut conn, &plan, verify, cancel, |_, _| {}).await
+}
+
+/// Write a plan's parameters one at a time, then save them
+///
+/// `cancel` is checked before each write; once set, the device is left
+/// unsaved and the outcome says how many parameters were written. With
+/// `verify`, every parameter is read back before saving. `on_written` is
+/// called with the count written so far and the total after each write.
+async fn write_plan(
+    conn: &mut DeviceConnection,
+    plan: &PresetUploadPlan,
+    verify: bool,
+    cancel: &AtomicBool,
+    mut on_written: impl FnMut(usize, usize),
+) -> Result<DeviceOutcome, CoreError> {
+    conn.ensure_supported(&plan.save_command)?;
+    let writes = write_commands_from_params(&plan.params)?;
+    let total = writes.len();
+    for (index, command) in writes.iter().enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(DeviceOutcome::Cancelled(format!(
+                "Cancelled after writing {} of {} parameters; nothing was saved",
+                index, total
+            )));
+        }
+        conn.send_raw(command).await?;
+        on_written(index + 1, total);
+    }
+    let mismatches = if verify {
+        conn.verify_params(&plan.params).await?
+    } else {
+        Vec::new()
+    };
+    conn.send_raw(&plan.save_command).await?;
+    Ok(DeviceOutcome::Applied {
+        mismatches,
+        reconnects: conn.reconnects(),
+    })
+}
+
+/// Apply the overrides matching a connected device to a preset plan
+///
+/// The device's UWB short address is only read if there are overrides.
+async fn plan_for_device(
+    conn: &mut DeviceConnection,
+    plan: &PresetUploadPlan,
+    overrides: &PresetOverrides,
+) -> Result<PresetUploadPlan, CoreError> {
+    if overrides.is_empty() {
+        return Ok(plan.clone());
+    }
+    let short_addr = conn
+        .send_raw(&Commands::read_param("uwb", "devShortAddr")?)
+        .await?;
+    Ok(plan.with_overrides(overrides_for(overrides, None, Some(short_addr.trim()))))
+}
+
+fn write_commands_from_params(params: &ParamPlan) -> Result<Vec<String>, CommandError> {
     params
-        .into_iter()
-        .map(|(group, name, value)| Commands::write_param(&group, &name, &value))
+        .iter()
+        .map(|(group, name, value)| Commands::write_param(group, name, value))
         .collect()
 }
 
-async fn register_ota_cancellation(
-    active_cancellations: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
-    ip: &str,
-) -> (Arc<AtomicBool>, OtaCancellationGuard) {
+async fn register_cancellation(
+    active_cancellations: CancellationFlags,
+    key: &str,
+) -> (Arc<AtomicBool>, CancellationGuard) {
     let cancel = Arc::new(AtomicBool::new(false));
     active_cancellations
         .write()
         .await
-        .insert(ip.to_string(), cancel.clone());
-    let guard = OtaCancellationGuard {
-        ip: ip.to_string(),
+        .insert(key.to_string(), cancel.clone());
+    let guard = CancellationGuard {
+        key: key.to_string(),
         cancel: cancel.clone(),
         active_cancellations,
     };
@@ -212,87 +476,354 @@ impl OtaProgressHandler for TauriOtaProgress {
 }
 
 /// Send a single command to a device and return the response.
+///
+/// Uses the device's pooled connection, waiting for any other command to
+/// it to finish first. Timeouts and transport errors are retried up to
+/// `retries` times (default none), waiting `retry_backoff_ms` (default
+/// 500) before the first retry and twice as long before each later one.
 #[tauri::command]
 pub async fn send_device_command(
     ip: String,
     command: String,
     timeout_ms: Option<u64>,
+    retries: Option<usize>,
+    retry_backoff_ms: Option<u64>,
+    state: State<'_, AppState>,
 ) -> Result<DeviceCommandResponse, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
-    send_command_parsed(&ip, &command, timeout)
+    check_command(&command)?;
+    let mut policy = RetryPolicy::default();
+    policy.retries = retries.unwrap_or(policy.retries);
+    if let Some(backoff_ms) = retry_backoff_ms {
+        policy.backoff = Duration::from_millis(backoff_ms);
+    }
+    let mut conn = state.connections.acquire(&ip, timeout).await;
+    conn.send_retrying(&command, policy)
         .await
         .map_err(AppError::from)
 }
 
+/// Responses to a batch of commands sent to one device
+#[derive(Debug, serde::Serialize)]
+#[serde(untagged)]
+pub enum CommandBatchResponse {
+    /// Every command succeeded
+    Responses(Vec<DeviceCommandResponse>),
+    /// The outcome of each command, when continuing past failures or
+    /// after a cancellation
+    Results(Vec<CommandResult>),
+}
+
+/// A command for [`send_device_commands`]: a plain string, or an object
+/// with its own response timeout
+#[derive(Debug, serde::Deserialize)]
+#[serde(untagged)]
+pub enum DeviceCommandInput {
+    Plain(String),
+    #[serde(rename_all = "camelCase")]
+    Timed {
+        command: String,
+        timeout_ms: Option<u64>,
+    },
+}
+
+impl From<DeviceCommandInput> for BatchCommand {
+    fn from(input: DeviceCommandInput) -> Self {
+        match input {
+            DeviceCommandInput::Plain(command) => command.into(),
+            DeviceCommandInput::Timed {
+                command,
+                timeout_ms,
+            } => BatchCommand {
+                command,
+                timeout: timeout_ms.map(Duration::from_millis),
+            },
+        }
+    }
+}
+
 /// Send multiple commands to a device sequentially and return all responses.
+///
+/// The commands go out back to back on the device's pooled connection;
+/// commands from other calls to the same device wait until all are done.
+/// The first failing command fails the call, unless `continue_on_error` is
+/// set: then every command is sent and the outcome of each is returned.
+///
+/// Each command waits `timeout_ms` (default 5000) for its response unless
+/// it is given as `{ command, timeoutMs }` with a timeout of its own.
+///
+/// With an `operation_id`, [`cancel_operation`] stops the batch before its
+/// next command. The outcome of each command is then returned, with the
+/// unsent ones marked as cancelled.
 #[tauri::command]
 pub async fn send_device_commands(
     ip: String,
-    commands: Vec<String>,
+    commands: Vec<DeviceCommandInput>,
     timeout_ms: Option<u64>,
-) -> Result<Vec<DeviceCommandResponse>, AppError> {
+    continue_on_error: Option<bool>,
+    operation_id: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<CommandBatchResponse, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+    let commands: Vec<BatchCommand> = commands.into_iter().map(BatchCommand::from).collect();
+    let (cancel, _cancel_guard) = match operation_id {
+        Some(ref operation_id) => {
+            let (cancel, guard) =
+                register_cancellation(state.operation_cancellations.clone(), operation_id).await;
+            (cancel, Some(guard))
+        }
+        None => (Arc::new(AtomicBool::new(false)), None),
+    };
+    if continue_on_error.unwrap_or(false) {
+        let mut conn = state.connections.acquire(&ip, timeout).await;
+        let mut results = Vec::with_capacity(commands.len());
+        for (index, cmd) in commands.iter().enumerate() {
+            if cancel.load(Ordering::Relaxed) {
+                results.push(CommandResult::cancelled(index, &cmd.command));
+                continue;
+            }
+            let result = match check_command(&cmd.command) {
+                Ok(()) => conn.send_with_timeout(&cmd.command, cmd.timeout).await,
+                Err(e) => Err(e.into()),
+            };
+            results.push(CommandResult::new(index, &cmd.command, result));
+        }
+        return Ok(CommandBatchResponse::Results(results));
+    }
+
+    for (index, cmd) in commands.iter().enumerate() {
+        check_command(&cmd.command)
+            .map_err(|e| AppError::InvalidInput(format!("Command {} failed: {}", index + 1, e)))?;
+    }
     let mut responses = Vec::new();
 
-    let mut conn = DeviceConnection::connect(&ip, timeout)
-        .await
-        .map_err(AppError::from)?;
+    let mut conn = state.connections.acquire(&ip, timeout).await;
 
     for (index, cmd) in commands.iter().enumerate() {
-        let response = conn.send(cmd).await.map_err(|e| {
-            let err = AppError::from(e);
-            match err {
-                AppError::Device(msg) => {
-                    AppError::Device(format!("Command {} failed: {}", index + 1, msg))
-                }
-                AppError::Io(msg) => AppError::Io(format!("Command {} failed: {}", index + 1, msg)),
-                AppError::InvalidName(msg) => {
-                    AppError::InvalidName(format!("Command {} failed: {}", index + 1, msg))
-                }
-                AppError::NotFound(msg) => {
-                    AppError::NotFound(format!("Command {} failed: {}", index + 1, msg))
-                }
-                AppError::Json(msg) => {
-                    AppError::Json(format!("Command {} failed: {}", index + 1, msg))
-                }
-                AppError::Discovery(msg) => {
-                    AppError::Discovery(format!("Command {} failed: {}", index + 1, msg))
+        if cancel.load(Ordering::Relaxed) {
+            let completed =
+                commands
+                    .iter()
+                    .zip(responses)
+                    .enumerate()
+                    .map(|(index, (cmd, response))| {
+                        CommandResult::new(index, &cmd.command, Ok(response))
+                    });
+            let unsent = commands
+                .iter()
+                .enumerate()
+                .skip(index)
+                .map(|(index, cmd)| CommandResult::cancelled(index, &cmd.command));
+            return Ok(CommandBatchResponse::Results(
+                completed.chain(unsent).collect(),
+            ));
+        }
+        let response = conn
+            .send_with_timeout(&cmd.command, cmd.timeout)
+            .await
+            .map_err(|e| {
+                let err = AppError::from(e);
+                match err {
+                    AppError::Device(msg) => {
+                        AppError::Device(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::Io(msg) => {
+                        AppError::Io(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::InvalidName(msg) => {
+                        AppError::InvalidName(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::NotFound(msg) => {
+                        AppError::NotFound(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::AlreadyExists(msg) => {
+                        AppError::AlreadyExists(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::Json(msg) => {
+                        AppError::Json(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::Discovery(msg) => {
+                        AppError::Discovery(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::InvalidInput(msg) => {
+                        AppError::InvalidInput(format!("Command {} failed: {}", index + 1, msg))
+                    }
+                    AppError::Cancelled(msg) => AppError::Cancelled(msg),
+                    AppError::Unauthorized(msg) => {
+                        AppError::Unauthorized(format!("Command {} failed: {}", index + 1, msg))
+                    }
                 }
-            }
-        })?;
+            })?;
 
         responses.push(response);
     }
 
-    Ok(responses)
+    Ok(CommandBatchResponse::Responses(responses))
 }
 
 /// Execute one raw command on multiple devices with backend-owned concurrency.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn run_bulk_device_command(
     ips: Vec<String>,
     command: String,
     timeout_ms: Option<u64>,
     concurrency: Option<usize>,
     operation_id: Option<String>,
+    skip_unreachable: Option<bool>,
+    state: State<'_, AppState>,
     app_handle: AppHandle,
 ) -> Result<Vec<DeviceOperationResult>, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
     let operation_id = operation_id.unwrap_or_else(|| "bulk-command".to_string());
-    let command_batches = ips.iter().map(|_| vec![command.clone()]).collect();
+    check_command(&command)?;
+    let command_batches = ips
+        .iter()
+        .map(|_| DeviceBatch::Commands(vec![command.clone()]))
+        .collect();
+    let (cancel, _cancel_guard) =
+        register_cancellation(state.operation_cancellations.clone(), &operation_id).await;
     Ok(run_device_batches(
         ips,
         command_batches,
         timeout,
+        None,
         concurrency.unwrap_or(5),
+        skip_unreachable.unwrap_or(false),
         operation_id,
+        cancel,
         app_handle,
     )
     .await)
 }
 
+/// Most devices [`send_command_to_devices`] talks to at once, so bursts of
+/// commands do not overwhelm small access points
+const MAX_BULK_CONCURRENCY: usize = 8;
+
+/// How one device's command in [`send_command_to_devices`] ended
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BulkStatus {
+    Success,
+    Timeout,
+    ConnectFailed,
+    CommandFailed,
+    /// Not sent: the device did not answer the reachability probe
+    Skipped,
+}
+
+/// Outcome of one device's command in [`send_command_to_devices`]
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BulkDeviceResult {
+    pub ip: String,
+    pub success: bool,
+    pub status: BulkStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// How long the answering attempt took
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+    /// Times the command was sent, counting retries
+    pub attempts: usize,
+}
+
+impl From<&BatchResult> for BulkDeviceResult {
+    fn from(result: &BatchResult) -> Self {
+        let status = match result.outcome {
+            BatchOutcome::Success { .. } => BulkStatus::Success,
+            BatchOutcome::Timeout => BulkStatus::Timeout,
+            BatchOutcome::ConnectFailed { .. } => BulkStatus::ConnectFailed,
+            BatchOutcome::CommandFailed { .. } => BulkStatus::CommandFailed,
+            BatchOutcome::Unreachable => BulkStatus::Skipped,
+        };
+        Self {
+            ip: result.ip.clone(),
+            success: result.is_success(),
+            status,
+            response: result.response().map(str::to_string),
+            error: result.error(),
+            elapsed_ms: result.elapsed().map(|elapsed| elapsed.as_millis() as u64),
+            attempts: result.attempts,
+        }
+    }
+}
+
+/// Payload of the `bulk-command-progress` event
+#[derive(Clone, serde::Serialize)]
+struct BulkCommandProgress<'a> {
+    completed: usize,
+    total: usize,
+    #[serde(flatten)]
+    result: &'a BulkDeviceResult,
+}
+
+/// Send one command to many devices concurrently.
+///
+/// At most `concurrency` devices (3 by default, never more than
+/// [`MAX_BULK_CONCURRENCY`]) are sent the command at once. Timeouts and
+/// transport errors are retried up to `retries` times (default none), as
+/// in [`send_device_command`]. A `bulk-command-progress` event reports
+/// each device as it completes. With `skip_unreachable`, devices that do
+/// not answer a reachability probe are reported as skipped without being
+/// sent the command.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn send_command_to_devices(
+    ips: Vec<String>,
+    command: String,
+    timeout_ms: Option<u64>,
+    concurrency: Option<usize>,
+    retries: Option<usize>,
+    retry_backoff_ms: Option<u64>,
+    skip_unreachable: Option<bool>,
+    app_handle: AppHandle,
+) -> Result<Vec<BulkDeviceResult>, AppError> {
+    check_command(&command)?;
+    let concurrency = concurrency.unwrap_or(3).clamp(1, MAX_BULK_CONCURRENCY);
+    let mut policy = RetryPolicy::default();
+    policy.retries = retries.unwrap_or(policy.retries);
+    if let Some(backoff_ms) = retry_backoff_ms {
+        policy.backoff = Duration::from_millis(backoff_ms);
+    }
+    let mut sender = BatchSender::new(timeout_ms.unwrap_or(5000), concurrency).with_retry(policy);
+    if skip_unreachable.unwrap_or(false) {
+        sender = sender.skip_unreachable(DEFAULT_PROBE_TIMEOUT);
+    }
+    let total = ips.len();
+    let mut results = Vec::with_capacity(total);
+
+    sender
+        .send_to_all_with_progress(&ips, &command, |result| {
+            let result = BulkDeviceResult::from(result);
+            let _ = app_handle.emit(
+                "bulk-command-progress",
+                BulkCommandProgress {
+                    completed: results.len() + 1,
+                    total,
+                    result: &result,
+                },
+            );
+            results.push(result);
+        })
+        .await;
+
+    Ok(results)
+}
+
 /// Apply a full config to multiple devices and save it as a named device config.
+///
+/// With `verify`, each parameter is read back before saving and those that
+/// differ are listed in the device's result. `pacing_ms` waits between the
+/// commands sent to each device, for devices that drop back-to-back frames.
+///
+/// With an `operation_id`, [`cancel_operation`] stops each device before its
+/// next write, leaving it unsaved, and devices not yet started are skipped.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn apply_config_to_devices(
     ips: Vec<String>,
     config: DeviceConfig,
@@ -300,21 +831,36 @@ pub async fn apply_config_to_devices(
     timeout_ms: Option<u64>,
     concurrency: Option<usize>,
     operation_id: Option<String>,
+    skip_unreachable: Option<bool>,
+    verify: Option<bool>,
+    pacing_ms: Option<u64>,
+    state: State<'_, AppState>,
     app_handle: AppHandle,
 ) -> Result<Vec<DeviceOperationResult>, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(3000));
     let operation_id = operation_id.unwrap_or_else(|| "apply-config".to_string());
-    let mut base_commands =
-        write_commands_from_params(config_to_params(&config).map_err(AppError::Json)?);
-    base_commands.push(Commands::save_config_as(&config_name));
-    let command_batches = ips.iter().map(|_| base_commands.clone()).collect();
+    let plan = Arc::new(PresetUploadPlan {
+        params: config_to_params(&config, ParamOptions::default()).map_err(AppError::Json)?,
+        save_command: Commands::save_config_as(&config_name)?,
+    });
+    let no_overrides = Arc::new(PresetOverrides::new());
+    let verify = verify.unwrap_or(false);
+    let command_batches = ips
+        .iter()
+        .map(|_| DeviceBatch::Preset(plan.clone(), no_overrides.clone(), verify))
+        .collect();
 
+    let (cancel, _cancel_guard) =
+        register_cancellation(state.operation_cancellations.clone(), &operation_id).await;
     Ok(run_device_batches(
         ips,
         command_batches,
         timeout,
+        pacing_ms.map(Duration::from_millis),
         concurrency.unwrap_or(3),
+        skip_unreachable.unwrap_or(false),
         operation_id,
+        cancel,
         app_handle,
     )
     .await)
@@ -322,75 +868,278 @@ pub async fn apply_config_to_devices(
 
 /// Activate a named config on multiple devices.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn activate_config_on_devices(
     ips: Vec<String>,
     config_name: String,
     timeout_ms: Option<u64>,
     concurrency: Option<usize>,
     operation_id: Option<String>,
+    skip_unreachable: Option<bool>,
+    state: State<'_, AppState>,
     app_handle: AppHandle,
 ) -> Result<Vec<DeviceOperationResult>, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
     let operation_id = operation_id.unwrap_or_else(|| "activate-config".to_string());
-    let command = Commands::load_config_named(&config_name);
-    let command_batches = ips.iter().map(|_| vec![command.clone()]).collect();
+    let command = Commands::load_config_named(&config_name)?;
+    let command_batches = ips
+        .iter()
+        .map(|_| DeviceBatch::Commands(vec![command.clone()]))
+        .collect();
 
+    let (cancel, _cancel_guard) =
+        register_cancellation(state.operation_cancellations.clone(), &operation_id).await;
     Ok(run_device_batches(
         ips,
         command_batches,
         timeout,
+        None,
         concurrency.unwrap_or(5),
+        skip_unreachable.unwrap_or(false),
         operation_id,
+        cancel,
         app_handle,
     )
     .await)
 }
 
 /// Upload a preset to multiple devices.
+///
+/// `overrides` are added to the preset's own per-device overrides,
+/// replacing entries for the same device. With `verify`, each parameter is
+/// read back before saving. `pacing_ms` waits between the commands sent to
+/// each device.
+///
+/// With an `operation_id`, [`cancel_operation`] stops each device before its
+/// next write, leaving it unsaved, and devices not yet started are skipped.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_preset_to_devices(
     ips: Vec<String>,
     preset: Preset,
+    overrides: Option<PresetOverrides>,
     timeout_ms: Option<u64>,
     concurrency: Option<usize>,
     operation_id: Option<String>,
+    skip_unreachable: Option<bool>,
+    verify: Option<bool>,
+    pacing_ms: Option<u64>,
+    state: State<'_, AppState>,
     app_handle: AppHandle,
 ) -> Result<Vec<DeviceOperationResult>, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(3000));
     let operation_id = operation_id.unwrap_or_else(|| "upload-preset".to_string());
-    let commands = match preset.preset_type {
-        PresetType::Full => {
-            let config = preset.config.as_ref().ok_or_else(|| {
-                AppError::Json("Full preset must include config data".to_string())
-            })?;
-            let mut commands =
-                write_commands_from_params(config_to_params(config).map_err(AppError::Json)?);
-            commands.push(Commands::save_config_as(&preset.name));
-            commands
-        }
-        PresetType::Locations => {
-            let locations = preset.locations.as_ref().ok_or_else(|| {
-                AppError::Json("Location preset must include location data".to_string())
-            })?;
-            let mut commands =
-                write_commands_from_params(location_to_params(locations).map_err(AppError::Json)?);
-            commands.push(Commands::save_config().to_string());
-            commands
-        }
-    };
-    let command_batches = ips.iter().map(|_| commands.clone()).collect();
+    let plan = Arc::new(preview_preset(&preset).map_err(AppError::InvalidInput)?);
+    let mut merged = preset.overrides;
+    merged.extend(overrides.unwrap_or_default());
+    let overrides = Arc::new(merged);
+    let verify = verify.unwrap_or(false);
+    let command_batches = ips
+        .iter()
+        .map(|_| DeviceBatch::Preset(plan.clone(), overrides.clone(), verify))
+        .collect();
 
+    let (cancel, _cancel_guard) =
+        register_cancellation(state.operation_cancellations.clone(), &operation_id).await;
     Ok(run_device_batches(
         ips,
         command_batches,
         timeout,
+        pacing_ms.map(Duration::from_millis),
         concurrency.unwrap_or(3),
+        skip_unreachable.unwrap_or(false),
         operation_id,
+        cancel,
         app_handle,
     )
     .await)
 }
 
+/// Apply a stored preset to several devices.
+///
+/// Each device gets its own connection over which every parameter is
+/// written, followed by a save and, optionally, a reboot. At most
+/// `concurrency` devices are configured at once. `preset-apply-progress`
+/// events report each written parameter; a device that fails does not
+/// stop the others. The preset's per-device overrides are applied to the
+/// devices they match, and successful devices are recorded in the preset's
+/// apply history. With `verify`, every parameter is read back before saving
+/// and those that differ are listed in the device's result.
+///
+/// With an `operation_id`, [`cancel_operation`] stops each device before its
+/// next write, leaving it unsaved, and devices not yet started are skipped.
+/// With `skip_unreachable`, devices that do not answer a reachability probe
+/// are reported as skipped without being contacted. `pacing_ms` waits
+/// between the commands sent to each device.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn apply_preset_to_devices(
+    preset_name: String,
+    ips: Vec<String>,
+    concurrency: Option<usize>,
+    reboot_after: Option<bool>,
+    timeout_ms: Option<u64>,
+    verify: Option<bool>,
+    operation_id: Option<String>,
+    skip_unreachable: Option<bool>,
+    pacing_ms: Option<u64>,
+    state: State<'_, AppState>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+    app_handle: AppHandle,
+) -> Result<Vec<DeviceOperationResult>, AppError> {
+    let preset = preset_service
+        .read(&preset_name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Preset '{}' not found", preset_name)))?;
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(3000));
+    let pacing = pacing_ms.map(Duration::from_millis);
+    let plan = Arc::new(preview_preset(&preset).map_err(AppError::InvalidInput)?);
+    let reboot_after = reboot_after.unwrap_or(false);
+    let verify = verify.unwrap_or(false);
+    let overrides = Arc::new(preset.overrides.clone());
+    let operation_id = operation_id.unwrap_or_else(|| "apply-preset".to_string());
+    let (cancel, _cancel_guard) =
+        register_cancellation(state.operation_cancellations.clone(), &operation_id).await;
+
+    let mut results = Vec::with_capacity(ips.len());
+    let unreachable = unreachable_devices(&ips, skip_unreachable.unwrap_or(false)).await;
+    let ips: Vec<String> = ips
+        .into_iter()
+        .filter(|ip| !unreachable.contains(ip))
+        .collect();
+    results.extend(unreachable.into_iter().map(DeviceOperationResult::skipped));
+    for chunk in ips.chunks(concurrency.unwrap_or(3).max(1)) {
+        if cancel.load(Ordering::Relaxed) {
+            results.extend(chunk.iter().map(|ip| {
+                DeviceOperationResult::cancelled(ip.clone(), "Cancelled before starting".into())
+            }));
+            continue;
+        }
+
+        let mut join_set = tokio::task::JoinSet::new();
+        let mut task_ips = HashMap::new();
+        for ip in chunk.iter().cloned() {
+            let plan = plan.clone();
+            let overrides = overrides.clone();
+            let cancel = cancel.clone();
+            let app_handle = app_handle.clone();
+            let preset_name = preset.name.clone();
+            let task_ip = ip.clone();
+            let handle = join_set.spawn(async move {
+                let result = apply_preset_to_device(
+                    &ip,
+                    &preset_name,
+                    &plan,
+                    &overrides,
+                    reboot_after,
+                    verify,
+                    &cancel,
+                    timeout,
+                    pacing,
+                    &app_handle,
+                )
+                .await;
+                (ip, result)
+            });
+            task_ips.insert(handle.id(), task_ip);
+        }
+
+        while let Some(joined) = join_set.join_next_with_id().await {
+            let (ip, result) = match joined {
+                Ok((id, v)) => {
+                    task_ips.remove(&id);
+                    v
+                }
+                Err(e) => {
+                    let ip = task_ips
+                        .remove(&e.id())
+                        .unwrap_or_else(|| "unknown".to_string());
+                    (ip, Err(e.to_string()))
+                }
+            };
+            results.push(match result {
+                Ok(DeviceOutcome::Applied {
+                    mismatches,
+                    reconnects,
+                }) => DeviceOperationResult {
+                    ip,
+                    success: true,
+                    error: None,
+                    mismatches,
+                    cancelled: false,
+                    reconnects,
+                    skipped: false,
+                },
+                Ok(DeviceOutcome::Cancelled(message)) => {
+                    DeviceOperationResult::cancelled(ip, message)
+                }
+                Err(e) => DeviceOperationResult::failed(ip, e),
+            });
+        }
+    }
+
+    let at = chrono::Utc::now().to_rfc3339();
+    let applied: Vec<AppliedRecord> = results
+        .iter()
+        .filter(|result| result.success)
+        .map(|result| AppliedRecord {
+            mac: None,
+            ip: result.ip.clone(),
+            at: at.clone(),
+        })
+        .collect();
+    // Apply history is informational and must not fail the upload
+    if let Err(e) = preset_service.record_applied(&preset.name, &applied).await {
+        eprintln!(
+            "Failed to record apply history for '{}': {}",
+            preset.name, e
+        );
+    }
+
+    Ok(results)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn apply_preset_to_device(
+    ip: &str,
+    preset_name: &str,
+    plan: &PresetUploadPlan,
+    overrides: &PresetOverrides,
+    reboot_after: bool,
+    verify: bool,
+    cancel: &AtomicBool,
+    timeout: Duration,
+    pacing: Option<Duration>,
+    app_handle: &AppHandle,
+) -> Result<DeviceOutcome, String> {
+    let mut conn = DeviceConnection::connect_resilient(ip, timeout)
+        .await
+        .map_err(|e| e.to_string())?;
+    conn.set_pacing(pacing);
+    let plan = plan_for_device(&mut conn, plan, overrides)
+        .await
+        .map_err(|e| e.to_string())?;
+    let outcome = write_plan(&mut conn, &plan, verify, cancel, |written, total| {
+        let _ = app_handle.emit(
+            "preset-apply-progress",
+            serde_json::json!({
+                "presetName": preset_name,
+                "ip": ip,
+                "written": written,
+                "total": total,
+            }),
+        );
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if reboot_after && matches!(outcome, DeviceOutcome::Applied { .. }) {
+        conn.send_raw(Commands::reboot())
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(outcome)
+}
+
 /// Run antenna calibration through the shared Rust core workflow.
 #[tauri::command]
 pub async fn run_antenna_calibration(
@@ -405,6 +1154,100 @@ pub async fn run_antenna_calibration(
     Ok(result)
 }
 
+/// Resolve the configured OTA endpoint and credentials for each device IP.
+///
+/// Both are stored per MAC address; IPs that are not in the device list or
+/// have no override use plain HTTP without credentials. Stored credentials
+/// that cannot be decrypted are skipped, so devices that need none can
+/// still be updated.
+async fn ota_endpoints(
+    ips: &[String],
+    state: &AppState,
+    settings_service: &SettingsService,
+) -> Result<HashMap<String, OtaEndpoint>, AppError> {
+    let settings = settings_service.get().await;
+    let credentials = settings_service.credentials().await.unwrap_or_else(|e| {
+        eprintln!("Uploading without stored device credentials: {}", e);
+        HashMap::new()
+    });
+    let devices = state.devices.read().await;
+    Ok(ips
+        .iter()
+        .map(|ip| {
+            let endpoint = devices
+                .get(ip)
+                .map(|device| OtaEndpoint {
+                    auth: credentials
+                        .get(&device.mac.to_ascii_uppercase())
+                        .cloned()
+                        .unwrap_or_default(),
+                    ..settings.endpoint_for(&device.mac)
+                })
+                .unwrap_or_default();
+            (ip.clone(), endpoint)
+        })
+        .collect())
+}
+
+/// List the MAC addresses that have stored credentials.
+///
+/// The credentials themselves never leave the backend.
+#[tauri::command]
+pub async fn get_devices_with_credentials(
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<Vec<String>, AppError> {
+    let mut macs: Vec<String> = settings_service.credentials().await?.into_keys().collect();
+    macs.sort();
+    Ok(macs)
+}
+
+/// Set or clear the credentials used for a device's OTA endpoint.
+#[tauri::command]
+pub async fn set_device_credentials(
+    mac: String,
+    auth: Option<OtaAuth>,
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<(), AppError> {
+    settings_service.set_credentials(&mac, auth).await
+}
+
+/// Forget the credentials of every device, e.g. after their key was lost.
+#[tauri::command]
+pub async fn reset_device_credentials(
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<(), AppError> {
+    settings_service.reset_credentials().await
+}
+
+/// Get the OTA endpoint overrides keyed by device MAC address.
+#[tauri::command]
+pub async fn get_device_endpoints(
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<HashMap<String, OtaEndpoint>, AppError> {
+    Ok(settings_service.get().await.device_endpoints)
+}
+
+/// Set or clear the OTA endpoint override for a device.
+#[tauri::command]
+pub async fn set_device_endpoint(
+    mac: String,
+    endpoint: Option<OtaEndpoint>,
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<(), AppError> {
+    let mac = mac.to_ascii_uppercase();
+    settings_service
+        .update(|settings| match endpoint {
+            Some(endpoint) => {
+                settings.device_endpoints.insert(mac, endpoint);
+            }
+            None => {
+                settings.device_endpoints.remove(&mac);
+            }
+        })
+        .await?;
+    Ok(())
+}
+
 /// Upload firmware from a file path to a single device.
 #[tauri::command]
 pub async fn upload_firmware_from_file(
@@ -412,6 +1255,7 @@ pub async fn upload_firmware_from_file(
     file_path: String,
     app_handle: AppHandle,
     state: State<'_, AppState>,
+    settings_service: State<'_, Arc<SettingsService>>,
 ) -> Result<(), AppError> {
     let path = PathBuf::from(&file_path);
 
@@ -425,11 +1269,15 @@ pub async fn upload_firmware_from_file(
         .unwrap_or("firmware.bin");
 
     let progress = TauriOtaProgress { app_handle };
-    let (cancel, _cancel_guard) =
-        register_ota_cancellation(state.ota_cancellations.clone(), &ip).await;
+    let (cancel, _cancel_guard) = register_cancellation(state.ota_cancellations.clone(), &ip).await;
+    let endpoint = ota_endpoints(std::slice::from_ref(&ip), &state, &settings_service)
+        .await?
+        .remove(&ip)
+        .unwrap_or_default();
 
     let result =
-        upload_firmware_with_progress_and_cancel(&ip, data, filename, &progress, cancel).await;
+        upload_firmware_with_progress_and_cancel(&ip, &endpoint, data, filename, &progress, cancel)
+            .await;
 
     if let Err(error) = result {
         progress.on_error(&ip, &error.to_string());
@@ -443,14 +1291,19 @@ pub async fn upload_firmware_from_file(
 
 /// Upload firmware to multiple devices concurrently.
 ///
-/// Returns per-device results as JSON array.
+/// Returns per-device results as JSON array. With `skip_unreachable`,
+/// devices whose OTA endpoint does not accept a connection are reported as
+/// skipped instead of waiting for their uploads to time out.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn upload_firmware_to_devices(
     ips: Vec<String>,
     file_path: String,
     concurrency: Option<usize>,
+    skip_unreachable: Option<bool>,
     app_handle: AppHandle,
     state: State<'_, AppState>,
+    settings_service: State<'_, Arc<SettingsService>>,
 ) -> Result<Vec<serde_json::Value>, AppError> {
     let path = PathBuf::from(&file_path);
 
@@ -468,13 +1321,20 @@ pub async fn upload_firmware_to_devices(
     let mut cancel_flags = HashMap::new();
     let mut cancel_guards = Vec::with_capacity(ips.len());
     for ip in &ips {
-        let (cancel, guard) = register_ota_cancellation(state.ota_cancellations.clone(), ip).await;
+        let (cancel, guard) = register_cancellation(state.ota_cancellations.clone(), ip).await;
         cancel_flags.insert(ip.clone(), cancel);
         cancel_guards.push(guard);
     }
+    let endpoints = ota_endpoints(&ips, &state, &settings_service).await?;
+    let (targets, skipped) = if skip_unreachable.unwrap_or(false) {
+        ota::skip_unreachable(&ips, &endpoints, DEFAULT_PROBE_TIMEOUT).await
+    } else {
+        (ips, Vec::new())
+    };
 
     let results = upload_firmware_bulk_with_cancel(
-        &ips,
+        &targets,
+        &endpoints,
         data,
         filename,
         concurrency,
@@ -484,7 +1344,7 @@ pub async fn upload_firmware_to_devices(
     .await;
     drop(cancel_guards);
 
-    let json_results: Vec<serde_json::Value> = results
+    let mut json_results: Vec<serde_json::Value> = results
         .into_iter()
         .map(|(ip, result)| {
             serde_json::json!({
@@ -494,10 +1354,35 @@ pub async fn upload_firmware_to_devices(
             })
         })
         .collect();
+    json_results.extend(skipped.into_iter().map(|(ip, _)| {
+        serde_json::json!({
+            "ip": ip,
+            "success": false,
+            "skipped": true,
+            "error": unreachable_message(&ip),
+        })
+    }));
 
     Ok(json_results)
 }
 
+/// Request cancellation for an active device operation.
+///
+/// The operation stops before its next step; whatever already completed is
+/// reported in its result.
+#[tauri::command]
+pub async fn cancel_operation(
+    operation_id: String,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    let active_cancellations = state.operation_cancellations.read().await;
+    if let Some(cancel) = active_cancellations.get(&operation_id) {
+        cancel.store(true, Ordering::Relaxed);
+        return Ok(true);
+    }
+    Ok(false)
+}
+
 /// Request cancellation for an active firmware upload.
 #[tauri::command]
 pub async fn cancel_firmware_upload(
@@ -517,13 +1402,32 @@ pub async fn cancel_firmware_upload(
 pub async fn get_firmware_info(
     ip: String,
     timeout_ms: Option<u64>,
-) -> Result<serde_json::Value, AppError> {
+) -> Result<FirmwareInfo, AppError> {
     let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
 
-    let response = send_command_parsed(&ip, "firmware-info", timeout)
-        .await
-        .map_err(AppError::from)?;
-    response
-        .json
-        .ok_or_else(|| AppError::Json("No JSON found in firmware info response".to_string()))
+    let mut conn = DeviceConnection::connect(&ip, timeout).await?;
+    Ok(conn.firmware_info().await?)
+}
+
+/// Get the commands a device supports.
+#[tauri::command]
+pub async fn get_device_capabilities(
+    ip: String,
+    timeout_ms: Option<u64>,
+) -> Result<DeviceCapabilities, AppError> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+
+    let mut conn = DeviceConnection::connect(&ip, timeout).await?;
+    Ok(conn.capabilities().await?)
+}
+
+/// Close the pooled connection to a device, returning whether one was open.
+///
+/// The next command to the device opens a new one.
+#[tauri::command]
+pub async fn close_device_connection(
+    ip: String,
+    state: State<'_, AppState>,
+) -> Result<bool, AppError> {
+    Ok(state.connections.close(&ip).await)
 }
@@ -4,24 +4,81 @@
 //! and for retrieving buffered logs.
 
 use crate::error::AppError;
-use crate::logging::service::LogMessage;
+use crate::logging::forwarder::{ForwardFormat, LogForwarder, LogForwardingStatus};
+use crate::logging::service::{
+    received_at_ms, LogMessage, LogReceiverService, LogSearchOptions, LogSearchResult, LogStats,
+    LogStreamInfo,
+};
+use crate::settings::SettingsService;
 use crate::state::AppState;
-use tauri::State;
+use crate::types::DeviceRole;
+use rtls_link_core::log_filter::TagFilter;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, State};
+
+/// Current state of the UDP log receiver
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogReceiverStatus {
+    /// UDP port the receiver is bound to (None if it failed to start)
+    pub port: Option<u16>,
+    /// Whether the receiver task is running
+    pub running: bool,
+}
+
+/// Auto-stream settings and the streams they currently own
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoStreamStatus {
+    /// Whether new devices get a log stream on discovery
+    pub enabled: bool,
+    /// Roles that are auto-streamed (None means all roles)
+    pub roles: Option<Vec<DeviceRole>>,
+    /// Device IPs whose streams were started automatically, sorted
+    pub auto_started: Vec<String>,
+}
 
 /// Start streaming logs from a device
 ///
 /// This adds the device IP to the active streams set, so incoming
 /// log messages from this device will be forwarded to the frontend.
+/// Optional tag globs (e.g. `uwb.*`) limit which messages are forwarded;
+/// all messages are still buffered.
 #[tauri::command]
 pub async fn start_log_stream(
     device_ip: String,
+    tag_filters: Option<Vec<String>>,
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     let mut streams = state.log_streams.write().await;
-    streams.active_streams.insert(device_ip.clone(), true);
+    streams.start_stream(&device_ip, TagFilter::new(tag_filters.unwrap_or_default()));
     Ok(())
 }
 
+/// Replace the tag filters of an active log stream
+///
+/// An empty list forwards all tags again.
+#[tauri::command]
+pub async fn update_log_stream_filters(
+    device_ip: String,
+    tag_filters: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let mut streams = state.log_streams.write().await;
+    match streams.active_streams.get_mut(&device_ip) {
+        Some(filter) => {
+            *filter = TagFilter::new(tag_filters);
+            Ok(())
+        }
+        None => Err(AppError::Device(format!(
+            "No active log stream for {}",
+            device_ip
+        ))),
+    }
+}
+
 /// Stop streaming logs from a device
 ///
 /// Removes the device IP from the active streams set.
@@ -31,21 +88,50 @@ pub async fn stop_log_stream(
     state: State<'_, AppState>,
 ) -> Result<(), AppError> {
     let mut streams = state.log_streams.write().await;
-    streams.active_streams.remove(&device_ip);
+    streams.stop_stream(&device_ip);
     Ok(())
 }
 
-/// Get list of devices currently being streamed
+/// Get stream and buffer information for devices with logs
+///
+/// Includes devices with an active stream as well as devices that only
+/// have buffered logs, so the UI can offer to view them.
 #[tauri::command]
-pub async fn get_active_log_streams(state: State<'_, AppState>) -> Result<Vec<String>, AppError> {
+pub async fn get_active_log_streams(
+    state: State<'_, AppState>,
+) -> Result<Vec<LogStreamInfo>, AppError> {
     let streams = state.log_streams.read().await;
-    let active: Vec<String> = streams
-        .active_streams
-        .iter()
-        .filter(|(_, &v)| v)
-        .map(|(k, _)| k.clone())
-        .collect();
-    Ok(active)
+    Ok(streams.stream_info())
+}
+
+/// Stop emitting logs to the frontend for all devices
+///
+/// Stream state is kept and logs continue to be buffered, so terminals
+/// can re-fetch the buffer after resuming.
+#[tauri::command]
+pub async fn pause_log_emissions(state: State<'_, AppState>) -> Result<(), AppError> {
+    let mut streams = state.log_streams.write().await;
+    streams.emissions_paused = true;
+    Ok(())
+}
+
+/// Resume emitting logs to the frontend
+///
+/// Emits a `log-emissions-resumed` event so open terminals can re-fetch
+/// their buffers to fill the gap.
+#[tauri::command]
+pub async fn resume_log_emissions(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<(), AppError> {
+    let mut streams = state.log_streams.write().await;
+    let was_paused = std::mem::replace(&mut streams.emissions_paused, false);
+    drop(streams);
+
+    if was_paused {
+        let _ = app_handle.emit("log-emissions-resumed", ());
+    }
+    Ok(())
 }
 
 /// Get buffered logs for a device
@@ -62,6 +148,25 @@ pub async fn get_buffered_logs(
     Ok(logs)
 }
 
+/// Search buffered logs
+///
+/// Runs a case-insensitive substring search (or regex search when
+/// `options.regex` is set) over one device's buffer or all buffers, with
+/// optional level and tag constraints. Results are capped; `truncated`
+/// reports whether more matches exist.
+#[tauri::command]
+pub async fn search_buffered_logs(
+    query: String,
+    options: Option<LogSearchOptions>,
+    state: State<'_, AppState>,
+) -> Result<LogSearchResult, AppError> {
+    let options = options.unwrap_or_default();
+    let streams = state.log_streams.read().await;
+    streams
+        .search(&query, &options)
+        .map_err(|e| AppError::InvalidInput(format!("Invalid search pattern: {}", e)))
+}
+
 /// Clear buffered logs for a device
 ///
 /// Removes all buffered logs for the specified device.
@@ -74,3 +179,234 @@ pub async fn clear_buffered_logs(
     streams.clear_logs(&device_ip);
     Ok(())
 }
+
+/// Clear buffered logs for every device
+///
+/// When `prune_unknown` is set, buffers for devices that are no longer in
+/// the discovered device list are removed entirely. Returns the number of
+/// messages discarded per device.
+#[tauri::command]
+pub async fn clear_all_buffered_logs(
+    prune_unknown: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<HashMap<String, usize>, AppError> {
+    let known_devices: Option<HashSet<String>> = if prune_unknown.unwrap_or(false) {
+        Some(state.devices.read().await.keys().cloned().collect())
+    } else {
+        None
+    };
+
+    let mut streams = state.log_streams.write().await;
+    Ok(streams.clear_all_logs(known_devices.as_ref()))
+}
+
+/// Get log receiver statistics
+///
+/// Includes the number of unparseable packets and, per device, the
+/// number of messages detected as lost through sequence number gaps and
+/// per-level counts over the buffer and the last minute. Pass a device IP
+/// to limit the result to that device.
+#[tauri::command]
+pub async fn get_log_stats(
+    device_ip: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<LogStats, AppError> {
+    let streams = state.log_streams.read().await;
+    Ok(streams.stats(device_ip.as_deref(), received_at_ms()))
+}
+
+/// Forward received logs to an external UDP collector
+///
+/// `target_addr` is a `host:port` address; pass `None` to disable
+/// forwarding. Messages are re-serialized in `format` (NDJSON by default).
+#[tauri::command]
+pub async fn set_log_forwarding(
+    target_addr: Option<String>,
+    format: Option<ForwardFormat>,
+    state: State<'_, AppState>,
+) -> Result<LogForwardingStatus, AppError> {
+    let forwarder = match target_addr {
+        Some(addr) => {
+            let target = tokio::net::lookup_host(&addr)
+                .await
+                .map_err(|e| AppError::Io(format!("Invalid forwarding target {}: {}", addr, e)))?
+                .next()
+                .ok_or_else(|| AppError::Io(format!("Could not resolve {}", addr)))?;
+            let forwarder =
+                LogForwarder::start(target, format.unwrap_or(ForwardFormat::Ndjson)).await?;
+            Some(forwarder)
+        }
+        None => None,
+    };
+
+    let status = forwarder
+        .as_ref()
+        .map(LogForwarder::status)
+        .unwrap_or_else(LogForwardingStatus::disabled);
+
+    let mut streams = state.log_streams.write().await;
+    streams.forwarder = forwarder;
+    Ok(status)
+}
+
+/// Get the log forwarding target and its sent/dropped counters
+#[tauri::command]
+pub async fn get_log_forwarding_status(
+    state: State<'_, AppState>,
+) -> Result<LogForwardingStatus, AppError> {
+    let streams = state.log_streams.read().await;
+    Ok(streams
+        .forwarder
+        .as_ref()
+        .map(LogForwarder::status)
+        .unwrap_or_else(LogForwardingStatus::disabled))
+}
+
+/// Restart the log receiver on a different UDP port
+///
+/// Binds the new port first so that a bind failure leaves the current
+/// receiver running. Restarting on the port already in use stops the
+/// current receiver first, since the port cannot be bound twice. On success
+/// the bound port is persisted for the next launch.
+#[tauri::command]
+pub async fn restart_log_receiver(
+    port: u16,
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<LogReceiverStatus, AppError> {
+    let mut receiver = state.log_receiver.write().await;
+
+    if receiver.as_ref().is_some_and(|handle| handle.port == port) {
+        if let Some(previous) = receiver.take() {
+            previous.stop().await;
+        }
+    }
+
+    let service = match LogReceiverService::new(port).await {
+        Ok(service) => service,
+        Err(e) => {
+            return Err(AppError::Io(format!(
+                "Failed to bind log receiver on UDP port {}: {}",
+                port, e
+            )));
+        }
+    };
+
+    if let Some(previous) = receiver.take() {
+        previous.stop().await;
+    }
+
+    let handle = service.spawn(state.log_streams.clone(), app_handle);
+    let bound_port = handle.port;
+    let status = LogReceiverStatus {
+        port: Some(bound_port),
+        running: handle.is_running(),
+    };
+    *receiver = Some(handle);
+    drop(receiver);
+
+    settings_service
+        .update(|settings| settings.log_receiver_port = Some(bound_port))
+        .await?;
+
+    Ok(status)
+}
+
+/// Get the bound port and running state of the log receiver
+#[tauri::command]
+pub async fn get_log_receiver_status(
+    state: State<'_, AppState>,
+) -> Result<LogReceiverStatus, AppError> {
+    let receiver = state.log_receiver.read().await;
+    Ok(match receiver.as_ref() {
+        Some(handle) => LogReceiverStatus {
+            port: Some(handle.port),
+            running: handle.is_running(),
+        },
+        None => LogReceiverStatus {
+            port: None,
+            running: false,
+        },
+    })
+}
+
+/// Enable or disable automatic log streams for newly discovered devices
+///
+/// Enabling also starts streams for matching devices that are already
+/// known. Disabling, or narrowing the roles, stops only the streams that
+/// were started automatically; streams opened by the user keep running.
+#[tauri::command]
+pub async fn set_auto_stream_logs(
+    enabled: bool,
+    roles: Option<Vec<DeviceRole>>,
+    state: State<'_, AppState>,
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<AutoStreamStatus, AppError> {
+    let settings = settings_service
+        .update(|settings| {
+            settings.auto_stream_logs = enabled;
+            settings.auto_stream_roles = roles;
+        })
+        .await?;
+
+    let devices = state.devices.read().await;
+    let mut streams = state.log_streams.write().await;
+    if settings.auto_stream_logs {
+        let unwanted: Vec<String> = streams
+            .auto_started
+            .iter()
+            .filter(|ip| {
+                devices
+                    .get(*ip)
+                    .is_some_and(|device| !settings.should_auto_stream(&device.role))
+            })
+            .cloned()
+            .collect();
+        for ip in unwanted {
+            streams.stop_stream(&ip);
+        }
+        for device in devices.values() {
+            if settings.should_auto_stream(&device.role) {
+                streams.auto_start_stream(&device.ip);
+            }
+        }
+    } else {
+        streams.stop_auto_started_streams();
+    }
+
+    Ok(auto_stream_status(
+        settings.auto_stream_logs,
+        settings.auto_stream_roles,
+        &streams.auto_started,
+    ))
+}
+
+/// Get the auto-stream settings and the streams they started
+#[tauri::command]
+pub async fn get_auto_stream_logs(
+    state: State<'_, AppState>,
+    settings_service: State<'_, Arc<SettingsService>>,
+) -> Result<AutoStreamStatus, AppError> {
+    let settings = settings_service.get().await;
+    let streams = state.log_streams.read().await;
+    Ok(auto_stream_status(
+        settings.auto_stream_logs,
+        settings.auto_stream_roles,
+        &streams.auto_started,
+    ))
+}
+
+fn auto_stream_status(
+    enabled: bool,
+    roles: Option<Vec<DeviceRole>>,
+    auto_started: &HashSet<String>,
+) -> AutoStreamStatus {
+    let mut auto_started: Vec<String> = auto_started.iter().cloned().collect();
+    auto_started.sort();
+    AutoStreamStatus {
+        enabled,
+        roles,
+        auto_started,
+    }
+}
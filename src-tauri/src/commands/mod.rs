@@ -4,6 +4,7 @@
 //! to the frontend via IPC.
 
 pub mod configs;
+pub mod data;
 pub mod device_comm;
 pub mod devices;
 pub mod logging;
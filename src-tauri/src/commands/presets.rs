@@ -2,20 +2,39 @@
 
 use crate::error::AppError;
 use crate::preset_storage::PresetStorageService;
-use crate::types::{GpsOrigin, LocationData, Preset, PresetInfo, PresetType};
+use crate::types::{GpsOrigin, LocationData, Preset, PresetList, PresetProblem, PresetType};
+use rtls_link_core::anchor_csv::parse_anchors_csv;
+use rtls_link_core::config_diff::{diff_preset, ConfigDiffEntry};
+use rtls_link_core::config_validation::{validate_locations, ValidationIssue};
 use rtls_link_core::device::mavlink::send_command_parsed;
+use rtls_link_core::location_template::{preset_from_template, Layout};
 use rtls_link_core::protocol::commands::Commands;
-use rtls_link_core::protocol::config_params::device_config_from_backup_value;
+use rtls_link_core::protocol::config_params::{
+    self, device_config_from_backup_value, PresetUploadPlan,
+};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::State;
 
-/// List all saved presets.
+/// List saved presets, optionally only those of `preset_type` and carrying
+/// `tag`, along with any preset files that could not be loaded.
 #[tauri::command]
 pub async fn list_presets(
+    preset_type: Option<PresetType>,
+    tag: Option<String>,
     preset_service: State<'_, Arc<PresetStorageService>>,
-) -> Result<Vec<PresetInfo>, AppError> {
-    preset_service.list().await
+) -> Result<PresetList, AppError> {
+    preset_service.list(preset_type, tag.as_deref()).await
+}
+
+/// Move corrupt preset files into the `corrupt/` subfolder of the preset
+/// directory, returning the files that were moved.
+#[tauri::command]
+pub async fn repair_presets(
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Vec<PresetProblem>, AppError> {
+    preset_service.move_corrupt().await
 }
 
 /// Get a specific preset by name.
@@ -27,13 +46,102 @@ pub async fn get_preset(
     preset_service.read(&name).await
 }
 
-/// Save a preset.
+/// Save a preset, returning any validation warnings.
+///
+/// Locations presets whose anchor layout has errors are rejected, as are
+/// names that differ only by case from an existing preset unless
+/// `overwrite` is set.
 #[tauri::command]
 pub async fn save_preset(
     preset: Preset,
+    overwrite: Option<bool>,
     preset_service: State<'_, Arc<PresetStorageService>>,
-) -> Result<bool, AppError> {
-    preset_service.save(preset).await
+) -> Result<Vec<ValidationIssue>, AppError> {
+    let warnings = match (&preset.preset_type, &preset.locations) {
+        (PresetType::Locations, Some(locations)) => validate_locations(locations)
+            .into_iter()
+            .filter(|issue| !issue.is_error())
+            .collect(),
+        _ => Vec::new(),
+    };
+    if overwrite.unwrap_or(false) {
+        preset_service.replace(preset).await?;
+    } else {
+        preset_service.save(preset).await?;
+    }
+    Ok(warnings)
+}
+
+/// Generate a locations preset from a room template and save it.
+#[tauri::command]
+pub async fn create_preset_from_template(
+    name: String,
+    layout: Layout,
+    ids: Option<Vec<String>>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Preset, AppError> {
+    let preset = preset_from_template(&name, layout, &ids.unwrap_or_default())
+        .map_err(AppError::InvalidInput)?;
+    preset_service.save(preset.clone()).await?;
+    Ok(preset)
+}
+
+/// List the parameter writes and save command uploading a preset would
+/// send, without contacting any device.
+#[tauri::command]
+pub async fn preview_preset(
+    name: String,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<PresetUploadPlan, AppError> {
+    let preset = preset_service
+        .read(&name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(name.clone()))?;
+    config_params::preview_preset(&preset).map_err(AppError::InvalidInput)
+}
+
+/// Save a locations preset from a survey CSV with an `id,x,y,z` header,
+/// returning any validation warnings.
+///
+/// Layouts with validation errors are rejected.
+#[tauri::command]
+pub async fn import_anchors_csv(
+    path: PathBuf,
+    name: String,
+    origin: GpsOrigin,
+    rotation: Option<f64>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Vec<ValidationIssue>, AppError> {
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| AppError::Io(e.to_string()))?;
+    let anchors = parse_anchors_csv(&content).map_err(AppError::InvalidInput)?;
+    let locations = LocationData {
+        origin,
+        rotation: rotation.unwrap_or(0.0),
+        anchors,
+        use_2d_estimator: None,
+    };
+
+    let now = chrono::Utc::now().to_rfc3339();
+    save_preset(
+        Preset {
+            name,
+            description: None,
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(locations),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        },
+        None,
+        preset_service,
+    )
+    .await
 }
 
 /// Delete a preset.
@@ -45,6 +153,78 @@ pub async fn delete_preset(
     preset_service.delete(&name).await
 }
 
+/// Rename a preset.
+#[tauri::command]
+pub async fn rename_preset(
+    old_name: String,
+    new_name: String,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<bool, AppError> {
+    preset_service.rename(&old_name, &new_name).await
+}
+
+/// Copy a preset under a new name.
+#[tauri::command]
+pub async fn duplicate_preset(
+    source: String,
+    target: String,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<bool, AppError> {
+    preset_service.duplicate(&source, &target).await
+}
+
+/// Convert a preset to another type (only Full to Locations is supported).
+///
+/// Saved under `new_name` if given, otherwise the preset is replaced.
+#[tauri::command]
+pub async fn convert_preset(
+    name: String,
+    target_type: PresetType,
+    new_name: Option<String>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Preset, AppError> {
+    preset_service
+        .convert(&name, target_type, new_name.as_deref())
+        .await
+}
+
+/// Save a new full preset with the settings of `full_name` and the anchor
+/// layout, origin and rotation of `locations_name`.
+#[tauri::command]
+pub async fn merge_presets(
+    full_name: String,
+    locations_name: String,
+    output_name: String,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Preset, AppError> {
+    preset_service
+        .merge(&full_name, &locations_name, &output_name)
+        .await
+}
+
+/// Export a preset to a shareable JSON file.
+#[tauri::command]
+pub async fn export_preset(
+    name: String,
+    path: PathBuf,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<bool, AppError> {
+    preset_service.export(&name, &path).await
+}
+
+/// Import a preset file, returning the name it was saved as.
+#[tauri::command]
+pub async fn import_preset(
+    path: PathBuf,
+    name: Option<String>,
+    overwrite: Option<bool>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<String, AppError> {
+    preset_service
+        .import(&path, name.as_deref(), overwrite.unwrap_or(false))
+        .await
+}
+
 /// Backup current config from a device and save it as a preset.
 #[tauri::command]
 pub async fn backup_device_preset(
@@ -72,31 +252,55 @@ pub async fn backup_device_preset(
             preset_type: PresetType::Full,
             config: Some(config),
             locations: None,
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        },
+        PresetType::Locations => Preset {
+            name,
+            description,
+            preset_type: PresetType::Locations,
+            config: None,
+            locations: Some(LocationData::from_config(&config)),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: now.clone(),
             updated_at: now,
         },
-        PresetType::Locations => {
-            let locations = LocationData {
-                origin: GpsOrigin {
-                    lat: config.uwb.origin_lat.unwrap_or(0.0),
-                    lon: config.uwb.origin_lon.unwrap_or(0.0),
-                    alt: config.uwb.origin_alt.unwrap_or(0.0),
-                },
-                rotation: config.uwb.rotation_degrees.unwrap_or(0.0),
-                anchors: config.uwb.anchors.unwrap_or_default(),
-                use_2d_estimator: config.uwb.use_2d_estimator,
-            };
-            Preset {
-                name,
-                description,
-                preset_type: PresetType::Locations,
-                config: None,
-                locations: Some(locations),
-                created_at: now.clone(),
-                updated_at: now,
-            }
-        }
     };
 
     preset_service.save(preset).await
 }
+
+/// Compare a preset against the configuration currently on a device.
+///
+/// Entries are from the preset's point of view: `removed` parameters are
+/// only in the preset, `added` ones only on the device.
+#[tauri::command]
+pub async fn diff_preset_against_device(
+    name: String,
+    ip: String,
+    timeout_ms: Option<u64>,
+    preset_service: State<'_, Arc<PresetStorageService>>,
+) -> Result<Vec<ConfigDiffEntry>, AppError> {
+    let preset = preset_service
+        .read(&name)
+        .await?
+        .ok_or_else(|| AppError::NotFound(name.clone()))?;
+
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+    let response = send_command_parsed(&ip, Commands::backup_config(), timeout)
+        .await
+        .map_err(AppError::from)?;
+    let json = response
+        .json
+        .ok_or_else(|| AppError::Json("No JSON found in backup-config response".to_string()))?;
+    let device = device_config_from_backup_value(json).map_err(AppError::from)?;
+
+    diff_preset(&preset, &device).map_err(AppError::InvalidInput)
+}
@@ -3,3 +3,4 @@
 mod service;
 
 pub use service::ConfigStorageService;
+pub(crate) use service::APP_VERSION;
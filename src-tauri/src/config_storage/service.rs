@@ -3,10 +3,17 @@
 //! Thin wrapper around core's ConfigStorage that gets the path from Tauri's AppHandle.
 
 use crate::error::AppError;
-use crate::types::{DeviceConfig, LocalConfig, LocalConfigInfo};
-use rtls_link_core::storage::ConfigStorage as CoreConfigStorage;
+use crate::types::{
+    ConfigRevision, ConfigSearchResult, DeviceConfig, LocalConfig, LocalConfigInfo,
+};
+use rtls_link_core::config_diff::{diff_configs, ConfigDiffEntry};
+use rtls_link_core::storage::{ConfigLabels, ConfigStorage as CoreConfigStorage};
+use std::path::Path;
 use tauri::{AppHandle, Manager};
 
+/// Application version recorded in exported files
+pub(crate) const APP_VERSION: &str = concat!("rtls-link-manager ", env!("CARGO_PKG_VERSION"));
+
 /// Service for managing local configuration files.
 pub struct ConfigStorageService {
     inner: CoreConfigStorage,
@@ -28,9 +35,20 @@ impl ConfigStorageService {
         Ok(Self { inner })
     }
 
-    /// List all saved configurations.
-    pub async fn list(&self) -> Result<Vec<LocalConfigInfo>, AppError> {
-        self.inner.list().await.map_err(|e| e.into())
+    /// List saved configurations, optionally only those carrying `tag`.
+    pub async fn list(&self, tag: Option<&str>) -> Result<Vec<LocalConfigInfo>, AppError> {
+        match tag {
+            Some(tag) => self.inner.list_with_tag(tag).await.map_err(|e| e.into()),
+            None => self.inner.list().await.map_err(|e| e.into()),
+        }
+    }
+
+    /// Get the listing entry for a configuration.
+    pub async fn info(&self, name: &str) -> Result<LocalConfigInfo, AppError> {
+        self.inner
+            .info(name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(name.to_string()))
     }
 
     /// Read a configuration by name.
@@ -39,11 +57,26 @@ impl ConfigStorageService {
     }
 
     /// Save a configuration.
-    pub async fn save(&self, name: &str, config: DeviceConfig) -> Result<bool, AppError> {
-        self.inner
-            .save(name, &config)
-            .await
-            .map_err(|e| AppError::from(e))?;
+    ///
+    /// Fails with `AlreadyExists` if the name is taken, unless `overwrite`
+    /// is set. Description and tags are replaced when `labels` is given and
+    /// kept otherwise.
+    pub async fn save(
+        &self,
+        name: &str,
+        config: DeviceConfig,
+        labels: Option<ConfigLabels>,
+        overwrite: bool,
+    ) -> Result<bool, AppError> {
+        match labels {
+            Some(labels) => {
+                self.inner
+                    .save_with_labels(name, &config, &labels, overwrite)
+                    .await
+            }
+            None => self.inner.save(name, &config, overwrite).await,
+        }
+        .map_err(|e| AppError::from(e))?;
         Ok(true)
     }
 
@@ -55,6 +88,72 @@ impl ConfigStorageService {
             .map_err(|e| AppError::from(e))?;
         Ok(true)
     }
+
+    /// Rename a configuration.
+    pub async fn rename(&self, old_name: &str, new_name: &str) -> Result<bool, AppError> {
+        self.inner.rename(old_name, new_name).await?;
+        Ok(true)
+    }
+
+    /// Export a configuration to a portable file.
+    pub async fn export(&self, name: &str, path: &Path) -> Result<bool, AppError> {
+        self.inner.export(name, path, APP_VERSION).await?;
+        Ok(true)
+    }
+
+    /// Import a configuration file, returning the name it was saved as.
+    pub async fn import(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        overwrite: bool,
+    ) -> Result<String, AppError> {
+        Ok(self.inner.import(path, name, overwrite).await?)
+    }
+
+    /// Copy a configuration under a new name.
+    pub async fn duplicate(
+        &self,
+        source: &str,
+        target: &str,
+        overwrite: bool,
+    ) -> Result<bool, AppError> {
+        self.inner.duplicate(source, target, overwrite).await?;
+        Ok(true)
+    }
+
+    /// Compare two configurations parameter by parameter.
+    pub async fn diff(&self, name_a: &str, name_b: &str) -> Result<Vec<ConfigDiffEntry>, AppError> {
+        let a = self.read_existing(name_a).await?;
+        let b = self.read_existing(name_b).await?;
+        Ok(diff_configs(&a.config, &b.config))
+    }
+
+    /// Find configurations with parameters matching `query`.
+    pub async fn search(
+        &self,
+        query: &str,
+        regex: bool,
+    ) -> Result<Vec<ConfigSearchResult>, AppError> {
+        Ok(self.inner.search(query, regex).await?)
+    }
+
+    async fn read_existing(&self, name: &str) -> Result<LocalConfig, AppError> {
+        self.read(name)
+            .await?
+            .ok_or_else(|| AppError::NotFound(name.to_string()))
+    }
+
+    /// List previous versions of a configuration, newest first.
+    pub async fn list_revisions(&self, name: &str) -> Result<Vec<ConfigRevision>, AppError> {
+        Ok(self.inner.list_revisions(name).await?)
+    }
+
+    /// Make a previous version of a configuration the current one.
+    pub async fn restore_revision(&self, name: &str, timestamp: &str) -> Result<bool, AppError> {
+        self.inner.restore_revision(name, timestamp).await?;
+        Ok(true)
+    }
 }
 
 #[cfg(test)]
@@ -82,6 +181,7 @@ mod tests {
                 log_udp_port: None,
                 log_serial_enabled: None,
                 log_udp_enabled: None,
+                extra: Default::default(),
             },
             uwb: crate::types::UwbConfig {
                 mode: 4,
@@ -125,10 +225,12 @@ mod tests {
                 anchor_pos_locked: None,
                 distance_avg_samples: None,
                 use_2d_estimator: None,
+                extra: Default::default(),
             },
             app: crate::types::AppConfig {
                 led2_pin: None,
                 led2_state: None,
+                extra: Default::default(),
             },
         }
     }
@@ -138,7 +240,7 @@ mod tests {
         let (service, _tmp) = create_test_service();
         let config = make_config();
 
-        service.save("test-config", &config).await.unwrap();
+        service.save("test-config", &config, false).await.unwrap();
 
         let loaded = service.read("test-config").await.unwrap();
         assert!(loaded.is_some());
@@ -152,9 +254,9 @@ mod tests {
         let (service, _tmp) = create_test_service();
         let config = make_config();
 
-        service.save("alpha", &config).await.unwrap();
-        service.save("beta", &config).await.unwrap();
-        service.save("gamma", &config).await.unwrap();
+        service.save("alpha", &config, false).await.unwrap();
+        service.save("beta", &config, false).await.unwrap();
+        service.save("gamma", &config, false).await.unwrap();
 
         let configs = service.list().await.unwrap();
         assert_eq!(configs.len(), 3);
@@ -168,7 +270,7 @@ mod tests {
         let (service, _tmp) = create_test_service();
         let config = make_config();
 
-        service.save("to-delete", &config).await.unwrap();
+        service.save("to-delete", &config, false).await.unwrap();
         assert!(service.read("to-delete").await.unwrap().is_some());
 
         service.delete("to-delete").await.unwrap();
@@ -182,13 +284,27 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_rename_config() {
+        let (service, _tmp) = create_test_service();
+        let config = make_config();
+
+        service.save("before", &config, false).await.unwrap();
+        service.save("taken", &config, false).await.unwrap();
+
+        assert!(service.rename("before", "taken").await.is_err());
+        service.rename("before", "after").await.unwrap();
+        assert!(service.read("before").await.unwrap().is_none());
+        assert!(service.read("after").await.unwrap().is_some());
+    }
+
     #[tokio::test]
     async fn test_validate_name() {
         let (service, _tmp) = create_test_service();
         let config = make_config();
 
-        assert!(service.save("valid-name", &config).await.is_ok());
-        assert!(service.save("", &config).await.is_err());
-        assert!(service.save("../etc", &config).await.is_err());
+        assert!(service.save("valid-name", &config, false).await.is_ok());
+        assert!(service.save("", &config, false).await.is_err());
+        assert!(service.save("../etc", &config, false).await.is_err());
     }
 }
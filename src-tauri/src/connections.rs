@@ -0,0 +1,215 @@
+//! Device connections kept open across Tauri commands.
+//!
+//! The UI polls devices every few seconds, and opening a new connection for
+//! each command adds work on both ends for every poll. Connections are kept
+//! per IP address and closed after sitting idle, when the device drops out
+//! of discovery, or on request.
+//!
+//! Each device's connection is behind its own lock, so commands from
+//! concurrent frontend calls to one device go out one at a time and cannot
+//! pick up each other's responses.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rtls_link_core::device::mavlink::{DeviceCommandResponse, DeviceConnection, RetryPolicy};
+use rtls_link_core::error::CoreError;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+/// How long an unused connection is kept open
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+struct Slot {
+    conn: Option<DeviceConnection>,
+    last_used: Instant,
+}
+
+/// Open device connections, keyed by IP address
+pub struct ConnectionPool {
+    slots: Mutex<HashMap<String, Arc<Mutex<Slot>>>>,
+    idle_timeout: Duration,
+}
+
+impl ConnectionPool {
+    pub fn new(idle_timeout: Duration) -> Self {
+        Self {
+            slots: Mutex::new(HashMap::new()),
+            idle_timeout,
+        }
+    }
+
+    /// Take the connection to `ip`, waiting while another command uses it
+    ///
+    /// Connections idle for longer than the idle timeout are closed first.
+    pub async fn acquire(&self, ip: &str, cmd_timeout: Duration) -> PooledConnection {
+        let slot = {
+            let mut slots = self.slots.lock().await;
+            slots.retain(|slot_ip, slot| {
+                slot_ip == ip
+                    || slot
+                        .try_lock()
+                        .map_or(true, |slot| slot.last_used.elapsed() < self.idle_timeout)
+            });
+            slots
+                .entry(ip.to_string())
+                .or_insert_with(|| {
+                    Arc::new(Mutex::new(Slot {
+                        conn: None,
+                        last_used: Instant::now(),
+                    }))
+                })
+                .clone()
+        };
+
+        PooledConnection {
+            ip: ip.to_string(),
+            timeout: cmd_timeout,
+            slot: slot.lock_owned().await,
+        }
+    }
+
+    /// Close the connection to `ip`, if one is open
+    ///
+    /// A command in flight finishes on it first.
+    pub async fn close(&self, ip: &str) -> bool {
+        self.slots.lock().await.remove(ip).is_some()
+    }
+
+    /// Close the connections to devices other than those in `ips`
+    pub async fn retain_only<'a>(&self, ips: impl IntoIterator<Item = &'a String>) {
+        let keep: Vec<&String> = ips.into_iter().collect();
+        self.slots.lock().await.retain(|ip, _| keep.contains(&ip));
+    }
+
+    #[cfg(test)]
+    async fn len(&self) -> usize {
+        self.slots.lock().await.len()
+    }
+}
+
+impl Default for ConnectionPool {
+    fn default() -> Self {
+        Self::new(IDLE_TIMEOUT)
+    }
+}
+
+/// Exclusive use of one device's pooled connection
+pub struct PooledConnection {
+    ip: String,
+    timeout: Duration,
+    slot: OwnedMutexGuard<Slot>,
+}
+
+impl PooledConnection {
+    /// Send one command and parse its response
+    ///
+    /// The socket is opened on first use. If a reused socket fails with an
+    /// I/O error, e.g. after the network changed, the command is sent once
+    /// more on a new one; sockets that failed are not kept.
+    pub async fn send(&mut self, command: &str) -> Result<DeviceCommandResponse, CoreError> {
+        self.send_with_timeout(command, None).await
+    }
+
+    /// Like [`PooledConnection::send`], waiting `cmd_timeout` for the
+    /// response instead of the connection's timeout when given
+    pub async fn send_with_timeout(
+        &mut self,
+        command: &str,
+        cmd_timeout: Option<Duration>,
+    ) -> Result<DeviceCommandResponse, CoreError> {
+        let cmd_timeout = cmd_timeout.unwrap_or(self.timeout);
+        let pooled = self.slot.conn.take();
+        let reused = pooled.is_some();
+        let mut conn = match pooled {
+            Some(conn) => conn,
+            None => DeviceConnection::connect(&self.ip, cmd_timeout).await?,
+        };
+        conn.set_timeout(cmd_timeout);
+
+        let mut result = conn.send(command).await;
+        if reused && matches!(result, Err(CoreError::Io(_))) {
+            conn = DeviceConnection::connect(&self.ip, cmd_timeout).await?;
+            result = conn.send(command).await;
+        }
+        if !matches!(result, Err(CoreError::Io(_))) {
+            self.slot.conn = Some(conn);
+        }
+        result
+    }
+
+    /// Like [`PooledConnection::send`], retrying failures `policy` allows
+    ///
+    /// The connection stays taken while waiting to retry, so other commands
+    /// to the device cannot slip in between attempts.
+    pub async fn send_retrying(
+        &mut self,
+        command: &str,
+        policy: RetryPolicy,
+    ) -> Result<DeviceCommandResponse, CoreError> {
+        let mut retry = 0;
+        loop {
+            match self.send(command).await {
+                Ok(mut response) => {
+                    response.attempts = retry + 1;
+                    return Ok(response);
+                }
+                Err(e) if retry < policy.retries && RetryPolicy::is_retryable(&e) => {
+                    tokio::time::sleep(policy.delay(retry)).await;
+                    retry += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        self.slot.last_used = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    #[tokio::test]
+    async fn test_one_command_at_a_time_per_device() {
+        let pool = ConnectionPool::default();
+        let held = pool.acquire("192.168.1.10", TIMEOUT).await;
+
+        let same = tokio::time::timeout(TIMEOUT, pool.acquire("192.168.1.10", TIMEOUT)).await;
+        assert!(same.is_err());
+        let other = tokio::time::timeout(TIMEOUT, pool.acquire("192.168.1.11", TIMEOUT)).await;
+        assert!(other.is_ok());
+
+        drop(held);
+        let same = tokio::time::timeout(TIMEOUT, pool.acquire("192.168.1.10", TIMEOUT)).await;
+        assert!(same.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_idle_and_closed_connections_are_dropped() {
+        let pool = ConnectionPool::new(Duration::ZERO);
+        drop(pool.acquire("192.168.1.10", TIMEOUT).await);
+        let held = pool.acquire("192.168.1.11", TIMEOUT).await;
+        assert_eq!(pool.len().await, 1);
+
+        // Connections in use are never expired
+        drop(pool.acquire("192.168.1.12", TIMEOUT).await);
+        assert_eq!(pool.len().await, 2);
+        drop(held);
+
+        let pool = ConnectionPool::default();
+        for ip in ["192.168.1.10", "192.168.1.11", "192.168.1.12"] {
+            drop(pool.acquire(ip, TIMEOUT).await);
+        }
+        assert!(pool.close("192.168.1.10").await);
+        assert!(!pool.close("192.168.1.10").await);
+        pool.retain_only(&["192.168.1.12".to_string()]).await;
+        assert_eq!(pool.len().await, 1);
+    }
+}
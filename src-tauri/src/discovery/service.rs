@@ -2,6 +2,9 @@
 //!
 //! This service uses the core heartbeat parser and adds Tauri event emission.
 
+use crate::connections::ConnectionPool;
+use crate::logging::service::LogStreamState;
+use crate::settings::SettingsService;
 use crate::types::Device;
 use rtls_link_core::discovery::heartbeat::{parse_heartbeat, prune_stale_devices};
 use rtls_link_core::discovery::service::{create_reusable_socket, DISCOVERY_PORT};
@@ -39,9 +42,15 @@ impl DiscoveryService {
     ///
     /// This continuously receives UDP packets, parses device heartbeats,
     /// updates the shared state, and emits events to the frontend.
+    /// Newly discovered devices get a log stream when auto-streaming is
+    /// enabled; pruned devices keep theirs so streams survive reboots, but
+    /// their pooled connections are closed.
     pub async fn run(
         &mut self,
         devices_state: Arc<RwLock<HashMap<String, Device>>>,
+        log_streams: Arc<RwLock<LogStreamState>>,
+        connections: Arc<ConnectionPool>,
+        settings: Arc<SettingsService>,
         app_handle: AppHandle,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let mut buf = vec![0u8; 1024];
@@ -54,6 +63,10 @@ impl DiscoveryService {
                     let ip = addr.ip().to_string();
 
                     if let Ok(device) = parse_heartbeat(&buf[..len], ip) {
+                        let is_new = !self.devices.contains_key(&device.ip);
+                        if is_new && settings.get().await.should_auto_stream(&device.role) {
+                            log_streams.write().await.auto_start_stream(&device.ip);
+                        }
                         self.devices
                             .insert(device.ip.clone(), (device.clone(), Instant::now()));
                     }
@@ -69,6 +82,9 @@ impl DiscoveryService {
             let before_prune = self.devices.len();
             prune_stale_devices(&mut self.devices);
             let after_prune = self.devices.len();
+            if before_prune != after_prune {
+                connections.retain_only(self.devices.keys()).await;
+            }
 
             if before_prune != after_prune || matches!(recv_result, Ok(Ok(_))) {
                 let mut device_list: Vec<Device> = {
@@ -6,16 +6,19 @@
 
 pub mod commands;
 pub mod config_storage;
+pub mod connections;
 pub mod discovery;
 pub mod error;
 pub mod logging;
 pub mod preset_storage;
+pub mod settings;
 pub mod state;
 pub mod types;
 
 use config_storage::ConfigStorageService;
 use logging::service::{LogReceiverService, LOG_RECEIVER_PORT};
 use preset_storage::PresetStorageService;
+use settings::SettingsService;
 use state::AppState;
 use std::sync::Arc;
 use tauri::Manager;
@@ -37,17 +40,34 @@ pub fn run() {
             let preset_service = PresetStorageService::new(&app_handle)
                 .expect("Failed to initialize preset storage");
 
+            // Initialize backend settings
+            let settings_service =
+                Arc::new(SettingsService::new(&app_handle).expect("Failed to initialize settings"));
+
             // Setup app state
             let app_state = AppState::new();
             let devices_clone = app_state.devices.clone();
             let log_streams_clone = app_state.log_streams.clone();
+            let discovery_log_streams = app_state.log_streams.clone();
+            let discovery_connections = app_state.connections.clone();
+            let log_receiver_clone = app_state.log_receiver.clone();
 
             // Spawn discovery service
             let app_handle_clone = app_handle.clone();
+            let settings_clone = settings_service.clone();
             tauri::async_runtime::spawn(async move {
                 match discovery::DiscoveryService::new().await {
                     Ok(mut service) => {
-                        if let Err(e) = service.run(devices_clone, app_handle_clone).await {
+                        if let Err(e) = service
+                            .run(
+                                devices_clone,
+                                discovery_log_streams,
+                                discovery_connections,
+                                settings_clone,
+                                app_handle_clone,
+                            )
+                            .await
+                        {
                             eprintln!("Discovery service error: {}", e);
                         }
                     }
@@ -57,14 +77,19 @@ pub fn run() {
                 }
             });
 
-            // Spawn log receiver service
+            // Spawn log receiver service on the persisted port (if any)
             let app_handle_clone = app_handle.clone();
+            let settings_clone = settings_service.clone();
             tauri::async_runtime::spawn(async move {
-                match LogReceiverService::new(LOG_RECEIVER_PORT).await {
+                let log_port = settings_clone
+                    .get()
+                    .await
+                    .log_receiver_port
+                    .unwrap_or(LOG_RECEIVER_PORT);
+                match LogReceiverService::new(log_port).await {
                     Ok(service) => {
-                        if let Err(e) = service.run(log_streams_clone, app_handle_clone).await {
-                            eprintln!("Log receiver service error: {}", e);
-                        }
+                        let handle = service.spawn(log_streams_clone, app_handle_clone);
+                        *log_receiver_clone.write().await = Some(handle);
                     }
                     Err(e) => {
                         eprintln!("Failed to start log receiver service: {}", e);
@@ -76,6 +101,7 @@ pub fn run() {
             app.manage(app_state);
             app.manage(Arc::new(config_service));
             app.manage(Arc::new(preset_service));
+            app.manage(settings_service);
 
             Ok(())
         })
@@ -87,28 +113,73 @@ pub fn run() {
             commands::configs::get_config,
             commands::configs::save_config,
             commands::configs::delete_config,
-            commands::configs::backup_device_config_to_local,
+            commands::configs::rename_config,
+            commands::configs::duplicate_config,
+            commands::configs::export_config,
+            commands::configs::import_config,
+            commands::configs::diff_configs,
+            commands::configs::search_configs,
+            commands::configs::validate_config_file,
+            commands::configs::get_param_registry,
+            commands::configs::list_config_revisions,
+            commands::configs::restore_config_revision,
+            commands::configs::backup_device_to_config,
+            commands::data::export_all,
+            commands::data::import_all,
             commands::presets::list_presets,
             commands::presets::get_preset,
             commands::presets::save_preset,
+            commands::presets::preview_preset,
+            commands::presets::create_preset_from_template,
+            commands::presets::import_anchors_csv,
+            commands::presets::repair_presets,
             commands::presets::delete_preset,
+            commands::presets::rename_preset,
+            commands::presets::duplicate_preset,
+            commands::presets::convert_preset,
+            commands::presets::merge_presets,
+            commands::presets::export_preset,
+            commands::presets::import_preset,
             commands::presets::backup_device_preset,
+            commands::presets::diff_preset_against_device,
             commands::device_comm::send_device_command,
             commands::device_comm::send_device_commands,
             commands::device_comm::run_bulk_device_command,
+            commands::device_comm::send_command_to_devices,
             commands::device_comm::apply_config_to_devices,
             commands::device_comm::activate_config_on_devices,
             commands::device_comm::upload_preset_to_devices,
+            commands::device_comm::apply_preset_to_devices,
             commands::device_comm::run_antenna_calibration,
+            commands::device_comm::get_device_endpoints,
+            commands::device_comm::set_device_endpoint,
+            commands::device_comm::get_devices_with_credentials,
+            commands::device_comm::set_device_credentials,
+            commands::device_comm::reset_device_credentials,
             commands::device_comm::upload_firmware_from_file,
             commands::device_comm::upload_firmware_to_devices,
             commands::device_comm::cancel_firmware_upload,
+            commands::device_comm::cancel_operation,
             commands::device_comm::get_firmware_info,
+            commands::device_comm::get_device_capabilities,
+            commands::device_comm::close_device_connection,
             commands::logging::start_log_stream,
             commands::logging::stop_log_stream,
+            commands::logging::update_log_stream_filters,
             commands::logging::get_active_log_streams,
+            commands::logging::pause_log_emissions,
+            commands::logging::resume_log_emissions,
             commands::logging::get_buffered_logs,
+            commands::logging::search_buffered_logs,
             commands::logging::clear_buffered_logs,
+            commands::logging::clear_all_buffered_logs,
+            commands::logging::get_log_stats,
+            commands::logging::set_log_forwarding,
+            commands::logging::get_log_forwarding_status,
+            commands::logging::restart_log_receiver,
+            commands::logging::get_log_receiver_status,
+            commands::logging::set_auto_stream_logs,
+            commands::logging::get_auto_stream_logs,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
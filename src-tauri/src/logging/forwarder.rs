@@ -0,0 +1,287 @@
+//! Forwarding of received device logs to an external collector.
+//!
+//! Every parsed log message can be re-serialized as NDJSON or RFC 5424
+//! syslog and sent over UDP to a central collector (syslog, Vector, ...).
+//! Messages are handed to a dedicated sender task through a bounded
+//! channel so the receive loop never blocks; when the channel backs up,
+//! messages are dropped and counted.
+
+use super::service::LogMessage;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rtls_link_core::types::{LogLevel, MessageLevel};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+/// Maximum number of messages waiting to be forwarded
+const FORWARD_QUEUE_CAPACITY: usize = 1024;
+
+/// Syslog facility used for forwarded messages (local0)
+const SYSLOG_FACILITY: u8 = 16;
+
+/// Wire format for forwarded logs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForwardFormat {
+    /// One JSON `LogMessage` per datagram, newline-terminated
+    Ndjson,
+    /// RFC 5424 syslog with the device IP as hostname
+    Syslog,
+}
+
+/// Current forwarding configuration and counters
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogForwardingStatus {
+    /// Whether forwarding is enabled
+    pub enabled: bool,
+    /// Collector address (None when disabled)
+    pub target: Option<String>,
+    /// Wire format (None when disabled)
+    pub format: Option<ForwardFormat>,
+    /// Messages sent to the collector
+    pub forwarded: u64,
+    /// Messages dropped because the queue was full
+    pub dropped: u64,
+    /// Messages that failed to send
+    pub send_errors: u64,
+}
+
+impl LogForwardingStatus {
+    /// Status reported when forwarding is disabled
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            target: None,
+            format: None,
+            forwarded: 0,
+            dropped: 0,
+            send_errors: 0,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ForwardCounters {
+    forwarded: AtomicU64,
+    dropped: AtomicU64,
+    send_errors: AtomicU64,
+}
+
+/// Forwarding sink feeding a background UDP sender task
+///
+/// The sender task exits once the forwarder is dropped.
+#[derive(Debug)]
+pub struct LogForwarder {
+    target: SocketAddr,
+    format: ForwardFormat,
+    sender: mpsc::Sender<LogMessage>,
+    counters: Arc<ForwardCounters>,
+}
+
+impl LogForwarder {
+    /// Start forwarding to `target` in the given format
+    pub async fn start(target: SocketAddr, format: ForwardFormat) -> std::io::Result<Self> {
+        Self::with_capacity(target, format, FORWARD_QUEUE_CAPACITY).await
+    }
+
+    async fn with_capacity(
+        target: SocketAddr,
+        format: ForwardFormat,
+        capacity: usize,
+    ) -> std::io::Result<Self> {
+        let bind_addr: SocketAddr = if target.is_ipv4() {
+            "0.0.0.0:0".parse().unwrap()
+        } else {
+            "[::]:0".parse().unwrap()
+        };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        let (sender, receiver) = mpsc::channel(capacity);
+        let counters = Arc::new(ForwardCounters::default());
+
+        tauri::async_runtime::spawn(run_sender(
+            socket,
+            target,
+            format,
+            receiver,
+            counters.clone(),
+        ));
+
+        Ok(Self {
+            target,
+            format,
+            sender,
+            counters,
+        })
+    }
+
+    /// Queue a message for forwarding without waiting
+    ///
+    /// Drops and counts the message if the queue is full.
+    pub fn forward(&self, log: &LogMessage) {
+        if self.sender.try_send(log.clone()).is_err() {
+            self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Snapshot the forwarding configuration and counters
+    pub fn status(&self) -> LogForwardingStatus {
+        LogForwardingStatus {
+            enabled: true,
+            target: Some(self.target.to_string()),
+            format: Some(self.format),
+            forwarded: self.counters.forwarded.load(Ordering::Relaxed),
+            dropped: self.counters.dropped.load(Ordering::Relaxed),
+            send_errors: self.counters.send_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+async fn run_sender(
+    socket: UdpSocket,
+    target: SocketAddr,
+    format: ForwardFormat,
+    mut receiver: mpsc::Receiver<LogMessage>,
+    counters: Arc<ForwardCounters>,
+) {
+    while let Some(log) = receiver.recv().await {
+        let payload = match format {
+            ForwardFormat::Ndjson => format_ndjson(&log),
+            ForwardFormat::Syslog => format_syslog(&log),
+        };
+
+        match socket.send_to(payload.as_bytes(), target).await {
+            Ok(_) => counters.forwarded.fetch_add(1, Ordering::Relaxed),
+            Err(_) => counters.send_errors.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+}
+
+/// Serialize a log message as a single NDJSON line
+pub fn format_ndjson(log: &LogMessage) -> String {
+    let mut line = serde_json::to_string(log).unwrap_or_else(|_| "{}".to_string());
+    line.push('\n');
+    line
+}
+
+/// Serialize a log message as an RFC 5424 syslog line
+///
+/// The device IP is used as HOSTNAME and the log tag as MSGID.
+pub fn format_syslog(log: &LogMessage) -> String {
+    let priority = SYSLOG_FACILITY * 8 + syslog_severity(&log.lvl);
+    let timestamp =
+        DateTime::<Utc>::from_timestamp_millis(log.wall_ts.unwrap_or(log.received_at) as i64)
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Millis, true))
+            .unwrap_or_else(|| "-".to_string());
+
+    format!(
+        "<{}>1 {} {} rtls-link - {} - {}",
+        priority,
+        timestamp,
+        syslog_field(&log.device_ip, 255),
+        syslog_field(&log.tag, 32),
+        log.msg
+    )
+}
+
+fn syslog_severity(level: &MessageLevel) -> u8 {
+    match level.level() {
+        Some(LogLevel::Error) => 3,
+        Some(LogLevel::Warn) => 4,
+        Some(LogLevel::Info) => 6,
+        Some(LogLevel::Debug) | Some(LogLevel::Verbose) => 7,
+        Some(LogLevel::None) | None => 5,
+    }
+}
+
+/// Header fields must be printable ASCII without spaces, or "-" if empty
+fn syslog_field(value: &str, max_len: usize) -> String {
+    let field: String = value
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(max_len)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_log() -> LogMessage {
+        LogMessage {
+            device_ip: "192.168.1.20".to_string(),
+            ts: 1_000,
+            lvl: MessageLevel::Known(LogLevel::Warn),
+            tag: "uwb.ranging".to_string(),
+            msg: "anchor 3 timeout".to_string(),
+            received_at: 1_700_000_000_123,
+            seq: None,
+            wall_ts: None,
+            repeat_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_format_syslog() {
+        let line = format_syslog(&sample_log());
+
+        assert_eq!(
+            line,
+            "<132>1 2023-11-14T22:13:20.123Z 192.168.1.20 rtls-link - uwb.ranging - anchor 3 timeout"
+        );
+    }
+
+    #[test]
+    fn test_format_syslog_sanitizes_header_fields() {
+        let mut log = sample_log();
+        log.tag = String::new();
+        log.lvl = MessageLevel::parse("trace");
+
+        let line = format_syslog(&log);
+
+        assert!(line.starts_with("<133>1 "));
+        assert!(line.contains(" rtls-link - - - "));
+    }
+
+    #[tokio::test]
+    async fn test_forward_ndjson_over_udp() {
+        let collector = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = collector.local_addr().unwrap();
+        let forwarder = LogForwarder::start(target, ForwardFormat::Ndjson)
+            .await
+            .unwrap();
+
+        forwarder.forward(&sample_log());
+
+        let mut buf = [0u8; 1024];
+        let (len, _) = collector.recv_from(&mut buf).await.unwrap();
+        let line = std::str::from_utf8(&buf[..len]).unwrap();
+        assert!(line.ends_with('\n'));
+        let json: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(json["deviceIp"], "192.168.1.20");
+        assert_eq!(json["lvl"], "warn");
+    }
+
+    #[tokio::test]
+    async fn test_forward_counts_drops_when_queue_is_full() {
+        let target: SocketAddr = "127.0.0.1:9".parse().unwrap();
+        let forwarder = LogForwarder::with_capacity(target, ForwardFormat::Ndjson, 1)
+            .await
+            .unwrap();
+
+        // The sender task cannot run between these calls on a current-thread runtime
+        for _ in 0..5 {
+            forwarder.forward(&sample_log());
+        }
+
+        assert_eq!(forwarder.status().dropped, 4);
+    }
+}
@@ -3,6 +3,7 @@
 //! This module provides a service that listens for binary log messages
 //! from devices over UDP and emits them to the frontend for display.
 
+pub mod forwarder;
 pub mod service;
 
 pub use service::LogReceiverService;
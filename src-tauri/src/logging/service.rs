@@ -4,11 +4,18 @@
 //! them to the frontend via Tauri events. Buffers logs per device so
 //! they can be retrieved even if the log terminal wasn't open.
 
-use rtls_link_core::protocol::binary::decode_log_message;
+use super::forwarder::LogForwarder;
+use rtls_link_core::discovery::service::create_exclusive_socket;
+use rtls_link_core::log_clock::WallClockOffset;
+use rtls_link_core::log_filter::TagFilter;
+use rtls_link_core::log_packet::parse_log_packet as core_parse_log_packet;
+use rtls_link_core::log_stats::{LogStatsSnapshot, LogStatsTracker};
+use rtls_link_core::types::{LogLevel, LogMessage as CoreLogMessage, MessageLevel};
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter};
 use tokio::net::UdpSocket;
 use tokio::sync::RwLock;
@@ -19,6 +26,42 @@ pub const LOG_RECEIVER_PORT: u16 = 3334;
 /// Maximum number of logs to buffer per device
 const MAX_LOGS_PER_DEVICE: usize = 500;
 
+/// Maximum UDP payload size (batched packets can exceed a single log line)
+const MAX_PACKET_SIZE: usize = 65_507;
+
+/// Minimum fraction of printable characters for an unstructured packet to
+/// be kept as raw text (bootloader output) rather than discarded
+const MIN_PRINTABLE_RATIO: f64 = 0.9;
+
+/// Level of messages stored from plain-text packets
+const RAW_LOG_LEVEL: &str = "RAW";
+
+/// Tag of messages stored from plain-text packets
+pub const RAW_LOG_TAG: &str = "raw";
+
+/// Maximum repeats collapsed into one buffered entry before a new one starts
+const MAX_REPEAT_COUNT: u32 = 1000;
+
+/// Sequence numbers at or below this value after a drop are treated as a reboot
+const REBOOT_SEQ_WINDOW: u32 = 16;
+
+/// Largest drop below the sequence watermark still treated as a reordered
+/// packet rather than a reboot
+const REORDER_SEQ_WINDOW: u32 = 64;
+
+/// Largest step back in device uptime still treated as a reordered packet
+/// rather than a reboot
+const REORDER_TS_WINDOW_MS: u64 = 5_000;
+
+/// Hard cap on the number of matches returned by a buffer search
+const MAX_SEARCH_RESULTS: usize = 1000;
+
+/// Minimum interval between activity notifications for a device
+const ACTIVITY_NOTIFY_INTERVAL_MS: u64 = 1000;
+
+/// Tag used for messages synthesized by the receiver itself
+pub const LOG_RECEIVER_TAG: &str = "log-receiver";
+
 /// A log message received from a device
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,48 +70,441 @@ pub struct LogMessage {
     pub device_ip: String,
     /// Timestamp in milliseconds (from device)
     pub ts: u64,
-    /// Log level string (ERROR, WARN, INFO, DEBUG, VERBOSE)
-    pub lvl: String,
+    /// Log level (serialized lowercase: error, warn, info, debug, verbose)
+    pub lvl: MessageLevel,
     /// Tag/module name
     pub tag: String,
     /// Log message content
     pub msg: String,
     /// Receive timestamp (local)
     pub received_at: u64,
+    /// Per-device sequence number (firmware that supports gap detection)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seq: Option<u32>,
+    /// Device timestamp aligned to local wall-clock time (ms since epoch)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wall_ts: Option<u64>,
+    /// Number of identical consecutive messages collapsed into this entry
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+}
+
+fn default_repeat_count() -> u32 {
+    1
+}
+
+/// Stream and buffer summary for a single device
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStreamInfo {
+    /// Device IP address
+    pub device_ip: String,
+    /// Whether logs from this device are forwarded to the frontend
+    pub active: bool,
+    /// Number of messages currently buffered
+    pub buffered_count: usize,
+    /// Local receive timestamp of the most recent message (ms)
+    pub last_message_at: Option<u64>,
+    /// Messages evicted from the buffer because it was full
+    pub dropped_count: u64,
+    /// Whether all log emissions to the frontend are paused
+    pub paused: bool,
+    /// Tag globs applied to emitted logs (empty means all tags)
+    pub tag_filters: Vec<String>,
+    /// Whether the stream was started automatically on discovery
+    pub auto_started: bool,
+}
+
+/// Options for searching buffered logs
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LogSearchOptions {
+    /// Limit the search to one device (all buffers when None)
+    pub device_ip: Option<String>,
+    /// Treat the query as a regular expression instead of a substring
+    pub regex: bool,
+    /// Only match messages at this level or more severe
+    pub min_level: Option<MessageLevel>,
+    /// Only match messages whose tag matches one of these globs
+    pub tags: Vec<String>,
+    /// Maximum matches to return (capped at 1000)
+    pub max_results: Option<usize>,
+}
+
+/// A buffered log message matching a search
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchMatch {
+    /// Device the message was buffered for
+    pub device_ip: String,
+    /// Position of the message in the device's buffer (0 = oldest)
+    pub index: usize,
+    /// The matching message
+    pub log: LogMessage,
+}
+
+/// Result of searching buffered logs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogSearchResult {
+    /// Matches ordered by device IP, then buffer position
+    pub matches: Vec<LogSearchMatch>,
+    /// Whether more matches exist than were returned
+    pub truncated: bool,
+}
+
+/// Notification that logs were buffered for a device without an active stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogActivity {
+    /// Device IP address
+    pub device_ip: String,
+    /// Most severe level seen since the last notification
+    pub level: MessageLevel,
+    /// Messages buffered since the last notification
+    pub count: u64,
+}
+
+/// Activity accumulated for a device between notifications
+#[derive(Debug, Clone, Default)]
+pub struct PendingActivity {
+    count: u64,
+    level: Option<MessageLevel>,
+    last_notified_at: Option<u64>,
+}
+
+/// Receiver statistics for a single device
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceLogStats {
+    /// Device IP address
+    pub device_ip: String,
+    /// Messages detected as lost through sequence number gaps
+    pub lost_messages: u64,
+    /// Per-level counts over the buffer and the last minute
+    #[serde(flatten)]
+    pub levels: LogStatsSnapshot,
 }
 
-/// Legacy JSON format from older firmware.
-#[derive(Debug, Deserialize)]
-struct RawLogMessage {
-    ts: u64,
-    lvl: String,
-    tag: String,
-    msg: String,
+/// Receiver statistics across all devices
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogStats {
+    /// Number of packets or batch elements that were discarded as unparseable
+    pub parse_failures: u64,
+    /// Number of plain-text lines stored as raw messages
+    pub raw_messages: u64,
+    /// Per-device statistics, sorted by IP
+    pub devices: Vec<DeviceLogStats>,
 }
 
 /// State for tracking active log streams and buffered logs
 #[derive(Debug, Default)]
 pub struct LogStreamState {
-    /// Set of device IPs we're actively streaming logs from (for UI display)
-    pub active_streams: HashMap<String, bool>,
+    /// Device IPs we're actively streaming logs from, with their tag filters
+    pub active_streams: HashMap<String, TagFilter>,
+    /// Active streams that were started automatically rather than by the user
+    pub auto_started: HashSet<String>,
     /// Buffered logs per device (ring buffer)
     pub log_buffers: HashMap<String, VecDeque<LogMessage>>,
+    /// Number of packets or batch elements that were discarded as unparseable
+    pub parse_failures: u64,
+    /// Number of plain-text lines stored as raw messages
+    pub raw_messages: u64,
+    /// Last sequence number seen per device
+    pub last_seq: HashMap<String, u32>,
+    /// Device timestamp of the message that set each device's `last_seq`
+    pub last_seq_ts: HashMap<String, u64>,
+    /// Messages detected as lost per device
+    pub lost_messages: HashMap<String, u64>,
+    /// Messages evicted from full buffers per device
+    pub dropped_counts: HashMap<String, u64>,
+    /// Local receive timestamp of the most recent message per device
+    pub last_message_at: HashMap<String, u64>,
+    /// Incremental per-level counters per device
+    pub level_stats: HashMap<String, LogStatsTracker>,
+    /// When set, logs are still buffered but not emitted to the frontend
+    pub emissions_paused: bool,
+    /// Offset between device uptime and wall-clock time per device
+    pub clock_offsets: HashMap<String, WallClockOffset>,
+    /// Throttled activity notifications for devices without an active stream
+    pub activity: HashMap<String, PendingActivity>,
+    /// Optional sink forwarding every received message to a collector
+    pub forwarder: Option<LogForwarder>,
 }
 
 impl LogStreamState {
+    /// Record an incoming log message, buffering it along with a gap marker
+    /// if its sequence number shows that messages were dropped.
+    ///
+    /// Returns the messages to forward to the frontend, in order.
+    pub fn ingest(&mut self, device_ip: &str, mut log: LogMessage) -> Vec<LogMessage> {
+        log.wall_ts = if log.tag == RAW_LOG_TAG {
+            // Raw lines carry no uptime; don't let them reset the clock offset
+            Some(log.received_at)
+        } else {
+            let clock = self.clock_offsets.entry(device_ip.to_string()).or_default();
+            Some(clock.observe(log.ts, log.received_at))
+        };
+
+        let mut messages = Vec::with_capacity(2);
+        if let Some(marker) = self.check_seq(device_ip, &log) {
+            self.add_log(device_ip, marker.clone());
+            messages.push(marker);
+        }
+        messages.push(self.add_log(device_ip, log));
+        messages
+    }
+
+    /// Track the sequence number of a message, returning a synthetic
+    /// warning when a gap is detected.
+    fn check_seq(&mut self, device_ip: &str, log: &LogMessage) -> Option<LogMessage> {
+        let seq = log.seq?;
+        let last_ts = self.last_seq_ts.insert(device_ip.to_string(), log.ts);
+        let last = self.last_seq.insert(device_ip.to_string(), seq)?;
+
+        if seq <= last {
+            // A restart near zero, a fall far below the watermark or uptime
+            // going back means the device rebooted, even if its first
+            // messages since were lost; anything else is a duplicate or
+            // reordered packet, so keep the higher watermark.
+            let rebooted = seq <= REBOOT_SEQ_WINDOW
+                || last - seq > REORDER_SEQ_WINDOW
+                || last_ts
+                    .is_some_and(|last_ts| log.ts.saturating_add(REORDER_TS_WINDOW_MS) < last_ts);
+            if !rebooted {
+                self.last_seq.insert(device_ip.to_string(), last);
+                if let Some(last_ts) = last_ts {
+                    self.last_seq_ts.insert(device_ip.to_string(), last_ts);
+                }
+            }
+            return None;
+        }
+
+        let lost = seq - last - 1;
+        if lost == 0 {
+            return None;
+        }
+
+        *self.lost_messages.entry(device_ip.to_string()).or_insert(0) += lost as u64;
+
+        Some(LogMessage {
+            device_ip: device_ip.to_string(),
+            ts: log.ts,
+            lvl: MessageLevel::Known(LogLevel::Warn),
+            tag: LOG_RECEIVER_TAG.to_string(),
+            msg: format!("{} messages lost", lost),
+            received_at: log.received_at,
+            seq: None,
+            wall_ts: log.wall_ts,
+            repeat_count: 1,
+        })
+    }
+
+    /// Search buffered logs with a case-insensitive substring or regex query
+    ///
+    /// The query is matched against the message text and tag. Returns an
+    /// error if `options.regex` is set and the query is not a valid regex.
+    pub fn search(
+        &self,
+        query: &str,
+        options: &LogSearchOptions,
+    ) -> Result<LogSearchResult, regex::Error> {
+        let pattern = if options.regex {
+            regex::RegexBuilder::new(query)
+                .case_insensitive(true)
+                .build()?
+        } else {
+            regex::RegexBuilder::new(&regex::escape(query))
+                .case_insensitive(true)
+                .build()?
+        };
+        let tag_filter = TagFilter::new(options.tags.iter().cloned());
+        let limit = options
+            .max_results
+            .unwrap_or(MAX_SEARCH_RESULTS)
+            .min(MAX_SEARCH_RESULTS);
+
+        let buffers = self
+            .log_buffers
+            .iter()
+            .filter(|(ip, _)| {
+                options
+                    .device_ip
+                    .as_ref()
+                    .is_none_or(|filter| filter == *ip)
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut matches = Vec::new();
+        let mut truncated = false;
+        'devices: for (device_ip, buffer) in buffers {
+            for (index, log) in buffer.iter().enumerate() {
+                if options.min_level.as_ref().is_some_and(|min| &log.lvl > min)
+                    || !tag_filter.matches(&log.tag)
+                    || !(pattern.is_match(&log.msg) || pattern.is_match(&log.tag))
+                {
+                    continue;
+                }
+                if matches.len() == limit {
+                    truncated = true;
+                    break 'devices;
+                }
+                matches.push(LogSearchMatch {
+                    device_ip: device_ip.clone(),
+                    index,
+                    log: log.clone(),
+                });
+            }
+        }
+
+        Ok(LogSearchResult { matches, truncated })
+    }
+
+    /// Record a buffered message for activity notifications
+    ///
+    /// Only devices without an active stream are tracked. Returns a
+    /// notification at most once per second per device, carrying the count
+    /// and most severe level since the previous one. Messages that arrive
+    /// within the interval are reported with the next notification.
+    pub fn track_activity(&mut self, device_ip: &str, log: &LogMessage) -> Option<LogActivity> {
+        if self.is_active(device_ip) {
+            self.activity.remove(device_ip);
+            return None;
+        }
+
+        let pending = self.activity.entry(device_ip.to_string()).or_default();
+        pending.count += 1;
+        if pending
+            .level
+            .as_ref()
+            .is_none_or(|level| severity(&log.lvl) < severity(level))
+        {
+            pending.level = Some(log.lvl.clone());
+        }
+
+        let now = log.received_at;
+        if pending
+            .last_notified_at
+            .is_some_and(|last| now.saturating_sub(last) < ACTIVITY_NOTIFY_INTERVAL_MS)
+        {
+            return None;
+        }
+
+        pending.last_notified_at = Some(now);
+        Some(LogActivity {
+            device_ip: device_ip.to_string(),
+            level: pending.level.take().unwrap_or_else(|| log.lvl.clone()),
+            count: std::mem::take(&mut pending.count),
+        })
+    }
+
+    /// Summarize every device that has an active stream or buffered logs,
+    /// sorted by IP
+    pub fn stream_info(&self) -> Vec<LogStreamInfo> {
+        self.active_streams
+            .keys()
+            .chain(
+                self.log_buffers
+                    .iter()
+                    .filter(|(_, buffer)| !buffer.is_empty())
+                    .map(|(ip, _)| ip),
+            )
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|ip| LogStreamInfo {
+                device_ip: ip.clone(),
+                active: self.is_active(ip),
+                buffered_count: self.log_buffers.get(ip).map_or(0, VecDeque::len),
+                last_message_at: self.last_message_at.get(ip).copied(),
+                dropped_count: self.dropped_counts.get(ip).copied().unwrap_or(0),
+                paused: self.emissions_paused,
+                tag_filters: self
+                    .active_streams
+                    .get(ip)
+                    .map(|filter| filter.patterns().to_vec())
+                    .unwrap_or_default(),
+                auto_started: self.auto_started.contains(ip),
+            })
+            .collect()
+    }
+
+    /// Snapshot the receiver statistics as of `now_ms`, optionally limited
+    /// to a single device
+    pub fn stats(&self, device_ip: Option<&str>, now_ms: u64) -> LogStats {
+        let devices: Vec<DeviceLogStats> = self
+            .level_stats
+            .keys()
+            .chain(self.lost_messages.keys())
+            .filter(|ip| device_ip.is_none_or(|filter| filter == ip.as_str()))
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .map(|ip| DeviceLogStats {
+                device_ip: ip.clone(),
+                lost_messages: self.lost_messages.get(ip).copied().unwrap_or(0),
+                levels: self
+                    .level_stats
+                    .get(ip)
+                    .map(|tracker| tracker.snapshot(now_ms))
+                    .unwrap_or_else(|| LogStatsTracker::new().snapshot(now_ms)),
+            })
+            .collect();
+
+        LogStats {
+            parse_failures: self.parse_failures,
+            raw_messages: self.raw_messages,
+            devices,
+        }
+    }
+
     /// Add a log message to the device's buffer
-    pub fn add_log(&mut self, device_ip: &str, log: LogMessage) {
+    ///
+    /// A message identical in level, tag and text to the last buffered one
+    /// is collapsed into it by bumping its `repeat_count`, up to
+    /// `MAX_REPEAT_COUNT`. Gap markers are buffered but left out of the
+    /// per-level counts, as the device never sent them. Returns the
+    /// buffered entry as stored.
+    pub fn add_log(&mut self, device_ip: &str, log: LogMessage) -> LogMessage {
         let buffer = self
             .log_buffers
             .entry(device_ip.to_string())
             .or_insert_with(|| VecDeque::with_capacity(MAX_LOGS_PER_DEVICE));
 
+        let level_stats = self.level_stats.entry(device_ip.to_string()).or_default();
+        if !is_gap_marker(&log) {
+            level_stats.record(level_of(&log), log.received_at);
+        }
+
+        self.last_message_at
+            .insert(device_ip.to_string(), log.received_at);
+
+        if let Some(last) = buffer.back_mut() {
+            if last.repeat_count < MAX_REPEAT_COUNT
+                && last.lvl == log.lvl
+                && last.tag == log.tag
+                && last.msg == log.msg
+            {
+                last.repeat_count += 1;
+                last.received_at = log.received_at;
+                return last.clone();
+            }
+        }
+
         // Remove oldest if at capacity
         if buffer.len() >= MAX_LOGS_PER_DEVICE {
-            buffer.pop_front();
+            if let Some(evicted) = buffer.pop_front() {
+                if !is_gap_marker(&evicted) {
+                    level_stats.evict(level_of(&evicted), evicted.repeat_count as u64);
+                }
+                *self
+                    .dropped_counts
+                    .entry(device_ip.to_string())
+                    .or_insert(0) += evicted.repeat_count as u64;
+            }
         }
 
-        buffer.push_back(log);
+        buffer.push_back(log.clone());
+        log
     }
 
     /// Get buffered logs for a device
@@ -84,25 +520,158 @@ impl LogStreamState {
         if let Some(buffer) = self.log_buffers.get_mut(device_ip) {
             buffer.clear();
         }
+        if let Some(level_stats) = self.level_stats.get_mut(device_ip) {
+            level_stats.reset_totals();
+        }
+    }
+
+    /// Clear buffered logs for every device
+    ///
+    /// When `known_devices` is given, buffers for devices outside that set
+    /// are removed entirely instead of just emptied. Returns the number of
+    /// messages discarded per device.
+    pub fn clear_all_logs(
+        &mut self,
+        known_devices: Option<&HashSet<String>>,
+    ) -> HashMap<String, usize> {
+        let discarded = self
+            .log_buffers
+            .iter_mut()
+            .map(|(device_ip, buffer)| {
+                let count = buffer.len();
+                buffer.clear();
+                (device_ip.clone(), count)
+            })
+            .collect();
+        for level_stats in self.level_stats.values_mut() {
+            level_stats.reset_totals();
+        }
+
+        if let Some(known) = known_devices {
+            self.log_buffers
+                .retain(|device_ip, _| known.contains(device_ip));
+            self.level_stats
+                .retain(|device_ip, _| known.contains(device_ip));
+        }
+
+        discarded
+    }
+
+    /// Start (or take over) a user-requested stream for a device
+    pub fn start_stream(&mut self, device_ip: &str, filter: TagFilter) {
+        self.active_streams.insert(device_ip.to_string(), filter);
+        self.auto_started.remove(device_ip);
+    }
+
+    /// Start a stream for a newly discovered device
+    ///
+    /// Streams that are already active are left untouched, so a stream the
+    /// user opened is never marked as auto-started. Returns whether a new
+    /// stream was started.
+    pub fn auto_start_stream(&mut self, device_ip: &str) -> bool {
+        if self.is_active(device_ip) {
+            return false;
+        }
+        self.active_streams
+            .insert(device_ip.to_string(), TagFilter::default());
+        self.auto_started.insert(device_ip.to_string());
+        true
+    }
+
+    /// Stop streaming logs from a device, however the stream was started
+    pub fn stop_stream(&mut self, device_ip: &str) {
+        self.active_streams.remove(device_ip);
+        self.auto_started.remove(device_ip);
+    }
+
+    /// Stop every auto-started stream, keeping the ones the user opened
+    ///
+    /// Returns the IPs whose streams were stopped.
+    pub fn stop_auto_started_streams(&mut self) -> Vec<String> {
+        let stopped: Vec<String> = self.auto_started.drain().collect();
+        for ip in &stopped {
+            self.active_streams.remove(ip);
+        }
+        stopped
     }
 
     /// Check if a device stream is active
     pub fn is_active(&self, device_ip: &str) -> bool {
-        self.active_streams.get(device_ip).copied().unwrap_or(false)
+        self.active_streams.contains_key(device_ip)
+    }
+
+    /// Check if a log message should be emitted to the frontend
+    ///
+    /// The device stream must be active and not paused, and the tag must
+    /// pass the stream's filters. Receiver markers always pass the filters.
+    pub fn should_emit(&self, device_ip: &str, tag: &str) -> bool {
+        if self.emissions_paused {
+            return false;
+        }
+        self.active_streams
+            .get(device_ip)
+            .is_some_and(|filter| tag == LOG_RECEIVER_TAG || filter.matches(tag))
+    }
+}
+
+/// Handle to a running log receiver task
+pub struct LogReceiverHandle {
+    /// UDP port the receiver is bound to
+    pub port: u16,
+    task: JoinHandle<()>,
+}
+
+impl LogReceiverHandle {
+    /// Check whether the receiver task is still running
+    pub fn is_running(&self) -> bool {
+        !self.task.inner().is_finished()
+    }
+
+    /// Cancel the receiver task and wait for it to release its socket
+    pub async fn stop(self) {
+        self.task.abort();
+        let _ = self.task.await;
     }
 }
 
 /// Log receiver service that listens for device logs over UDP
 pub struct LogReceiverService {
     socket: UdpSocket,
+    port: u16,
 }
 
 impl LogReceiverService {
     /// Create a new log receiver service bound to the specified port
+    ///
+    /// The socket does not share its port: another receiver holding it is
+    /// reported as a bind error rather than silently splitting the device
+    /// logs with it.
     pub async fn new(port: u16) -> Result<Self, std::io::Error> {
-        let socket = UdpSocket::bind(("0.0.0.0", port)).await?;
+        let std_socket = create_exclusive_socket(port)?;
+        let socket = UdpSocket::from_std(std_socket)?;
+        let port = socket.local_addr()?.port();
         println!("Log receiver listening on UDP port {}", port);
-        Ok(Self { socket })
+        Ok(Self { socket, port })
+    }
+
+    /// UDP port the service is bound to
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Spawn the receiver loop as a background task
+    pub fn spawn(
+        self,
+        stream_state: Arc<RwLock<LogStreamState>>,
+        app_handle: AppHandle,
+    ) -> LogReceiverHandle {
+        let port = self.port;
+        let task = tauri::async_runtime::spawn(async move {
+            if let Err(e) = self.run(stream_state, app_handle).await {
+                eprintln!("Log receiver service error: {}", e);
+            }
+        });
+        LogReceiverHandle { port, task }
     }
 
     /// Run the log receiver loop
@@ -114,24 +683,47 @@ impl LogReceiverService {
         stream_state: Arc<RwLock<LogStreamState>>,
         app_handle: AppHandle,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let mut buf = vec![0u8; 1024];
+        let mut buf = vec![0u8; MAX_PACKET_SIZE];
 
         loop {
             match self.socket.recv_from(&mut buf).await {
                 Ok((len, addr)) => {
                     let device_ip = addr.ip().to_string();
+                    let packet = parse_log_packet(&buf[..len], addr);
 
-                    if let Some(log_msg) = parse_log_message(&buf[..len], addr) {
-                        // Always buffer the log
-                        let mut state = stream_state.write().await;
-                        state.add_log(&device_ip, log_msg.clone());
-
-                        // Only emit to frontend if stream is active
-                        if state.is_active(&device_ip) {
-                            drop(state); // Release lock before emitting
-                            let _ = app_handle.emit("device-log", &log_msg);
+                    // Always buffer the logs
+                    let mut state = stream_state.write().await;
+                    state.parse_failures += packet.failures as u64;
+                    state.raw_messages += packet.raw as u64;
+                    let messages: Vec<LogMessage> = packet
+                        .messages
+                        .into_iter()
+                        .flat_map(|log_msg| state.ingest(&device_ip, log_msg))
+                        .collect();
+                    if let Some(forwarder) = &state.forwarder {
+                        for log_msg in &messages {
+                            forwarder.forward(log_msg);
                         }
                     }
+                    let activity: Vec<LogActivity> = messages
+                        .iter()
+                        .filter_map(|log_msg| state.track_activity(&device_ip, log_msg))
+                        .collect();
+
+                    // Only emit to frontend if stream is active, not paused
+                    // and the tag passes the stream's filters
+                    let to_emit: Vec<LogMessage> = messages
+                        .into_iter()
+                        .filter(|log_msg| state.should_emit(&device_ip, &log_msg.tag))
+                        .collect();
+                    drop(state); // Release lock before emitting
+
+                    for log_msg in &to_emit {
+                        let _ = app_handle.emit("device-log", log_msg);
+                    }
+                    for notification in &activity {
+                        let _ = app_handle.emit("device-log-activity", notification);
+                    }
                 }
                 Err(e) => {
                     eprintln!("Log receiver UDP error: {}", e);
@@ -141,32 +733,137 @@ impl LogReceiverService {
     }
 }
 
-/// Parse a log message from raw bytes
-pub fn parse_log_message(data: &[u8], addr: SocketAddr) -> Option<LogMessage> {
+/// Messages parsed from a single UDP packet
+#[derive(Debug, Default)]
+pub struct ParsedPacket {
+    /// Parsed messages in packet order
+    pub messages: Vec<LogMessage>,
+    /// Number of messages stored as raw text
+    pub raw: usize,
+    /// Number of packets or batch entries discarded as unparseable
+    pub failures: usize,
+}
+
+/// Parse every log message contained in a UDP packet
+///
+/// Packets carry either a single binary or JSON log message, or a JSON
+/// array of messages batched by newer firmware. Anything else that is
+/// mostly printable text (e.g. bootloader output) is kept line by line
+/// as raw messages; binary garbage is discarded.
+pub fn parse_log_packet(data: &[u8], addr: SocketAddr) -> ParsedPacket {
     let device_ip = addr.ip().to_string();
-    if let Ok(log) = decode_log_message(data, &device_ip) {
-        return Some(LogMessage {
-            device_ip,
-            ts: log.timestamp.unwrap_or(0),
-            lvl: log.level.as_str().to_string(),
-            tag: log.tag,
-            msg: log.message,
-            received_at: received_at_ms(),
-        });
+    let Ok(entries) = core_parse_log_packet(data, &device_ip) else {
+        return parse_raw_text(data, addr);
+    };
+
+    let received_at = received_at_ms();
+    let mut messages = Vec::with_capacity(entries.len());
+    let mut failures = 0;
+    for entry in entries {
+        match entry {
+            Ok(log) => messages.push(log_from_core(log, received_at)),
+            Err(_) => failures += 1,
+        }
+    }
+    ParsedPacket {
+        messages,
+        raw: 0,
+        failures,
+    }
+}
+
+/// Store a plain-text packet as one raw message per non-empty line
+fn parse_raw_text(data: &[u8], addr: SocketAddr) -> ParsedPacket {
+    let discarded = ParsedPacket {
+        failures: 1,
+        ..Default::default()
+    };
+    let text = String::from_utf8_lossy(data);
+    if !is_printable_text(&text) {
+        return discarded;
     }
 
-    let raw: RawLogMessage = serde_json::from_slice(data).ok()?;
-    Some(LogMessage {
-        device_ip,
-        ts: raw.ts,
-        lvl: raw.lvl,
-        tag: raw.tag,
-        msg: raw.msg,
-        received_at: received_at_ms(),
-    })
+    let device_ip = addr.ip().to_string();
+    let received_at = received_at_ms();
+    let messages: Vec<LogMessage> = text
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(|line| LogMessage {
+            device_ip: device_ip.clone(),
+            ts: 0,
+            lvl: MessageLevel::parse(RAW_LOG_LEVEL),
+            tag: RAW_LOG_TAG.to_string(),
+            msg: line.to_string(),
+            received_at,
+            seq: None,
+            wall_ts: None,
+            repeat_count: 1,
+        })
+        .collect();
+
+    if messages.is_empty() {
+        return discarded;
+    }
+    ParsedPacket {
+        raw: messages.len(),
+        messages,
+        failures: 0,
+    }
+}
+
+/// Check that enough characters are printable (invalid UTF-8 counts as not)
+fn is_printable_text(text: &str) -> bool {
+    let total = text.chars().count();
+    let printable = text
+        .chars()
+        .filter(|&c| c != char::REPLACEMENT_CHARACTER && (!c.is_control() || c.is_whitespace()))
+        .count();
+    total > 0 && printable as f64 / total as f64 >= MIN_PRINTABLE_RATIO
 }
 
-fn received_at_ms() -> u64 {
+/// Parse the first log message in a packet, without the raw-text fallback
+pub fn parse_log_message(data: &[u8], addr: SocketAddr) -> Option<LogMessage> {
+    let log = core_parse_log_packet(data, &addr.ip().to_string())
+        .ok()?
+        .into_iter()
+        .next()?
+        .ok()?;
+    Some(log_from_core(log, received_at_ms()))
+}
+
+fn log_from_core(log: CoreLogMessage, received_at: u64) -> LogMessage {
+    LogMessage {
+        device_ip: log.ip,
+        ts: log.timestamp.unwrap_or(0),
+        lvl: log.level,
+        tag: log.tag,
+        msg: log.message,
+        received_at,
+        seq: log.seq,
+        wall_ts: None,
+        repeat_count: 1,
+    }
+}
+
+/// Severity rank of a level, lower is more severe
+fn severity(level: &MessageLevel) -> u8 {
+    match level.level() {
+        Some(LogLevel::None) | None => u8::MAX,
+        Some(level) => level as u8,
+    }
+}
+
+fn level_of(log: &LogMessage) -> LogLevel {
+    log.lvl.level().unwrap_or(LogLevel::None)
+}
+
+/// Whether `log` is a lost-messages marker added by the receiver
+fn is_gap_marker(log: &LogMessage) -> bool {
+    log.tag == LOG_RECEIVER_TAG
+}
+
+pub(crate) fn received_at_ms() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map(|d| d.as_millis() as u64)
@@ -187,7 +884,7 @@ mod tests {
 
         assert_eq!(msg.device_ip, "192.168.1.100");
         assert_eq!(msg.ts, 12345);
-        assert_eq!(msg.lvl, "INFO");
+        assert_eq!(msg.lvl, MessageLevel::Known(LogLevel::Info));
         assert_eq!(msg.tag, "app.cpp");
         assert_eq!(msg.msg, "Hello world");
     }
@@ -211,6 +908,71 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_parse_batched_json_packet() {
+        let packet = br#"[
+            {"ts":1,"lvl":"INFO","tag":"uwb","msg":"first"},
+            {"ts":2,"lvl":"WARN","tag":"uwb","msg":"second"}
+        ]"#;
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)), 3334);
+
+        let ParsedPacket {
+            messages, failures, ..
+        } = parse_log_packet(packet, addr);
+
+        assert_eq!(failures, 0);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].msg, "first");
+        assert_eq!(messages[1].msg, "second");
+        assert_eq!(messages[1].lvl, MessageLevel::Known(LogLevel::Warn));
+        assert_eq!(messages[1].device_ip, "192.168.1.100");
+    }
+
+    #[test]
+    fn test_parse_batched_json_packet_with_malformed_entry() {
+        let packet = br#"[
+            {"ts":1,"lvl":"INFO","tag":"uwb","msg":"first"},
+            {"ts":"bad"},
+            {"ts":3,"lvl":"ERROR","tag":"uwb","msg":"third"}
+        ]"#;
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 3334);
+
+        let ParsedPacket {
+            messages, failures, ..
+        } = parse_log_packet(packet, addr);
+
+        assert_eq!(failures, 1);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].ts, 1);
+        assert_eq!(messages[1].ts, 3);
+    }
+
+    #[test]
+    fn test_parse_single_packet_failure_is_counted() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 3334);
+
+        let packet = parse_log_packet(&[0x00, 0xff, 0x13, 0x07, 0x9c, 0x01], addr);
+
+        assert!(packet.messages.is_empty());
+        assert_eq!(packet.raw, 0);
+        assert_eq!(packet.failures, 1);
+    }
+
+    #[test]
+    fn test_parse_plain_text_packet_as_raw() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)), 3334);
+
+        let packet = parse_log_packet(b"[boot] rst:0x1 (POWERON)\r\n\r\nload:0x3fff0030\r\n", addr);
+
+        assert_eq!(packet.failures, 0);
+        assert_eq!(packet.raw, 2);
+        assert_eq!(packet.messages[0].msg, "[boot] rst:0x1 (POWERON)");
+        assert_eq!(packet.messages[0].tag, RAW_LOG_TAG);
+        assert_eq!(packet.messages[0].lvl.as_str(), "raw");
+        assert_eq!(packet.messages[0].ts, 0);
+        assert_eq!(packet.messages[1].msg, "load:0x3fff0030");
+    }
+
     fn push_u16(out: &mut Vec<u8>, value: u16) {
         out.extend_from_slice(&value.to_le_bytes());
     }
@@ -251,10 +1013,13 @@ mod tests {
                 LogMessage {
                     device_ip: device_ip.to_string(),
                     ts: i as u64,
-                    lvl: "INFO".to_string(),
+                    lvl: MessageLevel::Known(LogLevel::Info),
                     tag: "test".to_string(),
                     msg: format!("Message {}", i),
                     received_at: 0,
+                    seq: None,
+                    wall_ts: None,
+                    repeat_count: 1,
                 },
             );
         }
@@ -277,10 +1042,13 @@ mod tests {
                 LogMessage {
                     device_ip: device_ip.to_string(),
                     ts: i as u64,
-                    lvl: "INFO".to_string(),
+                    lvl: MessageLevel::Known(LogLevel::Info),
                     tag: "test".to_string(),
                     msg: format!("Message {}", i),
                     received_at: 0,
+                    seq: None,
+                    wall_ts: None,
+                    repeat_count: 1,
                 },
             );
         }
@@ -290,4 +1058,479 @@ mod tests {
         // Should have the newest logs (100 to 599)
         assert_eq!(logs[0].ts, 100);
     }
+
+    fn seq_log(device_ip: &str, seq: u32) -> LogMessage {
+        LogMessage {
+            device_ip: device_ip.to_string(),
+            ts: seq as u64,
+            lvl: MessageLevel::Known(LogLevel::Info),
+            tag: "test".to_string(),
+            msg: format!("Message {}", seq),
+            received_at: 0,
+            seq: Some(seq),
+            wall_ts: None,
+            repeat_count: 1,
+        }
+    }
+
+    #[test]
+    fn test_seq_gap_inserts_marker() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        assert_eq!(state.ingest(device_ip, seq_log(device_ip, 100)).len(), 1);
+        assert_eq!(state.ingest(device_ip, seq_log(device_ip, 101)).len(), 1);
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 105));
+
+        assert_eq!(emitted.len(), 2);
+        assert_eq!(emitted[0].lvl, MessageLevel::Known(LogLevel::Warn));
+        assert_eq!(emitted[0].tag, LOG_RECEIVER_TAG);
+        assert_eq!(emitted[0].msg, "3 messages lost");
+        assert_eq!(emitted[1].seq, Some(105));
+
+        let logs = state.get_logs(device_ip);
+        assert_eq!(logs.len(), 4);
+        assert_eq!(logs[2].tag, LOG_RECEIVER_TAG);
+
+        let stats = state.stats(None, 0);
+        assert_eq!(stats.devices.len(), 1);
+        assert_eq!(stats.devices[0].lost_messages, 3);
+        // The marker is not a device WARN
+        assert_eq!(stats.devices[0].levels.totals.warn, 0);
+        assert_eq!(stats.devices[0].levels.totals.total(), 3);
+    }
+
+    #[test]
+    fn test_seq_reboot_resets_tracking() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        state.ingest(device_ip, seq_log(device_ip, 5000));
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 0));
+        assert_eq!(emitted.len(), 1);
+
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 1));
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(state.stats(None, 0).devices[0].lost_messages, 0);
+    }
+
+    #[test]
+    fn test_seq_reboot_detected_when_first_messages_are_lost() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        state.ingest(device_ip, seq_log(device_ip, 5000));
+        // Messages 0..=99 after the reboot never arrived
+        state.ingest(device_ip, seq_log(device_ip, 100));
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 101));
+
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(state.last_seq.get(device_ip), Some(&101));
+        assert_eq!(state.stats(None, 0).devices[0].lost_messages, 0);
+    }
+
+    #[test]
+    fn test_seq_reboot_detected_from_uptime_regression() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        let mut before = seq_log(device_ip, 500);
+        before.ts = 600_000;
+        state.ingest(device_ip, before);
+        // Close to the old watermark, but the device clock restarted
+        let mut after = seq_log(device_ip, 480);
+        after.ts = 1_000;
+        state.ingest(device_ip, after);
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 481));
+
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(state.last_seq.get(device_ip), Some(&481));
+    }
+
+    #[test]
+    fn test_seq_duplicate_with_huge_uptime_is_ignored() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        let mut log = seq_log(device_ip, 500);
+        log.ts = u64::MAX - 1;
+        state.ingest(device_ip, log.clone());
+        state.ingest(device_ip, log);
+
+        assert_eq!(state.last_seq.get(device_ip), Some(&500));
+        assert_eq!(state.last_seq_ts.get(device_ip), Some(&(u64::MAX - 1)));
+    }
+
+    #[test]
+    fn test_seq_reordered_packet_is_ignored() {
+        let mut state = LogStreamState::default();
+        let device_ip = "192.168.1.100";
+
+        state.ingest(device_ip, seq_log(device_ip, 200));
+        state.ingest(device_ip, seq_log(device_ip, 199));
+        let emitted = state.ingest(device_ip, seq_log(device_ip, 201));
+
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(state.last_seq.get(device_ip), Some(&201));
+    }
+
+    #[test]
+    fn test_clear_all_logs() {
+        let mut state = LogStreamState::default();
+        state.ingest("10.0.0.1", seq_log("10.0.0.1", 1));
+        state.ingest("10.0.0.1", seq_log("10.0.0.1", 2));
+        state.ingest("10.0.0.2", seq_log("10.0.0.2", 1));
+
+        let discarded = state.clear_all_logs(None);
+
+        assert_eq!(discarded.get("10.0.0.1"), Some(&2));
+        assert_eq!(discarded.get("10.0.0.2"), Some(&1));
+        assert!(state.get_logs("10.0.0.1").is_empty());
+        assert_eq!(state.log_buffers.len(), 2);
+    }
+
+    #[test]
+    fn test_clear_all_logs_prunes_unknown_devices() {
+        let mut state = LogStreamState::default();
+        state.ingest("10.0.0.1", seq_log("10.0.0.1", 1));
+        state.ingest("10.0.0.2", seq_log("10.0.0.2", 1));
+        let known: HashSet<String> = ["10.0.0.1".to_string()].into_iter().collect();
+
+        let discarded = state.clear_all_logs(Some(&known));
+
+        assert_eq!(discarded.len(), 2);
+        assert!(state.log_buffers.contains_key("10.0.0.1"));
+        assert!(!state.log_buffers.contains_key("10.0.0.2"));
+    }
+
+    #[test]
+    fn test_stream_info_includes_buffered_and_active_devices() {
+        let mut state = LogStreamState::default();
+        state
+            .active_streams
+            .insert("10.0.0.3".to_string(), TagFilter::default());
+        for seq in 0..(MAX_LOGS_PER_DEVICE as u32 + 5) {
+            let mut log = seq_log("10.0.0.1", seq);
+            log.received_at = 1000 + seq as u64;
+            state.add_log("10.0.0.1", log);
+        }
+
+        let info = state.stream_info();
+
+        assert_eq!(info.len(), 2);
+        assert_eq!(info[0].device_ip, "10.0.0.1");
+        assert!(!info[0].active);
+        assert_eq!(info[0].buffered_count, MAX_LOGS_PER_DEVICE);
+        assert_eq!(info[0].dropped_count, 5);
+        assert_eq!(
+            info[0].last_message_at,
+            Some(1000 + MAX_LOGS_PER_DEVICE as u64 + 4)
+        );
+        assert_eq!(info[1].device_ip, "10.0.0.3");
+        assert!(info[1].active);
+        assert_eq!(info[1].buffered_count, 0);
+        assert_eq!(info[1].last_message_at, None);
+    }
+
+    #[test]
+    fn test_level_stats_follow_buffer() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        for seq in 0..(MAX_LOGS_PER_DEVICE as u32 + 10) {
+            let mut log = seq_log(device_ip, seq);
+            if seq < 10 {
+                log.lvl = MessageLevel::Known(LogLevel::Error);
+            }
+            log.received_at = 5_000;
+            state.add_log(device_ip, log);
+        }
+        state.add_log("10.0.0.2", seq_log("10.0.0.2", 1));
+
+        let stats = state.stats(Some(device_ip), 5_000);
+
+        assert_eq!(stats.devices.len(), 1);
+        let levels = &stats.devices[0].levels;
+        // The ten ERROR messages were evicted from the buffer...
+        assert_eq!(levels.totals.error, 0);
+        assert_eq!(levels.totals.info, MAX_LOGS_PER_DEVICE as u64);
+        // ...but still count towards the last minute
+        assert_eq!(levels.window.error, 10);
+
+        state.clear_logs(device_ip);
+        let stats = state.stats(Some(device_ip), 5_000);
+        assert_eq!(stats.devices[0].levels.totals.total(), 0);
+    }
+
+    #[test]
+    fn test_paused_emissions_still_buffer() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        state
+            .active_streams
+            .insert(device_ip.to_string(), TagFilter::default());
+        assert!(state.should_emit(device_ip, "test"));
+
+        state.emissions_paused = true;
+        state.ingest(device_ip, seq_log(device_ip, 1));
+
+        assert!(!state.should_emit(device_ip, "test"));
+        assert_eq!(state.get_logs(device_ip).len(), 1);
+        assert!(state.stream_info()[0].paused);
+    }
+
+    #[test]
+    fn test_ingest_sets_wall_clock_timestamp() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        let mut first = seq_log(device_ip, 1);
+        first.ts = 1_000;
+        first.received_at = 1_700_000_001_040;
+        let mut second = seq_log(device_ip, 2);
+        second.ts = 2_000;
+        second.received_at = 1_700_000_002_010;
+
+        state.ingest(device_ip, first);
+        let emitted = state.ingest(device_ip, second);
+
+        assert_eq!(emitted[0].wall_ts, Some(1_700_000_002_010));
+        assert_eq!(
+            state.clock_offsets[device_ip].offset_ms(),
+            Some(1_700_000_000_010)
+        );
+    }
+
+    #[test]
+    fn test_tag_filters_apply_to_emission_only() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        state
+            .active_streams
+            .insert(device_ip.to_string(), TagFilter::new(["uwb.*"]));
+
+        assert!(state.should_emit(device_ip, "uwb.ranging"));
+        assert!(!state.should_emit(device_ip, "mavlink"));
+        assert!(state.should_emit(device_ip, LOG_RECEIVER_TAG));
+        assert!(!state.should_emit("10.0.0.2", "uwb.ranging"));
+
+        state.ingest(device_ip, seq_log(device_ip, 1));
+        assert_eq!(state.get_logs(device_ip).len(), 1);
+        assert_eq!(state.stream_info()[0].tag_filters, vec!["uwb.*"]);
+    }
+
+    #[test]
+    fn test_activity_is_throttled_per_device() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        let log_at = |received_at: u64, level: LogLevel| {
+            let mut log = seq_log(device_ip, 1);
+            log.received_at = received_at;
+            log.lvl = MessageLevel::Known(level);
+            log
+        };
+
+        let first = state.track_activity(device_ip, &log_at(10_000, LogLevel::Info));
+        assert_eq!(first.map(|a| a.count), Some(1));
+
+        assert!(state
+            .track_activity(device_ip, &log_at(10_200, LogLevel::Error))
+            .is_none());
+        assert!(state
+            .track_activity(device_ip, &log_at(10_500, LogLevel::Debug))
+            .is_none());
+
+        let next = state
+            .track_activity(device_ip, &log_at(11_000, LogLevel::Info))
+            .unwrap();
+        assert_eq!(next.count, 3);
+        assert_eq!(next.level, MessageLevel::Known(LogLevel::Error));
+    }
+
+    #[test]
+    fn test_activity_skips_active_streams() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        state
+            .active_streams
+            .insert(device_ip.to_string(), TagFilter::default());
+
+        assert!(state
+            .track_activity(device_ip, &seq_log(device_ip, 1))
+            .is_none());
+    }
+
+    #[test]
+    fn test_repeated_lines_are_collapsed() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        let repeated = |received_at: u64| {
+            let mut log = seq_log(device_ip, 0);
+            log.msg = "stuck".to_string();
+            log.received_at = received_at;
+            log
+        };
+
+        state.add_log(device_ip, repeated(100));
+        state.add_log(device_ip, repeated(200));
+        let stored = state.add_log(device_ip, repeated(300));
+        state.add_log(device_ip, seq_log(device_ip, 1));
+
+        assert_eq!(stored.repeat_count, 3);
+        assert_eq!(stored.received_at, 300);
+        let logs = state.get_logs(device_ip);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].repeat_count, 3);
+        assert_eq!(logs[1].repeat_count, 1);
+        // Stats still count every occurrence
+        assert_eq!(
+            state.stats(Some(device_ip), 300).devices[0]
+                .levels
+                .totals
+                .info,
+            4
+        );
+    }
+
+    #[test]
+    fn test_repeat_count_is_capped() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        let mut log = seq_log(device_ip, 0);
+        log.seq = None;
+
+        for _ in 0..(MAX_REPEAT_COUNT + 1) {
+            state.add_log(device_ip, log.clone());
+        }
+
+        let logs = state.get_logs(device_ip);
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].repeat_count, MAX_REPEAT_COUNT);
+        assert_eq!(logs[1].repeat_count, 1);
+    }
+
+    #[test]
+    fn test_search_substring_and_regex() {
+        let mut state = LogStreamState::default();
+        for (ip, msg) in [
+            ("10.0.0.2", "Anchor timeout"),
+            ("10.0.0.1", "ranging ok"),
+            ("10.0.0.1", "anchor TIMEOUT again"),
+        ] {
+            let mut log = seq_log(ip, 0);
+            log.seq = None;
+            log.msg = msg.to_string();
+            state.add_log(ip, log);
+        }
+
+        let result = state
+            .search("timeout", &LogSearchOptions::default())
+            .unwrap();
+        assert_eq!(result.matches.len(), 2);
+        assert_eq!(result.matches[0].device_ip, "10.0.0.1");
+        assert_eq!(result.matches[0].index, 1);
+        assert_eq!(result.matches[1].device_ip, "10.0.0.2");
+        assert!(!result.truncated);
+
+        let options = LogSearchOptions {
+            device_ip: Some("10.0.0.1".to_string()),
+            regex: true,
+            ..Default::default()
+        };
+        let result = state.search("^anchor .* again$", &options).unwrap();
+        assert_eq!(result.matches.len(), 1);
+
+        // Special characters are literal in substring mode
+        assert!(state
+            .search("(", &LogSearchOptions::default())
+            .unwrap()
+            .matches
+            .is_empty());
+        assert!(state
+            .search(
+                "(",
+                &LogSearchOptions {
+                    regex: true,
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_search_level_tag_and_cap() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        for seq in 0..20 {
+            let mut log = seq_log(device_ip, seq);
+            if seq % 2 == 0 {
+                log.lvl = MessageLevel::Known(LogLevel::Error);
+                log.tag = "uwb.tdoa".to_string();
+            }
+            state.add_log(device_ip, log);
+        }
+
+        let options = LogSearchOptions {
+            min_level: Some(MessageLevel::Known(LogLevel::Warn)),
+            tags: vec!["uwb.*".to_string()],
+            max_results: Some(3),
+            ..Default::default()
+        };
+        let result = state.search("message", &options).unwrap();
+
+        assert_eq!(result.matches.len(), 3);
+        assert!(result.truncated);
+        assert!(result
+            .matches
+            .iter()
+            .all(|m| m.log.lvl == MessageLevel::Known(LogLevel::Error)));
+    }
+
+    #[test]
+    fn test_search_min_level_excludes_none_level() {
+        let mut state = LogStreamState::default();
+        let device_ip = "10.0.0.1";
+        let mut log = seq_log(device_ip, 1);
+        log.lvl = MessageLevel::Known(LogLevel::None);
+        state.add_log(device_ip, log);
+
+        let options = LogSearchOptions {
+            min_level: Some(MessageLevel::Known(LogLevel::Error)),
+            ..Default::default()
+        };
+        let result = state.search("message", &options).unwrap();
+
+        assert!(result.matches.is_empty());
+    }
+
+    #[test]
+    fn test_auto_start_does_not_take_over_manual_streams() {
+        let mut state = LogStreamState::default();
+        state.start_stream("10.0.0.1", TagFilter::new(["uwb.*"]));
+
+        assert!(!state.auto_start_stream("10.0.0.1"));
+        assert!(state.auto_start_stream("10.0.0.2"));
+        assert!(!state.auto_start_stream("10.0.0.2"));
+
+        let info = state.stream_info();
+        assert!(!info[0].auto_started);
+        assert_eq!(info[0].tag_filters, vec!["uwb.*".to_string()]);
+        assert!(info[1].auto_started);
+    }
+
+    #[test]
+    fn test_stop_auto_started_streams_keeps_manual_ones() {
+        let mut state = LogStreamState::default();
+        state.auto_start_stream("10.0.0.1");
+        state.auto_start_stream("10.0.0.2");
+        state.start_stream("10.0.0.3", TagFilter::default());
+        // Opening a terminal on an auto-started stream makes it manual
+        state.start_stream("10.0.0.2", TagFilter::default());
+
+        let stopped = state.stop_auto_started_streams();
+
+        assert_eq!(stopped, vec!["10.0.0.1".to_string()]);
+        assert!(!state.is_active("10.0.0.1"));
+        assert!(state.is_active("10.0.0.2"));
+        assert!(state.is_active("10.0.0.3"));
+        assert!(state.auto_started.is_empty());
+    }
 }
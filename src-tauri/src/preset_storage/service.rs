@@ -3,8 +3,9 @@
 //! Thin wrapper around core's PresetStorage that gets the path from Tauri's AppHandle.
 
 use crate::error::AppError;
-use crate::types::{Preset, PresetInfo};
+use crate::types::{AppliedRecord, Preset, PresetList, PresetProblem, PresetType};
 use rtls_link_core::storage::PresetStorage as CorePresetStorage;
+use std::path::Path;
 use tauri::{AppHandle, Manager};
 
 /// Service for managing unified presets.
@@ -28,9 +29,22 @@ impl PresetStorageService {
         Ok(Self { inner })
     }
 
-    /// List all saved presets.
-    pub async fn list(&self) -> Result<Vec<PresetInfo>, AppError> {
-        self.inner.list().await.map_err(|e| e.into())
+    /// List saved presets, optionally only those of `preset_type` and
+    /// carrying `tag`.
+    pub async fn list(
+        &self,
+        preset_type: Option<PresetType>,
+        tag: Option<&str>,
+    ) -> Result<PresetList, AppError> {
+        self.inner
+            .list(preset_type, tag)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Move corrupt preset files into the `corrupt/` subdirectory.
+    pub async fn move_corrupt(&self) -> Result<Vec<PresetProblem>, AppError> {
+        self.inner.move_corrupt().await.map_err(|e| e.into())
     }
 
     /// Read a preset by name.
@@ -47,6 +61,24 @@ impl PresetStorageService {
         Ok(true)
     }
 
+    /// Save a preset, replacing any preset whose name differs only by case.
+    pub async fn replace(&self, preset: Preset) -> Result<bool, AppError> {
+        self.inner.replace(&preset).await?;
+        Ok(true)
+    }
+
+    /// Record successful uploads of a preset.
+    pub async fn record_applied(
+        &self,
+        name: &str,
+        records: &[AppliedRecord],
+    ) -> Result<(), AppError> {
+        self.inner
+            .record_applied(name, records)
+            .await
+            .map_err(|e| e.into())
+    }
+
     /// Delete a preset.
     pub async fn delete(&self, name: &str) -> Result<bool, AppError> {
         self.inner
@@ -55,6 +87,57 @@ impl PresetStorageService {
             .map_err(|e| AppError::from(e))?;
         Ok(true)
     }
+
+    /// Rename a preset.
+    pub async fn rename(&self, old_name: &str, new_name: &str) -> Result<bool, AppError> {
+        self.inner.rename(old_name, new_name).await?;
+        Ok(true)
+    }
+
+    /// Copy a preset under a new name.
+    pub async fn duplicate(&self, source: &str, target: &str) -> Result<bool, AppError> {
+        self.inner.duplicate(source, target).await?;
+        Ok(true)
+    }
+
+    /// Convert a preset to another type, returning the converted preset.
+    pub async fn convert(
+        &self,
+        name: &str,
+        target_type: PresetType,
+        new_name: Option<&str>,
+    ) -> Result<Preset, AppError> {
+        Ok(self.inner.convert(name, target_type, new_name).await?)
+    }
+
+    /// Merge a full preset with a locations preset into a new full preset.
+    pub async fn merge(
+        &self,
+        full_name: &str,
+        locations_name: &str,
+        output_name: &str,
+    ) -> Result<Preset, AppError> {
+        Ok(self
+            .inner
+            .merge(full_name, locations_name, output_name)
+            .await?)
+    }
+
+    /// Export a preset to a shareable file.
+    pub async fn export(&self, name: &str, path: &Path) -> Result<bool, AppError> {
+        self.inner.export(name, path).await?;
+        Ok(true)
+    }
+
+    /// Import a preset file, returning the name it was saved as.
+    pub async fn import(
+        &self,
+        path: &Path,
+        name: Option<&str>,
+        overwrite: bool,
+    ) -> Result<String, AppError> {
+        Ok(self.inner.import(path, name, overwrite).await?)
+    }
 }
 
 #[cfg(test)]
@@ -90,6 +173,7 @@ mod tests {
                     log_udp_port: None,
                     log_serial_enabled: None,
                     log_udp_enabled: None,
+                    extra: Default::default(),
                 },
                 uwb: UwbConfig {
                     mode: 4,
@@ -133,13 +217,19 @@ mod tests {
                     anchor_pos_locked: None,
                     distance_avg_samples: None,
                     use_2d_estimator: None,
+                    extra: Default::default(),
                 },
                 app: AppConfig {
                     led2_pin: None,
                     led2_state: None,
+                    extra: Default::default(),
                 },
             }),
             locations: None,
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         }
@@ -174,6 +264,10 @@ mod tests {
                 ],
                 use_2d_estimator: Some(1),
             }),
+            tags: Vec::new(),
+            overrides: Default::default(),
+            last_applied_at: None,
+            applied_to: Vec::new(),
             created_at: "2024-01-01T00:00:00Z".to_string(),
             updated_at: "2024-01-01T00:00:00Z".to_string(),
         }
@@ -221,7 +315,7 @@ mod tests {
             .await
             .unwrap();
 
-        let presets = service.list().await.unwrap();
+        let presets = service.list(None, None).await.unwrap().presets;
         assert_eq!(presets.len(), 2);
         assert_eq!(presets[0].name, "alpha-full");
         assert_eq!(presets[0].preset_type, PresetType::Full);
@@ -240,4 +334,25 @@ mod tests {
         service.delete("to-delete").await.unwrap();
         assert!(service.get("to-delete").await.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_overwrite_preserves_created_at() {
+        let (service, _temp_dir) = create_test_service();
+        service
+            .save(&create_test_full_preset("site"))
+            .await
+            .unwrap();
+
+        // Callers stamp both timestamps with "now" on every save
+        let mut edited = create_test_full_preset("site");
+        edited.description = Some("Edited".to_string());
+        edited.created_at = "2025-06-01T00:00:00Z".to_string();
+        edited.updated_at = "2025-06-01T00:00:00Z".to_string();
+        service.save(&edited).await.unwrap();
+
+        let saved = service.get("site").await.unwrap().unwrap();
+        assert_eq!(saved.description.as_deref(), Some("Edited"));
+        assert_eq!(saved.created_at, "2024-01-01T00:00:00Z");
+        assert_ne!(saved.updated_at, "2024-01-01T00:00:00Z");
+    }
 }
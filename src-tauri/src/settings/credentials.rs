@@ -0,0 +1,130 @@
+//! Encryption for device credentials kept in the settings file.
+//!
+//! Credentials are sealed with AES-256-GCM under a random key stored in a
+//! separate owner-only file, so the settings file on its own (in a backup or
+//! attached to a bug report) does not reveal them.
+
+use crate::error::AppError;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use rtls_link_core::device::ota::OtaAuth;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+
+const KEY_LEN: usize = 32;
+
+/// Key used to seal and open the stored credentials.
+pub(super) struct CredentialKey(LessSafeKey);
+
+impl CredentialKey {
+    /// Load the key at `path`, generating it on first use.
+    ///
+    /// Only call this while no credentials are sealed: a new key cannot open
+    /// them. Use [`CredentialKey::load`] otherwise.
+    pub fn load_or_create(path: &Path) -> Result<Self, AppError> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut bytes = vec![0u8; KEY_LEN];
+                SystemRandom::new()
+                    .fill(&mut bytes)
+                    .map_err(|_| AppError::Io("Failed to generate credential key".to_string()))?;
+                write_private(path, &bytes)?;
+                bytes
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_bytes(path, &bytes)
+    }
+
+    /// Load the existing key at `path`, failing if it is missing.
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(AppError::Io(format!(
+                    "Credential key {} is missing; reset the stored device credentials",
+                    path.display()
+                )));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        Self::from_bytes(path, &bytes)
+    }
+
+    fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self, AppError> {
+        let key = UnboundKey::new(&AES_256_GCM, bytes)
+            .map_err(|_| AppError::Io(format!("Invalid credential key in {}", path.display())))?;
+        Ok(Self(LessSafeKey::new(key)))
+    }
+
+    /// Encrypt credentials into a base64 string for the settings file.
+    pub fn seal(&self, credentials: &HashMap<String, OtaAuth>) -> Result<String, AppError> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| AppError::Io("Failed to generate nonce".to_string()))?;
+
+        let mut in_out = serde_json::to_vec(credentials)?;
+        self.0
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| AppError::Io("Failed to encrypt credentials".to_string()))?;
+
+        let mut sealed = nonce.to_vec();
+        sealed.extend_from_slice(&in_out);
+        Ok(base64::engine::general_purpose::STANDARD.encode(sealed))
+    }
+
+    /// Decrypt credentials produced by [`CredentialKey::seal`].
+    pub fn open(&self, sealed: &str) -> Result<HashMap<String, OtaAuth>, AppError> {
+        let unreadable = || {
+            AppError::Io(
+                "Stored device credentials could not be decrypted; reset them to store new ones"
+                    .to_string(),
+            )
+        };
+
+        let mut sealed = base64::engine::general_purpose::STANDARD
+            .decode(sealed)
+            .map_err(|_| unreadable())?;
+        if sealed.len() < NONCE_LEN {
+            return Err(unreadable());
+        }
+        let mut in_out = sealed.split_off(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(&sealed).map_err(|_| unreadable())?;
+        let plaintext = self
+            .0
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| unreadable())?;
+        Ok(serde_json::from_slice(plaintext)?)
+    }
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path)?;
+    file.write_all(bytes)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, bytes: &[u8]) -> Result<(), AppError> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(bytes)?;
+    Ok(())
+}
@@ -0,0 +1,6 @@
+//! Persistent application settings module.
+
+mod credentials;
+mod service;
+
+pub use service::{AppSettings, SettingsService};
@@ -0,0 +1,300 @@
+//! Backend settings storage service.
+//!
+//! Stores settings owned by the Rust backend (such as the log receiver port)
+//! as a small JSON file in the app data directory.
+
+use super::credentials::CredentialKey;
+use crate::error::AppError;
+use crate::types::DeviceRole;
+use rtls_link_core::device::ota::{OtaAuth, OtaEndpoint};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+use tokio::sync::RwLock;
+
+/// Settings file name inside the app data directory
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Backend-owned application settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSettings {
+    /// UDP port the log receiver binds to (default: 3334)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_receiver_port: Option<u16>,
+    /// Start a log stream for every newly discovered device
+    #[serde(default)]
+    pub auto_stream_logs: bool,
+    /// Limit auto-started streams to these roles (None means all roles)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_stream_roles: Option<Vec<DeviceRole>>,
+    /// OTA endpoint overrides keyed by device MAC address
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub device_endpoints: HashMap<String, OtaEndpoint>,
+    /// Device credentials keyed by MAC address, encrypted with the key file
+    /// stored next to the settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_credentials: Option<String>,
+}
+
+impl AppSettings {
+    /// Check whether a newly discovered device should get a log stream
+    pub fn should_auto_stream(&self, role: &DeviceRole) -> bool {
+        self.auto_stream_logs
+            && self
+                .auto_stream_roles
+                .as_ref()
+                .is_none_or(|roles| roles.contains(role))
+    }
+
+    /// OTA endpoint for a device, falling back to plain HTTP on port 80
+    pub fn endpoint_for(&self, mac: &str) -> OtaEndpoint {
+        self.device_endpoints
+            .get(&mac.to_ascii_uppercase())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Service for loading and persisting backend settings.
+pub struct SettingsService {
+    path: PathBuf,
+    settings: RwLock<AppSettings>,
+}
+
+impl SettingsService {
+    /// Create a new SettingsService, loading any previously saved settings.
+    pub fn new(app_handle: &AppHandle) -> Result<Self, AppError> {
+        let data_dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| AppError::Io(format!("Failed to get app data dir: {}", e)))?;
+
+        std::fs::create_dir_all(&data_dir)?;
+
+        Ok(Self::from_path(data_dir.join(SETTINGS_FILE)))
+    }
+
+    /// Create a SettingsService backed by the given file.
+    ///
+    /// A missing or unreadable file falls back to default settings.
+    pub fn from_path(path: PathBuf) -> Self {
+        let settings = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            settings: RwLock::new(settings),
+        }
+    }
+
+    /// Get a snapshot of the current settings.
+    pub async fn get(&self) -> AppSettings {
+        self.settings.read().await.clone()
+    }
+
+    /// Modify the settings and persist them to disk.
+    pub async fn update<F>(&self, f: F) -> Result<AppSettings, AppError>
+    where
+        F: FnOnce(&mut AppSettings),
+    {
+        let mut settings = self.settings.write().await;
+        f(&mut settings);
+        self.persist(&settings).await?;
+
+        Ok(settings.clone())
+    }
+
+    async fn persist(&self, settings: &AppSettings) -> Result<(), AppError> {
+        let content = serde_json::to_string_pretty(settings)?;
+        tokio::fs::write(&self.path, content).await?;
+        Ok(())
+    }
+
+    /// Get the decrypted device credentials, keyed by MAC address.
+    ///
+    /// Fails if they are stored but cannot be decrypted, for example because
+    /// the key file was lost; see [`SettingsService::reset_credentials`].
+    pub async fn credentials(&self) -> Result<HashMap<String, OtaAuth>, AppError> {
+        self.open_credentials(&*self.settings.read().await)
+    }
+
+    fn open_credentials(
+        &self,
+        settings: &AppSettings,
+    ) -> Result<HashMap<String, OtaAuth>, AppError> {
+        match &settings.device_credentials {
+            Some(sealed) => CredentialKey::load(&self.key_path())?.open(sealed),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// Set or clear the credentials for a device and persist them encrypted.
+    pub async fn set_credentials(&self, mac: &str, auth: Option<OtaAuth>) -> Result<(), AppError> {
+        // One lock across the read-modify-write so concurrent calls don't
+        // drop each other's changes
+        let mut settings = self.settings.write().await;
+        let mut credentials = self.open_credentials(&settings)?;
+        match auth {
+            Some(auth) if auth != OtaAuth::None => {
+                credentials.insert(mac.to_ascii_uppercase(), auth);
+            }
+            _ => {
+                credentials.remove(&mac.to_ascii_uppercase());
+            }
+        }
+
+        settings.device_credentials = if credentials.is_empty() {
+            None
+        } else {
+            Some(CredentialKey::load_or_create(&self.key_path())?.seal(&credentials)?)
+        };
+        self.persist(&settings).await
+    }
+
+    /// Forget all stored device credentials along with their key.
+    ///
+    /// The way out when the credentials can no longer be decrypted.
+    pub async fn reset_credentials(&self) -> Result<(), AppError> {
+        let mut settings = self.settings.write().await;
+        settings.device_credentials = None;
+        self.persist(&settings).await?;
+
+        match tokio::fs::remove_file(self.key_path()).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn key_path(&self) -> PathBuf {
+        self.path.with_extension("key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_missing_file_uses_defaults() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let service = SettingsService::from_path(temp_dir.path().join(SETTINGS_FILE));
+
+        assert_eq!(service.get().await, AppSettings::default());
+    }
+
+    #[tokio::test]
+    async fn test_update_persists_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+
+        let service = SettingsService::from_path(path.clone());
+        service
+            .update(|settings| settings.log_receiver_port = Some(4444))
+            .await
+            .unwrap();
+
+        let reloaded = SettingsService::from_path(path);
+        assert_eq!(reloaded.get().await.log_receiver_port, Some(4444));
+    }
+
+    #[test]
+    fn test_auto_stream_defaults_and_role_filter() {
+        let settings: AppSettings = serde_json::from_str(r#"{"logReceiverPort":4444}"#).unwrap();
+        assert!(!settings.auto_stream_logs);
+        assert!(!settings.should_auto_stream(&DeviceRole::TagTdoa));
+
+        let settings: AppSettings =
+            serde_json::from_str(r#"{"autoStreamLogs":true,"autoStreamRoles":["tag_tdoa"]}"#)
+                .unwrap();
+        assert!(settings.should_auto_stream(&DeviceRole::TagTdoa));
+        assert!(!settings.should_auto_stream(&DeviceRole::AnchorTdoa));
+    }
+
+    #[tokio::test]
+    async fn test_credentials_are_encrypted_at_rest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        let auth = OtaAuth::Basic {
+            username: "admin".to_string(),
+            password: "hunter2".to_string(),
+        };
+
+        let service = SettingsService::from_path(path.clone());
+        service
+            .set_credentials("aa:bb:cc:dd:ee:ff", Some(auth.clone()))
+            .await
+            .unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("hunter2"));
+
+        let reloaded = SettingsService::from_path(path.clone());
+        let credentials = reloaded.credentials().await.unwrap();
+        assert_eq!(credentials.get("AA:BB:CC:DD:EE:FF"), Some(&auth));
+
+        reloaded
+            .set_credentials("AA:BB:CC:DD:EE:FF", None)
+            .await
+            .unwrap();
+        assert!(reloaded.credentials().await.unwrap().is_empty());
+        assert_eq!(reloaded.get().await.device_credentials, None);
+    }
+
+    #[tokio::test]
+    async fn test_lost_key_is_not_replaced_until_reset() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join(SETTINGS_FILE);
+        let auth = OtaAuth::Bearer {
+            token: "secret".to_string(),
+        };
+
+        let service = SettingsService::from_path(path.clone());
+        service
+            .set_credentials("AA:BB:CC:DD:EE:FF", Some(auth.clone()))
+            .await
+            .unwrap();
+        std::fs::remove_file(service.key_path()).unwrap();
+
+        assert!(service.credentials().await.is_err());
+        assert!(service
+            .set_credentials("AA:BB:CC:DD:EE:FF", None)
+            .await
+            .is_err());
+        assert!(!service.key_path().exists());
+
+        service.reset_credentials().await.unwrap();
+        assert!(service.credentials().await.unwrap().is_empty());
+        service
+            .set_credentials("11:22:33:44:55:66", Some(auth.clone()))
+            .await
+            .unwrap();
+        let reloaded = SettingsService::from_path(path);
+        assert_eq!(
+            reloaded
+                .credentials()
+                .await
+                .unwrap()
+                .get("11:22:33:44:55:66"),
+            Some(&auth)
+        );
+    }
+
+    #[test]
+    fn test_endpoint_for_matches_mac_case_insensitively() {
+        let settings: AppSettings = serde_json::from_str(
+            r#"{"deviceEndpoints":{"AA:BB:CC:DD:EE:FF":{"tls":true,"acceptInvalidCerts":true}}}"#,
+        )
+        .unwrap();
+
+        let endpoint = settings.endpoint_for("aa:bb:cc:dd:ee:ff");
+        assert!(endpoint.tls);
+        assert!(endpoint.accept_invalid_certs);
+        assert_eq!(endpoint.port, None);
+        assert_eq!(
+            settings.endpoint_for("11:22:33:44:55:66"),
+            OtaEndpoint::default()
+        );
+    }
+}
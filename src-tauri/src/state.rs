@@ -3,7 +3,8 @@
 //! This module defines the shared state used across Tauri commands
 //! and background services.
 
-use crate::logging::service::LogStreamState;
+use crate::connections::ConnectionPool;
+use crate::logging::service::{LogReceiverHandle, LogStreamState};
 use crate::types::Device;
 use std::collections::HashMap;
 use std::sync::{atomic::AtomicBool, Arc};
@@ -16,8 +17,14 @@ pub struct AppState {
     pub devices: Arc<RwLock<HashMap<String, Device>>>,
     /// State for active log streams
     pub log_streams: Arc<RwLock<LogStreamState>>,
+    /// Handle to the running log receiver task, if one is bound
+    pub log_receiver: Arc<RwLock<Option<LogReceiverHandle>>>,
     /// Cooperative cancellation flags for active OTA uploads, keyed by IP address.
     pub ota_cancellations: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    /// Cooperative cancellation flags for active device operations, keyed by operation ID.
+    pub operation_cancellations: Arc<RwLock<HashMap<String, Arc<AtomicBool>>>>,
+    /// Device connections reused across commands
+    pub connections: Arc<ConnectionPool>,
 }
 
 impl AppState {
@@ -26,7 +33,10 @@ impl AppState {
         Self {
             devices: Arc::new(RwLock::new(HashMap::new())),
             log_streams: Arc::new(RwLock::new(LogStreamState::default())),
+            log_receiver: Arc::new(RwLock::new(None)),
             ota_cancellations: Arc::new(RwLock::new(HashMap::new())),
+            operation_cancellations: Arc::new(RwLock::new(HashMap::new())),
+            connections: Arc::new(ConnectionPool::default()),
         }
     }
 }
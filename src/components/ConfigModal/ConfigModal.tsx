@@ -4,6 +4,7 @@ import { Commands } from '@shared/commands';
 import { flatToAnchors, getAnchorWriteCommands, normalizeUwbShortAddr } from '@shared/anchors';
 import { validateConfig } from '@shared/config';
 import { useDeviceCommand } from '../../hooks/useDeviceCommand';
+import type { CommandAck } from '../../lib/tauri-api';
 import { GeneralSection } from './sections/GeneralSection';
 import { UWBSection } from './sections/UWBSection';
 import { AnchorListSection } from './sections/AnchorListSection';
@@ -46,7 +47,7 @@ const navItems: NavItem[] = [
 ];
 
 export function ConfigModal({ device, allDevices, onClose, isExpertMode = false }: ConfigModalProps) {
-  const { sendCommand, sendCommands, loading, close } = useDeviceCommand(device.ip, { mode: 'persistent' });
+  const { sendCommand, sendCommandAck, sendCommands, loading, close } = useDeviceCommand(device.ip, { mode: 'persistent' });
   const [config, setConfig] = useState<DeviceConfig | null>(null);
   const [savedConfigs, setSavedConfigs] = useState<string[]>([]);
   const [activeConfig, setActiveConfig] = useState<string | null>(null);
@@ -56,31 +57,10 @@ export function ConfigModal({ device, allDevices, onClose, isExpertMode = false
   const [showLogTerminal, setShowLogTerminal] = useState(false);
   const [activeSection, setActiveSection] = useState<SectionId>('general');
 
-  const findCommandError = (responses: string[] | null): string | null => {
-    if (!responses) return 'No response from device';
-    for (const response of responses) {
-      const jsonStart = response.search(/[\{\[]/);
-      if (jsonStart >= 0) {
-        try {
-          const parsed = JSON.parse(response.slice(jsonStart));
-          if (parsed?.success === true) {
-            continue;
-          }
-          if (parsed?.success === false) {
-            return parsed.message || parsed.error || 'Command failed';
-          }
-          if (parsed?.error !== undefined && parsed.error !== null && parsed.error !== '') {
-            return typeof parsed.error === 'string' ? parsed.error : JSON.stringify(parsed.error);
-          }
-        } catch {
-          // Fall through to text matching for non-JSON command responses.
-        }
-      }
-      if (/error|fail|invalid|not found/i.test(response)) {
-        return response;
-      }
-    }
-    return null;
+  const findCommandError = (acks: CommandAck[] | null): string | null => {
+    if (!acks) return 'No response from device';
+    const failed = acks.find((ack) => !ack.success);
+    return failed ? failed.message || 'Command failed' : null;
   };
 
   useEffect(() => {
@@ -197,15 +177,13 @@ export function ConfigModal({ device, allDevices, onClose, isExpertMode = false
       if (!validation.valid) {
         throw new Error(validation.errors[0] || 'Invalid configuration');
       }
-      const result = await sendCommand<{ success: boolean; error?: string }>(
-        Commands.loadConfigNamed(previewingConfig)
-      );
-      if (result?.success) {
+      const ack = await sendCommandAck(Commands.loadConfigNamed(previewingConfig));
+      if (ack?.success) {
         await loadSavedConfigs();
         setPreviewingConfig(null);
         alert(`Configuration "${previewingConfig}" activated successfully`);
       } else {
-        throw new Error(result?.error || 'Failed to activate configuration');
+        throw new Error(ack?.message || 'Failed to activate configuration');
       }
     } catch (e) {
       alert(e instanceof Error ? e.message : 'Failed to activate configuration');
@@ -11,19 +11,32 @@ interface LogTerminalProps {
 
 // Log level colors for terminal display
 const LOG_LEVEL_COLORS: Record<string, string> = {
-  ERROR: '#ff6b6b',
-  WARN: '#ffd43b',
-  INFO: '#69db7c',
-  DEBUG: '#74c0fc',
-  VERBOSE: '#b197fc',
+  error: '#ff6b6b',
+  warn: '#ffd43b',
+  info: '#69db7c',
+  debug: '#74c0fc',
+  verbose: '#b197fc',
+  raw: '#adb5bd',
 };
 
+const FILTER_LEVELS = ['error', 'warn', 'info', 'debug', 'verbose'];
+
+// Show wall-clock time when the backend could align it, else device uptime
+function formatTimestamp(log: LogMessage): string {
+  if (log.wallTs === undefined) {
+    return (log.ts / 1000).toFixed(3);
+  }
+  const date = new Date(log.wallTs);
+  const ms = String(date.getMilliseconds()).padStart(3, '0');
+  return `${date.toLocaleTimeString([], { hour12: false })}.${ms}`;
+}
+
 export function LogTerminal({ deviceIp, onClose }: LogTerminalProps) {
   const [logs, setLogs] = useState<LogMessage[]>([]);
   const [isStreaming, setIsStreaming] = useState(false);
   const [isPaused, setIsPaused] = useState(false);
   const [levelFilter, setLevelFilter] = useState<Set<string>>(
-    new Set(['ERROR', 'WARN', 'INFO', 'DEBUG', 'VERBOSE'])
+    new Set(FILTER_LEVELS)
   );
   const [tagFilter, setTagFilter] = useState<string>('');
   const [searchText, setSearchText] = useState<string>('');
@@ -81,6 +94,10 @@ export function LogTerminal({ deviceIp, onClose }: LogTerminalProps) {
             lastLog.ts === event.payload.ts &&
             lastLog.msg === event.payload.msg
           ) {
+            // Repeated lines are collapsed by the backend; refresh the count
+            if (event.payload.repeatCount !== lastLog.repeatCount) {
+              return [...prev.slice(0, -1), event.payload];
+            }
             return prev;
           }
 
@@ -177,7 +194,7 @@ export function LogTerminal({ deviceIp, onClose }: LogTerminalProps) {
 
       <div className={styles.filters}>
         <div className={styles.levelFilters}>
-          {['ERROR', 'WARN', 'INFO', 'DEBUG', 'VERBOSE'].map((level) => (
+          {FILTER_LEVELS.map((level) => (
             <label
               key={level}
               className={styles.levelCheckbox}
@@ -192,7 +209,7 @@ export function LogTerminal({ deviceIp, onClose }: LogTerminalProps) {
                 checked={levelFilter.has(level)}
                 onChange={() => toggleLevel(level)}
               />
-              {level}
+              {level.toUpperCase()}
             </label>
           ))}
         </div>
@@ -225,16 +242,19 @@ export function LogTerminal({ deviceIp, onClose }: LogTerminalProps) {
         {filteredLogs.map((log, idx) => (
           <div key={idx} className={styles.logLine}>
             <span className={styles.timestamp}>
-              [{(log.ts / 1000).toFixed(3)}]
+              [{formatTimestamp(log)}]
             </span>
             <span
               className={styles.level}
               style={{ color: LOG_LEVEL_COLORS[log.lvl] || '#888' }}
             >
-              [{log.lvl}]
+              [{log.lvl.toUpperCase()}]
             </span>
             <span className={styles.tag}>[{log.tag}]</span>
-            <span className={styles.message}>{log.msg}</span>
+            <span className={styles.message}>
+              {log.msg}
+              {log.repeatCount > 1 && ` (x${log.repeatCount})`}
+            </span>
           </div>
         ))}
         {filteredLogs.length === 0 && (
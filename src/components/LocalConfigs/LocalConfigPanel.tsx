@@ -1,12 +1,18 @@
 import { useState, useEffect, useCallback } from 'react';
-import { Device, DeviceConfig, LocalConfigInfo } from '@shared/types';
+import { open, save } from '@tauri-apps/plugin-dialog';
+import { ConfigSummary, Device, DeviceConfig, LocalConfigInfo } from '@shared/types';
 import {
   applyConfigToDevices,
-  backupDeviceConfigToLocal,
+  backupDeviceToConfig,
   deleteConfig,
+  exportAll,
+  exportConfig,
   getConfig,
+  importAll,
+  importConfig,
   listConfigs,
   onDeviceOperationProgress,
+  validateConfigFile,
 } from '../../lib/tauri-api';
 import { ProgressBar } from '../common/ProgressBar';
 import styles from './LocalConfigPanel.module.css';
@@ -16,6 +22,21 @@ interface LocalConfigPanelProps {
   allDevices: Device[];
 }
 
+function formatSummary(summary: ConfigSummary): string {
+  const role =
+    summary.uwbMode === 3 ? 'Anchor' : summary.uwbMode === 4 ? 'Tag' : `Mode ${summary.uwbMode}`;
+  const parts = [role];
+  if (summary.anchorCount > 0) parts.push(`${summary.anchorCount} anchors`);
+  if (summary.channel !== undefined) parts.push(`ch ${summary.channel}`);
+  if (summary.hasOrigin) parts.push('origin');
+  return parts.join(' · ');
+}
+
+function isAlreadyExists(e: unknown): boolean {
+  const cause = (e as { cause?: unknown }).cause;
+  return !!cause && typeof cause === 'object' && 'AlreadyExists' in cause;
+}
+
 interface BulkResult {
   ip: string;
   label: string;
@@ -161,11 +182,17 @@ export function LocalConfigPanel({ selectedDevices, allDevices }: LocalConfigPan
 
     try {
       const device = selectedDevices[0];
-      const success = await backupDeviceConfigToLocal(device.ip, newConfigName);
-      if (!success) throw new Error('Failed to save config');
+      let saved: LocalConfigInfo;
+      try {
+        saved = await backupDeviceToConfig(device.ip, newConfigName);
+      } catch (e) {
+        if (!isAlreadyExists(e)) throw e;
+        if (!confirm(`Config "${newConfigName}" already exists. Replace it?`)) return;
+        saved = await backupDeviceToConfig(device.ip, newConfigName, true);
+      }
 
       await fetchConfigs();
-      setSelectedConfig(newConfigName);
+      setSelectedConfig(saved.name);
       setNewConfigName('');
     } catch (e) {
       alert(`Failed to save config: ${e instanceof Error ? e.message : 'Unknown error'}`);
@@ -174,6 +201,106 @@ export function LocalConfigPanel({ selectedDevices, allDevices }: LocalConfigPan
     }
   };
 
+  // Export selected config to a portable file
+  const handleExportConfig = async () => {
+    if (!selectedConfig) return;
+    const path = await save({
+      defaultPath: `${selectedConfig}.json`,
+      filters: [{ name: 'Config', extensions: ['json'] }],
+    });
+    if (!path) return;
+
+    try {
+      await exportConfig(selectedConfig, path);
+    } catch (e) {
+      alert(`Failed to export config: ${e instanceof Error ? e.message : 'Unknown error'}`);
+    }
+  };
+
+  // Import a config file, asking before overwriting an existing one
+  const handleImportConfig = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: 'Config', extensions: ['json'] }],
+    });
+    if (typeof path !== 'string') return;
+
+    try {
+      const issues = await validateConfigFile(path);
+      const errors = issues.filter((issue) => issue.severity === 'error');
+      const warnings = issues.filter((issue) => issue.severity === 'warning');
+      const describe = (list: typeof issues) =>
+        list.map((issue) => `- ${issue.field}: ${issue.message}`).join('\n');
+      if (errors.length > 0) {
+        alert(`Config file has ${errors.length} error(s) and cannot be applied:\n${describe(errors)}`);
+        return;
+      }
+      if (
+        warnings.length > 0 &&
+        !confirm(`Config file has ${warnings.length} warning(s):\n${describe(warnings)}\n\nImport anyway?`)
+      ) {
+        return;
+      }
+
+      let name: string;
+      try {
+        name = await importConfig(path);
+      } catch (e) {
+        if (!isAlreadyExists(e)) throw e;
+        const existing = (e as { cause: { AlreadyExists: string } }).cause.AlreadyExists;
+        if (!confirm(`Config "${existing}" already exists. Overwrite it?`)) return;
+        name = await importConfig(path, { overwrite: true });
+      }
+      await fetchConfigs();
+      setSelectedConfig(name);
+    } catch (e) {
+      alert(`Failed to import config: ${e instanceof Error ? e.message : 'Unknown error'}`);
+    }
+  };
+
+  // Back up all configs and presets to one archive
+  const handleExportAll = async () => {
+    const path = await save({
+      defaultPath: `rtls-link-data-${new Date().toISOString().slice(0, 10)}.tar.gz`,
+      filters: [{ name: 'Archive', extensions: ['tar.gz', 'tgz'] }],
+    });
+    if (!path) return;
+
+    try {
+      const manifest = await exportAll(path);
+      alert(
+        `Exported ${manifest.configs.length} config(s) and ${manifest.presets.length} preset(s).`
+      );
+    } catch (e) {
+      alert(`Failed to export data: ${e instanceof Error ? e.message : 'Unknown error'}`);
+    }
+  };
+
+  // Restore configs and presets from an archive
+  const handleImportAll = async () => {
+    const path = await open({
+      multiple: false,
+      filters: [{ name: 'Archive', extensions: ['tar.gz', 'tgz'] }],
+    });
+    if (typeof path !== 'string') return;
+
+    try {
+      let report = await importAll(path);
+      if (
+        report.skipped.length > 0 &&
+        confirm(`${report.skipped.length} item(s) already exist. Overwrite them?`)
+      ) {
+        report = await importAll(path, true);
+      }
+      await fetchConfigs();
+      alert(
+        `Imported ${report.configs.length} config(s) and ${report.presets.length} preset(s).`
+      );
+    } catch (e) {
+      alert(`Failed to import data: ${e instanceof Error ? e.message : 'Unknown error'}`);
+    }
+  };
+
   // Delete selected config
   const handleDeleteConfig = async () => {
     if (!selectedConfig) return;
@@ -204,6 +331,23 @@ export function LocalConfigPanel({ selectedDevices, allDevices }: LocalConfigPan
             >
               + Save from Device
             </button>
+            <button onClick={handleImportConfig} disabled={loading} title="Import a config file">
+              Import
+            </button>
+            <button
+              onClick={handleExportAll}
+              disabled={loading}
+              title="Back up all configs and presets to one archive"
+            >
+              Backup All
+            </button>
+            <button
+              onClick={handleImportAll}
+              disabled={loading}
+              title="Restore configs and presets from an archive"
+            >
+              Restore All
+            </button>
           </div>
 
           {configs.length === 0 ? (
@@ -215,8 +359,12 @@ export function LocalConfigPanel({ selectedDevices, allDevices }: LocalConfigPan
                   key={c.name}
                   className={selectedConfig === c.name ? styles.selected : ''}
                   onClick={() => setSelectedConfig(c.name)}
+                  title={[c.description, c.tags.join(', ')].filter(Boolean).join('\n')}
                 >
                   <span className={styles.configName}>{c.name}</span>
+                  {c.summary && (
+                    <span className={styles.configSummary}>{formatSummary(c.summary)}</span>
+                  )}
                   <span className={styles.configDate}>
                     {new Date(c.updatedAt).toLocaleDateString()}
                   </span>
@@ -231,6 +379,9 @@ export function LocalConfigPanel({ selectedDevices, allDevices }: LocalConfigPan
             <>
               <div className={styles.previewHeader}>
                 <span>{selectedConfig}</span>
+                <button onClick={handleExportConfig} title="Export to a file">
+                  Export
+                </button>
                 <button onClick={handleDeleteConfig} className={styles.deleteBtn}>
                   Delete
                 </button>
@@ -3,6 +3,7 @@ import {
   Device,
   Preset,
   PresetInfo,
+  PresetProblem,
   PresetType,
   isTagRole,
 } from '@shared/types';
@@ -12,6 +13,7 @@ import {
   getPreset,
   listPresets,
   onDeviceOperationProgress,
+  repairPresets,
   uploadPresetToDevices,
 } from '../../lib/tauri-api';
 import { ProgressBar } from '../common/ProgressBar';
@@ -29,6 +31,7 @@ interface PresetUploadResult {
 
 export function PresetsPanel({ selectedDevices }: PresetsPanelProps) {
   const [presets, setPresets] = useState<PresetInfo[]>([]);
+  const [problems, setProblems] = useState<PresetProblem[]>([]);
   const [selectedPreset, setSelectedPreset] = useState<string | null>(null);
   const [presetData, setPresetData] = useState<Preset | null>(null);
   const [loading, setLoading] = useState(false);
@@ -43,7 +46,8 @@ export function PresetsPanel({ selectedDevices }: PresetsPanelProps) {
   const fetchPresets = useCallback(async () => {
     try {
       const data = await listPresets();
-      setPresets(data);
+      setPresets(data.presets);
+      setProblems(data.problems);
     } catch (e) {
       console.error('Failed to fetch presets', e);
     }
@@ -181,6 +185,17 @@ export function PresetsPanel({ selectedDevices }: PresetsPanelProps) {
     }
   };
 
+  const handleRepairPresets = async () => {
+    if (!confirm(`Move ${problems.length} unreadable preset file(s) to the corrupt/ folder?`)) return;
+
+    try {
+      await repairPresets();
+      await fetchPresets();
+    } catch (e) {
+      console.error('Failed to repair presets', e);
+    }
+  };
+
   const getPresetTypeLabel = (type: PresetType) => {
     return type === 'full' ? 'Full Config' : 'Locations';
   };
@@ -221,6 +236,19 @@ export function PresetsPanel({ selectedDevices }: PresetsPanelProps) {
               ))}
             </ul>
           )}
+          {problems.length > 0 && (
+            <div className={styles.problems}>
+              <div>{problems.length} preset file(s) could not be loaded:</div>
+              {problems.map((p) => (
+                <div key={p.file} title={p.error}>
+                  {p.file}
+                </div>
+              ))}
+              <button onClick={handleRepairPresets} className={styles.btnRepair}>
+                Move to corrupt/
+              </button>
+            </div>
+          )}
         </div>
 
         <div className={styles.preview}>
@@ -1,5 +1,5 @@
 import { useState, useCallback } from 'react';
-import { sendDeviceCommand, sendDeviceCommands } from '../lib/tauri-api';
+import { sendDeviceCommand, sendDeviceCommands, type CommandAck } from '../lib/tauri-api';
 
 interface UseDeviceCommandOptions {
   timeout?: number;
@@ -33,13 +33,30 @@ export function useDeviceCommand(deviceIp: string, options: UseDeviceCommandOpti
     }
   }, [deviceIp, timeoutMs]);
 
-  const sendCommandsBatch = useCallback(async (commands: string[]): Promise<string[]> => {
+  /** Send a command and report whether the device accepted it. */
+  const sendCommandAck = useCallback(async (command: string): Promise<CommandAck | null> => {
+    setLoading(true);
+    setError(null);
+
+    try {
+      const response = await sendDeviceCommand(deviceIp, command, timeoutMs);
+      return response.ack;
+    } catch (e) {
+      const message = e instanceof Error ? e.message : String(e);
+      setError(message);
+      return { success: false, message };
+    } finally {
+      setLoading(false);
+    }
+  }, [deviceIp, timeoutMs]);
+
+  const sendCommandsBatch = useCallback(async (commands: string[]): Promise<CommandAck[]> => {
     setLoading(true);
     setError(null);
 
     try {
       const results = await sendDeviceCommands(deviceIp, commands, timeoutMs);
-      return results.map((r) => r.raw);
+      return results.map((r) => r.ack);
     } catch (e) {
       const message = e instanceof Error ? e.message : String(e);
       setError(message);
@@ -52,5 +69,5 @@ export function useDeviceCommand(deviceIp: string, options: UseDeviceCommandOpti
   // No-op close for API compatibility with useDeviceUDP MAVLink
   const close = useCallback(() => {}, []);
 
-  return { sendCommand, sendCommands: sendCommandsBatch, loading, error, close };
+  return { sendCommand, sendCommandAck, sendCommands: sendCommandsBatch, loading, error, close };
 }
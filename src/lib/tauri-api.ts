@@ -11,18 +11,35 @@ import type {
   Device,
   LocalConfigInfo,
   LocalConfig,
+  ConfigRevision,
+  ConfigDiffEntry,
+  ConfigSearchResult,
+  ValidationIssue,
+  ParamSpec,
+  DataArchiveManifest,
+  DataImportReport,
   DeviceConfig,
   Preset,
   PresetInfo,
+  PresetList,
+  PresetProblem,
+  PresetType,
+  LayoutTemplate,
+  LocationData,
+  ParamOverride,
 } from '@shared/types';
 
 export type AppError =
   | { Io: string }
   | { InvalidName: string }
   | { NotFound: string }
+  | { AlreadyExists: string }
   | { Json: string }
   | { Discovery: string }
-  | { Device: string };
+  | { Device: string }
+  | { InvalidInput: string }
+  | { Cancelled: string }
+  | { Unauthorized: string };
 
 export function formatAppError(error: unknown): string {
   if (error instanceof Error) return error.message;
@@ -95,10 +112,10 @@ export async function clearDevices(): Promise<void> {
 // ============================================================================
 
 /**
- * List all saved local configurations.
+ * List saved local configurations, optionally only those carrying `tag`.
  */
-export async function listConfigs(): Promise<LocalConfigInfo[]> {
-  return await invokeSafe('list_configs');
+export async function listConfigs(tag?: string): Promise<LocalConfigInfo[]> {
+  return await invokeSafe('list_configs', { tag });
 }
 
 /**
@@ -109,13 +126,24 @@ export async function getConfig(name: string): Promise<LocalConfig | null> {
 }
 
 /**
- * Save a configuration with the given name.
+ * Save a configuration with the given name. Passing a description or tags
+ * replaces both; omitting them keeps the existing ones. Configs with
+ * validation errors are rejected; warnings are returned. Fails with
+ * `AlreadyExists` if the name is taken, unless `overwrite` is set.
  */
 export async function saveConfig(
   name: string,
-  config: DeviceConfig
-): Promise<boolean> {
-  return await invokeSafe('save_config', { name, config });
+  config: DeviceConfig,
+  labels?: { description?: string; tags?: string[] },
+  overwrite = false
+): Promise<ValidationIssue[]> {
+  return await invokeSafe('save_config', {
+    name,
+    config,
+    description: labels?.description,
+    tags: labels?.tags,
+    overwrite,
+  });
 }
 
 /**
@@ -125,12 +153,117 @@ export async function deleteConfig(name: string): Promise<boolean> {
   return await invokeSafe('delete_config', { name });
 }
 
-export async function backupDeviceConfigToLocal(
+/**
+ * Rename a configuration. Fails if the new name is already taken.
+ */
+export async function renameConfig(oldName: string, newName: string): Promise<boolean> {
+  return await invokeSafe('rename_config', { oldName, newName });
+}
+
+/**
+ * Copy a configuration under a new name. Fails if the target name is
+ * already taken unless `overwrite` is set.
+ */
+export async function duplicateConfig(
+  source: string,
+  target: string,
+  overwrite = false
+): Promise<boolean> {
+  return await invokeSafe('duplicate_config', { source, target, overwrite });
+}
+
+/**
+ * Export a configuration to a portable JSON file at `path`.
+ */
+export async function exportConfig(name: string, path: string): Promise<boolean> {
+  return await invokeSafe('export_config', { name, path });
+}
+
+/**
+ * Import a configuration file. The name defaults to the one recorded in the
+ * file (or the file name). Returns the name the config was saved as.
+ */
+export async function importConfig(
+  path: string,
+  options?: { name?: string; overwrite?: boolean }
+): Promise<string> {
+  return await invokeSafe('import_config', {
+    path,
+    name: options?.name,
+    overwrite: options?.overwrite ?? false,
+  });
+}
+
+/**
+ * Compare two saved configurations parameter by parameter.
+ */
+export async function diffConfigs(nameA: string, nameB: string): Promise<ConfigDiffEntry[]> {
+  return await invokeSafe('diff_configs', { nameA, nameB });
+}
+
+/**
+ * Check a configuration file for errors and warnings without importing it.
+ */
+export async function validateConfigFile(path: string): Promise<ValidationIssue[]> {
+  return await invokeSafe('validate_config_file', { path });
+}
+
+/**
+ * Get the known device parameters, with their types and accepted values.
+ */
+export async function getParamRegistry(): Promise<ParamSpec[]> {
+  return await invokeSafe('get_param_registry');
+}
+
+/**
+ * Search saved configurations by parameter name and value.
+ * The query is a case-insensitive substring, or a regex when `regex` is set.
+ */
+export async function searchConfigs(query: string, regex = false): Promise<ConfigSearchResult[]> {
+  return await invokeSafe('search_configs', { query, regex });
+}
+
+/**
+ * List previous versions of a configuration, newest first.
+ */
+export async function listConfigRevisions(name: string): Promise<ConfigRevision[]> {
+  return await invokeSafe('list_config_revisions', { name });
+}
+
+/**
+ * Restore a previous version of a configuration. The version being
+ * replaced is kept as a revision.
+ */
+export async function restoreConfigRevision(name: string, timestamp: string): Promise<boolean> {
+  return await invokeSafe('restore_config_revision', { name, timestamp });
+}
+
+/**
+ * Write all local configs and presets to a single archive (.tar.gz).
+ */
+export async function exportAll(path: string): Promise<DataArchiveManifest> {
+  return await invokeSafe('export_all', { path });
+}
+
+/**
+ * Restore configs and presets from an archive. Existing entries are kept
+ * (and reported as skipped) unless `overwrite` is set.
+ */
+export async function importAll(path: string, overwrite = false): Promise<DataImportReport> {
+  return await invokeSafe('import_all', { path, overwrite });
+}
+
+/**
+ * Back up a device's config straight into local storage.
+ * Fails with AlreadyExists unless `overwrite` is set.
+ */
+export async function backupDeviceToConfig(
   ip: string,
   name: string,
+  overwrite = false,
   timeoutMs?: number
-): Promise<boolean> {
-  return await invokeSafe('backup_device_config_to_local', { ip, name, timeoutMs });
+): Promise<LocalConfigInfo> {
+  return await invokeSafe('backup_device_to_config', { ip, name, overwrite, timeoutMs });
 }
 
 // ============================================================================
@@ -138,10 +271,25 @@ export async function backupDeviceConfigToLocal(
 // ============================================================================
 
 /**
- * List all saved presets.
+ * List saved presets, optionally only those of a given type or carrying a
+ * tag. Files that could not be loaded are reported in `problems`.
+ */
+export async function listPresets(options?: {
+  type?: PresetType;
+  tag?: string;
+}): Promise<PresetList> {
+  return await invokeSafe('list_presets', {
+    presetType: options?.type,
+    tag: options?.tag,
+  });
+}
+
+/**
+ * Move corrupt preset files into the `corrupt/` subfolder.
+ * Returns the files that were moved.
  */
-export async function listPresets(): Promise<PresetInfo[]> {
-  return await invokeSafe('list_presets');
+export async function repairPresets(): Promise<PresetProblem[]> {
+  return await invokeSafe('repair_presets');
 }
 
 /**
@@ -152,10 +300,49 @@ export async function getPreset(name: string): Promise<Preset | null> {
 }
 
 /**
- * Save a preset.
+ * Save a preset. Returns any validation warnings for the anchor layout;
+ * layouts with errors are rejected. A name differing only by case from an
+ * existing preset is rejected unless `overwrite` is set.
  */
-export async function savePreset(preset: Preset): Promise<boolean> {
-  return await invokeSafe('save_preset', { preset });
+export async function savePreset(preset: Preset, overwrite?: boolean): Promise<ValidationIssue[]> {
+  return await invokeSafe('save_preset', { preset, overwrite });
+}
+
+/**
+ * Generate a locations preset from a room template and save it.
+ * Anchor ids default to 0, 1, 2, ... in placement order.
+ */
+export async function createPresetFromTemplate(
+  name: string,
+  layout: LayoutTemplate,
+  ids?: string[]
+): Promise<Preset> {
+  return await invokeSafe('create_preset_from_template', { name, layout, ids });
+}
+
+/**
+ * Save a locations preset from a survey CSV with an `id,x,y,z` header.
+ * Returns validation warnings; layouts with errors are rejected.
+ */
+export async function importAnchorsCsv(
+  path: string,
+  name: string,
+  origin: LocationData['origin'],
+  rotation?: number
+): Promise<ValidationIssue[]> {
+  return await invokeSafe('import_anchors_csv', { path, name, origin, rotation });
+}
+
+export interface PresetUploadPlan {
+  params: [string, string, string][];  // [group, name, value] in write order
+  saveCommand: string;
+}
+
+/**
+ * List the parameter writes and save command uploading a preset would send.
+ */
+export async function previewPreset(name: string): Promise<PresetUploadPlan> {
+  return await invokeSafe('preview_preset', { name });
 }
 
 /**
@@ -165,6 +352,80 @@ export async function deletePreset(name: string): Promise<boolean> {
   return await invokeSafe('delete_preset', { name });
 }
 
+/**
+ * Rename a preset. Fails if the new name is already taken.
+ */
+export async function renamePreset(oldName: string, newName: string): Promise<boolean> {
+  return await invokeSafe('rename_preset', { oldName, newName });
+}
+
+/**
+ * Copy a preset under a new name. Fails if the target name is already taken.
+ */
+export async function duplicatePreset(source: string, target: string): Promise<boolean> {
+  return await invokeSafe('duplicate_preset', { source, target });
+}
+
+/**
+ * Convert a preset to another type. Only full to locations is supported;
+ * the anchor layout is extracted from the config. Saved as `newName` if
+ * given, otherwise the preset is replaced.
+ */
+export async function convertPreset(
+  name: string,
+  targetType: 'full' | 'locations',
+  newName?: string
+): Promise<Preset> {
+  return await invokeSafe('convert_preset', { name, targetType, newName });
+}
+
+/**
+ * Save a new full preset combining the settings of `fullName` with the
+ * anchors, origin and rotation of `locationsName`.
+ */
+export async function mergePresets(
+  fullName: string,
+  locationsName: string,
+  outputName: string
+): Promise<Preset> {
+  return await invokeSafe('merge_presets', { fullName, locationsName, outputName });
+}
+
+/**
+ * Export a preset to a shareable JSON file at `path`.
+ */
+export async function exportPreset(name: string, path: string): Promise<boolean> {
+  return await invokeSafe('export_preset', { name, path });
+}
+
+/**
+ * Import a preset file. The name defaults to the one recorded in the file.
+ * Returns the name the preset was saved as.
+ */
+export async function importPreset(
+  path: string,
+  options?: { name?: string; overwrite?: boolean }
+): Promise<string> {
+  return await invokeSafe('import_preset', {
+    path,
+    name: options?.name,
+    overwrite: options?.overwrite ?? false,
+  });
+}
+
+/**
+ * Compare a preset against the configuration currently on a device. Empty
+ * when they match; `removed` entries are only in the preset, `added` ones
+ * only on the device.
+ */
+export async function diffPresetAgainstDevice(
+  name: string,
+  ip: string,
+  timeoutMs?: number
+): Promise<ConfigDiffEntry[]> {
+  return await invokeSafe('diff_preset_against_device', { name, ip, timeoutMs });
+}
+
 export async function backupDevicePreset(
   ip: string,
   name: string,
@@ -179,9 +440,29 @@ export async function backupDevicePreset(
 // Device Communication Commands
 // ============================================================================
 
+/** A command's outcome as the device reported it. */
+export interface CommandAck {
+  success: boolean;
+  /** The error, or a status line; absent for a bare "OK". */
+  message?: string;
+  /** JSON the reply carried, without its success/message/error fields. */
+  payload?: unknown;
+}
+
 export interface DeviceCommandResponse {
   raw: string;
   json?: unknown;
+  ack: CommandAck;
+  /** Times the command was sent before this response came back. */
+  attempts: number;
+}
+
+/** Retries for timeouts and transport errors; refusals are never retried. */
+export interface RetryOptions {
+  /** Attempts after the first (default 0). */
+  retries?: number;
+  /** Wait before the first retry, doubled for each later one (default 500). */
+  retryBackoffMs?: number;
 }
 
 /**
@@ -190,26 +471,114 @@ export interface DeviceCommandResponse {
 export async function sendDeviceCommand(
   ip: string,
   command: string,
-  timeoutMs?: number
+  timeoutMs?: number,
+  retry?: RetryOptions
 ): Promise<DeviceCommandResponse> {
-  return await invokeSafe('send_device_command', { ip, command, timeoutMs });
+  return await invokeSafe('send_device_command', {
+    ip,
+    command,
+    timeoutMs,
+    retries: retry?.retries,
+    retryBackoffMs: retry?.retryBackoffMs,
+  });
+}
+
+/** Outcome of one command of a batch sent with `continueOnError` */
+export interface CommandResult {
+  /** Position of the command in the batch, from 0 */
+  index: number;
+  command: string;
+  success: boolean;
+  response?: DeviceCommandResponse;
+  error?: string;
+  /** Not sent because the batch was cancelled */
+  cancelled?: boolean;
 }
 
+/** A batch command, optionally waiting longer than the batch timeout (e.g. `save-config-as`). */
+export type BatchCommand = string | { command: string; timeoutMs?: number };
+
 /**
  * Send multiple UDP MAVLink commands to a device sequentially.
+ *
+ * The first failing command rejects the call. With `continueOnError`, every
+ * command is sent and the outcome of each is returned instead. With an
+ * `operationId`, `cancelOperation` stops the batch before its next command;
+ * the outcome of each command is then returned, unsent ones marked cancelled.
  */
 export async function sendDeviceCommands(
   ip: string,
-  commands: string[],
-  timeoutMs?: number
-): Promise<DeviceCommandResponse[]> {
-  return await invokeSafe('send_device_commands', { ip, commands, timeoutMs });
+  commands: BatchCommand[],
+  timeoutMs?: number,
+  options?: { continueOnError?: false }
+): Promise<DeviceCommandResponse[]>;
+export async function sendDeviceCommands(
+  ip: string,
+  commands: BatchCommand[],
+  timeoutMs: number | undefined,
+  options: { continueOnError?: false; operationId: string }
+): Promise<DeviceCommandResponse[] | CommandResult[]>;
+export async function sendDeviceCommands(
+  ip: string,
+  commands: BatchCommand[],
+  timeoutMs: number | undefined,
+  options: { continueOnError: true; operationId?: string }
+): Promise<CommandResult[]>;
+export async function sendDeviceCommands(
+  ip: string,
+  commands: BatchCommand[],
+  timeoutMs?: number,
+  options?: { continueOnError?: boolean; operationId?: string }
+): Promise<DeviceCommandResponse[] | CommandResult[]> {
+  return await invokeSafe('send_device_commands', {
+    ip,
+    commands,
+    timeoutMs,
+    continueOnError: options?.continueOnError,
+    operationId: options?.operationId,
+  });
+}
+
+/**
+ * Stop an operation started with this `operationId` before its next step.
+ *
+ * Resolves to false if no such operation is running.
+ */
+export async function cancelOperation(operationId: string): Promise<boolean> {
+  return await invokeSafe('cancel_operation', { operationId });
+}
+
+/** A parameter that read back differently from what was written */
+export interface ParamMismatch {
+  group: string;
+  name: string;
+  written: string;
+  /** Missing if the device would not return the value */
+  read: string | null;
 }
 
 export interface DeviceOperationResult {
   ip: string;
   success: boolean;
   error?: string;
+  /** Set for verified uploads that read some parameters back differently */
+  mismatches?: ParamMismatch[];
+  /** The operation was cancelled before this device's part completed */
+  cancelled?: boolean;
+  /** Times the link to the device dropped and was reopened */
+  reconnects?: number;
+  /** The device did not answer the reachability probe and was left alone */
+  skipped?: boolean;
+}
+
+/** Probe devices first and skip those that do not answer, instead of waiting for their timeouts. */
+export interface ProbeOptions {
+  skipUnreachable?: boolean;
+}
+
+/** Wait between the commands sent to each device, for devices that drop back-to-back frames. */
+export interface PacingOptions {
+  pacingMs?: number;
 }
 
 export interface DeviceOperationProgressEvent {
@@ -224,7 +593,7 @@ export interface DeviceOperationProgressEvent {
 export async function runBulkDeviceCommand(
   ips: string[],
   command: string,
-  options?: { timeoutMs?: number; concurrency?: number; operationId?: string }
+  options?: ProbeOptions & { timeoutMs?: number; concurrency?: number; operationId?: string }
 ): Promise<DeviceOperationResult[]> {
   return await invokeSafe('run_bulk_device_command', {
     ips,
@@ -232,6 +601,48 @@ export async function runBulkDeviceCommand(
     timeoutMs: options?.timeoutMs,
     concurrency: options?.concurrency,
     operationId: options?.operationId,
+    skipUnreachable: options?.skipUnreachable,
+  });
+}
+
+/** Outcome of one device's command in `sendCommandToDevices` */
+export interface BulkDeviceResult {
+  ip: string;
+  success: boolean;
+  status: 'success' | 'timeout' | 'connectFailed' | 'commandFailed' | 'skipped';
+  response?: string;
+  error?: string;
+  /** How long the answering attempt took */
+  elapsedMs?: number;
+  /** Times the command was sent, counting retries */
+  attempts: number;
+}
+
+export interface BulkCommandProgressEvent extends BulkDeviceResult {
+  completed: number;
+  total: number;
+}
+
+/**
+ * Send one command to many devices concurrently.
+ *
+ * `concurrency` defaults to 3 and is capped by the backend; each device is
+ * reported through `onBulkCommandProgress` as it completes. Timeouts and
+ * transport errors are retried up to `retries` times (default none).
+ */
+export async function sendCommandToDevices(
+  ips: string[],
+  command: string,
+  options?: RetryOptions & ProbeOptions & { timeoutMs?: number; concurrency?: number }
+): Promise<BulkDeviceResult[]> {
+  return await invokeSafe('send_command_to_devices', {
+    ips,
+    command,
+    timeoutMs: options?.timeoutMs,
+    concurrency: options?.concurrency,
+    retries: options?.retries,
+    retryBackoffMs: options?.retryBackoffMs,
+    skipUnreachable: options?.skipUnreachable,
   });
 }
 
@@ -239,7 +650,13 @@ export async function applyConfigToDevices(
   ips: string[],
   config: DeviceConfig,
   configName: string,
-  options?: { timeoutMs?: number; concurrency?: number; operationId?: string }
+  options?: ProbeOptions &
+    PacingOptions & {
+      timeoutMs?: number;
+      concurrency?: number;
+      operationId?: string;
+      verify?: boolean;
+    }
 ): Promise<DeviceOperationResult[]> {
   return await invokeSafe('apply_config_to_devices', {
     ips,
@@ -248,13 +665,16 @@ export async function applyConfigToDevices(
     timeoutMs: options?.timeoutMs,
     concurrency: options?.concurrency,
     operationId: options?.operationId,
+    skipUnreachable: options?.skipUnreachable,
+    verify: options?.verify,
+    pacingMs: options?.pacingMs,
   });
 }
 
 export async function activateConfigOnDevices(
   ips: string[],
   configName: string,
-  options?: { timeoutMs?: number; concurrency?: number; operationId?: string }
+  options?: ProbeOptions & { timeoutMs?: number; concurrency?: number; operationId?: string }
 ): Promise<DeviceOperationResult[]> {
   return await invokeSafe('activate_config_on_devices', {
     ips,
@@ -262,20 +682,68 @@ export async function activateConfigOnDevices(
     timeoutMs: options?.timeoutMs,
     concurrency: options?.concurrency,
     operationId: options?.operationId,
+    skipUnreachable: options?.skipUnreachable,
   });
 }
 
 export async function uploadPresetToDevices(
   ips: string[],
   preset: Preset,
-  options?: { timeoutMs?: number; concurrency?: number; operationId?: string }
+  options?: ProbeOptions &
+    PacingOptions & {
+      overrides?: Record<string, ParamOverride[]>;
+      timeoutMs?: number;
+      concurrency?: number;
+      operationId?: string;
+      verify?: boolean;
+    }
 ): Promise<DeviceOperationResult[]> {
   return await invokeSafe('upload_preset_to_devices', {
     ips,
     preset,
+    overrides: options?.overrides,
     timeoutMs: options?.timeoutMs,
     concurrency: options?.concurrency,
     operationId: options?.operationId,
+    skipUnreachable: options?.skipUnreachable,
+    verify: options?.verify,
+    pacingMs: options?.pacingMs,
+  });
+}
+
+export interface PresetApplyProgress {
+  presetName: string;
+  ip: string;
+  written: number;
+  total: number;
+}
+
+/**
+ * Apply a stored preset to several devices, one connection per device.
+ * Emits `preset-apply-progress` events as parameters are written.
+ */
+export async function applyPresetToDevices(
+  presetName: string,
+  ips: string[],
+  options?: ProbeOptions &
+    PacingOptions & {
+      concurrency?: number;
+      rebootAfter?: boolean;
+      timeoutMs?: number;
+      verify?: boolean;
+      operationId?: string;
+    }
+): Promise<DeviceOperationResult[]> {
+  return await invokeSafe('apply_preset_to_devices', {
+    presetName,
+    ips,
+    concurrency: options?.concurrency,
+    rebootAfter: options?.rebootAfter,
+    timeoutMs: options?.timeoutMs,
+    verify: options?.verify,
+    operationId: options?.operationId,
+    skipUnreachable: options?.skipUnreachable,
+    pacingMs: options?.pacingMs,
   });
 }
 
@@ -340,6 +808,48 @@ export async function runAntennaCalibration(
   return await invokeSafe('run_antenna_calibration', { config });
 }
 
+/** How firmware uploads reach a device (e.g. HTTPS behind a reverse proxy). */
+export interface OtaEndpoint {
+  tls: boolean;
+  /** Defaults to 443 with TLS, 80 otherwise */
+  port?: number;
+  acceptInvalidCerts: boolean;
+}
+
+/** OTA endpoint overrides keyed by device MAC address. */
+export async function getDeviceEndpoints(): Promise<Record<string, OtaEndpoint>> {
+  return await invokeSafe('get_device_endpoints');
+}
+
+/** Set the OTA endpoint for a device, or clear it with `null`. */
+export async function setDeviceEndpoint(
+  mac: string,
+  endpoint: OtaEndpoint | null
+): Promise<void> {
+  await invokeSafe('set_device_endpoint', { mac, endpoint });
+}
+
+/** Credentials for devices that protect their web server. */
+export type OtaAuth =
+  | { type: 'none' }
+  | { type: 'basic'; username: string; password: string }
+  | { type: 'bearer'; token: string };
+
+/** MAC addresses that have stored credentials (the secrets stay in the backend). */
+export async function getDevicesWithCredentials(): Promise<string[]> {
+  return await invokeSafe('get_devices_with_credentials');
+}
+
+/** Store credentials for a device encrypted at rest, or clear them with `null`. */
+export async function setDeviceCredentials(mac: string, auth: OtaAuth | null): Promise<void> {
+  await invokeSafe('set_device_credentials', { mac, auth });
+}
+
+/** Forget all stored device credentials, e.g. when they can no longer be decrypted. */
+export async function resetDeviceCredentials(): Promise<void> {
+  await invokeSafe('reset_device_credentials');
+}
+
 /**
  * Upload firmware to a single device from a file path.
  *
@@ -356,6 +866,8 @@ export interface FirmwareResult {
   ip: string;
   success: boolean;
   error?: string;
+  /** The device's OTA endpoint did not answer the reachability probe */
+  skipped?: boolean;
 }
 
 /**
@@ -366,16 +878,31 @@ export interface FirmwareResult {
 export async function uploadFirmwareBulk(
   ips: string[],
   filePath: string,
-  concurrency?: number
+  concurrency?: number,
+  skipUnreachable?: boolean
 ): Promise<FirmwareResult[]> {
-  return await invokeSafe('upload_firmware_to_devices', { ips, filePath, concurrency });
+  return await invokeSafe('upload_firmware_to_devices', {
+    ips,
+    filePath,
+    concurrency,
+    skipUnreachable,
+  });
 }
 
 export async function cancelFirmwareUpload(ip: string): Promise<boolean> {
   return await invokeSafe('cancel_firmware_upload', { ip });
 }
 
+/** Firmware details; builds report different subsets of these fields. */
 export interface FirmwareInfo {
+  device?: string;
+  version?: string;
+  board?: string;
+  buildDate?: string;
+  buildTime?: string;
+  partition?: string;
+  freeHeap?: number;
+  sketchSize?: number;
   [key: string]: unknown;
 }
 
@@ -389,6 +916,32 @@ export async function getFirmwareInfo(
   return await invokeSafe('get_firmware_info', { ip, timeoutMs });
 }
 
+/** Commands a device supports; `derived` when taken from the dialect rather than reported by the device. */
+export interface DeviceCapabilities {
+  protocolVersion?: number;
+  firmwareVersion?: string;
+  commands: string[];
+  derived: boolean;
+}
+
+/**
+ * Get the commands a device supports, e.g. whether it can write-batch.
+ */
+export async function getDeviceCapabilities(
+  ip: string,
+  timeoutMs?: number
+): Promise<DeviceCapabilities> {
+  return await invokeSafe('get_device_capabilities', { ip, timeoutMs });
+}
+
+/**
+ * Close the backend's pooled connection to a device. Returns whether one was
+ * open; the next command to the device opens a new one.
+ */
+export async function closeDeviceConnection(ip: string): Promise<boolean> {
+  return await invokeSafe('close_device_connection', { ip });
+}
+
 // ============================================================================
 // Event Listeners
 // ============================================================================
@@ -466,6 +1019,14 @@ export async function onDeviceOperationProgress(
   });
 }
 
+export async function onBulkCommandProgress(
+  callback: (event: BulkCommandProgressEvent) => void
+): Promise<UnlistenFn> {
+  return await listen<BulkCommandProgressEvent>('bulk-command-progress', (event) => {
+    callback(event.payload);
+  });
+}
+
 export async function onAntennaCalibrationEvent(
   callback: (event: CalibrationEvent) => void
 ): Promise<UnlistenFn> {